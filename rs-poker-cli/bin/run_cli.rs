@@ -1,4 +1,8 @@
 use clap::{Parser, Subcommand};
+use rs_poker::arena::{
+    GameType,
+    agent::{AgentConfig, AgentRegistry},
+};
 use rs_poker_cli::{
     ai_battle::ai_battle,
     run_game::{client, run_example_game},
@@ -113,6 +117,15 @@ enum TournamentType {
     Random3,
     /// 3 AI players tournament.
     AI3,
+    /// 3 AI players pot limit omaha tournament.
+    AI3Plo,
+    /// Tournament between automat players built from agent configs, e.g.
+    /// `--player calling --player '{"type":"cfr","iterations":5}'`.
+    Automat {
+        /// Agent config for each player: a registered type name or JSON.
+        #[arg(short, long = "player", required = true)]
+        players: Vec<AgentConfig>,
+    },
 }
 
 #[derive(Subcommand)]
@@ -180,10 +193,15 @@ async fn main() {
                     starting_player_stack: 100.0,
                     starting_small_blind: 5.0,
                     double_blinds_every_n_games: Some(3),
+                    blind_structure: None,
+                    payout_structure: None,
+                    buy_in: None,
+                    format: Default::default(),
                     end_condition: TournamentEndCondition::SingleWinner,
                     see_historical_thoughts: false,
                     public_chat: false,
                     random_seed: 42,
+                    game_type: GameType::NoLimitHoldem,
                 },
                 SeriesCommand::RunTop3 => SeriesSettings {
                     series_id: SeriesId::new("top3"),
@@ -196,10 +214,15 @@ async fn main() {
                     starting_player_stack: 200.0,
                     starting_small_blind: 5.0,
                     double_blinds_every_n_games: Some(5),
+                    blind_structure: None,
+                    payout_structure: None,
+                    buy_in: None,
+                    format: Default::default(),
                     end_condition: TournamentEndCondition::SingleWinner,
                     see_historical_thoughts: false,
                     public_chat: false,
                     random_seed: 42,
+                    game_type: GameType::NoLimitHoldem,
                 },
             };
             run_series(cli.mock_server, config).await;
@@ -260,11 +283,16 @@ async fn create_tournament(mock_server: bool, tournament_type: TournamentType) {
             starting_player_stack: 100.0,
             starting_small_blind: 10.0,
             double_blinds_every_n_games: Some(2),
+            blind_structure: None,
+            payout_structure: None,
+            buy_in: None,
+            format: Default::default(),
             end_condition: TournamentEndCondition::SingleWinner,
             see_historical_thoughts: true,
             public_chat: false,
+            game_type: GameType::NoLimitHoldem,
         },
-        TournamentType::AI3 => TournamentSettings {
+        TournamentType::AI3 | TournamentType::AI3Plo => TournamentSettings {
             tournament_id: TournamentId::random(),
             players: vec![
                 Player::ai("Alice", "gpt-4o-mini", "Play tight aggressive"),
@@ -274,10 +302,49 @@ async fn create_tournament(mock_server: bool, tournament_type: TournamentType) {
             starting_player_stack: 100.0,
             starting_small_blind: 10.0,
             double_blinds_every_n_games: Some(2),
+            blind_structure: None,
+            payout_structure: None,
+            buy_in: None,
+            format: Default::default(),
             end_condition: TournamentEndCondition::SingleWinner,
             see_historical_thoughts: true,
             public_chat: true,
+            game_type: if matches!(tournament_type, TournamentType::AI3Plo) {
+                GameType::PotLimitOmaha
+            } else {
+                GameType::NoLimitHoldem
+            },
         },
+        TournamentType::Automat { players } => {
+            let registry = AgentRegistry::default();
+            if let Some((config, err)) = players
+                .iter()
+                .find_map(|config| registry.build(config).err().map(|err| (config, err)))
+            {
+                eprintln!("Invalid agent config {}: {}", config, err);
+                eprintln!("Known agent types: {}", registry.names().join(", "));
+                std::process::exit(1);
+            }
+            TournamentSettings {
+                tournament_id: TournamentId::random(),
+                players: players
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, config)| Player::automat(&format!("Bot{}", i + 1), config))
+                    .collect(),
+                starting_player_stack: 100.0,
+                starting_small_blind: 5.0,
+                double_blinds_every_n_games: Some(5),
+                blind_structure: None,
+                payout_structure: None,
+                buy_in: None,
+                format: Default::default(),
+                end_condition: TournamentEndCondition::SingleWinner,
+                see_historical_thoughts: false,
+                public_chat: false,
+                game_type: GameType::NoLimitHoldem,
+            }
+        }
     };
     let client = client(mock_server);
     match client.new_tournament(settings).await {
@@ -1,3 +1,4 @@
+use rs_poker::arena::GameType;
 use rs_poker_server::{
     handler::{game_make_action::MakeActionRequest, game_player_view::GamePlayerViewRequest},
     poker_client::PokerClient,
@@ -48,11 +49,14 @@ pub async fn play_game(client: &PokerClient, players: Vec<Player>) -> GameInfo {
             tournament_game_number: None,
             game_id: None,
             small_blind: 5.0,
+            big_blind: None,
+            ante: 0.0,
             players,
             stacks: vec![100.0; players_count],
             hands: None,
             community_cards: None,
             dealer_index: 0,
+            game_type: GameType::NoLimitHoldem,
         })
         .await
         .unwrap();
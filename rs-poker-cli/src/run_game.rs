@@ -2,7 +2,7 @@ use crossterm::{
     event::{self, Event, KeyCode},
     terminal,
 };
-use rs_poker::arena::action::AgentAction;
+use rs_poker::arena::{GameType, action::AgentAction};
 use rs_poker_server::{
     handler::{game_make_action::MakeActionRequest, game_player_view::GamePlayerViewRequest},
     poker_client::PokerClient,
@@ -69,11 +69,14 @@ async fn initialize_game(client: &PokerClient) -> GameId {
             tournament_game_number: None,
             game_id: None,
             small_blind: 5.0,
+            big_blind: None,
+            ante: 0.0,
             players,
             stacks: vec![100.0; players_count],
             hands: None,
             community_cards: None,
             dealer_index: 0,
+            game_type: GameType::NoLimitHoldem,
         })
         .await
         .unwrap();
@@ -1,8 +1,6 @@
+use rs_poker::arena::GameType;
 use rs_poker_engine::{game_instance::GameInstance, game_summary::GameSummary};
-use rs_poker_types::{
-    game::GameSettings,
-    player::{AutomatType, Player, PlayerName},
-};
+use rs_poker_types::{game::GameSettings, player::Player};
 
 fn main() {
     let num_of_players = 3;
@@ -10,10 +8,7 @@ fn main() {
     let small_blind = 5.0;
 
     let players: Vec<Player> = (1..=num_of_players)
-        .map(|i| Player::Automat {
-            name: PlayerName::new(&format!("Player{}", i)),
-            automat_type: AutomatType::Random,
-        })
+        .map(|i| Player::random(&format!("Player{}", i)))
         .collect();
 
     let game_settings = GameSettings {
@@ -23,9 +18,12 @@ fn main() {
         players: players.clone(),
         stacks: vec![initial_stack; num_of_players],
         small_blind,
+        big_blind: None,
+        ante: 0.0,
         hands: None,
         community_cards: None,
         dealer_index: 0,
+        game_type: GameType::default(),
     };
 
     let mut game_instance = GameInstance::new(game_settings);
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use rs_poker::arena::GameType;
 use rs_poker_engine::{
     game_instance::GameInstance, tournament_instance::TournamentInstance,
     tournament_summary::TournamentSummary,
@@ -23,9 +24,14 @@ fn main() {
         starting_player_stack: 100.0,
         starting_small_blind: 5.0,
         double_blinds_every_n_games: Some(3),
+        blind_structure: None,
+        payout_structure: None,
+        buy_in: None,
+        format: Default::default(),
         end_condition: TournamentEndCondition::SingleWinner,
         see_historical_thoughts: false,
         public_chat: false,
+        game_type: GameType::default(),
     };
 
     let mut tournament = TournamentInstance::new(&settings);
@@ -0,0 +1,124 @@
+use std::{
+    fmt,
+    sync::{Arc, mpsc},
+    thread,
+};
+
+use rs_poker::arena::{
+    Agent, GameState, PlayerView,
+    action::{Action, AgentAction},
+    agent::{AgentConfig, AgentRegistry},
+};
+use tracing::{Level, event};
+
+/// One automat decision, answered on `reply`.
+struct DecisionRequest {
+    idx: usize,
+    config: AgentConfig,
+    game_state: GameState,
+    actions: Vec<Action>,
+    reply: mpsc::Sender<Result<AgentAction, String>>,
+}
+
+/// The agents playing the automat seats of a game.
+///
+/// Each seat's agent is built the first time it acts and then kept for the
+/// rest of the game, so process agents keep running and agents that learn
+/// keep what they've seen. Agents aren't `Send`, so they live on a thread
+/// started with the first decision and the game sends every decision
+/// there. Clones share the same agents.
+#[derive(Clone)]
+pub(crate) struct AutomatAgents {
+    registry: Arc<AgentRegistry>,
+    requests: Option<mpsc::Sender<DecisionRequest>>,
+}
+
+impl AutomatAgents {
+    pub(crate) fn new(registry: AgentRegistry) -> Self {
+        Self {
+            registry: Arc::new(registry),
+            requests: None,
+        }
+    }
+
+    /// The action of the automat in seat `idx`, building its agent from
+    /// `config` if it hasn't acted yet.
+    pub(crate) fn act(
+        &mut self,
+        idx: usize,
+        config: &AgentConfig,
+        game_state: &GameState,
+        actions: &[Action],
+    ) -> Result<AgentAction, String> {
+        let (reply, answer) = mpsc::channel();
+        let request = DecisionRequest {
+            idx,
+            config: config.clone(),
+            game_state: game_state.clone(),
+            actions: actions.to_vec(),
+            reply,
+        };
+        let registry = self.registry.clone();
+        self.requests
+            .get_or_insert_with(|| spawn_agents(registry))
+            .send(request)
+            .map_err(|_| "The automat agents stopped".to_string())?;
+        answer
+            .recv()
+            .map_err(|_| "The automat agent panicked".to_string())?
+    }
+}
+
+impl Default for AutomatAgents {
+    fn default() -> Self {
+        Self::new(AgentRegistry::default())
+    }
+}
+
+impl fmt::Debug for AutomatAgents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AutomatAgents")
+            .field("started", &self.requests.is_some())
+            .finish()
+    }
+}
+
+/// The agents aren't part of the game's state, every game is equal to its
+/// replay.
+impl PartialEq for AutomatAgents {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// Start the thread that owns the agents. It stops once every sender is
+/// dropped, dropping the agents with it.
+fn spawn_agents(registry: Arc<AgentRegistry>) -> mpsc::Sender<DecisionRequest> {
+    let (requests, received) = mpsc::channel::<DecisionRequest>();
+    thread::spawn(move || {
+        let mut agents: Vec<Option<Box<dyn Agent>>> = vec![];
+        for request in received {
+            if agents.len() <= request.idx {
+                agents.resize_with(request.idx + 1, || None);
+            }
+            let agent = match &mut agents[request.idx] {
+                Some(agent) => agent,
+                empty => match registry.build(&request.config) {
+                    Ok(agent_gen) => empty.insert(agent_gen.generate(&request.game_state)),
+                    Err(err) => {
+                        event!(Level::WARN, ?err, idx = request.idx, "invalid automat");
+                        let _ = request.reply.send(Err(err.to_string()));
+                        continue;
+                    }
+                },
+            };
+            let view = if agent.omniscient() {
+                PlayerView::omniscient(request.idx, &request.game_state, &request.actions)
+            } else {
+                PlayerView::new(request.idx, &request.game_state, &request.actions)
+            };
+            let _ = request.reply.send(Ok(agent.act(0u128, &view)));
+        }
+    });
+    requests
+}
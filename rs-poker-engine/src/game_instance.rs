@@ -1,7 +1,11 @@
 use rand::rngs::ThreadRng;
 use rs_poker::{
-    arena::{Agent, action::AgentAction, agent::RandomAgent},
+    arena::{
+        action::AgentAction, agent::AgentRegistry, hand_query::RecordedHand,
+        historian::HandHistoryBuilder,
+    },
     core::{Card, Deck},
+    open_hand_history::{HandHistory, write_pokerstars_hand},
 };
 use rs_poker_types::{
     game::{
@@ -9,11 +13,12 @@ use rs_poker_types::{
         GameStatus,
     },
     game_event::GameEvent,
-    player::{AutomatType, Player, PlayerName},
+    player::{Player, PlayerName},
     tournament::TournamentId,
 };
 
 use crate::{
+    automat_agents::AutomatAgents,
     game_simulation::{GameActionRequired, GameSimulation},
     game_summary::GameSummary,
 };
@@ -24,6 +29,7 @@ pub struct GameInstance {
     pub tournament_id: Option<TournamentId>,
     pub simulation: GameSimulation,
     pub players: Vec<Player>,
+    automats: AutomatAgents,
 }
 
 impl GameInstance {
@@ -38,13 +44,11 @@ impl GameInstance {
 
         // Determine player hands
         if config.hands.is_none() {
+            let num_hole_cards = config.game_type.num_hole_cards();
             let new_hands = config
                 .players
                 .iter()
-                .map(|_| {
-                    let cards = n_cards(&mut deck, 2, &mut rng);
-                    [cards[0], cards[1]]
-                })
+                .map(|_| n_cards(&mut deck, num_hole_cards, &mut rng))
                 .collect();
             config.hands = Some(new_hands);
         }
@@ -90,9 +94,17 @@ impl GameInstance {
             tournament_id,
             simulation,
             players,
+            automats: AutomatAgents::default(),
         }
     }
 
+    /// Build the automat players' agents from `registry` instead of the
+    /// default one.
+    pub fn with_registry(mut self, registry: AgentRegistry) -> Self {
+        self.automats = AutomatAgents::new(registry);
+        self
+    }
+
     pub fn game_id(&self) -> GameId {
         self.game_id.clone()
     }
@@ -112,26 +124,21 @@ impl GameInstance {
                         automat_type,
                         name: _,
                     } => {
-                        let decision = match automat_type {
-                            AutomatType::Random => {
-                                let mut agent = RandomAgent::default();
-                                let action = agent.act(0u128, &self.simulation.game_state);
-                                Decision {
-                                    action,
-                                    reason: String::from("RandomAgent decision"),
-                                }
-                            }
-                            AutomatType::AllIn => Decision {
-                                action: AgentAction::AllIn,
-                                reason: String::from("AllIn Automat decision"),
+                        let decision = match self.automats.act(
+                            idx,
+                            automat_type,
+                            &self.simulation.game_state,
+                            &self.simulation.actions,
+                        ) {
+                            Ok(action) => Decision {
+                                action,
+                                reason: format!("{} Automat decision", automat_type.kind),
                             },
-                            AutomatType::Calling => Decision {
-                                action: AgentAction::Call,
-                                reason: String::from("Calling Automat decision"),
-                            },
-                            AutomatType::Filding => Decision {
+                            // Settings are validated so this shouldn't happen, but don't hang the
+                            // game if it does.
+                            Err(err) => Decision {
                                 action: AgentAction::Fold,
-                                reason: String::from("Filding Automat decision"),
+                                reason: format!("Invalid automat: {}", err),
                             },
                         };
                         self.simulation.execute_player_action(decision);
@@ -226,11 +233,47 @@ impl GameInstance {
                 game_id: self.game_id.clone(),
                 player_names: self.players.iter().map(|p| p.name()).collect(),
                 final_stacks: self.simulation.game_state.stacks.clone(),
+                actions: self.simulation.actions.clone(),
             })
         } else {
             None
         }
     }
+
+    /// The game so far as an Open Hand History, with the players' names.
+    pub fn hand_history(&self) -> HandHistory {
+        let mut builder =
+            HandHistoryBuilder::new(self.game_id.to_string(), &self.simulation.game_state);
+        for action in &self.simulation.actions {
+            builder.record(action);
+        }
+        let mut hand = builder.build();
+        for player in hand.players.iter_mut() {
+            if let Some(name) = self.simulation.player_names.get(player.id as usize) {
+                player.name = name.to_string();
+            }
+        }
+        hand
+    }
+
+    /// The game so far as a PokerStars style text hand history.
+    pub fn pokerstars_hand_history(&self) -> String {
+        write_pokerstars_hand(&self.hand_history())
+    }
+
+    /// The game so far as a hand to search with a
+    /// [`HandQuery`](rs_poker::arena::hand_query::HandQuery). Build one from
+    /// a list of `GameEvent`s with `GameInstance::from(events)`.
+    pub fn recorded_hand(&self) -> RecordedHand {
+        RecordedHand::new(self.game_id.to_string(), self.simulation.actions.clone())
+            .with_player_names(
+                self.simulation
+                    .player_names
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            )
+    }
 }
 
 impl From<Vec<GameEvent>> for GameInstance {
@@ -313,18 +356,26 @@ fn n_cards(deck: &mut Deck, n: usize, rng: &mut ThreadRng) -> Vec<Card> {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    use rs_poker::arena::{
+        Agent, AgentGenerator, GameState, GameType,
+        agent::CallingAgent,
+        hand_query::{HandPredicate, HandQuery, PlayerSelector},
+    };
+
     use super::*;
 
-    fn random_game() -> GameInstance {
+    fn random_game(game_type: GameType) -> GameInstance {
         let num_of_players = 5;
         let initial_stack = 100.0;
         let small_blind = 5.0;
 
         let players: Vec<Player> = (1..=num_of_players)
-            .map(|i| Player::Automat {
-                name: PlayerName::new(&format!("Player{}", i)),
-                automat_type: AutomatType::Random,
-            })
+            .map(|i| Player::random(&format!("Player{}", i)))
             .collect();
 
         let settings = GameSettings {
@@ -332,11 +383,14 @@ mod tests {
             tournament_game_number: None,
             game_id: None,
             small_blind,
+            big_blind: None,
+            ante: 0.0,
             players,
             stacks: vec![initial_stack; num_of_players],
             hands: None,
             community_cards: None,
             dealer_index: 0,
+            game_type,
         };
         let mut game_instance = GameInstance::new(settings);
 
@@ -347,10 +401,162 @@ mod tests {
     #[test]
     fn test_game_instance_serialization() {
         for _ in 0..100 {
-            let game = random_game();
+            let game = random_game(GameType::NoLimitHoldem);
+            let events = game.events();
+            let reconstructed_game = GameInstance::from(events.clone());
+            assert_eq!(game, reconstructed_game);
+        }
+    }
+
+    #[test]
+    fn test_pot_limit_omaha_game_instance_serialization() {
+        for _ in 0..100 {
+            let game = random_game(GameType::PotLimitOmaha);
+            assert!(game.is_complete());
+            for idx in 0..game.players.len() {
+                assert_eq!(4, game.simulation.game_state.hole_cards(idx).len());
+            }
             let events = game.events();
             let reconstructed_game = GameInstance::from(events.clone());
             assert_eq!(game, reconstructed_game);
         }
     }
+
+    #[test]
+    fn test_automat_players_from_configs() {
+        let players = vec![
+            Player::automat("Calling", "calling".parse().unwrap()),
+            Player::automat("AllIn", "all_in".parse().unwrap()),
+            Player::automat(
+                "Random",
+                r#"{"type":"random","fold":[0.0],"call":[1.0]}"#.parse().unwrap(),
+            ),
+            // Configs written before the registry used the enum variant names.
+            serde_json::from_str(r#"{"Automat":{"name":"Old","automat_type":"Filding"}}"#).unwrap(),
+        ];
+        let settings = GameSettings {
+            tournament_id: None,
+            tournament_game_number: None,
+            game_id: None,
+            small_blind: 5.0,
+            big_blind: None,
+            ante: 0.0,
+            stacks: vec![100.0; players.len()],
+            players,
+            hands: None,
+            community_cards: None,
+            dealer_index: 0,
+            game_type: GameType::NoLimitHoldem,
+        };
+        let mut game_instance = GameInstance::new(settings);
+        game_instance.run();
+
+        assert!(game_instance.is_complete());
+        let reconstructed_game = GameInstance::from(game_instance.events());
+        assert_eq!(game_instance, reconstructed_game);
+    }
+
+    /// Builds calling agents, counting how many it's built.
+    #[derive(Clone)]
+    struct CountingGenerator(Arc<AtomicUsize>);
+
+    impl AgentGenerator for CountingGenerator {
+        fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Box::new(CallingAgent)
+        }
+    }
+
+    #[test]
+    fn test_automat_agents_built_once_per_game() {
+        let built = Arc::new(AtomicUsize::new(0));
+        let mut registry = AgentRegistry::empty();
+        let generator = CountingGenerator(built.clone());
+        registry.register("calling", move |_| Ok(Box::new(generator.clone())));
+
+        let players: Vec<Player> = ["Alice", "Bob", "Carol"]
+            .iter()
+            .map(|name| Player::automat(name, "calling".parse().unwrap()))
+            .collect();
+        let settings = GameSettings {
+            tournament_id: None,
+            tournament_game_number: None,
+            game_id: None,
+            small_blind: 5.0,
+            big_blind: None,
+            ante: 0.0,
+            stacks: vec![100.0; players.len()],
+            players,
+            hands: None,
+            community_cards: None,
+            dealer_index: 0,
+            game_type: GameType::NoLimitHoldem,
+        };
+        let mut game_instance = GameInstance::new(settings).with_registry(registry);
+        game_instance.run();
+
+        // Everyone calls down, so every seat acts on every street but each
+        // only gets one agent.
+        assert!(game_instance.is_complete());
+        assert_eq!(5, game_instance.simulation.game_state.board.len());
+        assert_eq!(3, built.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_pokerstars_hand_history() {
+        let game = random_game(GameType::NoLimitHoldem);
+        let text = game.pokerstars_hand_history();
+        assert!(text.starts_with(&format!("PokerStars Hand #{}:", game.game_id)));
+
+        let hand = rs_poker::open_hand_history::parse_pokerstars_hand(&text).unwrap();
+        let names: Vec<String> = hand.players.iter().map(|p| p.name.clone()).collect();
+        let expected: Vec<String> = game.players.iter().map(|p| p.name().to_string()).collect();
+        assert_eq!(expected, names);
+
+        let total_pot: f32 = hand.pots.iter().map(|pot| pot.amount).sum();
+        assert!((total_pot - game.simulation.game_state.total_pot).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_recorded_hand() {
+        let games: Vec<GameInstance> = (0..5)
+            .map(|_| GameInstance::from(random_game(GameType::NoLimitHoldem).events()))
+            .collect();
+        let hands: Vec<_> = games.iter().map(GameInstance::recorded_hand).collect();
+        let result = HandQuery::new()
+            .filter(HandPredicate::Player(
+                PlayerSelector::Name("Player1".to_string()),
+                vec![],
+            ))
+            .run(&hands);
+        assert_eq!(5, result.stats.hands);
+
+        for (game, summary) in games.iter().zip(&result.hands) {
+            assert_eq!(game.game_id.to_string(), summary.id);
+            assert!((summary.total_pot - game.simulation.game_state.total_pot).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid automat player")]
+    fn test_unknown_automat_type() {
+        let settings = GameSettings {
+            tournament_id: None,
+            tournament_game_number: None,
+            game_id: None,
+            small_blind: 5.0,
+            big_blind: None,
+            ante: 0.0,
+            stacks: vec![100.0; 2],
+            players: vec![
+                Player::random("Alice"),
+                Player::automat("Bob", "no_such_bot".parse().unwrap()),
+            ],
+            hands: None,
+            community_cards: None,
+            dealer_index: 0,
+            game_type: GameType::NoLimitHoldem,
+        };
+        GameInstance::new(settings);
+    }
 }
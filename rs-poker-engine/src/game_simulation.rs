@@ -11,7 +11,7 @@ use tracing::{Level, debug_span, event, instrument, trace_span};
 
 use rs_poker::arena::action::{FailedActionPayload, PlayedActionPayload};
 use rs_poker::arena::game_state::Round;
-use rs_poker::core::{Card, Rank, Rankable};
+use rs_poker::core::{Card, Rank};
 
 use rs_poker::arena::action::{
     Action, AgentAction, AwardPayload, DealStartingHandPayload, ForcedBetPayload, ForcedBetType,
@@ -39,7 +39,7 @@ pub struct GameSimulation {
     pub game_state: GameState,
     pub actions: Vec<Action>,
     pub events: Vec<GameEvent>,
-    pub hands: Vec<[Card; 2]>,
+    pub hands: Vec<Vec<Card>>,
     pub community_cards: [Card; 5],
     pub player_names: Vec<PlayerName>,
 }
@@ -50,9 +50,10 @@ impl GameSimulation {
             config.stacks.clone(),
             config.big_blind(),
             config.small_blind,
-            0.0,
+            config.ante,
             config.dealer_index,
-        );
+        )
+        .with_game_type(config.game_type);
 
         let game_id = config
             .game_id
@@ -204,7 +205,7 @@ impl GameSimulation {
         // Check if we need to deal cards to any player
         while self.game_state.current_round_num_active_players() > 0 {
             let idx = self.game_state.to_act_idx();
-            let cards = self.hands[idx].to_vec();
+            let cards = self.hands[idx].clone();
             self.deal_player_cards(cards);
             // Move to next player or advance round if all players have been dealt
             self.game_state.round_data.needs_action.disable(idx);
@@ -346,7 +347,7 @@ impl GameSimulation {
         // the values are vectors of player index, for players that had that hand
         let ranks = active
             .ones()
-            .map(|idx| (idx, self.game_state.hands[idx].rank()))
+            .map(|idx| (idx, self.game_state.player_rank(idx)))
             .fold(
                 BTreeMap::new(),
                 |mut map: BTreeMap<Rank, Vec<usize>>, (idx, rank)| {
@@ -546,9 +547,8 @@ impl GameSimulation {
         let current_bet = self.game_state.current_round_bet();
         let min_raise = self.game_state.current_round_min_raise();
         let min_bet = current_bet + min_raise;
-        let player_stack = self.game_state.current_player_stack();
-        let current_player_bet = self.game_state.current_round_current_player_bet();
-        let max_bet = current_player_bet + player_stack;
+        // All in, or a pot sized raise in pot limit games.
+        let max_bet = self.game_state.current_round_max_bet();
 
         if min_bet <= max_bet {
             actions.push(PossibleAction::Bet {
@@ -823,8 +823,8 @@ impl GameSimulation {
                 }
             }
             AgentAction::AllIn => {
-                let all_in_amount = self.game_state.current_round_current_player_bet()
-                    + self.game_state.current_player_stack();
+                // In pot limit games this is capped at a pot sized raise.
+                let all_in_amount = self.game_state.current_round_max_bet();
                 let bet_result = self.game_state.do_bet(all_in_amount, false);
 
                 match bet_result {
@@ -878,6 +878,10 @@ impl GameSimulation {
                             AgentAction::Bet(_) => AgentAction::Bet(player_bet),
                             AgentAction::Fold => AgentAction::Fold,
                             AgentAction::Call => AgentAction::Call,
+                            // A pot limit cap can leave chips behind, so that's a bet.
+                            AgentAction::AllIn if self.game_state.stacks[idx] > 0.0 => {
+                                AgentAction::Bet(player_bet)
+                            }
                             AgentAction::AllIn => AgentAction::AllIn,
                         };
                         // If the game_state.do_bet function returned Ok then
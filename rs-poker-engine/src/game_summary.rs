@@ -40,8 +40,12 @@ impl GameSummary {
                     let players_count = config.players.len();
 
                     summary.push_str(&format!("Game Started - ID: {:?}\n", config.game_id));
+                    summary.push_str(&format!("Game type: {}\n", config.game_type));
                     summary.push_str(&format!("Small blind: {}\n", config.small_blind));
                     summary.push_str(&format!("Big blind: {}\n", config.big_blind()));
+                    if config.ante > 0.0 {
+                        summary.push_str(&format!("Ante: {}\n", config.ante));
+                    }
                     summary.push_str(&format!("Players: {}\n", players_count));
 
                     for i in 0..players_count {
@@ -68,9 +72,12 @@ impl GameSummary {
                                 .find(|(_, p)| p.name() == *name)
                             {
                                 let hand = &config.hands.clone().unwrap();
-                                let hand = &hand[i];
-                                summary
-                                    .push_str(&format!("\nYour Hand: {} {}\n", hand[0], hand[1]));
+                                let hand = hand[i]
+                                    .iter()
+                                    .map(|c| c.to_string())
+                                    .collect::<Vec<_>>()
+                                    .join(" ");
+                                summary.push_str(&format!("\nYour Hand: {}\n", hand));
                             } else {
                                 // Player wasn't in this game (e.g., eliminated/insufficient chips)
                                 summary.push_str(&format!(
@@ -83,10 +90,12 @@ impl GameSummary {
                             let hands = config.hands.clone().unwrap();
                             for (i, hand) in hands.iter().enumerate() {
                                 let player_name = config.players[i].name();
-                                summary.push_str(&format!(
-                                    "- {}: {:?} {:?}\n",
-                                    player_name, hand[0], hand[1]
-                                ));
+                                let hand = hand
+                                    .iter()
+                                    .map(|c| format!("{:?}", c))
+                                    .collect::<Vec<_>>()
+                                    .join(" ");
+                                summary.push_str(&format!("- {}: {}\n", player_name, hand));
                             }
                             let community_cards = config.community_cards.unwrap();
                             summary.push_str(&format!(
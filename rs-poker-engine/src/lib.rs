@@ -1,6 +1,8 @@
+mod automat_agents;
 pub mod game_instance;
 pub mod game_simulation;
 pub mod game_summary;
 pub mod poker_engine;
+pub mod rating;
 pub mod tournament_instance;
 pub mod tournament_summary;
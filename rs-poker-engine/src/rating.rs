@@ -0,0 +1,180 @@
+use rs_poker::arena::rating::{MatchResult, RatingSystem, Ratings};
+use rs_poker_types::{player::Player, tournament_event::TournamentEvent};
+
+use crate::tournament_instance::TournamentInstance;
+
+/// What a rating belongs to.
+#[derive(serde::Deserialize, serde::Serialize, PartialEq, Eq, Debug, Clone, Copy, Default)]
+pub enum RatingKey {
+    /// Every player name gets its own rating.
+    #[default]
+    Player,
+    /// AI players are rated by model, so every tournament a model plays
+    /// counts towards one number. Other players keep their names.
+    Model,
+}
+
+impl RatingKey {
+    pub fn key(&self, player: &Player) -> String {
+        match (self, player) {
+            (RatingKey::Model, Player::AI { model, .. }) => model.clone(),
+            _ => player.name().to_string(),
+        }
+    }
+}
+
+/// Where every player finished in a tournament.
+///
+/// Players who busted in a later game finish ahead of those who busted
+/// earlier, and players who busted in the same game share a place. Anyone
+/// still in when the tournament stopped is ranked by their stack.
+///
+/// If two players in the tournament have the same key only the place of the
+/// last one counts.
+pub fn tournament_result(tournament: &TournamentInstance, key: RatingKey) -> MatchResult {
+    let players = &tournament.settings.players;
+    let busted_in = tournament.busted_in();
+    let games = tournament
+        .events
+        .iter()
+        .filter(|event| matches!(event, TournamentEvent::GameEnded(_)))
+        .count();
+
+    // Bigger is better: the game busted in, or past the last game with the
+    // final stack as the tie break.
+    let standing: Vec<(usize, f32)> = busted_in
+        .iter()
+        .zip(&tournament.player_stacks)
+        .map(|(busted_in, stack)| match busted_in {
+            Some(game) => (*game, 0.0),
+            None => (games, *stack),
+        })
+        .collect();
+    MatchResult::from_places(players.iter().zip(&standing).map(|(player, own)| {
+        let better = standing
+            .iter()
+            .filter(|other| other.0 > own.0 || (other.0 == own.0 && other.1 > own.1))
+            .count();
+        (key.key(player), better + 1)
+    }))
+}
+
+/// Rate everyone over a set of tournaments, for example every tournament
+/// in a series. Tournaments that haven't finished are skipped.
+pub fn rate_tournaments<'a>(
+    tournaments: impl IntoIterator<Item = &'a TournamentInstance>,
+    key: RatingKey,
+    system: RatingSystem,
+) -> Ratings {
+    let mut ratings = Ratings::new(system);
+    for tournament in tournaments.into_iter().filter(|t| t.is_completed()) {
+        ratings.record(&tournament_result(tournament, key));
+    }
+    ratings
+}
+
+#[cfg(test)]
+mod tests {
+    use rs_poker_types::{
+        game::GameFinalResults,
+        player::PlayerName,
+        tournament::{TournamentEndCondition, TournamentId, TournamentSettings},
+    };
+
+    use super::*;
+
+    fn finish(tournament: &mut TournamentInstance, stacks: Vec<f32>) {
+        let game = tournament.start_next_game().unwrap();
+        tournament
+            .finish_game(&GameFinalResults {
+                game_id: game.game_id.unwrap(),
+                player_names: tournament
+                    .settings
+                    .players
+                    .iter()
+                    .map(|p| p.name())
+                    .collect(),
+                final_stacks: stacks,
+                actions: vec![],
+            })
+            .unwrap();
+    }
+
+    fn tournament() -> TournamentInstance {
+        TournamentInstance::new(&TournamentSettings {
+            tournament_id: TournamentId::new("rated"),
+            players: vec![
+                Player::ai("Alice", "model-a", "aggressive"),
+                Player::ai("Bob", "model-b", "tight"),
+                Player::random("Charlie"),
+                Player::random("Dave"),
+            ],
+            starting_player_stack: 100.0,
+            starting_small_blind: 5.0,
+            double_blinds_every_n_games: None,
+            blind_structure: None,
+            payout_structure: None,
+            buy_in: None,
+            format: Default::default(),
+            end_condition: TournamentEndCondition::SingleWinner,
+            see_historical_thoughts: false,
+            public_chat: false,
+            game_type: Default::default(),
+        })
+    }
+
+    #[test]
+    fn test_places_from_busts() {
+        let mut tournament = tournament();
+        finish(&mut tournament, vec![150.0, 150.0, 0.0, 100.0]);
+        finish(&mut tournament, vec![200.0, 200.0, 0.0, 0.0]);
+        finish(&mut tournament, vec![400.0, 0.0, 0.0, 0.0]);
+        assert!(tournament.is_completed());
+
+        let result = tournament_result(&tournament, RatingKey::Player);
+        assert_eq!(
+            vec![
+                ("Alice".to_string(), 1),
+                ("Bob".to_string(), 2),
+                ("Charlie".to_string(), 4),
+                ("Dave".to_string(), 3),
+            ],
+            result.places
+        );
+
+        let result = tournament_result(&tournament, RatingKey::Model);
+        assert_eq!(("model-a".to_string(), 1), result.places[0]);
+        assert_eq!(
+            PlayerName::new("Alice"),
+            tournament.winner().unwrap().name()
+        );
+    }
+
+    #[test]
+    fn test_same_game_busts_share_a_place() {
+        let mut tournament = tournament();
+        finish(&mut tournament, vec![200.0, 200.0, 0.0, 0.0]);
+        finish(&mut tournament, vec![400.0, 0.0, 0.0, 0.0]);
+
+        let result = tournament_result(&tournament, RatingKey::Player);
+        assert_eq!(3, result.places[2].1);
+        assert_eq!(3, result.places[3].1);
+    }
+
+    #[test]
+    fn test_rate_tournaments_skips_unfinished() {
+        let mut finished = tournament();
+        finish(&mut finished, vec![400.0, 0.0, 0.0, 0.0]);
+        let mut unfinished = tournament();
+        finish(&mut unfinished, vec![100.0, 100.0, 200.0, 0.0]);
+
+        let ratings = rate_tournaments(
+            [&finished, &unfinished],
+            RatingKey::Model,
+            RatingSystem::true_skill(),
+        );
+        let leaderboard = ratings.leaderboard();
+        assert_eq!("model-a", leaderboard[0].name);
+        assert_eq!(1, leaderboard[0].rating.games);
+    }
+}
@@ -1,7 +1,10 @@
+use rs_poker::arena::competition::{Bounties, icm, knocked_out_by, split_prizes};
 use rs_poker_types::{
     game::{GameFinalResults, GameId, GameSettings},
-    player::Player,
-    tournament::{TournamentId, TournamentInfo, TournamentSettings, TournamentStatus},
+    player::{Player, PlayerName},
+    tournament::{
+        TournamentEndCondition, TournamentId, TournamentInfo, TournamentSettings, TournamentStatus,
+    },
     tournament_event::{
         GameEndedEvent, GameStartedEvent, TournamentCreatedEvent, TournamentEvent,
         TournamentFinishedEvent,
@@ -19,6 +22,11 @@ pub struct TournamentInstance {
     pub current_game_id: Option<GameId>,
     pub player_stacks: Vec<f32>,
     pub game_ids: Vec<GameId>,
+    /// How many times each player has rebought.
+    pub rebuys: Vec<usize>,
+    /// How many add-ons each player has taken.
+    pub add_ons: Vec<usize>,
+    pub bounties: Option<Bounties>,
 }
 
 impl TournamentInstance {
@@ -29,10 +37,16 @@ impl TournamentInstance {
             settings: settings.clone(),
             status: TournamentStatus::WaitingForNextGame,
             next_game_number: 0,
-            next_small_blind: settings.starting_small_blind,
+            next_small_blind: settings.small_blind(0),
             current_game_id: None,
             player_stacks: vec![settings.starting_player_stack; settings.players.len()],
             game_ids: vec![],
+            rebuys: vec![0; settings.players.len()],
+            add_ons: vec![0; settings.players.len()],
+            bounties: settings
+                .format
+                .bounty
+                .map(|format| Bounties::new(format, settings.players.len())),
         }
     }
 
@@ -86,19 +100,7 @@ impl TournamentInstance {
                     .filter(|&&stack| stack > small_blind)
                     .count();
                 if players_with_sufficient_chips <= 1 {
-                    self.status = TournamentStatus::Completed;
-
-                    // Record tournament finished event if there's a winner
-                    if let Some(winner) = self.winner() {
-                        let tournament_finished_event =
-                            TournamentEvent::TournamentFinished(TournamentFinishedEvent {
-                                timestamp: std::time::SystemTime::now(),
-                                tournament_id: self.tournament_id.clone(),
-                                winner: winner.name(),
-                            });
-                        self.events.push(tournament_finished_event);
-                    }
-
+                    self.complete();
                     None
                 } else {
                     // Start next game
@@ -123,6 +125,35 @@ impl TournamentInstance {
         }
     }
 
+    /// Mark the tournament as completed, pay the winner the bounty on their
+    /// own head and record the tournament finished event if there's a
+    /// winner.
+    fn complete(&mut self) {
+        self.status = TournamentStatus::Completed;
+
+        if let Some(winner) = self.winner().map(|winner| winner.name()) {
+            self.finish_bounties(&winner);
+            let tournament_finished_event =
+                TournamentEvent::TournamentFinished(TournamentFinishedEvent {
+                    timestamp: std::time::SystemTime::now(),
+                    tournament_id: self.tournament_id.clone(),
+                    winner,
+                });
+            self.events.push(tournament_finished_event);
+        }
+    }
+
+    fn finish_bounties(&mut self, winner: &PlayerName) {
+        let winner_idx = self.player_idx(winner);
+        if let (Some(bounties), Some(idx)) = (self.bounties.as_mut(), winner_idx) {
+            bounties.finish(idx);
+        }
+    }
+
+    fn player_idx(&self, name: &PlayerName) -> Option<usize> {
+        self.settings.players.iter().position(|p| p.name() == *name)
+    }
+
     pub fn start_next_game(&mut self) -> Result<GameSettings, TournamentError> {
         // Can only start a new game if the tournament is waiting for the next game.
         if !matches!(self.status, TournamentStatus::WaitingForNextGame) {
@@ -132,18 +163,24 @@ impl TournamentInstance {
         let game_number = self.next_game_number;
         self.next_game_number += 1;
 
-        let mut small_blind = self.next_small_blind;
-        // if double_blinds_every_n_games is set to 3 it should have double on game 3,
-        // 6, 9, ...
-        if let Some(n) = self.settings.double_blinds_every_n_games {
-            if game_number > 0 && game_number % n == 0 {
-                small_blind *= 2.0;
-                self.next_small_blind = small_blind;
-            }
-        }
+        let blinds = self.settings.blind_level(game_number);
+        let small_blind = blinds.small_blind;
+        self.next_small_blind = small_blind;
 
         let game_id = GameId::for_tournament(game_number);
 
+        // Everyone still in takes the add-on.
+        let mut add_ons = vec![];
+        if let Some(add_on) = self.settings.format.add_on_before(game_number) {
+            for (idx, stack) in self.player_stacks.iter_mut().enumerate() {
+                if *stack > small_blind {
+                    *stack += add_on.chips;
+                    self.add_ons[idx] += 1;
+                    add_ons.push(self.settings.players[idx].name());
+                }
+            }
+        }
+
         // Only include players with positive stacks (more than small blind).
         let mut positive_stacks_ids: Vec<usize> = self
             .player_stacks
@@ -177,11 +214,14 @@ impl TournamentInstance {
             tournament_game_number: Some(game_number),
             game_id: Some(game_id.clone()),
             small_blind,
+            big_blind: Some(blinds.big_blind),
+            ante: blinds.ante,
             players: positive_players,
             stacks: positive_stacks,
             hands: None,
             community_cards: None,
             dealer_index: 0, // TODO: use this.s
+            game_type: self.settings.game_type,
         };
 
         // Record game started event
@@ -190,6 +230,7 @@ impl TournamentInstance {
             game_id: game_id.clone(),
             player_names: self.settings.players.iter().map(|p| p.name()).collect(),
             player_stacks: self.player_stacks.clone(),
+            add_ons,
         });
         self.events.push(game_started_event);
 
@@ -229,6 +270,7 @@ impl TournamentInstance {
 
         // Update player stacks based on final results
         // Only update stacks for players who participated in the game
+        let starting_stacks = self.player_stacks.clone();
         for (i, player_name) in game_final_results.player_names.iter().enumerate() {
             if let Some(tournament_index) = tournament_player_names
                 .iter()
@@ -238,12 +280,58 @@ impl TournamentInstance {
             }
         }
 
+        // Pay out bounties and rebuy for the players that busted.
+        let game_number = self.next_game_number - 1;
+        let busted: Vec<usize> = (0..self.player_stacks.len())
+            .filter(|idx| self.player_stacks[*idx] <= 0.0 && starting_stacks[*idx] > 0.0)
+            .collect();
+        let mut knockouts = vec![];
+        if let Some(bounties) = self.bounties.as_mut() {
+            // The game seats players in its own order.
+            let game_idx = |idx: usize| {
+                game_final_results
+                    .player_names
+                    .iter()
+                    .position(|name| *name == tournament_player_names[idx])
+            };
+            for idx in &busted {
+                let Some(busted_game_idx) = game_idx(*idx) else {
+                    continue;
+                };
+                let knocked_out_by: Vec<usize> =
+                    knocked_out_by(&game_final_results.actions, busted_game_idx)
+                        .into_iter()
+                        .filter_map(|winner| {
+                            let name = game_final_results.player_names.get(winner)?;
+                            tournament_player_names.iter().position(|n| n == name)
+                        })
+                        .collect();
+                knockouts.extend(bounties.knock_out(game_number, &knocked_out_by, *idx));
+            }
+        }
+        let mut rebuys = vec![];
+        if let Some(rebuy) = self.settings.format.rebuys {
+            for idx in busted {
+                if self
+                    .settings
+                    .format
+                    .can_rebuy(game_number, self.rebuys[idx])
+                {
+                    self.player_stacks[idx] = rebuy.chips;
+                    self.rebuy(idx);
+                    rebuys.push(self.settings.players[idx].name());
+                }
+            }
+        }
+
         // Record game ended event with full tournament state
         let game_ended_event = TournamentEvent::GameEnded(GameEndedEvent {
             timestamp: std::time::SystemTime::now(),
             game_id: game_final_results.game_id.clone(),
             player_names: self.settings.players.iter().map(|p| p.name()).collect(),
             player_stacks: self.player_stacks.clone(),
+            knockouts,
+            rebuys,
         });
         self.events.push(game_ended_event);
 
@@ -255,18 +343,7 @@ impl TournamentInstance {
             .filter(|&&stack| stack > self.next_small_blind)
             .count();
         if players_with_sufficient_chips <= 1 {
-            self.status = TournamentStatus::Completed;
-
-            // Record tournament finished event
-            if let Some(winner) = self.winner() {
-                let tournament_finished_event =
-                    TournamentEvent::TournamentFinished(TournamentFinishedEvent {
-                        timestamp: std::time::SystemTime::now(),
-                        tournament_id: self.tournament_id.clone(),
-                        winner: winner.name(),
-                    });
-                self.events.push(tournament_finished_event);
-            }
+            self.complete();
         } else {
             self.status = TournamentStatus::WaitingForNextGame;
         }
@@ -276,6 +353,23 @@ impl TournamentInstance {
         Ok(())
     }
 
+    fn rebuy(&mut self, idx: usize) {
+        self.rebuys[idx] += 1;
+        if let Some(bounties) = self.bounties.as_mut() {
+            bounties.rebuy(idx);
+        }
+    }
+
+    /// The prize pool including the cost of every rebuy and add-on so far.
+    pub fn prize_pool(&self) -> f32 {
+        let format = &self.settings.format;
+        let rebuys: usize = self.rebuys.iter().sum();
+        let add_ons: usize = self.add_ons.iter().sum();
+        self.settings.prize_pool()
+            + format.rebuys.map_or(0.0, |r| r.cost * rebuys as f32)
+            + format.add_on.map_or(0.0, |a| a.cost * add_ons as f32)
+    }
+
     pub fn info(&self) -> TournamentInfo {
         TournamentInfo {
             settings: self.settings.clone(),
@@ -283,7 +377,88 @@ impl TournamentInstance {
             games_played: self.next_game_number,
             current_game_id: self.current_game_id.clone(),
             winner: self.winner().map(|p| p.name()),
+            prizes: self.prizes(),
+            bounties: self.bounties.as_ref().map(|b| b.won().to_vec()),
+        }
+    }
+
+    /// The game, counting from 0, that each player lost their last chip in,
+    /// or `None` if they still have chips.
+    pub fn busted_in(&self) -> Vec<Option<usize>> {
+        let players = &self.settings.players;
+        let mut busted_in = vec![None; players.len()];
+        let game_ends = self.events.iter().filter_map(|event| match event {
+            TournamentEvent::GameEnded(game_ended) => Some(game_ended),
+            _ => None,
+        });
+        for (game, game_ended) in game_ends.enumerate() {
+            for (idx, player) in players.iter().enumerate() {
+                let stack = game_ended
+                    .player_names
+                    .iter()
+                    .position(|name| *name == player.name())
+                    .map(|pos| game_ended.player_stacks[pos]);
+                if busted_in[idx].is_none() && stack.is_some_and(|stack| stack <= 0.0) {
+                    busted_in[idx] = Some(game);
+                }
+            }
         }
+        busted_in
+    }
+
+    /// The prize money for each player, in the same order as the settings,
+    /// or `None` if the tournament has no payout structure.
+    ///
+    /// Players who busted in a later game place ahead of those who busted
+    /// earlier, and players who busted in the same game split the prizes
+    /// for their places. Once a tournament has a single winner everyone
+    /// left is placed by stack. Otherwise, for example when the tournament
+    /// is still running or stopped after a number of games, the players
+    /// still in get the ICM equity of their stacks in the prizes left.
+    pub fn prizes(&self) -> Option<Vec<f32>> {
+        let payout_structure = self.settings.payout_structure.as_ref()?;
+        let num_players = self.settings.players.len();
+        let prizes = payout_structure.prizes(num_players, self.prize_pool());
+        let busted_in = self.busted_in();
+        let remaining: Vec<usize> = (0..num_players)
+            .filter(|idx| busted_in[*idx].is_none())
+            .collect();
+
+        let mut places = vec![0; num_players];
+        for (idx, busted) in busted_in.iter().enumerate() {
+            if let Some(game) = busted {
+                places[idx] = 1 + busted_in
+                    .iter()
+                    .filter(|other| other.is_none_or(|other| other > *game))
+                    .count();
+            }
+        }
+
+        let single_winner = self.is_completed()
+            && matches!(
+                self.settings.end_condition,
+                TournamentEndCondition::SingleWinner
+            );
+        if single_winner {
+            for idx in &remaining {
+                places[*idx] = 1 + remaining
+                    .iter()
+                    .filter(|other| self.player_stacks[**other] > self.player_stacks[*idx])
+                    .count();
+            }
+            return Some(split_prizes(&places, &prizes));
+        }
+
+        let mut payouts = split_prizes(&places, &prizes);
+        let stacks: Vec<f32> = remaining
+            .iter()
+            .map(|idx| self.player_stacks[*idx])
+            .collect();
+        let equity = icm(&stacks, &prizes[..remaining.len()]);
+        for (idx, equity) in remaining.iter().zip(equity) {
+            payouts[*idx] = equity;
+        }
+        Some(payouts)
     }
 }
 
@@ -305,10 +480,16 @@ impl From<Vec<TournamentEvent>> for TournamentInstance {
             settings: settings.clone(),
             status: TournamentStatus::WaitingForNextGame,
             next_game_number: 0,
-            next_small_blind: settings.starting_small_blind,
+            next_small_blind: settings.small_blind(0),
             current_game_id: None,
             player_stacks: vec![settings.starting_player_stack; settings.players.len()],
             game_ids: vec![],
+            rebuys: vec![0; settings.players.len()],
+            add_ons: vec![0; settings.players.len()],
+            bounties: settings
+                .format
+                .bounty
+                .map(|format| Bounties::new(format, settings.players.len())),
         };
 
         // Process each event to rebuild state
@@ -323,25 +504,51 @@ impl From<Vec<TournamentEvent>> for TournamentInstance {
                     instance.game_ids.push(game_started.game_id.clone());
                     instance.next_game_number += 1;
 
-                    // Update blinds if needed (recalculate based on game number)
+                    // Update blinds (recalculate based on game number)
                     let game_number = instance.next_game_number - 1; // 0-indexed
-                    if let Some(n) = instance.settings.double_blinds_every_n_games {
-                        let mut small_blind = instance.settings.starting_small_blind;
-                        let blind_doublings = game_number / n;
-                        for _ in 0..blind_doublings {
-                            small_blind *= 2.0;
+                    instance.next_small_blind = instance.settings.small_blind(game_number);
+
+                    for name in &game_started.add_ons {
+                        if let Some(idx) = instance.player_idx(name) {
+                            instance.add_ons[idx] += 1;
                         }
-                        instance.next_small_blind = small_blind;
                     }
                 }
                 TournamentEvent::GameEnded(game_ended) => {
                     instance.status = TournamentStatus::WaitingForNextGame;
                     instance.current_game_id = None;
                     instance.player_stacks = game_ended.player_stacks.clone();
+
+                    // Knock out each defeated player again with everyone
+                    // that shared their bounty.
+                    if let Some(bounties) = instance.bounties.as_mut() {
+                        let mut defeated: Vec<usize> = game_ended
+                            .knockouts
+                            .iter()
+                            .map(|knockout| knockout.defeated_idx)
+                            .collect();
+                        defeated.dedup();
+                        for defeated_idx in defeated {
+                            let players: Vec<usize> = game_ended
+                                .knockouts
+                                .iter()
+                                .filter(|knockout| knockout.defeated_idx == defeated_idx)
+                                .map(|knockout| knockout.player_idx)
+                                .collect();
+                            let game_number = instance.next_game_number.saturating_sub(1);
+                            bounties.knock_out(game_number, &players, defeated_idx);
+                        }
+                    }
+                    for name in &game_ended.rebuys {
+                        if let Some(idx) = instance.player_idx(name) {
+                            instance.rebuy(idx);
+                        }
+                    }
                 }
-                TournamentEvent::TournamentFinished(_) => {
+                TournamentEvent::TournamentFinished(tournament_finished) => {
                     instance.status = TournamentStatus::Completed;
                     instance.current_game_id = None;
+                    instance.finish_bounties(&tournament_finished.winner);
                 }
             }
             instance.events.push(event);
@@ -367,6 +574,16 @@ pub enum TournamentError {
 
 #[cfg(test)]
 mod tests {
+    use rs_poker::{
+        arena::{
+            action::{Action, AwardPayload},
+            competition::{
+                AddOn, BlindLevel, BlindStructure, BountyFormat, PayoutStructure, Rebuys,
+                TournamentFormat,
+            },
+        },
+        core::Rank,
+    };
     use rs_poker_types::{
         player::{Player, PlayerName},
         tournament::TournamentEndCondition,
@@ -374,6 +591,223 @@ mod tests {
 
     use super::*;
 
+    fn settings(blind_structure: Option<BlindStructure>) -> TournamentSettings {
+        TournamentSettings {
+            tournament_id: TournamentId::new("blinds"),
+            players: vec![
+                Player::random("Alice"),
+                Player::random("Bob"),
+                Player::random("Charlie"),
+            ],
+            starting_player_stack: 100.0,
+            starting_small_blind: 5.0,
+            double_blinds_every_n_games: Some(2),
+            blind_structure,
+            payout_structure: None,
+            buy_in: None,
+            format: Default::default(),
+            end_condition: TournamentEndCondition::SingleWinner,
+            see_historical_thoughts: false,
+            public_chat: false,
+            game_type: Default::default(),
+        }
+    }
+
+    /// Play a game that ends with the given stacks and return its small
+    /// blind.
+    fn play_game(tournament: &mut TournamentInstance, final_stacks: Vec<f32>) -> f32 {
+        play_game_with_actions(tournament, final_stacks, vec![])
+    }
+
+    fn play_game_with_actions(
+        tournament: &mut TournamentInstance,
+        final_stacks: Vec<f32>,
+        actions: Vec<Action>,
+    ) -> f32 {
+        let game = tournament.start_next_game().unwrap();
+        tournament
+            .finish_game(&GameFinalResults {
+                game_id: game.game_id.unwrap(),
+                player_names: vec![
+                    PlayerName::new("Alice"),
+                    PlayerName::new("Bob"),
+                    PlayerName::new("Charlie"),
+                ],
+                final_stacks,
+                actions,
+            })
+            .unwrap();
+        game.small_blind
+    }
+
+    /// The pot of a game going to the player in seat `idx`.
+    fn won_by(idx: usize) -> Vec<Action> {
+        vec![Action::Award(AwardPayload {
+            total_pot: 0.0,
+            award_amount: 1.0,
+            rank: Some(Rank::HighCard(0)),
+            hand: None,
+            idx,
+        })]
+    }
+
+    /// Play games where nobody wins or loses and return the small blinds.
+    fn play_even_games(tournament: &mut TournamentInstance, games: usize) -> Vec<f32> {
+        (0..games)
+            .map(|_| play_game(tournament, vec![100.0; 3]))
+            .collect()
+    }
+
+    #[test]
+    fn test_double_blinds() {
+        let mut tournament = TournamentInstance::new(&settings(None));
+        assert_eq!(
+            vec![5.0, 5.0, 10.0, 10.0, 20.0],
+            play_even_games(&mut tournament, 5)
+        );
+
+        let replayed = TournamentInstance::from(tournament.events());
+        assert_eq!(20.0, replayed.next_small_blind);
+    }
+
+    #[test]
+    fn test_prizes() {
+        let mut settings = settings(None);
+        settings.payout_structure = Some(PayoutStructure::percentages(vec![70.0, 30.0]));
+        settings.buy_in = Some(10.0);
+        let mut tournament = TournamentInstance::new(&settings);
+
+        // Charlie is out in third, Alice and Bob split the rest by ICM.
+        play_game(&mut tournament, vec![200.0, 100.0, 0.0]);
+        let prizes = tournament.prizes().unwrap();
+        assert!((prizes[0] - 17.0).abs() < 1e-4);
+        assert!((prizes[1] - 13.0).abs() < 1e-4);
+        assert_eq!(0.0, prizes[2]);
+
+        play_game(&mut tournament, vec![300.0, 0.0, 0.0]);
+        assert!(tournament.is_completed());
+        assert_eq!(Some(vec![21.0, 9.0, 0.0]), tournament.info().prizes);
+    }
+
+    #[test]
+    fn test_prizes_with_a_full_table_left() {
+        let mut settings = settings(None);
+        settings.players = (0..12)
+            .map(|idx| Player::random(&format!("Player {idx}")))
+            .collect();
+        settings.payout_structure = Some(PayoutStructure::standard(12));
+        settings.buy_in = Some(10.0);
+        let mut tournament = TournamentInstance::new(&settings);
+
+        // Everyone is still in, only the five paid places are worked out.
+        let game = tournament.start_next_game().unwrap();
+        let mut final_stacks = vec![100.0; 12];
+        final_stacks[0] = 200.0;
+        final_stacks[1] = 0.0;
+        final_stacks[2] = 100.0 + 1.0;
+        final_stacks[3] = 100.0 - 1.0;
+        tournament
+            .finish_game(&GameFinalResults {
+                game_id: game.game_id.unwrap(),
+                player_names: settings.players.iter().map(|p| p.name()).collect(),
+                final_stacks,
+                actions: vec![],
+            })
+            .unwrap();
+
+        let prizes = tournament.prizes().unwrap();
+        assert!((prizes.iter().sum::<f32>() - 120.0).abs() < 1e-3);
+        assert_eq!(0.0, prizes[1]);
+        assert!(prizes[0] > prizes[2]);
+        assert!(prizes[2] > prizes[4]);
+        assert!(prizes[4] > prizes[3]);
+    }
+
+    #[test]
+    fn test_rebuys_add_on_and_progressive_knockout() {
+        let mut settings = settings(None);
+        settings.payout_structure = Some(PayoutStructure::WinnerTakeAll);
+        settings.buy_in = Some(10.0);
+        settings.format = TournamentFormat {
+            rebuys: Some(Rebuys {
+                chips: 100.0,
+                cost: 10.0,
+                until_hand: 2,
+                max_per_player: Some(1),
+            }),
+            add_on: Some(AddOn {
+                chips: 50.0,
+                cost: 5.0,
+                at_hand: 1,
+            }),
+            bounty: Some(BountyFormat::Progressive { bounty: 10.0 }),
+        };
+        let mut tournament = TournamentInstance::new(&settings);
+
+        // Alice knocks Charlie out and he rebuys.
+        play_game_with_actions(&mut tournament, vec![200.0, 100.0, 0.0], won_by(0));
+        assert_eq!(vec![200.0, 100.0, 100.0], tournament.player_stacks);
+        assert_eq!(vec![0, 0, 1], tournament.rebuys);
+
+        // Everyone takes the add-on, then Alice knocks out both. Charlie has
+        // used his rebuy.
+        play_game_with_actions(&mut tournament, vec![550.0, 0.0, 0.0], won_by(0));
+        assert_eq!(vec![1, 1, 1], tournament.add_ons);
+        assert_eq!(vec![550.0, 100.0, 0.0], tournament.player_stacks);
+        assert_eq!(65.0, tournament.prize_pool());
+
+        // The rebuy period is over.
+        play_game_with_actions(&mut tournament, vec![650.0, 0.0, 0.0], won_by(0));
+        assert!(tournament.is_completed());
+
+        let info = tournament.info();
+        assert_eq!(Some(vec![65.0, 0.0, 0.0]), info.prizes);
+        // Five bounties were put up, Alice won half of the four she knocked
+        // out and then the 30 on her own head.
+        assert_eq!(Some(vec![50.0, 0.0, 0.0]), info.bounties);
+
+        let replayed = TournamentInstance::from(tournament.events());
+        assert_eq!(tournament.rebuys, replayed.rebuys);
+        assert_eq!(tournament.add_ons, replayed.add_ons);
+        assert_eq!(tournament.bounties, replayed.bounties);
+        assert_eq!(info.prizes, replayed.prizes());
+    }
+
+    #[test]
+    fn test_games_use_the_levels_big_blind_and_ante() {
+        let structure = BlindStructure::new(vec![BlindLevel {
+            small_blind: 4.0,
+            big_blind: 10.0,
+            ante: 1.0,
+            hands: 1,
+        }]);
+        let mut tournament = TournamentInstance::new(&settings(Some(structure)));
+        let game = tournament.start_next_game().unwrap();
+        assert_eq!(4.0, game.small_blind);
+        assert_eq!(10.0, game.big_blind());
+        assert_eq!(1.0, game.ante);
+    }
+
+    #[test]
+    fn test_blind_structure_replaces_doubling() {
+        let level = |small_blind| BlindLevel {
+            small_blind,
+            big_blind: small_blind * 2.0,
+            ante: 0.0,
+            hands: 1,
+        };
+        let structure = BlindStructure::new(vec![level(1.0), level(3.0), level(60.0)]);
+        let mut tournament = TournamentInstance::new(&settings(Some(structure)));
+        assert_eq!(1.0, tournament.next_small_blind);
+        assert_eq!(
+            vec![1.0, 3.0, 60.0, 60.0],
+            play_even_games(&mut tournament, 4)
+        );
+
+        let replayed = TournamentInstance::from(tournament.events());
+        assert_eq!(60.0, replayed.next_small_blind);
+    }
+
     // TODO: Uncomment.
     // #[test]
     // fn test_tournament_complete() {
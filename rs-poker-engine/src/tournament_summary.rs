@@ -4,7 +4,7 @@ use rs_poker_types::{
     game::GameId, game_event::GameEvent, player::PlayerName, tournament_event::TournamentEvent,
 };
 
-use crate::game_summary::GameSummary;
+use crate::{game_summary::GameSummary, tournament_instance::TournamentInstance};
 
 pub struct TournamentSummary {
     pub tournament_events: Vec<TournamentEvent>,
@@ -91,13 +91,33 @@ impl TournamentSummary {
                 "Starting Stack: {}\n",
                 settings.starting_player_stack
             ));
+            let starting_blinds = settings.blind_level(0);
             summary.push_str(&format!(
                 "Starting Blinds: {}/{}\n",
-                settings.starting_small_blind,
-                settings.starting_small_blind * 2.0
+                starting_blinds.small_blind, starting_blinds.big_blind
             ));
 
-            if let Some(double_blinds_every) = settings.double_blinds_every_n_games {
+            if let Some(blind_structure) = &settings.blind_structure {
+                summary.push_str(&format!(
+                    "Blind Levels: {}\n",
+                    blind_structure
+                        .levels
+                        .iter()
+                        .map(|level| if level.ante > 0.0 {
+                            format!(
+                                "{}/{} ante {} for {} games",
+                                level.small_blind, level.big_blind, level.ante, level.hands
+                            )
+                        } else {
+                            format!(
+                                "{}/{} for {} games",
+                                level.small_blind, level.big_blind, level.hands
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            } else if let Some(double_blinds_every) = settings.double_blinds_every_n_games {
                 summary.push_str(&format!(
                     "Blind Increase Frequency: Every {} games\n",
                     double_blinds_every
@@ -117,6 +137,31 @@ impl TournamentSummary {
                 };
                 summary.push_str(&format!("Winner: {}\n", winner_display));
             }
+            let tournament = TournamentInstance::from(self.tournament_events.clone());
+            if let Some(prizes) = tournament.prizes() {
+                summary.push_str(&format!(
+                    "Prizes: {}\n",
+                    settings
+                        .players
+                        .iter()
+                        .zip(prizes)
+                        .map(|(player, prize)| format!("{}: {:.2}", player.name(), prize))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
+            if let Some(bounties) = &tournament.bounties {
+                summary.push_str(&format!(
+                    "Bounties: {}\n",
+                    settings
+                        .players
+                        .iter()
+                        .zip(bounties.won())
+                        .map(|(player, bounty)| format!("{}: {:.2}", player.name(), bounty))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ));
+            }
             summary.push_str("\n");
         }
 
@@ -1,4 +1,4 @@
-use rs_poker::arena::action::AgentAction;
+use rs_poker::arena::{GameType, action::AgentAction};
 use rs_poker_types::{
     game::{Decision, GameId, GameInfo, GameSettings, GameStatus},
     player::{Player, PlayerName},
@@ -66,11 +66,14 @@ async fn run_scenario() -> Result<(), Box<dyn std::error::Error>> {
         tournament_game_number: None,
         game_id: Some(game_id.clone()),
         small_blind: 5.0,
+        big_blind: None,
+        ante: 0.0,
         players: players.clone(),
         stacks,
         hands: None,
         community_cards: None,
         dealer_index: 0,
+        game_type: GameType::NoLimitHoldem,
     };
 
     let create_resp = client.new_game(&game_settings).await?;
@@ -20,6 +20,10 @@ BETTING RULES:
 - If you're in small blind (Y) and want to raise, Bet(Z) means you're adding Z-Y more chips (Z total - Y already posted)
 - Invalid bet amounts will result in an automatic fold, so always ensure your bet meets minimum requirements
 - Use Call to match the current bet, AllIn to bet all remaining chips, or Fold to quit the hand
+
+POT LIMIT OMAHA:
+- If the game type is Pot Limit Omaha you get four hole cards and must use exactly two of them with three community cards
+- No bet can be larger than the pot after calling, AllIn is capped at that amount
 "#;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
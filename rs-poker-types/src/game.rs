@@ -1,6 +1,13 @@
 use std::fmt::Display;
 
-use rs_poker::{arena::action::AgentAction, core::Card};
+use rs_poker::{
+    arena::{
+        GameType,
+        action::{Action, AgentAction},
+        agent::AgentRegistry,
+    },
+    core::Card,
+};
 use rs_poker_llm_client::LLMResponse;
 
 use crate::{
@@ -114,11 +121,20 @@ pub struct GameSettings {
     pub tournament_game_number: Option<usize>,
     pub game_id: Option<GameId>,
     pub small_blind: f32,
+    /// Defaults to double the small blind.
+    #[serde(default)]
+    pub big_blind: Option<f32>,
+    #[serde(default)]
+    pub ante: f32,
     pub players: Vec<Player>,
     pub stacks: Vec<f32>,
-    pub hands: Option<Vec<[Card; 2]>>,
+    /// Predefined hole cards, one entry per player. Each hand must have as
+    /// many cards as the game type deals.
+    pub hands: Option<Vec<Vec<Card>>>,
     pub community_cards: Option<[Card; 5]>,
     pub dealer_index: usize,
+    #[serde(default)]
+    pub game_type: GameType,
 }
 
 impl GameSettings {
@@ -135,6 +151,12 @@ impl GameSettings {
         if self.small_blind <= 0.0 {
             return Err("Small blind must be greater than zero.".to_string());
         }
+        if self.big_blind() < self.small_blind {
+            return Err("Big blind must be at least the small blind.".to_string());
+        }
+        if self.ante < 0.0 {
+            return Err("Ante can't be negative.".to_string());
+        }
         // Dealer index must be valid
         if self.dealer_index >= self.players.len() {
             return Err("Dealer index is out of bounds.".to_string());
@@ -146,6 +168,15 @@ impl GameSettings {
                 return Err(format!("Duplicate player name found: {}", player.name()));
             }
         }
+        // Check automat players can be built.
+        let registry = AgentRegistry::default();
+        for player in &self.players {
+            if let Player::Automat { name, automat_type } = player
+                && let Err(err) = registry.build(automat_type)
+            {
+                return Err(format!("Invalid automat player {}: {}", name, err));
+            }
+        }
         // Check both hands and community cards if provided or neither
         match (&self.hands, &self.community_cards) {
             (Some(_), None) | (None, Some(_)) => {
@@ -156,6 +187,18 @@ impl GameSettings {
             }
             // Validate all cards are unique if predefined hands are provided
             (Some(hands), Some(community)) => {
+                if hands.len() != self.players.len() {
+                    return Err("The number of hands must match the number of players.".to_string());
+                }
+                let num_hole_cards = self.game_type.num_hole_cards();
+                if let Some(hand) = hands.iter().find(|h| h.len() != num_hole_cards) {
+                    return Err(format!(
+                        "Each hand must have {} cards for {}, found {}.",
+                        num_hole_cards,
+                        self.game_type,
+                        hand.len()
+                    ));
+                }
                 let mut used_cards = std::collections::HashSet::new();
                 for hand in hands {
                     for &card in hand {
@@ -179,7 +222,7 @@ impl GameSettings {
     }
 
     pub fn big_blind(&self) -> f32 {
-        self.small_blind * 2.0
+        self.big_blind.unwrap_or(self.small_blind * 2.0)
     }
 
     pub fn player_names(&self) -> Vec<PlayerName> {
@@ -192,4 +235,7 @@ pub struct GameFinalResults {
     pub game_id: GameId,
     pub player_names: Vec<PlayerName>,
     pub final_stacks: Vec<f32>,
+    /// Everything that happened in the game, to see who won which pot.
+    #[serde(default)]
+    pub actions: Vec<Action>,
 }
@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+pub use rs_poker::arena::agent::AgentConfig;
+
 #[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone, Hash, Eq)]
 #[serde(transparent)]
 pub struct PlayerName(String);
@@ -16,19 +18,13 @@ impl Display for PlayerName {
     }
 }
 
-#[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone)]
-pub enum AutomatType {
-    Random,
-    AllIn,
-    Calling,
-    Filding,
-}
-
 #[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone)]
 pub enum Player {
+    /// A bot built from the agent registry, for example
+    /// `{"type":"random"}` or `{"type":"cfr","iterations":10}`.
     Automat {
         name: PlayerName,
-        automat_type: AutomatType,
+        automat_type: AgentConfig,
     },
     Human {
         name: PlayerName,
@@ -64,18 +60,19 @@ impl Player {
         }
     }
 
-    pub fn random(name: &str) -> Self {
+    pub fn automat(name: &str, automat_type: AgentConfig) -> Self {
         Player::Automat {
             name: PlayerName::new(name),
-            automat_type: AutomatType::Random,
+            automat_type,
         }
     }
 
+    pub fn random(name: &str) -> Self {
+        Player::automat(name, AgentConfig::new("random"))
+    }
+
     pub fn all_in(name: &str) -> Self {
-        Player::Automat {
-            name: PlayerName::new(name),
-            automat_type: AutomatType::AllIn,
-        }
+        Player::automat(name, AgentConfig::new("all_in"))
     }
 
     pub fn is_human(&self) -> bool {
@@ -1,4 +1,8 @@
 use rand::{SeedableRng, seq::SliceRandom};
+use rs_poker::arena::{
+    GameType,
+    competition::{BlindStructure, PayoutStructure, TournamentFormat},
+};
 
 use crate::{
     player::Player,
@@ -29,10 +33,15 @@ pub struct SeriesSettings {
     pub starting_player_stack: f32,
     pub starting_small_blind: f32,
     pub double_blinds_every_n_games: Option<usize>,
+    pub blind_structure: Option<BlindStructure>,
+    pub payout_structure: Option<PayoutStructure>,
+    pub buy_in: Option<f32>,
+    pub format: TournamentFormat,
     pub end_condition: TournamentEndCondition,
     pub see_historical_thoughts: bool,
     pub public_chat: bool,
     pub random_seed: u64,
+    pub game_type: GameType,
 }
 
 impl SeriesSettings {
@@ -59,9 +68,14 @@ impl SeriesSettings {
                 starting_player_stack: self.starting_player_stack,
                 starting_small_blind: self.starting_small_blind,
                 double_blinds_every_n_games: self.double_blinds_every_n_games,
+                blind_structure: self.blind_structure.clone(),
+                payout_structure: self.payout_structure.clone(),
+                buy_in: self.buy_in,
+                format: self.format,
                 end_condition: self.end_condition.clone(),
                 see_historical_thoughts: self.see_historical_thoughts,
                 public_chat: self.public_chat,
+                game_type: self.game_type,
             });
         }
         tournaments
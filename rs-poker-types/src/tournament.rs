@@ -1,5 +1,10 @@
 use std::fmt::Display;
 
+use rs_poker::arena::{
+    GameType,
+    competition::{BlindLevel, BlindStructure, PayoutStructure, TournamentFormat},
+};
+
 use crate::{
     game::GameId,
     player::{Player, PlayerName},
@@ -40,9 +45,70 @@ pub struct TournamentSettings {
     pub starting_player_stack: f32,
     pub starting_small_blind: f32,
     pub double_blinds_every_n_games: Option<usize>,
+    /// Blind levels, with each level lasting `hands` games. When set this
+    /// replaces `starting_small_blind` and `double_blinds_every_n_games`.
+    #[serde(default)]
+    pub blind_structure: Option<BlindStructure>,
+    /// How the prize pool is paid out. Without one there is no prize money
+    /// and only the winner is recorded.
+    #[serde(default)]
+    pub payout_structure: Option<PayoutStructure>,
+    /// What each player paid to enter, the prize pool is the buy in times
+    /// the number of players. Defaults to the starting stack.
+    #[serde(default)]
+    pub buy_in: Option<f32>,
+    /// Rebuys, an add-on and bounties. Players always rebuy when they can
+    /// and always take the add-on. Their costs go into the prize pool.
+    #[serde(default)]
+    pub format: TournamentFormat,
     pub end_condition: TournamentEndCondition,
     pub see_historical_thoughts: bool,
     pub public_chat: bool,
+    #[serde(default)]
+    pub game_type: GameType,
+}
+
+impl TournamentSettings {
+    /// The blind levels the tournament is played at, from `blind_structure`
+    /// or else from the starting small blind and how often it doubles.
+    pub fn blinds(&self) -> BlindStructure {
+        match (&self.blind_structure, self.double_blinds_every_n_games) {
+            (Some(blind_structure), _) => blind_structure.clone(),
+            (None, Some(n)) => BlindStructure::doubling(
+                self.starting_small_blind,
+                self.starting_small_blind * 2.0,
+                n,
+            ),
+            (None, None) => BlindStructure::fixed(
+                self.starting_small_blind,
+                self.starting_small_blind * 2.0,
+                0.0,
+            ),
+        }
+    }
+
+    /// The prize pool from the buy ins, before any rebuys or add-ons.
+    pub fn prize_pool(&self) -> f32 {
+        self.buy_in.unwrap_or(self.starting_player_stack) * self.players.len() as f32
+    }
+
+    /// The blinds and ante for a game, counting from 0.
+    pub fn blind_level(&self, game_number: usize) -> BlindLevel {
+        self.blinds()
+            .level(game_number)
+            .copied()
+            .unwrap_or(BlindLevel {
+                small_blind: self.starting_small_blind,
+                big_blind: self.starting_small_blind * 2.0,
+                ante: 0.0,
+                hands: usize::MAX,
+            })
+    }
+
+    /// The small blind for a game, counting from 0.
+    pub fn small_blind(&self, game_number: usize) -> f32 {
+        self.blind_level(game_number).small_blind
+    }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, PartialEq, Debug, Clone)]
@@ -65,6 +131,15 @@ pub struct TournamentInfo {
     pub games_played: usize,
     pub current_game_id: Option<GameId>,
     pub winner: Option<PlayerName>,
+    /// The prize money for each player, in the same order as the settings.
+    /// Players still in an unfinished tournament get their ICM equity.
+    /// Only set if the tournament has a payout structure.
+    #[serde(default)]
+    pub prizes: Option<Vec<f32>>,
+    /// The bounty money each player has won, in the same order as the
+    /// settings. Only set if the tournament has bounties.
+    #[serde(default)]
+    pub bounties: Option<Vec<f32>>,
 }
 
 impl TournamentInfo {
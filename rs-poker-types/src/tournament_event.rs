@@ -1,5 +1,7 @@
 use std::time::SystemTime;
 
+use rs_poker::arena::competition::Knockout;
+
 use crate::{
     game::GameId,
     player::PlayerName,
@@ -37,6 +39,10 @@ pub struct GameStartedEvent {
     pub game_id: GameId,
     pub player_names: Vec<PlayerName>,
     pub player_stacks: Vec<f32>,
+    /// The players that took the add-on before the game. Their stacks
+    /// include it.
+    #[serde(default)]
+    pub add_ons: Vec<PlayerName>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
@@ -45,6 +51,14 @@ pub struct GameEndedEvent {
     pub game_id: GameId,
     pub player_names: Vec<PlayerName>,
     pub player_stacks: Vec<f32>,
+    /// The players knocked out in the game in a bounty tournament, by
+    /// their index in the settings.
+    #[serde(default)]
+    pub knockouts: Vec<Knockout>,
+    /// The players that busted in the game and rebought. Their stacks are
+    /// the rebuy chips.
+    #[serde(default)]
+    pub rebuys: Vec<PlayerName>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, Clone, PartialEq)]
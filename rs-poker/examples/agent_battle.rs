@@ -2,7 +2,7 @@ extern crate rs_poker;
 
 use rs_poker::arena::{
     AgentGenerator, CloneHistorianGenerator, HistorianGenerator,
-    agent::{CloneAgentGenerator, RandomAgentGenerator, RandomPotControlAgent},
+    agent::AgentRegistry,
     competition::{HoldemCompetition, StandardSimulationIterator},
     game_state::RandomGameStateGenerator,
     historian::DirectoryHistorian,
@@ -10,17 +10,18 @@ use rs_poker::arena::{
 
 const ROUNDS_BATCH: usize = 500;
 fn main() {
-    // Start with very random dumb agents.
-    let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
-        Box::<RandomAgentGenerator>::default(),
-        Box::<RandomAgentGenerator>::default(),
-        Box::new(CloneAgentGenerator::new(RandomPotControlAgent::new(vec![
-            0.5, 0.3,
-        ]))),
-        Box::new(CloneAgentGenerator::new(RandomPotControlAgent::new(vec![
-            0.3, 0.3,
-        ]))),
-    ];
+    // Start with very random dumb agents. These could just as well be read
+    // from a config file.
+    let registry = AgentRegistry::default();
+    let agent_gens: Vec<Box<dyn AgentGenerator>> = [
+        r#"{"type": "random"}"#,
+        r#"{"type": "random"}"#,
+        r#"{"type": "random_pot_control", "call": [0.5, 0.3]}"#,
+        r#"{"type": "random_pot_control", "call": [0.3, 0.3]}"#,
+    ]
+    .iter()
+    .map(|config| registry.build_str(config).expect("valid agent config"))
+    .collect();
 
     // Show how to use the historian to record the games.
     let path = std::env::current_dir().unwrap();
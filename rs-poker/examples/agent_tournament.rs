@@ -1,19 +1,17 @@
 use std::vec;
 
 use rs_poker::arena::{
-    AgentGenerator,
-    agent::{CallingAgentGenerator, RandomAgentGenerator},
-    competition::SingleTableTournamentBuilder,
+    AgentGenerator, agent::AgentRegistry, competition::SingleTableTournamentBuilder,
 };
 
 fn main() {
     let stacks = vec![100.0, 100.0, 50.0];
 
-    let agent_builders: Vec<Box<dyn AgentGenerator>> = vec![
-        Box::new(CallingAgentGenerator),
-        Box::<RandomAgentGenerator>::default(),
-        Box::<RandomAgentGenerator>::default(),
-    ];
+    let registry = AgentRegistry::default();
+    let agent_builders: Vec<Box<dyn AgentGenerator>> = ["calling", "random", "random"]
+        .iter()
+        .map(|config| registry.build_str(config).expect("valid agent config"))
+        .collect();
 
     let game_state =
         rs_poker::arena::game_state::GameState::new_starting(stacks, 10.0, 5.0, 0.0, 0);
@@ -0,0 +1,48 @@
+use std::path::Path;
+use std::process::ExitCode;
+
+use rs_poker::arena::hand_log::{hand_log_to_json_dir, json_dir_to_hand_log};
+
+/// Converts between binary hand logs and the directories of JSON files
+/// written by `DirectoryHistorian`.
+///
+/// ```text
+/// cargo run --example hand_log_convert -- to-json hands.log json_dir
+/// cargo run --example hand_log_convert -- from-json json_dir hands.log
+/// ```
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() != 4 {
+        eprintln!(
+            "Usage: {} (to-json <log> <json dir> | from-json <json dir> <log>)",
+            args[0]
+        );
+        return ExitCode::FAILURE;
+    }
+
+    let from = Path::new(&args[2]);
+    let to = Path::new(&args[3]);
+    let result = match args[1].as_str() {
+        "to-json" => hand_log_to_json_dir(from, to),
+        "from-json" => json_dir_to_hand_log(from, to),
+        command => {
+            eprintln!("Unknown command {command}, expected to-json or from-json");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match result {
+        Ok(hands) => {
+            println!(
+                "Converted {hands} hands from {} to {}",
+                from.display(),
+                to.display()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Conversion failed: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpStream, ToSocketAddrs},
+};
+
+use tracing::{Level, event};
+
+use crate::arena::{Agent, AgentGenerator, PlayerView};
+
+use super::{ACPC_VERSION, AcpcAction, AcpcError, AcpcGame, MatchState, is_ignored_line};
+
+/// Plays an `Agent` against other bots on an ACPC dealer.
+///
+/// A fresh agent is generated for every hand. Agents only see what the
+/// match state tells them: the game state with their own hole cards and
+/// the board. The action history in their `PlayerView` is empty.
+pub struct AcpcClient {
+    game: AcpcGame,
+    agent_gen: Box<dyn AgentGenerator>,
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl AcpcClient {
+    /// Connect to the dealer at `addr` and introduce ourselves.
+    pub fn connect(
+        addr: impl ToSocketAddrs,
+        game: AcpcGame,
+        agent_gen: Box<dyn AgentGenerator>,
+    ) -> Result<Self, AcpcError> {
+        let stream = TcpStream::connect(addr)?;
+        stream.set_nodelay(true)?;
+        let mut writer = stream.try_clone()?;
+        write!(writer, "{ACPC_VERSION}\r\n")?;
+        writer.flush()?;
+
+        Ok(Self {
+            game,
+            agent_gen,
+            reader: BufReader::new(stream),
+            writer,
+        })
+    }
+
+    /// Play until the dealer closes the connection.
+    ///
+    /// Returns the number of hands that were played.
+    pub fn run(&mut self) -> Result<u64, AcpcError> {
+        let mut hands = 0;
+        let mut current: Option<(u64, Box<dyn Agent>)> = None;
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Ok(hands);
+            }
+            if is_ignored_line(&line) {
+                continue;
+            }
+
+            let raw = line.trim_end();
+            let state: MatchState = raw.parse()?;
+            if current
+                .as_ref()
+                .is_none_or(|(hand_number, _)| *hand_number != state.hand_number)
+            {
+                hands += 1;
+                let agent = self.agent_gen.generate(&self.game.starting_game_state());
+                current = Some((state.hand_number, agent));
+            }
+
+            let game_state = state.to_game_state(&self.game)?;
+            if super::player_to_act(&game_state) != Some(state.position) {
+                continue;
+            }
+
+            let Some((_, agent)) = current.as_mut() else {
+                continue;
+            };
+            let view = PlayerView::new(state.position, &game_state, &[]);
+            let action = agent.act(state.hand_number as u128, &view);
+            let reply = AcpcAction::from_agent_action(&action, &game_state);
+            event!(Level::TRACE, state = raw, ?action, %reply, "acpc_client_act");
+
+            write!(self.writer, "{raw}:{reply}\r\n")?;
+            self.writer.flush()?;
+        }
+    }
+}
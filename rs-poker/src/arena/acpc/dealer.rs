@@ -0,0 +1,422 @@
+use std::{
+    cell::RefCell,
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use tracing::{Level, event};
+
+use crate::arena::{
+    Agent, HoldemSimulationBuilder, PlayerView, action::AgentAction, game_state::Round,
+};
+
+use super::{ACPC_VERSION, AcpcAction, AcpcError, AcpcGame, MatchState, is_ignored_line};
+
+/// How long a bot gets to answer each decision by default.
+pub const DEFAULT_ACPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// One bot's connection to the dealer.
+struct Connection {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+    timeout: Duration,
+}
+
+impl Connection {
+    fn read_line(&mut self) -> Result<String, AcpcError> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if self.reader.read_line(&mut line)? == 0 {
+                return Err(AcpcError::Io(std::io::ErrorKind::UnexpectedEof.into()));
+            }
+            if !is_ignored_line(&line) {
+                return Ok(line.trim_end().to_string());
+            }
+        }
+    }
+
+    /// Wait for the bot's answer to `state`, returning the action part.
+    ///
+    /// Answers that arrive after a decision timed out are still in the
+    /// buffer at the next decision, so lines that don't answer `state` are
+    /// skipped until the bot answers or runs out of time.
+    fn read_answer(&mut self, state: &str) -> Result<String, AcpcError> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(AcpcError::Io(std::io::ErrorKind::TimedOut.into()));
+            }
+            self.reader.get_ref().set_read_timeout(Some(remaining))?;
+            let line = self.read_line()?;
+            match line
+                .strip_prefix(state)
+                .and_then(|rest| rest.strip_prefix(':'))
+            {
+                Some(action) => return Ok(action.to_string()),
+                None => event!(Level::DEBUG, line, "acpc_skipped_line"),
+            }
+        }
+    }
+
+    fn send(&mut self, state: &MatchState) -> Result<(), AcpcError> {
+        write!(self.writer, "{state}\r\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// The `Agent` the dealer seats for every bot. It sends the bot the match
+/// state and waits for the answer.
+struct ConnectionAgent {
+    connection: Rc<RefCell<Connection>>,
+    hand_number: u64,
+}
+
+impl ConnectionAgent {
+    fn request_action(&mut self, view: &PlayerView) -> Result<AgentAction, AcpcError> {
+        let mut hole_cards = vec![vec![]; view.num_players];
+        hole_cards[view.player_idx()] = view.hole_cards();
+        let state = MatchState::from_actions(
+            view.player_idx(),
+            self.hand_number,
+            view.actions(),
+            hole_cards,
+        );
+        let state_str = state.to_string();
+
+        let mut connection = self.connection.borrow_mut();
+        connection.send(&state)?;
+        let action: AcpcAction = connection.read_answer(&state_str)?.parse()?;
+        Ok(action.to_agent_action(view.game_state()))
+    }
+}
+
+impl Agent for ConnectionAgent {
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        // Everyone else is all in so there's nothing to decide.
+        if view.num_active_players() == 1
+            && view.current_round_bet() == view.current_round_current_player_bet()
+        {
+            return AgentAction::Call;
+        }
+
+        match self.request_action(view) {
+            Ok(action) => action,
+            Err(error) => {
+                event!(Level::WARN, ?error, "acpc_bot_error");
+                AgentAction::Fold
+            }
+        }
+    }
+}
+
+/// The outcome of a match run by `AcpcDealer`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AcpcMatchResults {
+    pub hands_played: u64,
+    /// The chips won or lost by each bot, in the order they connected.
+    pub total_change: Vec<f32>,
+}
+
+/// A dealer that ACPC bots can connect to.
+///
+/// Bots connect over TCP and the dealer runs a `HoldemSimulation` for every
+/// hand. Seats rotate every hand so each bot plays every position. Bots are
+/// sent a match state when it's their turn to act and again when the hand
+/// is over, with any hole cards that were shown down. Bots that time out,
+/// disconnect, or send something invalid fold.
+///
+/// ```no_run
+/// use rs_poker::arena::acpc::{AcpcDealer, AcpcGame};
+///
+/// let dealer = AcpcDealer::bind("127.0.0.1:18791", AcpcGame::default()).unwrap();
+/// let mut rng = rand::rng();
+/// let results = dealer.run(1000, &mut rng).unwrap();
+/// println!("{:?}", results.total_change);
+/// ```
+pub struct AcpcDealer {
+    game: AcpcGame,
+    listener: TcpListener,
+    timeout: Duration,
+}
+
+impl AcpcDealer {
+    pub fn bind(addr: impl ToSocketAddrs, game: AcpcGame) -> Result<Self, AcpcError> {
+        Ok(Self {
+            game,
+            listener: TcpListener::bind(addr)?,
+            timeout: DEFAULT_ACPC_TIMEOUT,
+        })
+    }
+
+    /// How long each bot has to answer a decision.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The address bots should connect to.
+    pub fn local_addr(&self) -> Result<SocketAddr, AcpcError> {
+        Ok(self.listener.local_addr()?)
+    }
+
+    /// Wait for every bot to connect and then play `num_hands` hands.
+    pub fn run<R: Rng>(&self, num_hands: u64, rng: &mut R) -> Result<AcpcMatchResults, AcpcError> {
+        let connections = self.accept_all()?;
+        let num_players = self.game.num_players;
+        let mut total_change = vec![0.0; num_players];
+
+        for hand_number in 0..num_hands {
+            // Bot `c` sits in position `(c + hand_number) % num_players`.
+            let seated: Vec<usize> = (0..num_players)
+                .map(|position| {
+                    (position + num_players - (hand_number as usize % num_players)) % num_players
+                })
+                .collect();
+            let agents: Vec<Box<dyn Agent>> = seated
+                .iter()
+                .map(|&conn| {
+                    Box::new(ConnectionAgent {
+                        connection: connections[conn].clone(),
+                        hand_number,
+                    }) as Box<dyn Agent>
+                })
+                .collect();
+
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(self.game.starting_game_state())
+                .agents(agents)
+                .build()
+                .expect("Agents and game state are always set");
+            sim.run(rng);
+
+            let game_state = &sim.game_state;
+            let showdown = game_state.round_before == Round::Showdown;
+            let shown = game_state.player_active | game_state.player_all_in;
+            for (position, &conn) in seated.iter().enumerate() {
+                total_change[conn] += game_state.player_reward(position);
+
+                let hole_cards = (0..num_players)
+                    .map(|idx| {
+                        if idx == position || (showdown && shown.get(idx)) {
+                            game_state.hole_cards(idx)
+                        } else {
+                            vec![]
+                        }
+                    })
+                    .collect();
+                let state =
+                    MatchState::from_actions(position, hand_number, &sim.actions, hole_cards);
+                if let Err(error) = connections[conn].borrow_mut().send(&state) {
+                    event!(Level::WARN, ?error, "acpc_bot_error");
+                }
+            }
+        }
+
+        Ok(AcpcMatchResults {
+            hands_played: num_hands,
+            total_change,
+        })
+    }
+
+    fn accept_all(&self) -> Result<Vec<Rc<RefCell<Connection>>>, AcpcError> {
+        (0..self.game.num_players)
+            .map(|_| {
+                let (stream, addr) = self.listener.accept()?;
+                event!(Level::DEBUG, ?addr, "acpc_bot_connected");
+                stream.set_nodelay(true)?;
+                stream.set_read_timeout(Some(self.timeout))?;
+                let mut connection = Connection {
+                    reader: BufReader::new(stream.try_clone()?),
+                    writer: stream,
+                    timeout: self.timeout,
+                };
+
+                let version = connection.read_line()?;
+                if !version.starts_with("VERSION:") {
+                    return Err(AcpcError::UnexpectedMessage(version));
+                }
+                if version != ACPC_VERSION {
+                    event!(Level::WARN, version, "acpc_unknown_version");
+                }
+                Ok(Rc::new(RefCell::new(connection)))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    use crate::arena::agent::{
+        AllInAgentGenerator, CallingAgentGenerator, FoldingAgentGenerator, RandomAgentGenerator,
+    };
+    use crate::arena::{AgentGenerator, acpc::AcpcClient};
+
+    use super::*;
+
+    fn play(agent_gens: Vec<Box<dyn AgentGenerator + Send>>, num_hands: u64) -> AcpcMatchResults {
+        let game = AcpcGame {
+            num_players: agent_gens.len(),
+            ..AcpcGame::default()
+        };
+        let dealer = AcpcDealer::bind("127.0.0.1:0", game).unwrap();
+        let addr = dealer.local_addr().unwrap();
+
+        let clients: Vec<_> = agent_gens
+            .into_iter()
+            .map(|agent_gen| {
+                let handle = thread::spawn(move || {
+                    let mut client = AcpcClient::connect(addr, game, agent_gen).unwrap();
+                    client.run().unwrap()
+                });
+                // Connect in order so results line up with the generators.
+                thread::sleep(Duration::from_millis(20));
+                handle
+            })
+            .collect();
+
+        let mut rng = rand::rng();
+        let results = dealer.run(num_hands, &mut rng).unwrap();
+        drop(dealer);
+
+        for client in clients {
+            assert_eq!(num_hands, client.join().unwrap());
+        }
+        results
+    }
+
+    #[test]
+    fn test_heads_up_match_is_zero_sum() {
+        let results = play(
+            vec![
+                Box::new(CallingAgentGenerator),
+                Box::<RandomAgentGenerator>::default(),
+            ],
+            20,
+        );
+        assert_eq!(20, results.hands_played);
+        assert!(results.total_change.iter().sum::<f32>().abs() < 0.01);
+    }
+
+    #[test]
+    fn test_folder_loses_blinds() {
+        let results = play(
+            vec![
+                Box::new(FoldingAgentGenerator),
+                Box::new(AllInAgentGenerator),
+            ],
+            10,
+        );
+        // Every hand the folder gives up either the small or the big blind.
+        assert_eq!(-750.0, results.total_change[0]);
+        assert_eq!(750.0, results.total_change[1]);
+    }
+
+    #[test]
+    fn test_three_player_match() {
+        let results = play(
+            vec![
+                Box::new(CallingAgentGenerator),
+                Box::new(CallingAgentGenerator),
+                Box::<RandomAgentGenerator>::default(),
+            ],
+            10,
+        );
+        assert!(results.total_change.iter().sum::<f32>().abs() < 0.01);
+    }
+
+    #[test]
+    fn test_bad_bot_folds() {
+        let dealer = AcpcDealer::bind("127.0.0.1:0", AcpcGame::default())
+            .unwrap()
+            .with_timeout(Duration::from_millis(100));
+        let addr = dealer.local_addr().unwrap();
+
+        let good = thread::spawn(move || {
+            let mut client =
+                AcpcClient::connect(addr, AcpcGame::default(), Box::new(AllInAgentGenerator))
+                    .unwrap();
+            client.run().unwrap()
+        });
+        thread::sleep(Duration::from_millis(20));
+        let bad = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "{ACPC_VERSION}\r\n").unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                if write!(stream, "not an answer\r\n").is_err() {
+                    break;
+                }
+                line.clear();
+            }
+        });
+
+        let mut rng = rand::rng();
+        let results = dealer.run(4, &mut rng).unwrap();
+        drop(dealer);
+        good.join().unwrap();
+        bad.join().unwrap();
+
+        // The bad bot folds to every shove and as the small blind.
+        assert_eq!(300.0, results.total_change[0]);
+        assert_eq!(-300.0, results.total_change[1]);
+    }
+
+    #[test]
+    fn test_late_answer_is_skipped() {
+        let dealer = AcpcDealer::bind("127.0.0.1:0", AcpcGame::default())
+            .unwrap()
+            .with_timeout(Duration::from_millis(200));
+        let addr = dealer.local_addr().unwrap();
+
+        let shover = thread::spawn(move || {
+            let mut client =
+                AcpcClient::connect(addr, AcpcGame::default(), Box::new(AllInAgentGenerator))
+                    .unwrap();
+            client.run().unwrap()
+        });
+        thread::sleep(Duration::from_millis(20));
+        // Calls everything, but misses the time limit on its first decision.
+        let late = thread::spawn(move || {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            write!(stream, "{ACPC_VERSION}\r\n").unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            let mut first = true;
+            let mut showdowns = vec![];
+            while reader.read_line(&mut line).unwrap_or(0) > 0 {
+                let state = line.trim_end();
+                if first {
+                    thread::sleep(Duration::from_millis(300));
+                    first = false;
+                }
+                let parsed: MatchState = state.parse().unwrap();
+                if parsed.hole_cards.iter().all(|cards| !cards.is_empty()) {
+                    showdowns.push(parsed.hand_number);
+                }
+                if write!(stream, "{state}:c\r\n").is_err() {
+                    break;
+                }
+                line.clear();
+            }
+            showdowns
+        });
+
+        let mut rng = rand::rng();
+        dealer.run(4, &mut rng).unwrap();
+        drop(dealer);
+        assert_eq!(4, shover.join().unwrap());
+
+        // The first hand is folded on the timeout. The late answer is
+        // skipped so every later shove is called.
+        assert_eq!(vec![1, 2, 3], late.join().unwrap());
+    }
+}
@@ -0,0 +1,547 @@
+use std::{fmt, str::FromStr};
+
+use crate::arena::{
+    GameState,
+    action::{Action, AgentAction, PlayedActionPayload},
+    game_state::Round,
+};
+use crate::core::Card;
+
+use super::{AcpcError, AcpcGame};
+
+/// A single action in an ACPC betting string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AcpcAction {
+    /// `f`
+    Fold,
+    /// `c`, or `k` which some bots send for checks.
+    Call,
+    /// `r<N>` where `N` is the total the player has put in over the
+    /// whole hand after raising. Limit games send a bare `r`.
+    Raise(Option<u32>),
+}
+
+impl AcpcAction {
+    /// Turn what an agent wants to do into the ACPC action that the
+    /// player to act in `game_state` would send.
+    ///
+    /// Bets that don't put in more than the current bet become calls.
+    pub fn from_agent_action(action: &AgentAction, game_state: &GameState) -> Self {
+        let idx = game_state.to_act_idx();
+        let earlier_rounds = game_state.player_bet[idx] - game_state.current_round_player_bet(idx);
+        let round_total = match action {
+            AgentAction::Fold => {
+                // Folding when there's nothing to call is a check.
+                if game_state.current_round_bet() > game_state.current_round_player_bet(idx) {
+                    return AcpcAction::Fold;
+                }
+                return AcpcAction::Call;
+            }
+            AgentAction::Call => return AcpcAction::Call,
+            AgentAction::Bet(amount) => *amount,
+            AgentAction::AllIn => game_state.current_round_player_bet(idx) + game_state.stacks[idx],
+        };
+
+        if round_total <= game_state.current_round_bet() {
+            AcpcAction::Call
+        } else {
+            AcpcAction::Raise(Some((earlier_rounds + round_total).round() as u32))
+        }
+    }
+
+    /// Turn an ACPC action from the player to act in `game_state` into an
+    /// `AgentAction` that the simulation understands.
+    pub fn to_agent_action(&self, game_state: &GameState) -> AgentAction {
+        let idx = game_state.to_act_idx();
+        match self {
+            AcpcAction::Fold => AgentAction::Fold,
+            AcpcAction::Call => AgentAction::Call,
+            AcpcAction::Raise(Some(total)) => {
+                let earlier_rounds =
+                    game_state.player_bet[idx] - game_state.current_round_player_bet(idx);
+                AgentAction::Bet(*total as f32 - earlier_rounds)
+            }
+            // A limit raise is always the minimum raise.
+            AcpcAction::Raise(None) => AgentAction::Bet(
+                game_state.current_round_bet() + game_state.current_round_min_raise(),
+            ),
+        }
+    }
+}
+
+impl fmt::Display for AcpcAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AcpcAction::Fold => write!(f, "f"),
+            AcpcAction::Call => write!(f, "c"),
+            AcpcAction::Raise(Some(total)) => write!(f, "r{total}"),
+            AcpcAction::Raise(None) => write!(f, "r"),
+        }
+    }
+}
+
+impl FromStr for AcpcAction {
+    type Err = AcpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut actions = parse_betting_round(s)?;
+        match (actions.pop(), actions.is_empty()) {
+            (Some(action), true) => Ok(action),
+            _ => Err(AcpcError::InvalidAction(s.to_string())),
+        }
+    }
+}
+
+/// The state of a hand from the point of view of one seat, as sent by an
+/// ACPC dealer.
+///
+/// The wire format is
+/// `MATCHSTATE:<position>:<hand number>:<betting>:<cards>` where betting
+/// rounds and board cards are separated by `/` and hole cards are
+/// separated by `|`. Hole cards the seat can't see are left empty.
+///
+/// ```
+/// use rs_poker::arena::acpc::{AcpcAction, MatchState};
+///
+/// let state: MatchState = "MATCHSTATE:0:30:r300c/:9s8h|/8c8d5c".parse().unwrap();
+/// assert_eq!(0, state.position);
+/// assert_eq!(30, state.hand_number);
+/// assert_eq!(
+///     vec![AcpcAction::Raise(Some(300)), AcpcAction::Call],
+///     state.betting[0]
+/// );
+/// assert!(state.betting[1].is_empty());
+/// assert_eq!(3, state.board[0].len());
+/// assert_eq!("MATCHSTATE:0:30:r300c/:9s8h|/8c8d5c", state.to_string());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MatchState {
+    /// The seat the state is for.
+    pub position: usize,
+    pub hand_number: u64,
+    /// The actions in every betting round that has started.
+    pub betting: Vec<Vec<AcpcAction>>,
+    /// Hole cards for every seat. Empty when they aren't known.
+    pub hole_cards: Vec<Vec<Card>>,
+    /// Board cards dealt in each round after preflop.
+    pub board: Vec<Vec<Card>>,
+}
+
+impl MatchState {
+    /// Build the match state for `position` from the actions of a
+    /// simulated hand.
+    ///
+    /// `hole_cards` are the cards to show for every seat, so callers
+    /// decide what `position` gets to see.
+    pub fn from_actions(
+        position: usize,
+        hand_number: u64,
+        actions: &[Action],
+        hole_cards: Vec<Vec<Card>>,
+    ) -> Self {
+        let mut betting = vec![vec![]];
+        let mut board: Vec<Vec<Card>> = vec![];
+        // What every player has put in over the whole hand.
+        let mut committed = vec![0.0_f32; hole_cards.len()];
+
+        for action in actions {
+            match action {
+                Action::RoundAdvance(Round::DealFlop | Round::DealTurn | Round::DealRiver) => {
+                    betting.push(vec![]);
+                    board.push(vec![]);
+                }
+                Action::DealCommunity(card) => {
+                    if let Some(cards) = board.last_mut() {
+                        cards.push(*card);
+                    }
+                }
+                Action::ForcedBet(payload) => {
+                    if let Some(total) = committed.get_mut(payload.idx) {
+                        *total += payload.bet;
+                    }
+                }
+                Action::PlayedAction(payload) => {
+                    push_played(&mut betting, &mut committed, payload);
+                }
+                Action::FailedAction(payload) => {
+                    push_played(&mut betting, &mut committed, &payload.result);
+                }
+                _ => {}
+            }
+        }
+
+        Self {
+            position,
+            hand_number,
+            betting,
+            hole_cards,
+            board,
+        }
+    }
+
+    /// Replay the betting in this state to get the `GameState` as it is
+    /// now. Hole cards that aren't known are left out of the hands.
+    pub fn to_game_state(&self, game: &AcpcGame) -> Result<GameState, AcpcError> {
+        if self.hole_cards.len() != game.num_players || self.position >= game.num_players {
+            return Err(AcpcError::WrongNumberOfPlayers(self.hole_cards.len()));
+        }
+        if self.board.len() + 1 < self.betting.len() || self.betting.len() > 4 {
+            return Err(AcpcError::InvalidMatchState(self.to_string()));
+        }
+
+        let mut game_state = game.starting_game_state();
+        // Starting -> Ante -> DealPreflop
+        game_state.advance_round();
+        game_state.advance_round();
+        for (hand, cards) in game_state.hands.iter_mut().zip(&self.hole_cards) {
+            hand.extend(cards.iter().copied());
+        }
+        game_state.advance_round();
+
+        game_state.do_bet(game_state.small_blind, true)?;
+        game_state.sb_posted = true;
+        game_state.do_bet(game_state.big_blind, true)?;
+        game_state.bb_posted = true;
+
+        for (round_idx, actions) in self.betting.iter().enumerate() {
+            if round_idx > 0 {
+                // Deal the board for this round.
+                game_state.advance_round();
+                let cards = &self.board[round_idx - 1];
+                for hand in game_state.hands.iter_mut() {
+                    hand.extend(cards.iter().copied());
+                }
+                game_state.board.extend(cards.iter().copied());
+                game_state.advance_round();
+            }
+
+            for action in actions {
+                match action.to_agent_action(&game_state) {
+                    AgentAction::Fold => game_state.fold(),
+                    AgentAction::Call => {
+                        game_state.do_bet(game_state.current_round_bet(), false)?;
+                    }
+                    AgentAction::Bet(amount) => {
+                        game_state.do_bet(amount, false)?;
+                    }
+                    AgentAction::AllIn => unreachable!("ACPC actions are never all in"),
+                }
+            }
+        }
+
+        Ok(game_state)
+    }
+}
+
+/// Add a played action to the betting of the round it happened in.
+fn push_played(
+    betting: &mut [Vec<AcpcAction>],
+    committed: &mut [f32],
+    payload: &PlayedActionPayload,
+) {
+    let added = payload.final_player_bet - payload.starting_player_bet;
+    if let Some(total) = committed.get_mut(payload.idx) {
+        *total += added;
+    }
+
+    // When everyone else is all in the last player gets asked to check
+    // through the rest of the board. ACPC skips straight to the next round.
+    let facing_bet = payload.starting_bet > payload.starting_player_bet;
+    if payload.players_active.count() == 1 && added == 0.0 && !facing_bet {
+        return;
+    }
+
+    let action = if payload.action == AgentAction::Fold {
+        AcpcAction::Fold
+    } else if payload.final_bet > payload.starting_bet {
+        AcpcAction::Raise(Some(committed[payload.idx].round() as u32))
+    } else {
+        AcpcAction::Call
+    };
+
+    if let Some(round) = betting.last_mut() {
+        round.push(action);
+    }
+}
+
+/// Which seat should act next, if any.
+///
+/// No one acts once the hand is over, and no one acts when only a single
+/// player is left who isn't all in.
+pub fn player_to_act(game_state: &GameState) -> Option<usize> {
+    let idx = game_state.to_act_idx();
+    if game_state.is_complete()
+        || (game_state.num_active_players() < 2 && game_state.current_round_bet() == 0.0)
+        || !game_state.round_data.needs_action.get(idx)
+    {
+        None
+    } else {
+        Some(idx)
+    }
+}
+
+fn parse_betting_round(s: &str) -> Result<Vec<AcpcAction>, AcpcError> {
+    let mut actions = vec![];
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        let action = match c {
+            'f' => AcpcAction::Fold,
+            'c' | 'k' => AcpcAction::Call,
+            'r' => {
+                let mut digits = String::new();
+                while let Some(d) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(d);
+                }
+                if digits.is_empty() {
+                    AcpcAction::Raise(None)
+                } else {
+                    let total = digits
+                        .parse()
+                        .map_err(|_| AcpcError::InvalidAction(s.to_string()))?;
+                    AcpcAction::Raise(Some(total))
+                }
+            }
+            _ => return Err(AcpcError::InvalidAction(s.to_string())),
+        };
+        actions.push(action);
+    }
+    Ok(actions)
+}
+
+fn parse_cards(s: &str) -> Result<Vec<Card>, AcpcError> {
+    let chars: Vec<char> = s.chars().collect();
+    if !chars.len().is_multiple_of(2) {
+        return Err(AcpcError::InvalidCards(s.to_string()));
+    }
+    chars
+        .chunks(2)
+        .map(|pair| {
+            let card: String = pair.iter().collect();
+            Card::try_from(card.as_str()).map_err(|_| AcpcError::InvalidCards(s.to_string()))
+        })
+        .collect()
+}
+
+fn write_cards(f: &mut fmt::Formatter<'_>, cards: &[Card]) -> fmt::Result {
+    for card in cards {
+        write!(f, "{card}")?;
+    }
+    Ok(())
+}
+
+impl FromStr for MatchState {
+    type Err = AcpcError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AcpcError::InvalidMatchState(s.to_string());
+
+        let parts: Vec<&str> = s.trim_end().split(':').collect();
+        if parts.len() != 5 || parts[0] != "MATCHSTATE" {
+            return Err(invalid());
+        }
+        let position = parts[1].parse().map_err(|_| invalid())?;
+        let hand_number = parts[2].parse().map_err(|_| invalid())?;
+        let betting = parts[3]
+            .split('/')
+            .map(parse_betting_round)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut card_rounds = parts[4].split('/');
+        let hole_cards = card_rounds
+            .next()
+            .unwrap_or_default()
+            .split('|')
+            .map(parse_cards)
+            .collect::<Result<Vec<_>, _>>()?;
+        let board = card_rounds
+            .map(parse_cards)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if position >= hole_cards.len() {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            position,
+            hand_number,
+            betting,
+            hole_cards,
+            board,
+        })
+    }
+}
+
+impl fmt::Display for MatchState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MATCHSTATE:{}:{}:", self.position, self.hand_number)?;
+        for (round_idx, actions) in self.betting.iter().enumerate() {
+            if round_idx > 0 {
+                write!(f, "/")?;
+            }
+            for action in actions {
+                write!(f, "{action}")?;
+            }
+        }
+        write!(f, ":")?;
+        for (idx, cards) in self.hole_cards.iter().enumerate() {
+            if idx > 0 {
+                write!(f, "|")?;
+            }
+            write_cards(f, cards)?;
+        }
+        for cards in &self.board {
+            write!(f, "/")?;
+            write_cards(f, cards)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{HoldemSimulationBuilder, agent::CallingAgent};
+    use crate::core::{Suit, Value};
+
+    use super::*;
+
+    fn cards(s: &str) -> Vec<Card> {
+        parse_cards(s).unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_format_round_trip() {
+        for s in [
+            "MATCHSTATE:0:0::AsKd|",
+            "MATCHSTATE:1:7:r250c/cr500f:|7h2c/Tc9c8c",
+            "MATCHSTATE:0:30:cc/r300c/cc/r20000c:9s8h|AcAd/8c8d5c/Th/2s",
+            "MATCHSTATE:2:1:rrc:||QhQs",
+        ] {
+            let state: MatchState = s.parse().unwrap();
+            assert_eq!(s, state.to_string());
+        }
+    }
+
+    #[test]
+    fn test_parse_fields() {
+        let state: MatchState = "MATCHSTATE:1:7:r250c/cr500:|7h2c/Tc9c8c\r\n"
+            .parse()
+            .unwrap();
+        assert_eq!(1, state.position);
+        assert_eq!(7, state.hand_number);
+        assert_eq!(
+            vec![
+                vec![AcpcAction::Raise(Some(250)), AcpcAction::Call],
+                vec![AcpcAction::Call, AcpcAction::Raise(Some(500))],
+            ],
+            state.betting
+        );
+        assert!(state.hole_cards[0].is_empty());
+        assert_eq!(
+            vec![
+                Card::new(Value::Seven, Suit::Heart),
+                Card::new(Value::Two, Suit::Club)
+            ],
+            state.hole_cards[1]
+        );
+        assert_eq!(vec![cards("Tc9c8c")], state.board);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for s in [
+            "",
+            "MATCHSTATE:0:0:",
+            "NOTSTATE:0:0::AsKd|",
+            "MATCHSTATE:x:0::AsKd|",
+            "MATCHSTATE:0:0:cx:AsKd|",
+            "MATCHSTATE:0:0::AsK|",
+            "MATCHSTATE:0:0::AsKz|",
+            "MATCHSTATE:2:0::AsKd|",
+        ] {
+            assert!(s.parse::<MatchState>().is_err(), "{s} should not parse");
+        }
+    }
+
+    #[test]
+    fn test_to_game_state_heads_up() {
+        let game = AcpcGame::default();
+        let state: MatchState = "MATCHSTATE:0:0:r300c/r600:9s8h|/8c8d5c".parse().unwrap();
+        let game_state = state.to_game_state(&game).unwrap();
+
+        assert_eq!(Round::Flop, game_state.round);
+        assert_eq!(cards("8c8d5c"), game_state.board);
+        assert_eq!(2, game_state.hole_cards(0).len());
+        assert!(game_state.hole_cards(1).is_empty());
+        // Both put in 300 preflop, then seat 0 bet 300 more.
+        assert_eq!(vec![600.0, 300.0], game_state.player_bet);
+        assert_eq!(Some(1), player_to_act(&game_state));
+    }
+
+    #[test]
+    fn test_who_acts() {
+        let game = AcpcGame::default();
+        let to_act = |s: &str| {
+            let state: MatchState = s.parse().unwrap();
+            player_to_act(&state.to_game_state(&game).unwrap())
+        };
+
+        // The small blind is the button and acts first preflop.
+        assert_eq!(Some(1), to_act("MATCHSTATE:0:0::9s8h|"));
+        assert_eq!(Some(0), to_act("MATCHSTATE:0:0:c:9s8h|"));
+        // Seat 0 acts first after the flop.
+        assert_eq!(Some(0), to_act("MATCHSTATE:0:0:cc/:9s8h|/8c8d5c"));
+        assert_eq!(None, to_act("MATCHSTATE:0:0:f:9s8h|"));
+        assert_eq!(
+            None,
+            to_act("MATCHSTATE:0:0:cc/cc/cc/cc:9s8h|/8c8d5c/2s/3s")
+        );
+        assert_eq!(None, to_act("MATCHSTATE:0:0:r20000c///:9s8h|/8c8d5c/2s/3s"));
+    }
+
+    #[test]
+    fn test_agent_action_conversion() {
+        let game = AcpcGame::default();
+        let state: MatchState = "MATCHSTATE:0:0:r300c/:9s8h|/8c8d5c".parse().unwrap();
+        let game_state = state.to_game_state(&game).unwrap();
+
+        // A bet of 200 on the flop is 500 in total for the hand.
+        let raise = AcpcAction::from_agent_action(&AgentAction::Bet(200.0), &game_state);
+        assert_eq!(AcpcAction::Raise(Some(500)), raise);
+        assert_eq!(AgentAction::Bet(200.0), raise.to_agent_action(&game_state));
+
+        assert_eq!(
+            AcpcAction::Raise(Some(20000)),
+            AcpcAction::from_agent_action(&AgentAction::AllIn, &game_state)
+        );
+        // Nothing to call so folding is checking.
+        assert_eq!(
+            AcpcAction::Call,
+            AcpcAction::from_agent_action(&AgentAction::Fold, &game_state)
+        );
+        assert_eq!("r500".parse::<AcpcAction>().unwrap(), raise);
+        assert!("rc".parse::<AcpcAction>().is_err());
+    }
+
+    #[test]
+    fn test_from_actions_matches_replay() {
+        let game = AcpcGame::default();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game.starting_game_state())
+            .agents(vec![Box::new(CallingAgent), Box::new(CallingAgent)])
+            .build()
+            .unwrap();
+        let mut rng = rand::rng();
+        sim.run(&mut rng);
+
+        let hole_cards = (0..2).map(|idx| sim.game_state.hole_cards(idx)).collect();
+        let state = MatchState::from_actions(0, 3, &sim.actions, hole_cards);
+        assert_eq!(4, state.betting.len());
+        assert_eq!(3, state.board.len());
+        assert!(state.to_string().starts_with("MATCHSTATE:0:3:cc/cc/cc/cc:"));
+
+        let replayed = state.to_game_state(&game).unwrap();
+        assert_eq!(sim.game_state.board, replayed.board);
+        assert_eq!(sim.game_state.player_bet, replayed.player_bet);
+        assert_eq!(None, player_to_act(&replayed));
+    }
+}
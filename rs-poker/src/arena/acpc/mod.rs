@@ -0,0 +1,114 @@
+//! Support for the Annual Computer Poker Competition (ACPC) protocol.
+//!
+//! ACPC bots connect to a dealer over TCP. After sending `VERSION:2.0.0`
+//! they receive one `MATCHSTATE` line per decision and answer by echoing
+//! it back with `:<action>` appended, where the action is `f`, `c`, or
+//! `r<N>`.
+//!
+//! - [`MatchState`] parses and formats those lines and maps them to and from
+//!   `GameState` and the simulation's `Action` history.
+//! - [`AcpcClient`] connects an `Agent` to any ACPC dealer.
+//! - [`AcpcDealer`] runs `HoldemSimulation` for ACPC speaking bots.
+//!
+//! Only no limit hold'em with blinds is supported. Seats follow the ACPC
+//! layout: the last seat has the button and the seats before it post the
+//! blinds, so heads up seat 0 is the big blind and seat 1 the small blind.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use rs_poker::arena::acpc::{AcpcClient, AcpcGame};
+//! use rs_poker::arena::agent::CallingAgentGenerator;
+//!
+//! let mut client = AcpcClient::connect(
+//!     "127.0.0.1:18791",
+//!     AcpcGame::default(),
+//!     Box::new(CallingAgentGenerator),
+//! )
+//! .unwrap();
+//! let hands = client.run().unwrap();
+//! println!("Played {hands} hands");
+//! ```
+mod client;
+mod dealer;
+mod match_state;
+
+use thiserror::Error;
+
+use super::{GameState, errors::GameStateError};
+
+pub use client::AcpcClient;
+pub use dealer::{AcpcDealer, AcpcMatchResults, DEFAULT_ACPC_TIMEOUT};
+pub use match_state::{AcpcAction, MatchState, player_to_act};
+
+/// The protocol version line sent by clients.
+pub const ACPC_VERSION: &str = "VERSION:2.0.0";
+
+#[derive(Error, Debug)]
+pub enum AcpcError {
+    #[error("Error talking to the other side of the connection")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid match state: {0}")]
+    InvalidMatchState(String),
+
+    #[error("Invalid action: {0}")]
+    InvalidAction(String),
+
+    #[error("Invalid cards: {0}")]
+    InvalidCards(String),
+
+    #[error("Match state has {0} players which doesn't match the game")]
+    WrongNumberOfPlayers(usize),
+
+    #[error("Betting in the match state isn't valid")]
+    InvalidBetting(#[from] GameStateError),
+
+    #[error("Unexpected message: {0}")]
+    UnexpectedMessage(String),
+}
+
+/// The parameters of an ACPC game. Stacks reset at the start of every hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AcpcGame {
+    pub num_players: usize,
+    pub stack: f32,
+    pub big_blind: f32,
+    pub small_blind: f32,
+}
+
+impl AcpcGame {
+    /// The heads up no limit game used in the competition: 20000 chip
+    /// stacks and 50/100 blinds.
+    pub fn heads_up_no_limit() -> Self {
+        Self {
+            num_players: 2,
+            stack: 20000.0,
+            big_blind: 100.0,
+            small_blind: 50.0,
+        }
+    }
+
+    /// The game state at the start of every hand.
+    pub fn starting_game_state(&self) -> GameState {
+        GameState::new_starting(
+            vec![self.stack; self.num_players],
+            self.big_blind,
+            self.small_blind,
+            0.0,
+            self.num_players - 1,
+        )
+    }
+}
+
+impl Default for AcpcGame {
+    fn default() -> Self {
+        Self::heads_up_no_limit()
+    }
+}
+
+/// Is this line a comment or keep alive that should be skipped.
+fn is_ignored_line(line: &str) -> bool {
+    let line = line.trim();
+    line.is_empty() || line.starts_with('#') || line.starts_with(';')
+}
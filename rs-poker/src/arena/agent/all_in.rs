@@ -1,4 +1,4 @@
-use crate::arena::{GameState, action::AgentAction};
+use crate::arena::{GameState, PlayerView, action::AgentAction};
 
 use super::{Agent, AgentGenerator};
 
@@ -9,8 +9,8 @@ use super::{Agent, AgentGenerator};
 pub struct AllInAgent;
 
 impl Agent for AllInAgent {
-    fn act(self: &mut AllInAgent, _id: u128, game_state: &GameState) -> AgentAction {
-        AgentAction::Bet(game_state.current_player_stack() + game_state.current_round_bet())
+    fn act(self: &mut AllInAgent, _id: u128, view: &PlayerView) -> AgentAction {
+        AgentAction::Bet(view.current_player_stack() + view.current_round_bet())
     }
 }
 
@@ -0,0 +1,28 @@
+//! Small helpers shared by the rule based agents for turning a decision
+//! into a legal `AgentAction`.
+use crate::arena::{action::AgentAction, game_state::GameState};
+
+/// How much more the player to act has to put in to call.
+pub(super) fn to_call(game_state: &GameState) -> f32 {
+    game_state.current_round_bet() - game_state.current_round_current_player_bet()
+}
+
+/// Check when it's free, otherwise fold.
+pub(super) fn check_or_fold(to_call: f32) -> AgentAction {
+    if to_call <= 0.0 {
+        AgentAction::Call
+    } else {
+        AgentAction::Fold
+    }
+}
+
+/// Raise the round's bet to `target`, kept within what's allowed.
+pub(super) fn raise_to(game_state: &GameState, target: f32) -> AgentAction {
+    let max = game_state.current_round_max_bet();
+    if max <= game_state.current_round_bet() {
+        // Can't raise, the best we can do is call.
+        return AgentAction::Call;
+    }
+    let min = game_state.current_round_bet() + game_state.current_round_min_raise();
+    AgentAction::Bet(target.round().max(min).min(max))
+}
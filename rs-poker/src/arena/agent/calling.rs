@@ -1,4 +1,4 @@
-use crate::arena::{action::AgentAction, game_state::GameState};
+use crate::arena::{PlayerView, action::AgentAction, game_state::GameState};
 
 use super::{Agent, AgentGenerator};
 
@@ -9,8 +9,8 @@ use super::{Agent, AgentGenerator};
 pub struct CallingAgent;
 
 impl Agent for CallingAgent {
-    fn act(self: &mut CallingAgent, _id: u128, game_state: &GameState) -> AgentAction {
-        AgentAction::Bet(game_state.current_round_bet())
+    fn act(self: &mut CallingAgent, _id: u128, view: &PlayerView) -> AgentAction {
+        AgentAction::Bet(view.current_round_bet())
     }
 }
 
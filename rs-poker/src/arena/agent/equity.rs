@@ -0,0 +1,383 @@
+use std::{collections::HashMap, sync::Arc};
+
+use rand::{rng, seq::IndexedRandom};
+use tracing::{Level, event};
+
+use crate::{
+    arena::{PlayerView, action::AgentAction, game_state::GameState},
+    core::{Card, Hand, RSPokerError},
+    holdem::{HandRange, MonteCarloGame},
+};
+
+use super::{
+    Agent, AgentGenerator,
+    betting::{check_or_fold, raise_to, to_call},
+};
+
+/// How many boards to run out for each guess at the opponents' hands when
+/// they're drawn from ranges.
+const ITERATIONS_PER_RANGE_SAMPLE: usize = 10;
+
+/// Everything that decides how an `EquityAgent` plays.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct EquityConfig {
+    /// The number of boards to run out with `MonteCarloGame` for every
+    /// decision.
+    pub iterations: usize,
+    /// How eager the agent is to bet and raise. Zero never raises, one is
+    /// the default, and larger values raise thinner.
+    pub aggression: f32,
+    /// Bets and raises add this fraction of the pot (after calling).
+    pub bet_fraction: f32,
+    /// The range every opponent is assumed to hold. Empty means any two
+    /// cards.
+    pub opponent_range: String,
+    /// Ranges for specific seats that override `opponent_range`.
+    pub seat_ranges: HashMap<usize, String>,
+}
+
+impl Default for EquityConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 1_000,
+            aggression: 1.0,
+            bet_fraction: 0.75,
+            opponent_range: String::new(),
+            seat_ranges: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ParsedRanges {
+    // Empty means any two cards.
+    default: Vec<(Card, Card)>,
+    seats: HashMap<usize, Vec<(Card, Card)>>,
+}
+
+impl ParsedRanges {
+    fn parse(config: &EquityConfig) -> Result<Self, RSPokerError> {
+        let combos = |range: &str| -> Result<Vec<(Card, Card)>, RSPokerError> {
+            let mut combos: Vec<_> = HandRange::parse(range)?.combos().collect();
+            // Keep sampling repeatable for a seeded deck.
+            combos.sort();
+            Ok(combos)
+        };
+        Ok(Self {
+            default: combos(&config.opponent_range)?,
+            seats: config
+                .seat_ranges
+                .iter()
+                .map(|(seat, range)| Ok((*seat, combos(range)?)))
+                .collect::<Result<_, RSPokerError>>()?,
+        })
+    }
+
+    fn for_seat(&self, idx: usize) -> &[(Card, Card)] {
+        self.seats.get(&idx).unwrap_or(&self.default)
+    }
+}
+
+/// An agent that plays by comparing its equity with the pot odds.
+///
+/// Every decision it runs out the board `iterations` times with
+/// `MonteCarloGame` to estimate its share of the pot against the players
+/// still in the hand. Opponents hold any two cards unless they have been
+/// given an assumed range.
+///
+/// - If the equity is below the pot odds (the call amount over the pot after
+///   calling) it folds, or checks when that's free.
+/// - If the equity is well above a fair share of the pot it bets or raises. How
+///   far above is controlled by `aggression`.
+/// - Otherwise it calls.
+///
+/// Every decision is logged at trace level with the equity and pot odds
+/// that led to it.
+///
+/// `MonteCarloGame` only knows hold'em, so with more hole cards (Omaha) it
+/// checks when it can and folds otherwise.
+#[derive(Debug, Clone)]
+pub struct EquityAgent {
+    config: Arc<EquityConfig>,
+    ranges: Arc<ParsedRanges>,
+}
+
+impl EquityAgent {
+    /// Create an agent, failing if any of the ranges can't be parsed.
+    pub fn new(config: EquityConfig) -> Result<Self, RSPokerError> {
+        let ranges = ParsedRanges::parse(&config)?;
+        Ok(Self {
+            config: Arc::new(config),
+            ranges: Arc::new(ranges),
+        })
+    }
+
+    pub fn config(&self) -> &EquityConfig {
+        &self.config
+    }
+
+    /// The share of the pot this seat expects to win if the hand were
+    /// checked down from here. `None` if it can't be simulated.
+    pub fn estimate_equity(&self, view: &PlayerView) -> Option<f32> {
+        let idx = view.player_idx();
+        if view.hole_cards().len() != 2 {
+            return None;
+        }
+        let mut known = view.hands[idx];
+        let opponents: Vec<usize> = (0..view.num_players)
+            .filter(|&i| i != idx && (view.player_active.get(i) || view.player_all_in.get(i)))
+            .collect();
+        if opponents.is_empty() {
+            return Some(1.0);
+        }
+
+        let board = Hand::new_with_cards(view.board.clone());
+        let iterations = self.config.iterations.max(1);
+        if opponents
+            .iter()
+            .all(|&i| self.ranges.for_seat(i).is_empty())
+        {
+            let mut hands = vec![known];
+            hands.extend(opponents.iter().map(|_| board));
+            let mut monte = MonteCarloGame::new(hands).ok()?;
+            return Some(monte.estimate_equity(iterations)[0]);
+        }
+
+        // Guess hands for the opponents with ranges, then run out a few
+        // boards for each guess.
+        let mut rng = rng();
+        let samples = (iterations / ITERATIONS_PER_RANGE_SAMPLE).max(1);
+        let mut total = 0.0;
+        for _ in 0..samples {
+            let mut hands = vec![view.hands[idx]];
+            for &opponent in &opponents {
+                let mut hand = board;
+                let combo = self
+                    .ranges
+                    .for_seat(opponent)
+                    .iter()
+                    .filter(|(high, low)| !known.contains(high) && !known.contains(low))
+                    .collect::<Vec<_>>()
+                    .choose(&mut rng)
+                    .copied();
+                // With no possible combo left the opponent gets random cards.
+                if let Some(&(high, low)) = combo {
+                    known.insert(high);
+                    known.insert(low);
+                    hand.insert(high);
+                    hand.insert(low);
+                }
+                hands.push(hand);
+            }
+            known = view.hands[idx];
+
+            let mut monte = MonteCarloGame::new(hands).ok()?;
+            total += monte.estimate_equity(ITERATIONS_PER_RANGE_SAMPLE)[0];
+        }
+        Some(total / samples as f32)
+    }
+}
+
+impl Default for EquityAgent {
+    fn default() -> Self {
+        Self::new(EquityConfig::default()).expect("Default config is valid")
+    }
+}
+
+impl Agent for EquityAgent {
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        let to_call = to_call(view);
+        // Everyone else is all in so there's nobody left to bet against.
+        if view.num_active_players() == 1 && to_call <= 0.0 {
+            return AgentAction::Call;
+        }
+        let Some(equity) = self.estimate_equity(view) else {
+            return check_or_fold(to_call);
+        };
+
+        let pot_after_call = view.total_pot() + to_call;
+        let pot_odds = to_call / pot_after_call;
+        // With this many players in the hand an even share of the pot.
+        let players_in_hand = (view.player_active | view.player_all_in).count() as f32;
+        let fair_share = 1.0 / players_in_hand;
+        let raise_equity = if self.config.aggression > 0.0 {
+            fair_share + (1.0 - fair_share) / (2.0 * self.config.aggression)
+        } else {
+            f32::INFINITY
+        };
+
+        let action = if equity >= raise_equity {
+            raise_to(
+                view,
+                view.current_round_bet() + pot_after_call * self.config.bet_fraction,
+            )
+        } else if equity >= pot_odds {
+            AgentAction::Call
+        } else {
+            check_or_fold(to_call)
+        };
+
+        event!(
+            Level::TRACE,
+            equity,
+            pot_odds,
+            raise_equity,
+            ?action,
+            "equity_agent_decision"
+        );
+        action
+    }
+}
+
+/// Generates `EquityAgent`s that all share the same parsed ranges.
+#[derive(Debug, Clone, Default)]
+pub struct EquityAgentGenerator {
+    agent: EquityAgent,
+}
+
+impl EquityAgentGenerator {
+    pub fn new(config: EquityConfig) -> Result<Self, RSPokerError> {
+        Ok(Self {
+            agent: EquityAgent::new(config)?,
+        })
+    }
+}
+
+impl AgentGenerator for EquityAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        Box::new(self.agent.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{
+        HoldemSimulationBuilder,
+        action::Action,
+        agent::{CallingAgent, RandomAgent},
+        test_util::assert_valid_game_state,
+    };
+
+    use super::*;
+
+    /// A heads up game with the blinds posted and the button, who acts
+    /// first, holding `hero`.
+    fn heads_up(hero: &str, villain: &str) -> GameState {
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.hands[0] = Hand::new_from_str(hero).unwrap();
+        game_state.hands[1] = Hand::new_from_str(villain).unwrap();
+        game_state.advance_round();
+        game_state.do_bet(1.0, true).unwrap();
+        game_state.do_bet(2.0, true).unwrap();
+        game_state
+    }
+
+    fn agent(config: EquityConfig) -> EquityAgent {
+        EquityAgent::new(config).unwrap()
+    }
+
+    #[test]
+    fn test_equity_against_any_two_cards() {
+        let game_state = heads_up("AsAh", "7c2d");
+        let view = PlayerView::new(0, &game_state, &[]);
+        let equity = EquityAgent::default().estimate_equity(&view).unwrap();
+        assert!(equity > 0.75, "Aces should be big favourites: {equity}");
+    }
+
+    #[test]
+    fn test_equity_against_a_range() {
+        let game_state = heads_up("KsKh", "7c2d");
+        let view = PlayerView::new(0, &game_state, &[]);
+        let agent = agent(EquityConfig {
+            seat_ranges: HashMap::from([(1, "AA".to_string())]),
+            ..EquityConfig::default()
+        });
+        let equity = agent.estimate_equity(&view).unwrap();
+        assert!(equity < 0.3, "Kings are way behind aces: {equity}");
+    }
+
+    #[test]
+    fn test_raises_big_favourite() {
+        let game_state = heads_up("AsAh", "7c2d");
+        let view = PlayerView::new(0, &game_state, &[]);
+        assert!(matches!(
+            EquityAgent::default().act(0, &view),
+            AgentAction::Bet(_)
+        ));
+    }
+
+    #[test]
+    fn test_folds_without_pot_odds() {
+        let mut game_state = heads_up("7c2d", "AsAh");
+        // The button shoves and the big blind has to call 98 to win 102.
+        game_state.do_bet(100.0, false).unwrap();
+        let view = PlayerView::new(0, &game_state, &[]);
+        let mut agent = agent(EquityConfig {
+            opponent_range: "QQ+".to_string(),
+            ..EquityConfig::default()
+        });
+        assert_eq!(AgentAction::Fold, agent.act(0, &view));
+    }
+
+    #[test]
+    fn test_passive_never_raises() {
+        let game_state = heads_up("AsAh", "7c2d");
+        let view = PlayerView::new(0, &game_state, &[]);
+        let mut agent = agent(EquityConfig {
+            aggression: 0.0,
+            ..EquityConfig::default()
+        });
+        assert_eq!(AgentAction::Call, agent.act(0, &view));
+    }
+
+    #[test]
+    fn test_bad_range_is_an_error() {
+        assert!(
+            EquityAgent::new(EquityConfig {
+                opponent_range: "AK-J9".to_string(),
+                ..EquityConfig::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test_log::test]
+    fn test_equity_agents_play_valid_games() {
+        let mut rng = rand::rng();
+        let config = EquityConfig {
+            iterations: 100,
+            opponent_range: "22+,A2s+,KTs+,ATo+,KQo".to_string(),
+            ..EquityConfig::default()
+        };
+        for _ in 0..10 {
+            let game_state = GameState::new_starting(vec![100.0; 4], 2.0, 1.0, 0.0, 0);
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::new(agent(config.clone())),
+                Box::<RandomAgent>::default(),
+                Box::<EquityAgent>::default(),
+                Box::<CallingAgent>::default(),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+
+            assert!(sim.game_state.is_complete());
+            assert_valid_game_state(&sim.game_state);
+            for action in &sim.actions {
+                if let Action::FailedAction(failed) = action {
+                    assert!(
+                        failed.result.idx % 2 == 1,
+                        "Equity agent made an invalid action {failed:?}"
+                    );
+                }
+            }
+        }
+    }
+}
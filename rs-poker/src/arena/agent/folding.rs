@@ -1,4 +1,4 @@
-use crate::arena::{action::AgentAction, game_state::GameState};
+use crate::arena::{PlayerView, action::AgentAction, game_state::GameState};
 
 use super::{Agent, AgentGenerator};
 
@@ -7,10 +7,10 @@ use super::{Agent, AgentGenerator};
 pub struct FoldingAgent;
 
 impl Agent for FoldingAgent {
-    fn act(self: &mut FoldingAgent, _id: u128, game_state: &GameState) -> AgentAction {
-        let count = game_state.current_round_num_active_players() + game_state.num_all_in_players();
+    fn act(self: &mut FoldingAgent, _id: u128, view: &PlayerView) -> AgentAction {
+        let count = view.current_round_num_active_players() + view.num_all_in_players();
         if count == 1 {
-            AgentAction::Bet(game_state.current_round_bet())
+            AgentAction::Bet(view.current_round_bet())
         } else {
             AgentAction::Fold
         }
@@ -0,0 +1,492 @@
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use tracing::{Level, event};
+
+use crate::{
+    arena::{
+        GameState, HoldemSimulationBuilder, PlayerView,
+        action::{Action, AgentAction, PlayedActionPayload},
+    },
+    core::Card,
+};
+
+use super::{
+    Agent, AgentGenerator, CallingAgentGenerator,
+    betting::{check_or_fold, raise_to, to_call},
+};
+
+/// The search budget and shape for an `IsmctsAgent`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct IsmctsConfig {
+    /// The most rollouts to run for each decision.
+    pub iterations: usize,
+    /// Stop searching after this many milliseconds even if there are
+    /// iterations left.
+    pub time_limit_ms: Option<u64>,
+    /// The UCB1 exploration constant. Rewards are scaled by the starting
+    /// stack so they are roughly between -1 and 1.
+    pub exploration: f32,
+    /// Bets and raises to consider, as fractions of the pot after calling.
+    /// Folding, calling and going all in are always considered.
+    pub bet_fractions: Vec<f32>,
+}
+
+impl Default for IsmctsConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            time_limit_ms: None,
+            exploration: 0.7,
+            bet_fractions: vec![0.5, 1.0],
+        }
+    }
+}
+
+impl IsmctsConfig {
+    /// The actions the search chooses between, without duplicates.
+    pub fn candidate_actions(&self, game_state: &GameState) -> Vec<AgentAction> {
+        let to_call = to_call(game_state);
+        let mut actions = Vec::with_capacity(self.bet_fractions.len() + 3);
+        if to_call > 0.0 {
+            actions.push(AgentAction::Fold);
+        }
+        actions.push(AgentAction::Call);
+
+        let all_in =
+            game_state.current_round_current_player_bet() + game_state.current_player_stack();
+        let pot_after_call = game_state.total_pot + to_call;
+        for fraction in &self.bet_fractions {
+            let target = game_state.current_round_bet() + pot_after_call * fraction;
+            if let AgentAction::Bet(amount) = raise_to(game_state, target)
+                && amount < all_in
+                && !actions.contains(&AgentAction::Bet(amount))
+            {
+                actions.push(AgentAction::Bet(amount));
+            }
+        }
+        if all_in > game_state.current_round_bet() {
+            actions.push(AgentAction::AllIn);
+        }
+        actions
+    }
+}
+
+/// Something public that happened after the decision being searched.
+///
+/// Sequences of these identify the information sets in the search tree.
+/// Bet sizes are bucketed so that rollout agents betting slightly
+/// different amounts still share nodes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum InfoEvent {
+    Fold(usize),
+    Call(usize),
+    Raise(usize, u8),
+    Card(Card),
+}
+
+impl InfoEvent {
+    fn from_payload(payload: &PlayedActionPayload) -> Self {
+        if payload.action == AgentAction::Fold {
+            InfoEvent::Fold(payload.idx)
+        } else if payload.final_bet > payload.starting_bet {
+            let pot_fraction = payload.raise_amount() / payload.starting_pot.max(1.0);
+            let bucket = match pot_fraction {
+                f if f < 0.4 => 0,
+                f if f < 0.8 => 1,
+                f if f < 1.5 => 2,
+                _ => 3,
+            };
+            InfoEvent::Raise(payload.idx, bucket)
+        } else {
+            InfoEvent::Call(payload.idx)
+        }
+    }
+
+    fn key(actions: &[Action]) -> Vec<InfoEvent> {
+        actions
+            .iter()
+            .filter_map(|action| match action {
+                Action::PlayedAction(payload) => Some(InfoEvent::from_payload(payload)),
+                Action::FailedAction(failed) => Some(InfoEvent::from_payload(&failed.result)),
+                Action::DealCommunity(card) => Some(InfoEvent::Card(*card)),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Edge {
+    action: AgentAction,
+    visits: u32,
+    total_reward: f32,
+}
+
+impl Edge {
+    fn mean(&self) -> f32 {
+        self.total_reward / self.visits.max(1) as f32
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    visits: u32,
+    edges: Vec<Edge>,
+}
+
+impl Node {
+    fn new(actions: Vec<AgentAction>) -> Self {
+        Self {
+            visits: 0,
+            edges: actions
+                .into_iter()
+                .map(|action| Edge {
+                    action,
+                    visits: 0,
+                    total_reward: 0.0,
+                })
+                .collect(),
+        }
+    }
+
+    /// UCB1, trying every edge once first.
+    fn select(&self, exploration: f32) -> usize {
+        if let Some(unvisited) = self.edges.iter().position(|edge| edge.visits == 0) {
+            return unvisited;
+        }
+        let log_visits = (self.visits.max(1) as f32).ln();
+        let score =
+            |edge: &Edge| edge.mean() + exploration * (log_visits / edge.visits as f32).sqrt();
+        self.edges
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| score(a).total_cmp(&score(b)))
+            .map_or(0, |(idx, _)| idx)
+    }
+}
+
+/// The information set tree for one decision.
+#[derive(Debug, Default)]
+struct SearchTree {
+    nodes: HashMap<Vec<InfoEvent>, Node>,
+    // The (node, edge) pairs chosen during the current rollout.
+    path: Vec<(Vec<InfoEvent>, usize)>,
+}
+
+impl SearchTree {
+    fn backpropagate(&mut self, reward: f32) {
+        for (key, edge_idx) in self.path.drain(..) {
+            if let Some(node) = self.nodes.get_mut(&key) {
+                node.visits += 1;
+                let edge = &mut node.edges[edge_idx];
+                edge.visits += 1;
+                edge.total_reward += reward;
+            }
+        }
+    }
+}
+
+/// Plays the searching seat during a rollout. Inside the tree it picks
+/// actions with UCB1, adds one new node per rollout, and after that hands
+/// over to the rollout agent.
+struct TreeAgent {
+    tree: Rc<RefCell<SearchTree>>,
+    config: Arc<IsmctsConfig>,
+    rollout: Box<dyn Agent>,
+    expanded: bool,
+}
+
+impl Agent for TreeAgent {
+    fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction {
+        let key = InfoEvent::key(view.actions());
+        let mut tree = self.tree.borrow_mut();
+        if !tree.nodes.contains_key(&key) {
+            if self.expanded {
+                drop(tree);
+                return self.rollout.act(id, view);
+            }
+            self.expanded = true;
+            let actions = self.config.candidate_actions(view);
+            tree.nodes.insert(key.clone(), Node::new(actions));
+        }
+
+        let node = &tree.nodes[&key];
+        let edge_idx = node.select(self.config.exploration);
+        let action = node.edges[edge_idx].action.clone();
+        tree.path.push((key, edge_idx));
+        action
+    }
+}
+
+/// An information set Monte Carlo tree search (ISMCTS) agent.
+///
+/// At each decision it repeatedly:
+///
+/// 1. Deals the opponents still in the hand random hole cards that don't clash
+///    with its own cards or the board.
+/// 2. Plays the rest of the hand out as a `HoldemSimulation`. The opponents
+///    (and this seat once it leaves the search tree) are played by agents from
+///    the rollout generator.
+/// 3. Adds the result to every information set node it chose an action at.
+///
+/// Nodes are keyed by what this seat can see after the decision, so
+/// rollouts with different opponent cards share statistics. It stops after
+/// `iterations` rollouts or when the time limit is up, whichever is first,
+/// and plays the root action with the best average reward.
+#[derive(Clone)]
+pub struct IsmctsAgent {
+    config: Arc<IsmctsConfig>,
+    rollout: Rc<dyn AgentGenerator>,
+}
+
+impl IsmctsAgent {
+    pub fn new(config: IsmctsConfig, rollout: Rc<dyn AgentGenerator>) -> Self {
+        Self {
+            config: Arc::new(config),
+            rollout,
+        }
+    }
+
+    pub fn config(&self) -> &IsmctsConfig {
+        &self.config
+    }
+
+    /// Run the search and return the root node.
+    fn search(&self, view: &PlayerView) -> Option<Node> {
+        let me = view.player_idx();
+        let scale = view.starting_stacks[me].max(view.big_blind).max(1.0);
+        let deadline = self
+            .config
+            .time_limit_ms
+            .map(|ms| Instant::now() + Duration::from_millis(ms));
+        let tree = Rc::new(RefCell::new(SearchTree::default()));
+        let mut rng = rand::rng();
+
+        for iteration in 0..self.config.iterations.max(1) {
+            if iteration > 0 && deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+
+            let game_state = view.sample_game_state(&mut rng);
+            let agents = (0..game_state.num_players)
+                .map(|idx| {
+                    let rollout = self.rollout.generate(&game_state);
+                    if idx == me {
+                        Box::new(TreeAgent {
+                            tree: tree.clone(),
+                            config: self.config.clone(),
+                            rollout,
+                            expanded: false,
+                        }) as Box<dyn Agent>
+                    } else {
+                        rollout
+                    }
+                })
+                .collect();
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .ok()?;
+            sim.run(&mut rng);
+
+            let reward = sim.game_state.player_reward(me) / scale;
+            tree.borrow_mut().backpropagate(reward);
+        }
+
+        let mut tree = tree.borrow_mut();
+        tree.nodes.remove(&Vec::new())
+    }
+}
+
+impl Default for IsmctsAgent {
+    fn default() -> Self {
+        Self::new(IsmctsConfig::default(), Rc::new(CallingAgentGenerator))
+    }
+}
+
+impl Agent for IsmctsAgent {
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        let to_call = to_call(view);
+        // Everyone else is all in so there's nothing to decide.
+        if view.num_active_players() == 1 && to_call <= 0.0 {
+            return AgentAction::Call;
+        }
+
+        let Some(root) = self.search(view) else {
+            return check_or_fold(to_call);
+        };
+        let best = root
+            .edges
+            .iter()
+            .filter(|edge| edge.visits > 0)
+            .max_by(|a, b| a.mean().total_cmp(&b.mean()));
+        event!(
+            Level::TRACE,
+            rollouts = root.visits,
+            edges = ?root.edges,
+            "ismcts_search_complete"
+        );
+        best.map_or_else(|| check_or_fold(to_call), |edge| edge.action.clone())
+    }
+}
+
+/// Generates `IsmctsAgent`s that all use the same rollout generator.
+#[derive(Clone, Default)]
+pub struct IsmctsAgentGenerator {
+    agent: IsmctsAgent,
+}
+
+impl IsmctsAgentGenerator {
+    pub fn new(config: IsmctsConfig, rollout: Rc<dyn AgentGenerator>) -> Self {
+        Self {
+            agent: IsmctsAgent::new(config, rollout),
+        }
+    }
+}
+
+impl AgentGenerator for IsmctsAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        Box::new(self.agent.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        arena::{
+            agent::{RandomAgent, RandomAgentGenerator},
+            test_util::assert_valid_game_state,
+        },
+        core::Hand,
+    };
+
+    use super::*;
+
+    /// Heads up preflop after the button shoves, with the big blind holding
+    /// `hero`.
+    fn facing_shove(hero: &str) -> GameState {
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.hands[1] = Hand::new_from_str(hero).unwrap();
+        game_state.advance_round();
+        game_state.do_bet(1.0, true).unwrap();
+        game_state.sb_posted = true;
+        game_state.do_bet(2.0, true).unwrap();
+        game_state.bb_posted = true;
+        game_state.do_bet(100.0, false).unwrap();
+        game_state
+    }
+
+    fn agent(iterations: usize) -> IsmctsAgent {
+        IsmctsAgent::new(
+            IsmctsConfig {
+                iterations,
+                ..IsmctsConfig::default()
+            },
+            Rc::new(CallingAgentGenerator),
+        )
+    }
+
+    #[test]
+    fn test_candidate_actions() {
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.do_bet(1.0, true).unwrap();
+        game_state.do_bet(2.0, true).unwrap();
+
+        // Pot of 3, 1 to call: half pot is to 4, pot is to 6.
+        assert_eq!(
+            vec![
+                AgentAction::Fold,
+                AgentAction::Call,
+                AgentAction::Bet(4.0),
+                AgentAction::Bet(6.0),
+                AgentAction::AllIn
+            ],
+            IsmctsConfig::default().candidate_actions(&game_state)
+        );
+
+        let game_state = facing_shove("AsAh");
+        assert_eq!(
+            vec![AgentAction::Fold, AgentAction::Call],
+            IsmctsConfig::default().candidate_actions(&game_state)
+        );
+    }
+
+    #[test]
+    fn test_folds_trash_to_shove() {
+        let game_state = facing_shove("7c2d");
+        let view = PlayerView::new(1, &game_state, &[]);
+        assert_eq!(AgentAction::Fold, agent(200).act(0, &view));
+    }
+
+    #[test]
+    fn test_calls_shove_with_aces() {
+        let game_state = facing_shove("AsAh");
+        let view = PlayerView::new(1, &game_state, &[]);
+        assert_eq!(AgentAction::Call, agent(200).act(0, &view));
+    }
+
+    #[test]
+    fn test_time_limit() {
+        let game_state = facing_shove("AsAh");
+        let view = PlayerView::new(1, &game_state, &[]);
+        let mut agent = IsmctsAgent::new(
+            IsmctsConfig {
+                iterations: usize::MAX,
+                time_limit_ms: Some(50),
+                ..IsmctsConfig::default()
+            },
+            Rc::new(CallingAgentGenerator),
+        );
+        let start = Instant::now();
+        agent.act(0, &view);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test_log::test]
+    fn test_plays_valid_games() {
+        let mut rng = rand::rng();
+        let generator = IsmctsAgentGenerator::new(
+            IsmctsConfig {
+                iterations: 30,
+                ..IsmctsConfig::default()
+            },
+            Rc::new(RandomAgentGenerator::default()),
+        );
+        for _ in 0..5 {
+            let game_state = GameState::new_starting(vec![100.0; 3], 2.0, 1.0, 0.0, 0);
+            let agents: Vec<Box<dyn Agent>> = vec![
+                generator.generate(&game_state),
+                Box::<RandomAgent>::default(),
+                Box::<RandomAgent>::default(),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+
+            assert!(sim.game_state.is_complete());
+            assert_valid_game_state(&sim.game_state);
+            for action in &sim.actions {
+                if let Action::FailedAction(failed) = action {
+                    assert_ne!(0, failed.result.idx, "Invalid search action {failed:?}");
+                }
+            }
+        }
+    }
+}
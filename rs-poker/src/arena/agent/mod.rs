@@ -3,12 +3,21 @@
 //!
 //! Some basic agents are provided as a way of testing baseline value.
 mod all_in;
+mod betting;
 mod calling;
+mod equity;
 mod folding;
+mod ismcts;
+mod opponent_model;
+#[cfg(feature = "serde")]
+mod process;
 mod random;
+#[cfg(feature = "serde")]
+mod registry;
 mod replay;
+mod tag;
 
-use super::{Historian, action::AgentAction, game_state::GameState};
+use super::{Historian, PlayerView, action::AgentAction, game_state::GameState};
 /// This is the trait that you need to implement in order to implenet
 /// different strategies. It's up to you to to implement the logic and state.
 ///
@@ -17,7 +26,17 @@ use super::{Historian, action::AgentAction, game_state::GameState};
 /// not to need `Arc<Mutex<T>>`'s overhead.
 pub trait Agent {
     /// This is the method that will be called by the game to get the action
-    fn act(&mut self, id: u128, game_state: &GameState) -> AgentAction;
+    ///
+    /// The view only contains what this seat is allowed to know unless
+    /// `omniscient` returns true.
+    fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction;
+
+    /// Agents that need to see every player's hole cards (for example CFR
+    /// which simulates from the true game state) can opt in here. Defaults to
+    /// false so agents can't cheat.
+    fn omniscient(&self) -> bool {
+        false
+    }
 
     // Some Agents may need to be able to see the changes in the game
     // state. This is the method that will be called to create historians
@@ -71,6 +90,18 @@ where
 
 pub use all_in::{AllInAgent, AllInAgentGenerator};
 pub use calling::{CallingAgent, CallingAgentGenerator};
+pub use equity::{EquityAgent, EquityAgentGenerator, EquityConfig};
 pub use folding::{FoldingAgent, FoldingAgentGenerator};
+pub use ismcts::{IsmctsAgent, IsmctsAgentGenerator, IsmctsConfig};
+pub use opponent_model::{
+    OpponentModelAgent, OpponentModelAgentGenerator, OpponentModelConfig, OpponentType,
+};
+#[cfg(feature = "serde")]
+pub use process::{
+    DEFAULT_PROCESS_AGENT_TIMEOUT, ProcessAgent, ProcessAgentError, ProcessAgentGenerator,
+};
 pub use random::{RandomAgent, RandomAgentGenerator, RandomPotControlAgent};
+#[cfg(feature = "serde")]
+pub use registry::{AgentConfig, AgentConfigError, AgentFactory, AgentRegistry};
 pub use replay::{SliceReplayAgent, VecReplayAgent};
+pub use tag::{HandStrength, Position, PositionRanges, TagAgent, TagAgentGenerator, TagConfig};
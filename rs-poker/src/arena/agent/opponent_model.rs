@@ -0,0 +1,426 @@
+use std::{cell::RefCell, rc::Rc};
+
+use rand::{Rng, rng};
+use tracing::{Level, event};
+
+use crate::arena::{
+    GameState, Historian, PlayerView,
+    action::AgentAction,
+    game_state::Round,
+    historian::{PlayerStats, StatsStorage, StatsTrackingHistorian},
+};
+
+use super::{
+    Agent, AgentGenerator, EquityAgent, EquityConfig,
+    betting::{check_or_fold, raise_to, to_call},
+};
+
+/// How the `OpponentModelAgent` reads an opponent from their statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpponentType {
+    /// Not enough hands seen yet.
+    Unknown,
+    /// Folds to continuation bets too often. Bluff them.
+    OverFolder,
+    /// Plays lots of hands and rarely bets or raises. Value bet them thinly
+    /// and never bluff.
+    CallingStation,
+    /// Nothing to exploit.
+    Regular,
+}
+
+/// Everything that decides how an `OpponentModelAgent` plays.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct OpponentModelConfig {
+    /// The number of boards to run out for each equity estimate.
+    pub iterations: usize,
+    /// How eager the agent is to bet and raise for value, like
+    /// `EquityConfig::aggression`.
+    pub aggression: f32,
+    /// Value bets and raises add this fraction of the pot (after calling).
+    pub bet_fraction: f32,
+
+    /// Hands an opponent has to have played before they're read.
+    pub min_hands: usize,
+    /// Continuation bets an opponent has to have faced before they can be
+    /// an over folder.
+    pub min_cbets_faced: usize,
+    /// Opponents folding to continuation bets at least this often are over
+    /// folders.
+    pub over_folder_fold_to_cbet: f32,
+    /// How often to bluff when checked to by nothing but over folders.
+    pub bluff_frequency: f64,
+    /// Bluffs are this fraction of the pot.
+    pub bluff_fraction: f32,
+
+    /// Opponents playing at least this fraction of hands, with an
+    /// aggression factor of at most `station_aggression_factor`, are calling
+    /// stations.
+    pub station_vpip: f32,
+    pub station_aggression_factor: f32,
+    /// Against calling stations `aggression` is multiplied by this.
+    pub station_aggression_boost: f32,
+}
+
+impl Default for OpponentModelConfig {
+    fn default() -> Self {
+        Self {
+            iterations: 500,
+            aggression: 1.0,
+            bet_fraction: 0.75,
+            min_hands: 20,
+            min_cbets_faced: 5,
+            over_folder_fold_to_cbet: 0.6,
+            bluff_frequency: 0.8,
+            bluff_fraction: 0.5,
+            station_vpip: 0.4,
+            station_aggression_factor: 1.0,
+            station_aggression_boost: 2.0,
+        }
+    }
+}
+
+impl OpponentModelConfig {
+    /// How an `OpponentModelAgent` with this config reads a player.
+    pub fn classify(&self, stats: &PlayerStats) -> OpponentType {
+        if stats.hands < self.min_hands {
+            return OpponentType::Unknown;
+        }
+        let fold_to_cbet = stats.flop.fold_to_cbet;
+        if fold_to_cbet.opportunities >= self.min_cbets_faced
+            && fold_to_cbet
+                .frequency()
+                .is_some_and(|fold| fold >= self.over_folder_fold_to_cbet)
+        {
+            return OpponentType::OverFolder;
+        }
+        let loose = stats
+            .vpip
+            .frequency()
+            .is_some_and(|vpip| vpip >= self.station_vpip);
+        // Someone who never bets after the flop is as passive as it gets.
+        let passive = stats
+            .aggression_factor()
+            .is_none_or(|af| af <= self.station_aggression_factor);
+        if loose && passive {
+            OpponentType::CallingStation
+        } else {
+            OpponentType::Regular
+        }
+    }
+}
+
+/// An agent that keeps statistics on its opponents across hands and adapts
+/// to them.
+///
+/// It plays like an `EquityAgent`, comparing Monte Carlo equity with the pot
+/// odds, and then exploits what it has learned:
+///
+/// - Checked to by nothing but over folders after the flop it bluffs.
+/// - Against calling stations it value bets thinner and never bluffs.
+///
+/// Statistics (VPIP, PFR, aggression factor, fold to c-bet) are gathered
+/// with a `StatsTrackingHistorian` that the agent plugs in through
+/// `Agent::historian`. Every agent made by the same
+/// `OpponentModelAgentGenerator` shares the same storage, so use one
+/// generator per seat and keep players in the same seats.
+#[derive(Debug, Clone)]
+pub struct OpponentModelAgent {
+    config: OpponentModelConfig,
+    equity: EquityAgent,
+    stats: Rc<RefCell<StatsStorage>>,
+}
+
+impl OpponentModelAgent {
+    pub fn new(config: OpponentModelConfig, stats: Rc<RefCell<StatsStorage>>) -> Self {
+        let equity = EquityAgent::new(EquityConfig {
+            iterations: config.iterations,
+            ..EquityConfig::default()
+        })
+        .expect("Any two cards is always a valid range");
+        Self {
+            config,
+            equity,
+            stats,
+        }
+    }
+
+    /// The statistics gathered so far.
+    pub fn stats(&self) -> Rc<RefCell<StatsStorage>> {
+        self.stats.clone()
+    }
+
+    /// How every opponent still in the hand is read.
+    fn read_opponents(&self, view: &PlayerView) -> Vec<OpponentType> {
+        let stats = self.stats.borrow();
+        (view.player_active | view.player_all_in)
+            .ones()
+            .filter(|&idx| idx != view.player_idx())
+            .map(|idx| self.config.classify(&stats.player_stats(idx)))
+            .collect()
+    }
+}
+
+impl Agent for OpponentModelAgent {
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        let to_call = to_call(view);
+        // Everyone else is all in so there's nobody left to bet against.
+        if view.num_active_players() == 1 && to_call <= 0.0 {
+            return AgentAction::Call;
+        }
+        let Some(equity) = self.equity.estimate_equity(view) else {
+            return check_or_fold(to_call);
+        };
+
+        let opponents = self.read_opponents(view);
+        let stations = opponents.contains(&OpponentType::CallingStation);
+        let over_folders =
+            !opponents.is_empty() && opponents.iter().all(|o| *o == OpponentType::OverFolder);
+
+        let aggression = if stations {
+            self.config.aggression * self.config.station_aggression_boost
+        } else {
+            self.config.aggression
+        };
+        let pot_after_call = view.total_pot() + to_call;
+        let pot_odds = to_call / pot_after_call;
+        let fair_share = 1.0 / (opponents.len() + 1) as f32;
+        let raise_equity = if aggression > 0.0 {
+            fair_share + (1.0 - fair_share) / (2.0 * aggression)
+        } else {
+            f32::INFINITY
+        };
+
+        let action = if equity >= raise_equity {
+            raise_to(
+                view,
+                view.current_round_bet() + pot_after_call * self.config.bet_fraction,
+            )
+        } else if to_call <= 0.0
+            && over_folders
+            && view.round != Round::Preflop
+            && rng().random_bool(self.config.bluff_frequency.clamp(0.0, 1.0))
+        {
+            raise_to(view, view.total_pot() * self.config.bluff_fraction)
+        } else if equity >= pot_odds {
+            AgentAction::Call
+        } else {
+            check_or_fold(to_call)
+        };
+
+        event!(
+            Level::TRACE,
+            equity,
+            pot_odds,
+            raise_equity,
+            ?opponents,
+            ?action,
+            "opponent_model_decision"
+        );
+        action
+    }
+
+    fn historian(&self) -> Option<Box<dyn Historian>> {
+        Some(Box::new(StatsTrackingHistorian::new_with_storage(
+            self.stats.clone(),
+        )))
+    }
+}
+
+/// Generates `OpponentModelAgent`s that remember what they've seen in
+/// earlier hands.
+#[derive(Debug, Clone)]
+pub struct OpponentModelAgentGenerator {
+    agent: OpponentModelAgent,
+}
+
+impl OpponentModelAgentGenerator {
+    pub fn new(config: OpponentModelConfig) -> Self {
+        Self {
+            agent: OpponentModelAgent::new(
+                config,
+                Rc::new(RefCell::new(StatsStorage::new_with_num_players(0))),
+            ),
+        }
+    }
+
+    /// The statistics gathered so far, shared with every generated agent.
+    pub fn stats(&self) -> Rc<RefCell<StatsStorage>> {
+        self.agent.stats()
+    }
+}
+
+impl Default for OpponentModelAgentGenerator {
+    fn default() -> Self {
+        Self::new(OpponentModelConfig::default())
+    }
+}
+
+impl AgentGenerator for OpponentModelAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        Box::new(self.agent.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        arena::{
+            HoldemSimulationBuilder,
+            agent::{CallingAgentGenerator, FoldingAgentGenerator, Position},
+            competition::{HoldemCompetition, StandardSimulationIterator},
+            game_state::CloneGameStateGenerator,
+            historian::{StatCount, StreetStats},
+            test_util::assert_valid_game_state,
+        },
+        core::Hand,
+    };
+
+    use super::*;
+
+    fn stats_with(hands: usize, vpip: usize, aggressive: usize, calls: usize) -> PlayerStats {
+        PlayerStats {
+            hands,
+            vpip: StatCount {
+                count: vpip,
+                opportunities: hands,
+            },
+            flop: StreetStats {
+                aggressive,
+                calls,
+                ..StreetStats::default()
+            },
+            ..PlayerStats::default()
+        }
+    }
+
+    fn over_folder(folds: usize) -> PlayerStats {
+        let mut stats = stats_with(50, 10, 20, 5);
+        stats.flop.fold_to_cbet = StatCount {
+            count: folds,
+            opportunities: 10,
+        };
+        stats
+    }
+
+    #[test]
+    fn test_classify() {
+        let config = OpponentModelConfig::default();
+
+        assert_eq!(
+            OpponentType::Unknown,
+            config.classify(&stats_with(5, 5, 0, 5))
+        );
+        assert_eq!(
+            OpponentType::CallingStation,
+            config.classify(&stats_with(50, 40, 2, 30))
+        );
+        assert_eq!(
+            OpponentType::Regular,
+            config.classify(&stats_with(50, 10, 20, 5))
+        );
+        assert_eq!(OpponentType::OverFolder, config.classify(&over_folder(8)));
+    }
+
+    #[test]
+    fn test_bluffs_over_folders() {
+        let mut stats = StatsStorage::new_with_num_players(2);
+        stats.by_position[1].insert(Position::BigBlind, over_folder(10));
+        let mut agent = OpponentModelAgent::new(
+            OpponentModelConfig {
+                bluff_frequency: 1.0,
+                ..OpponentModelConfig::default()
+            },
+            Rc::new(RefCell::new(stats)),
+        );
+
+        // Heads up on the flop with nothing, checked to by the big blind.
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.hands[0] = Hand::new_from_str("7c2d").unwrap();
+        game_state.hands[1] = Hand::new_from_str("AsAh").unwrap();
+        game_state.advance_round();
+        game_state.do_bet(1.0, true).unwrap();
+        game_state.do_bet(2.0, true).unwrap();
+        game_state.do_bet(2.0, false).unwrap();
+        game_state.do_bet(2.0, false).unwrap();
+        game_state.advance_round();
+        let board = Hand::new_from_str("KsQsJh").unwrap();
+        for hand in game_state.hands.iter_mut() {
+            hand.extend(board.iter());
+        }
+        game_state.board.extend(board.iter());
+        game_state.advance_round();
+        game_state.do_bet(0.0, false).unwrap();
+        assert_eq!(0, game_state.to_act_idx());
+
+        let view = PlayerView::new(0, &game_state, &[]);
+        assert_eq!(AgentAction::Bet(2.0), agent.act(0, &view));
+
+        // Without the read it just checks.
+        let mut agent = OpponentModelAgent::new(
+            OpponentModelConfig::default(),
+            Rc::new(RefCell::new(StatsStorage::new_with_num_players(2))),
+        );
+        assert_eq!(AgentAction::Call, agent.act(0, &view));
+    }
+
+    #[test]
+    fn test_stats_persist_across_hands() {
+        let generator = OpponentModelAgentGenerator::new(OpponentModelConfig {
+            iterations: 50,
+            ..OpponentModelConfig::default()
+        });
+        let stats = generator.stats();
+        let game_state = GameState::new_starting(vec![100.0; 3], 2.0, 1.0, 0.0, 0);
+        let sim_gen = StandardSimulationIterator::new(
+            vec![
+                Box::new(CallingAgentGenerator),
+                Box::new(FoldingAgentGenerator),
+                Box::new(generator),
+            ],
+            vec![],
+            CloneGameStateGenerator::new(game_state),
+        );
+        let mut competition = HoldemCompetition::new(sim_gen);
+        competition.run(30).unwrap();
+
+        let stats = stats.borrow();
+        for idx in 0..3 {
+            assert_eq!(30, stats.player_stats(idx).hands);
+        }
+        // The calling agent always calls the big blind. The folding agent
+        // in the small blind folds before anyone can raise it, and only
+        // calls when it's the last player left to act.
+        assert_eq!(Some(1.0), stats.player_stats(0).vpip.frequency());
+        assert_eq!(Some(0.0), stats.player_stats(1).vpip.frequency());
+    }
+
+    #[test_log::test]
+    fn test_plays_valid_games() {
+        let mut rng = rand::rng();
+        let generator = OpponentModelAgentGenerator::new(OpponentModelConfig {
+            iterations: 50,
+            ..OpponentModelConfig::default()
+        });
+        for _ in 0..10 {
+            let game_state = GameState::new_starting(vec![100.0; 3], 2.0, 1.0, 0.0, 0);
+            let agents = vec![
+                generator.generate(&game_state),
+                CallingAgentGenerator.generate(&game_state),
+                FoldingAgentGenerator.generate(&game_state),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+            assert_valid_game_state(&sim.game_state);
+        }
+        assert_eq!(10, generator.stats().borrow().player_stats(0).hands);
+    }
+}
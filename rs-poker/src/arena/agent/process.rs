@@ -0,0 +1,369 @@
+//! Agents that run in another process.
+//!
+//! The external program is started with its stdin and stdout piped. Every
+//! time the agent has to act one line of JSON is written to the program's
+//! stdin:
+//!
+//! ```json
+//! {"seq":3,"id":"1234","view":{"player_idx":0,"game_state":{...},"actions":[...],"omniscient":false}}
+//! ```
+//!
+//! The program must answer with one line of JSON on stdout echoing the `seq`
+//! it's answering:
+//!
+//! ```json
+//! {"seq":3,"action":"Call"}
+//! {"seq":4,"action":{"Bet":40.0}}
+//! ```
+//!
+//! Anything the program writes to stderr is passed through. Lines on stdout
+//! that aren't an answer to the current request are skipped. If the program
+//! doesn't answer in time the agent folds, and if it exits the agent folds
+//! for the rest of the game.
+use std::{
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use thiserror::Error;
+use tracing::{Level, event};
+
+use crate::arena::{GameState, PlayerView, action::AgentAction};
+
+use super::{Agent, AgentGenerator, FoldingAgent};
+
+/// How long an external agent gets to decide by default.
+pub const DEFAULT_PROCESS_AGENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Error, Debug)]
+pub enum ProcessAgentError {
+    #[error("Error talking to the agent process")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unable to encode or decode agent message")]
+    Json(#[from] serde_json::Error),
+
+    #[error("The agent process didn't respond in time")]
+    Timeout,
+
+    #[error("The agent process has exited")]
+    Exited,
+}
+
+#[derive(serde::Serialize)]
+struct ProcessRequest<'a> {
+    seq: u64,
+    id: String,
+    view: &'a PlayerView,
+}
+
+#[derive(serde::Deserialize)]
+struct ProcessResponse {
+    seq: u64,
+    action: AgentAction,
+}
+
+/// An `Agent` backed by an external program speaking newline delimited JSON
+/// over stdin and stdout.
+pub struct ProcessAgent {
+    child: Option<Child>,
+    stdin: Option<ChildStdin>,
+    responses: Option<Receiver<String>>,
+    timeout: Duration,
+    seq: u64,
+}
+
+impl ProcessAgent {
+    /// Start the program described by `command`. Its stdin and stdout will
+    /// be replaced with pipes.
+    pub fn spawn(command: &mut Command, timeout: Duration) -> Result<Self, ProcessAgentError> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+
+        let stdin = child.stdin.take();
+        let stdout = child.stdout.take().ok_or(ProcessAgentError::Exited)?;
+
+        // Read on another thread so that we can time out waiting for a line.
+        let (sender, responses) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) => {
+                        if sender.send(line).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+        });
+
+        Ok(Self {
+            child: Some(child),
+            stdin,
+            responses: Some(responses),
+            timeout,
+            seq: 0,
+        })
+    }
+
+    /// Is the external program still able to play.
+    pub fn is_alive(&self) -> bool {
+        self.child.is_some()
+    }
+
+    fn request_action(
+        &mut self,
+        id: u128,
+        view: &PlayerView,
+    ) -> Result<AgentAction, ProcessAgentError> {
+        let seq = self.seq;
+        self.seq += 1;
+
+        let stdin = self.stdin.as_mut().ok_or(ProcessAgentError::Exited)?;
+        let request = serde_json::to_string(&ProcessRequest {
+            seq,
+            id: id.to_string(),
+            view,
+        })?;
+        writeln!(stdin, "{}", request)?;
+        stdin.flush()?;
+
+        let responses = self.responses.as_ref().ok_or(ProcessAgentError::Exited)?;
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let line = responses.recv_timeout(remaining).map_err(|e| match e {
+                RecvTimeoutError::Timeout => ProcessAgentError::Timeout,
+                RecvTimeoutError::Disconnected => ProcessAgentError::Exited,
+            })?;
+            // Skip anything that isn't an answer, and answers to earlier
+            // requests that timed out.
+            match serde_json::from_str::<ProcessResponse>(&line) {
+                Ok(response) if response.seq == seq => return Ok(response.action),
+                Ok(response) => {
+                    event!(
+                        Level::DEBUG,
+                        response.seq,
+                        seq,
+                        "process_agent_stale_answer"
+                    );
+                }
+                Err(error) => {
+                    event!(Level::WARN, ?error, line, "process_agent_invalid_answer");
+                }
+            }
+        }
+    }
+
+    fn shutdown(&mut self) {
+        self.stdin.take();
+        self.responses.take();
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+impl Agent for ProcessAgent {
+    fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction {
+        if !self.is_alive() {
+            return AgentAction::Fold;
+        }
+
+        match self.request_action(id, view) {
+            Ok(action) => action,
+            Err(ProcessAgentError::Timeout) => {
+                // The program might just be slow, so give it another chance next time.
+                event!(Level::WARN, "process_agent_timeout");
+                AgentAction::Fold
+            }
+            Err(error) => {
+                event!(Level::ERROR, ?error, "process_agent_error");
+                self.shutdown();
+                AgentAction::Fold
+            }
+        }
+    }
+}
+
+impl Drop for ProcessAgent {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// `AgentGenerator` that starts a new copy of an external program for every
+/// game.
+///
+/// If the program can't be started the seat is filled with a `FoldingAgent`.
+#[derive(Debug, Clone)]
+pub struct ProcessAgentGenerator {
+    program: PathBuf,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl ProcessAgentGenerator {
+    pub fn new(program: impl Into<PathBuf>, args: Vec<String>) -> Self {
+        Self {
+            program: program.into(),
+            args,
+            timeout: DEFAULT_PROCESS_AGENT_TIMEOUT,
+        }
+    }
+
+    /// How long the program has to answer each decision.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl AgentGenerator for ProcessAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+        match ProcessAgent::spawn(&mut command, self.timeout) {
+            Ok(agent) => Box::new(agent),
+            Err(error) => {
+                event!(Level::ERROR, ?error, program = ?self.program, "process_agent_spawn");
+                Box::new(FoldingAgent)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use crate::arena::{
+        HoldemSimulationBuilder,
+        action::Action,
+        agent::CallingAgent,
+        historian::{HistoryRecord, VecHistorian},
+        test_util::{assert_valid_game_state, assert_valid_history},
+    };
+
+    use super::*;
+
+    /// A shell bot that calls everything.
+    const CALLING_BOT: &str = r#"
+        while read -r line; do
+            seq=${line#*\"seq\":}
+            seq=${seq%%,*}
+            echo "{\"seq\":$seq,\"action\":\"Call\"}"
+        done
+    "#;
+
+    fn shell_agent(script: &str, timeout: Duration) -> Box<dyn Agent> {
+        ProcessAgentGenerator::new("sh", vec!["-c".to_string(), script.to_string()])
+            .with_timeout(timeout)
+            .generate(&GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0))
+    }
+
+    fn run_against_caller(agent: Box<dyn Agent>) -> (GameState, Vec<HistoryRecord>) {
+        let historian = Box::new(VecHistorian::default());
+        let storage = historian.get_storage();
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(vec![agent, Box::new(CallingAgent)])
+            .historians(vec![historian])
+            .build()
+            .unwrap();
+        let mut rng = rand::rng();
+        sim.run(&mut rng);
+
+        let history = storage.borrow().clone();
+        (sim.game_state, history)
+    }
+
+    fn folded(history: &[HistoryRecord]) -> bool {
+        history.iter().any(|record| match &record.action {
+            Action::PlayedAction(payload) => {
+                payload.idx == 0 && payload.action == AgentAction::Fold
+            }
+            Action::FailedAction(payload) => {
+                payload.result.idx == 0 && payload.result.action == AgentAction::Fold
+            }
+            _ => false,
+        })
+    }
+
+    #[test]
+    fn test_process_agent_plays_to_showdown() {
+        let (game_state, history) =
+            run_against_caller(shell_agent(CALLING_BOT, DEFAULT_PROCESS_AGENT_TIMEOUT));
+
+        assert_eq!(5, game_state.board.len());
+        assert!(!folded(&history));
+        assert_valid_game_state(&game_state);
+        assert_valid_history(&history);
+    }
+
+    #[test]
+    fn test_process_agent_timeout_folds() {
+        let (game_state, history) =
+            run_against_caller(shell_agent("sleep 10", Duration::from_millis(50)));
+
+        assert!(folded(&history));
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_process_agent_crash_folds() {
+        let (game_state, history) =
+            run_against_caller(shell_agent("exit 1", DEFAULT_PROCESS_AGENT_TIMEOUT));
+
+        assert!(folded(&history));
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_process_agent_garbage_folds() {
+        let script = "while read -r line; do echo 'not json'; done";
+        let (game_state, history) =
+            run_against_caller(shell_agent(script, Duration::from_millis(50)));
+
+        assert!(folded(&history));
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_process_agent_skips_lines_that_arent_answers() {
+        // Chatter and a stale answer before every real one.
+        let script = r#"
+            while read -r line; do
+                echo 'thinking...'
+                echo '{"seq":1000,"action":"Fold"}'
+                seq=${line#*\"seq\":}
+                seq=${seq%%,*}
+                echo "{\"seq\":$seq,\"action\":\"Call\"}"
+            done
+        "#;
+        let (game_state, history) =
+            run_against_caller(shell_agent(script, DEFAULT_PROCESS_AGENT_TIMEOUT));
+
+        assert_eq!(5, game_state.board.len());
+        assert!(!folded(&history));
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_missing_program_folds() {
+        let agent = ProcessAgentGenerator::new("/does/not/exist", vec![])
+            .generate(&GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0));
+        let (game_state, history) = run_against_caller(agent);
+
+        assert!(folded(&history));
+        assert_valid_game_state(&game_state);
+    }
+}
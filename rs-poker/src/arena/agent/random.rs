@@ -2,10 +2,10 @@ use rand::{Rng, rng};
 
 use crate::{
     arena::{
+        PlayerView,
         action::AgentAction,
         game_state::{GameState, Round},
     },
-    core::Hand,
     holdem::MonteCarloGame,
 };
 
@@ -36,10 +36,10 @@ impl Default for RandomAgent {
 }
 
 impl Agent for RandomAgent {
-    fn act(self: &mut RandomAgent, _id: u128, game_state: &GameState) -> AgentAction {
-        let round_data = &game_state.round_data;
+    fn act(self: &mut RandomAgent, _id: u128, view: &PlayerView) -> AgentAction {
+        let round_data = &view.round_data;
         let player_bet = round_data.current_player_bet();
-        let player_stack = game_state.stacks[round_data.to_act_idx];
+        let player_stack = view.stacks[round_data.to_act_idx];
         let curr_bet = round_data.bet;
         let raise_count = round_data.total_raise_count;
 
@@ -57,8 +57,9 @@ impl Agent for RandomAgent {
         // calling a pot sized bet (plus a little more for spicyness)
         //
         // That could be the same as the min
-        let pot_value = (round_data.num_players_need_action() as f32 + 1.0) * game_state.total_pot;
-        let max = (player_bet + player_stack).min(pot_value).max(min);
+        let pot_value = (round_data.num_players_need_action() as f32 + 1.0) * view.total_pot;
+        // In pot limit games the max is also capped at a pot sized raise.
+        let max = view.current_round_max_bet().min(pot_value).max(min);
 
         // We shouldn't fold when checking is an option.
         let can_fold = curr_bet > player_bet;
@@ -92,8 +93,19 @@ impl Agent for RandomAgent {
 }
 
 pub struct RandomAgentGenerator {
-    percent_fold: Vec<f64>,
-    percent_call: Vec<f64>,
+    pub(super) percent_fold: Vec<f64>,
+    pub(super) percent_call: Vec<f64>,
+}
+
+impl RandomAgentGenerator {
+    /// Generate `RandomAgent`s with the given per round fold and call
+    /// probabilities.
+    pub fn new(percent_fold: Vec<f64>, percent_call: Vec<f64>) -> Self {
+        Self {
+            percent_fold,
+            percent_call,
+        }
+    }
 }
 
 impl AgentGenerator for RandomAgentGenerator {
@@ -135,27 +147,6 @@ impl RandomPotControlAgent {
         }
     }
 
-    fn clean_hands(&self, game_state: &GameState) -> Vec<Hand> {
-        let mut default_hand = Hand::new();
-        // Copy the board into the default hand
-        default_hand.extend(game_state.board.iter().cloned());
-
-        let to_act_idx = game_state.to_act_idx();
-        game_state
-            .hands
-            .clone()
-            .into_iter()
-            .enumerate()
-            .map(|(hand_idx, hand)| {
-                if hand_idx == to_act_idx {
-                    hand
-                } else {
-                    default_hand
-                }
-            })
-            .collect()
-    }
-
     fn monte_carlo_based_action(
         &self,
         game_state: &GameState,
@@ -220,13 +211,11 @@ impl RandomPotControlAgent {
 }
 
 impl Agent for RandomPotControlAgent {
-    fn act(&mut self, _id: u128, game_state: &GameState) -> AgentAction {
-        // We don't want to cheat.
-        // So replace all the hands but our own
-        let clean_hands = self.clean_hands(game_state);
-        // Now check if we can simulate that
-        if let Ok(monte) = MonteCarloGame::new(clean_hands) {
-            self.monte_carlo_based_action(game_state, monte)
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        // The view has already replaced all the hands but our own with just
+        // the board, so simulate from there.
+        if let Ok(monte) = MonteCarloGame::new(view.hands.clone()) {
+            self.monte_carlo_based_action(view, monte)
         } else {
             AgentAction::Fold
         }
@@ -238,6 +227,7 @@ mod tests {
     use crate::{
         arena::{
             HoldemSimulationBuilder,
+            game_state::GameType,
             test_util::{assert_valid_game_state, assert_valid_round_data},
         },
         core::Deck,
@@ -359,4 +349,31 @@ mod tests {
         assert!(sim.game_state.is_complete());
         assert_valid_game_state(&sim.game_state);
     }
+
+    #[test_log::test]
+    fn test_random_agents_pot_limit_omaha() {
+        let stacks = vec![100.0; 5];
+        let game_state = GameState::new_starting(stacks, 10.0, 5.0, 0.0, 0)
+            .with_game_type(GameType::PotLimitOmaha);
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(RandomAgent::new(vec![0.0], vec![0.5])),
+            Box::new(RandomAgent::new(vec![0.0], vec![0.5])),
+            Box::new(RandomAgent::new(vec![0.0], vec![0.5])),
+            Box::new(RandomAgent::new(vec![0.0], vec![0.5])),
+            Box::new(RandomAgent::new(vec![0.0], vec![0.5])),
+        ];
+        let mut rng = rand::rng();
+        let mut sim = HoldemSimulationBuilder::default()
+            .agents(agents)
+            .game_state(game_state)
+            .build()
+            .unwrap();
+
+        sim.run(&mut rng);
+        assert!(sim.game_state.is_complete());
+        for idx in 0..5 {
+            assert_eq!(4, sim.game_state.hole_cards(idx).len());
+        }
+        assert_valid_game_state(&sim.game_state);
+    }
 }
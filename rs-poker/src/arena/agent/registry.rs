@@ -0,0 +1,574 @@
+//! Build agents from names and parameters instead of code.
+//!
+//! An [`AgentConfig`] is a `type` name plus whatever parameters that kind of
+//! agent takes. It can be written as JSON:
+//!
+//! ```json
+//! {"type": "random", "fold": [0.1, 0.2, 0.3], "call": [0.5, 0.5, 0.5]}
+//! {"type": "cfr", "iterations": 10}
+//! "calling"
+//! ```
+//!
+//! or, with the `toml` feature, read from a TOML table with
+//! [`AgentConfig::from_toml`]:
+//!
+//! ```toml
+//! type = "random"
+//! fold = [0.1, 0.2, 0.3]
+//! call = [0.5, 0.5, 0.5]
+//! ```
+//!
+//! The [`AgentRegistry`] maps the `type` to a function that turns the
+//! config into an `AgentGenerator`. The default registry knows every agent
+//! in this crate, and more can be registered without touching the callers.
+//!
+//! ```
+//! use rs_poker::arena::agent::{AgentConfig, AgentRegistry};
+//! use rs_poker::arena::game_state::GameState;
+//!
+//! let registry = AgentRegistry::default();
+//! let config: AgentConfig = r#"{"type":"random","fold":[0.0],"call":[1.0]}"#.parse().unwrap();
+//! let agent_gen = registry.build(&config).unwrap();
+//!
+//! let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+//! let _agent = agent_gen.generate(&game_state);
+//! ```
+use std::{collections::HashMap, fmt, rc::Rc, str::FromStr, time::Duration};
+
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+use crate::arena::{
+    GameState,
+    cfr::{BasicCFRActionGenerator, CFRAgent, FixedGameStateIteratorGen, StateStore},
+};
+use crate::core::RSPokerError;
+
+use super::{Agent, AgentGenerator};
+use super::{
+    AllInAgentGenerator, CallingAgentGenerator, CloneAgentGenerator, DEFAULT_PROCESS_AGENT_TIMEOUT,
+    EquityAgentGenerator, EquityConfig, FoldingAgentGenerator, IsmctsAgentGenerator, IsmctsConfig,
+    OpponentModelAgentGenerator, OpponentModelConfig, ProcessAgentGenerator, RandomAgentGenerator,
+    RandomPotControlAgent, TagAgentGenerator, TagConfig,
+};
+use crate::arena::{PlayerView, action::AgentAction};
+
+#[derive(Error, Debug)]
+pub enum AgentConfigError {
+    #[error("Unknown agent type: {0}")]
+    UnknownType(String),
+
+    #[error("Invalid parameters for agent type {kind}: {source}")]
+    InvalidParams {
+        kind: String,
+        source: serde_json::Error,
+    },
+
+    #[error("Invalid hand range for agent type {kind}: {source}")]
+    InvalidRange { kind: String, source: RSPokerError },
+
+    #[error("Unable to parse agent config")]
+    Json(#[from] serde_json::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("Unable to parse TOML agent config")]
+    Toml(#[from] toml::de::Error),
+}
+
+/// The name of an agent kind plus its parameters.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(from = "AgentConfigRepr")]
+pub struct AgentConfig {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(flatten)]
+    pub params: Map<String, Value>,
+}
+
+/// Configs can be a full object or just the name of the type.
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum AgentConfigRepr {
+    Name(String),
+    Full {
+        #[serde(rename = "type")]
+        kind: String,
+        #[serde(flatten)]
+        params: Map<String, Value>,
+    },
+}
+
+impl From<AgentConfigRepr> for AgentConfig {
+    fn from(repr: AgentConfigRepr) -> Self {
+        match repr {
+            AgentConfigRepr::Name(kind) => AgentConfig::new(&kind),
+            AgentConfigRepr::Full { kind, params } => AgentConfig { kind, params },
+        }
+    }
+}
+
+impl AgentConfig {
+    /// A config for `kind` with no parameters.
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            params: Map::new(),
+        }
+    }
+
+    /// Set a parameter.
+    pub fn with_param(mut self, name: &str, value: impl Into<Value>) -> Self {
+        self.params.insert(name.to_string(), value.into());
+        self
+    }
+
+    /// Parse a config from a TOML table, with the `type` key naming the
+    /// agent.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Self, AgentConfigError> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// Read the parameters into a typed struct.
+    pub fn params<T: DeserializeOwned>(&self) -> Result<T, AgentConfigError> {
+        serde_json::from_value(Value::Object(self.params.clone())).map_err(|source| {
+            AgentConfigError::InvalidParams {
+                kind: self.kind.clone(),
+                source,
+            }
+        })
+    }
+}
+
+impl FromStr for AgentConfig {
+    type Err = AgentConfigError;
+
+    /// Parse either a JSON config or a bare type name.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.starts_with('{') || s.starts_with('"') {
+            Ok(serde_json::from_str(s)?)
+        } else {
+            Ok(AgentConfig::new(s))
+        }
+    }
+}
+
+impl fmt::Display for AgentConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| fmt::Error)?;
+        write!(f, "{json}")
+    }
+}
+
+/// Turns a config into an `AgentGenerator`.
+pub type AgentFactory =
+    Box<dyn Fn(&AgentConfig) -> Result<Box<dyn AgentGenerator>, AgentConfigError> + Send + Sync>;
+
+/// Maps agent type names to the functions that build them.
+///
+/// Names are matched ignoring case, `_` and `-`, so `all_in`, `AllIn` and
+/// `all-in` are the same agent.
+pub struct AgentRegistry {
+    // Keyed by the normalized name, keeping the name it was registered as.
+    factories: HashMap<String, (String, AgentFactory)>,
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+impl AgentRegistry {
+    /// A registry that doesn't know any agents.
+    pub fn empty() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Add (or replace) the factory used for `name`.
+    pub fn register<F>(&mut self, name: &str, factory: F)
+    where
+        F: Fn(&AgentConfig) -> Result<Box<dyn AgentGenerator>, AgentConfigError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.factories
+            .insert(normalize(name), (name.to_string(), Box::new(factory)));
+    }
+
+    /// Is there a factory for `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.factories.contains_key(&normalize(name))
+    }
+
+    /// The names of every registered agent type, sorted.
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self
+            .factories
+            .values()
+            .map(|(name, _)| name.clone())
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Build the generator described by `config`.
+    pub fn build(&self, config: &AgentConfig) -> Result<Box<dyn AgentGenerator>, AgentConfigError> {
+        let (_, factory) = self
+            .factories
+            .get(&normalize(&config.kind))
+            .ok_or_else(|| AgentConfigError::UnknownType(config.kind.clone()))?;
+        factory(config)
+    }
+
+    /// Parse `config` (JSON or a bare name) and build it.
+    pub fn build_str(&self, config: &str) -> Result<Box<dyn AgentGenerator>, AgentConfigError> {
+        self.build(&config.parse()?)
+    }
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoParams {}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RandomParams {
+    fold: Option<Vec<f64>>,
+    call: Option<Vec<f64>>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RandomPotControlParams {
+    call: Vec<f64>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CFRParams {
+    iterations: Option<usize>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProcessParams {
+    program: String,
+    #[serde(default)]
+    args: Vec<String>,
+    timeout_ms: Option<u64>,
+}
+
+fn no_params<G>(
+    generator: G,
+) -> impl Fn(&AgentConfig) -> Result<Box<dyn AgentGenerator>, AgentConfigError>
+where
+    G: AgentGenerator + Clone + Send + Sync + 'static,
+{
+    move |config| {
+        config.params::<NoParams>()?;
+        Ok(Box::new(generator.clone()))
+    }
+}
+
+impl Default for AgentRegistry {
+    /// A registry with every agent in this crate.
+    ///
+    /// | type | parameters |
+    /// |------|------------|
+    /// | `calling` | |
+    /// | `folding` | |
+    /// | `all_in` | |
+    /// | `random` | `fold`, `call`: per round probabilities |
+    /// | `random_pot_control` | `call`: per round probabilities |
+    /// | `cfr` | `iterations`: hands explored per decision |
+    /// | `process` | `program`, `args`, `timeout_ms` |
+    /// | `tag` | any `TagConfig` field |
+    /// | `equity` | any `EquityConfig` field |
+    /// | `opponent_model` | any `OpponentModelConfig` field |
+    /// | `ismcts` | any `IsmctsConfig` field, `rollout`: agent config (default `calling`) |
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry.register("calling", no_params(CallingAgentGenerator));
+        registry.register("folding", no_params(FoldingAgentGenerator));
+        // Older engine configs spelled it this way.
+        registry.register("filding", no_params(FoldingAgentGenerator));
+        registry.register("all_in", no_params(AllInAgentGenerator));
+        registry.register("random", |config| {
+            let params: RandomParams = config.params()?;
+            let default = RandomAgentGenerator::default();
+            Ok(Box::new(RandomAgentGenerator::new(
+                params.fold.unwrap_or(default.percent_fold),
+                params.call.unwrap_or(default.percent_call),
+            )))
+        });
+        registry.register("random_pot_control", |config| {
+            let params: RandomPotControlParams = config.params()?;
+            Ok(Box::new(CloneAgentGenerator::new(
+                RandomPotControlAgent::new(params.call),
+            )))
+        });
+        registry.register("cfr", |config| {
+            let params: CFRParams = config.params()?;
+            Ok(Box::new(CFRSearchAgentGenerator {
+                iterations: params
+                    .iterations
+                    .unwrap_or(FixedGameStateIteratorGen::default().num_hands),
+            }))
+        });
+        registry.register("tag", |config| {
+            let params: TagConfig = config.params()?;
+            let generator = TagAgentGenerator::new(params).map_err(|source| {
+                AgentConfigError::InvalidRange {
+                    kind: config.kind.clone(),
+                    source,
+                }
+            })?;
+            Ok(Box::new(generator))
+        });
+        registry.register("equity", |config| {
+            let params: EquityConfig = config.params()?;
+            let generator = EquityAgentGenerator::new(params).map_err(|source| {
+                AgentConfigError::InvalidRange {
+                    kind: config.kind.clone(),
+                    source,
+                }
+            })?;
+            Ok(Box::new(generator))
+        });
+        registry.register("opponent_model", |config| {
+            let params: OpponentModelConfig = config.params()?;
+            Ok(Box::new(OpponentModelAgentGenerator::new(params)))
+        });
+        registry.register("ismcts", |config| {
+            let mut config = config.clone();
+            let rollout = match config.params.remove("rollout") {
+                Some(rollout) => serde_json::from_value(rollout).map_err(|source| {
+                    AgentConfigError::InvalidParams {
+                        kind: config.kind.clone(),
+                        source,
+                    }
+                })?,
+                None => AgentConfig::new("calling"),
+            };
+            let params: IsmctsConfig = config.params()?;
+            let rollout = AgentRegistry::default().build(&rollout)?;
+            Ok(Box::new(IsmctsAgentGenerator::new(
+                params,
+                Rc::from(rollout),
+            )))
+        });
+        registry.register("process", |config| {
+            let params: ProcessParams = config.params()?;
+            let timeout = params
+                .timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_PROCESS_AGENT_TIMEOUT);
+            Ok(Box::new(
+                ProcessAgentGenerator::new(params.program, params.args).with_timeout(timeout),
+            ))
+        });
+        registry
+    }
+}
+
+/// A CFR agent that doesn't need to be set up for a particular seat ahead
+/// of time.
+///
+/// Every decision it builds a fresh CFR tree rooted at the current game
+/// state and explores it `iterations` times. That makes it usable anywhere
+/// an `AgentGenerator` is, at the cost of not reusing work between
+/// decisions. It can't see the other players' cards, so the tree is built
+/// from a guess at them.
+#[derive(Debug, Clone)]
+struct CFRSearchAgent {
+    iterations: usize,
+}
+
+impl Agent for CFRSearchAgent {
+    fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction {
+        let game_state = view.sample_game_state(&mut rand::rng());
+        let mut state_store = StateStore::new();
+        let states: Vec<_> = (0..game_state.num_players)
+            .map(|idx| state_store.new_state(game_state.clone(), idx))
+            .collect();
+        let (cfr_state, traversal_state) = states[view.player_idx()].clone();
+
+        let mut agent = CFRAgent::<BasicCFRActionGenerator, FixedGameStateIteratorGen>::new(
+            state_store,
+            cfr_state,
+            traversal_state,
+            FixedGameStateIteratorGen::new(self.iterations),
+        );
+        agent.act(
+            id,
+            &PlayerView::omniscient(view.player_idx(), &game_state, view.actions()),
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CFRSearchAgentGenerator {
+    iterations: usize,
+}
+
+impl AgentGenerator for CFRSearchAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        Box::new(CFRSearchAgent {
+            iterations: self.iterations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{HoldemSimulationBuilder, test_util::assert_valid_game_state};
+
+    use super::*;
+
+    fn play(configs: &[&str]) -> GameState {
+        let registry = AgentRegistry::default();
+        let game_state = GameState::new_starting(vec![100.0; configs.len()], 10.0, 5.0, 0.0, 0);
+        let agents = configs
+            .iter()
+            .map(|config| registry.build_str(config).unwrap().generate(&game_state))
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .build()
+            .unwrap();
+        let mut rng = rand::rng();
+        sim.run(&mut rng);
+        sim.game_state
+    }
+
+    #[test]
+    fn test_parse_configs() {
+        let config: AgentConfig = r#"{"type":"cfr","iterations":3}"#.parse().unwrap();
+        assert_eq!("cfr", config.kind);
+        assert_eq!(Some(&Value::from(3)), config.params.get("iterations"));
+        assert_eq!(config, serde_json::from_str(&config.to_string()).unwrap());
+
+        // Bare names work both as strings and as JSON strings.
+        assert_eq!(AgentConfig::new("calling"), "calling".parse().unwrap());
+        assert_eq!(AgentConfig::new("Random"), "\"Random\"".parse().unwrap());
+        assert_eq!(
+            AgentConfig::new("random").with_param("fold", vec![0.5]),
+            r#"{"type":"random","fold":[0.5]}"#.parse().unwrap()
+        );
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_parse_toml_configs() {
+        let config = AgentConfig::from_toml(
+            r#"
+            type = "ismcts"
+            iterations = 20
+            bet_fractions = [0.5, 1.0]
+
+            [rollout]
+            type = "random"
+            fold = [0.1]
+            call = [0.8]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            r#"{"type":"ismcts","iterations":20,"bet_fractions":[0.5,1.0],"rollout":{"type":"random","fold":[0.1],"call":[0.8]}}"#
+                .parse::<AgentConfig>()
+                .unwrap(),
+            config
+        );
+        assert!(AgentRegistry::default().build(&config).is_ok());
+
+        assert!(matches!(
+            AgentConfig::from_toml("iterations = 20"),
+            Err(AgentConfigError::Toml(_))
+        ));
+    }
+
+    #[test]
+    fn test_default_registry_builds_everything() {
+        let game_state = play(&[
+            "calling",
+            "AllIn",
+            "Filding",
+            r#"{"type":"random","fold":[0.0],"call":[1.0]}"#,
+            r#"{"type":"random_pot_control","call":[0.5, 0.3]}"#,
+            "tag",
+            r#"{"type":"equity","iterations":50,"aggression":2.0,"seat_ranges":{"0":"TT+"}}"#,
+            r#"{"type":"opponent_model","iterations":50,"min_hands":5}"#,
+            r#"{"type":"tag","open_size_bb":3.0,"late":{"open":"22+,A2s+","three_bet":"AA","call":""}}"#,
+        ]);
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_ismcts_from_config() {
+        let game_state = play(&[
+            r#"{"type":"ismcts","iterations":20,"rollout":{"type":"random","fold":[0.1],"call":[0.8]}}"#,
+            r#"{"type":"ismcts","iterations":20,"bet_fractions":[0.75]}"#,
+            "calling",
+        ]);
+        assert_valid_game_state(&game_state);
+    }
+
+    #[test]
+    fn test_cfr_from_config() {
+        let game_state = play(&[r#"{"type":"cfr","iterations":1}"#, "calling"]);
+        assert_valid_game_state(&game_state);
+
+        let agent = AgentRegistry::default()
+            .build_str("cfr")
+            .unwrap()
+            .generate(&game_state);
+        assert!(!agent.omniscient());
+    }
+
+    #[test]
+    fn test_errors() {
+        let registry = AgentRegistry::default();
+        assert!(matches!(
+            registry.build_str("no_such_agent"),
+            Err(AgentConfigError::UnknownType(_))
+        ));
+        assert!(matches!(
+            registry.build_str(r#"{"type":"calling","fold":[0.5]}"#),
+            Err(AgentConfigError::InvalidParams { .. })
+        ));
+        assert!(matches!(
+            registry.build_str(r#"{"type":"random_pot_control"}"#),
+            Err(AgentConfigError::InvalidParams { .. })
+        ));
+        assert!(matches!(
+            registry.build_str(r#"{"type":"tag","four_bet":"AK-J9"}"#),
+            Err(AgentConfigError::InvalidRange { .. })
+        ));
+        assert!(matches!(
+            registry.build_str(r#"{"type":"ismcts","rollout":"no_such_agent"}"#),
+            Err(AgentConfigError::UnknownType(_))
+        ));
+        assert!(matches!(
+            registry.build_str("{not json"),
+            Err(AgentConfigError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_custom_agent() {
+        let mut registry = AgentRegistry::empty();
+        assert!(!registry.contains("calling"));
+        registry.register("always_call", |_| Ok(Box::new(CallingAgentGenerator)));
+        assert!(registry.contains("Always-Call"));
+        assert_eq!(vec!["always_call".to_string()], registry.names());
+        assert!(registry.build_str("always_call").is_ok());
+    }
+}
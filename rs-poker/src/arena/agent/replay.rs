@@ -1,4 +1,4 @@
-use crate::arena::{action::AgentAction, game_state::GameState};
+use crate::arena::{PlayerView, action::AgentAction};
 
 use super::Agent;
 
@@ -49,7 +49,7 @@ impl<'a> SliceReplayAgent<'a> {
 }
 
 impl Agent for VecReplayAgent {
-    fn act(self: &mut VecReplayAgent, _id: u128, _game_state: &GameState) -> AgentAction {
+    fn act(self: &mut VecReplayAgent, _id: u128, _view: &PlayerView) -> AgentAction {
         let idx = self.idx;
         self.idx += 1;
         self.actions
@@ -59,7 +59,7 @@ impl Agent for VecReplayAgent {
 }
 
 impl<'a> Agent for SliceReplayAgent<'a> {
-    fn act(self: &mut SliceReplayAgent<'a>, _id: u128, _game_state: &GameState) -> AgentAction {
+    fn act(self: &mut SliceReplayAgent<'a>, _id: u128, _view: &PlayerView) -> AgentAction {
         let idx = self.idx;
         self.idx += 1;
         self.actions
@@ -0,0 +1,658 @@
+use std::sync::Arc;
+
+use rand::{Rng, rng};
+
+use crate::{
+    arena::{
+        PlayerView,
+        action::{Action, AgentAction},
+        game_state::{GameState, Round},
+    },
+    core::{Card, RSPokerError, Rank, Rankable},
+    holdem::HandRange,
+};
+
+use super::{
+    Agent, AgentGenerator,
+    betting::{check_or_fold, raise_to, to_call},
+};
+
+/// Where a seat sits relative to the button. Tight aggressive players play
+/// far fewer hands out of position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Position {
+    Early,
+    Middle,
+    /// The button and the seat right before it.
+    Late,
+    SmallBlind,
+    BigBlind,
+}
+
+impl Position {
+    /// The position of `idx` in this hand.
+    ///
+    /// Heads up the button is `Late` and the other seat is the `BigBlind`.
+    pub fn of(game_state: &GameState, idx: usize) -> Self {
+        let num_players = game_state.num_players;
+        let offset = (idx + num_players - game_state.dealer_idx) % num_players;
+        if num_players == 2 {
+            return if offset == 0 {
+                Position::Late
+            } else {
+                Position::BigBlind
+            };
+        }
+        match (offset, num_players - offset) {
+            (0, _) => Position::Late,
+            (1, _) => Position::SmallBlind,
+            (2, _) => Position::BigBlind,
+            (_, 1) => Position::Late,
+            (_, 2) => Position::Middle,
+            _ => Position::Early,
+        }
+    }
+}
+
+/// A rough bucket for how good a hand is after the flop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum HandStrength {
+    /// Nothing worth putting money in with.
+    Air,
+    /// A flush draw or an open ended (or double gutshot) straight draw.
+    Draw,
+    /// A pair below the top card of the board.
+    Medium,
+    /// Top pair or an overpair.
+    Strong,
+    /// Two pair using both hole cards, sets, trips, and anything better.
+    Monster,
+}
+
+impl HandStrength {
+    /// How strong the hand of `idx` is on the current board.
+    ///
+    /// Only made hands that use the player's hole cards count; a pair on
+    /// the board is the same for everyone.
+    pub fn of(game_state: &GameState, idx: usize) -> Self {
+        let board = &game_state.board;
+        let hole_cards = game_state.hole_cards(idx);
+        let made = made_strength(game_state.player_rank(idx), &hole_cards, board);
+        if made >= HandStrength::Medium || board.len() >= 5 {
+            return made;
+        }
+        if has_flush_draw(&hole_cards, board) || has_straight_draw(&hole_cards, board) {
+            HandStrength::Draw
+        } else {
+            made
+        }
+    }
+}
+
+fn category(rank: &Rank) -> u8 {
+    match rank {
+        Rank::HighCard(_) => 0,
+        Rank::OnePair(_) => 1,
+        Rank::TwoPair(_) => 2,
+        Rank::ThreeOfAKind(_) => 3,
+        Rank::Straight(_) => 4,
+        Rank::Flush(_) => 5,
+        Rank::FullHouse(_) => 6,
+        Rank::FourOfAKind(_) => 7,
+        Rank::StraightFlush(_) => 8,
+    }
+}
+
+fn made_strength(rank: Rank, hole_cards: &[Card], board: &[Card]) -> HandStrength {
+    let board_category = if board.is_empty() {
+        0
+    } else {
+        category(&board.rank())
+    };
+    let made_category = category(&rank);
+    if made_category <= board_category {
+        return HandStrength::Air;
+    }
+
+    match rank {
+        Rank::HighCard(_) => HandStrength::Air,
+        // Two pair on an unpaired board uses both hole cards.
+        Rank::TwoPair(_) if board_category == 0 => HandStrength::Monster,
+        Rank::OnePair(_) | Rank::TwoPair(_) => {
+            // The best pair that involves our hole cards.
+            let pair = hole_cards
+                .iter()
+                .enumerate()
+                .filter(|(i, card)| {
+                    board.iter().any(|b| b.value == card.value)
+                        || hole_cards[i + 1..].iter().any(|h| h.value == card.value)
+                })
+                .map(|(_, card)| card.value)
+                .max();
+            let top_of_board = board.iter().map(|card| card.value).max();
+            match (pair, top_of_board) {
+                (Some(pair), Some(top)) if pair >= top => HandStrength::Strong,
+                (Some(_), _) => HandStrength::Medium,
+                (None, _) => HandStrength::Air,
+            }
+        }
+        _ => HandStrength::Monster,
+    }
+}
+
+fn has_flush_draw(hole_cards: &[Card], board: &[Card]) -> bool {
+    // Omaha hands have to use two hole cards.
+    let hole_needed = if hole_cards.len() > 2 { 2 } else { 1 };
+    (0..4u8).any(|suit| {
+        let in_hole = hole_cards.iter().filter(|c| c.suit as u8 == suit).count();
+        let on_board = board.iter().filter(|c| c.suit as u8 == suit).count();
+        in_hole >= hole_needed && in_hole + on_board >= 4
+    })
+}
+
+fn value_set(cards: &[Card]) -> u32 {
+    cards
+        .iter()
+        .fold(0, |set, card| set | 1 << card.value as u8)
+}
+
+fn is_straight(values: u32) -> bool {
+    // Ace, two, three, four, five
+    const WHEEL: u32 = 0b1_0000_0000_1111;
+    values & WHEEL == WHEEL || (0..9).any(|low| values & (0b11111 << low) == 0b11111 << low)
+}
+
+/// Two or more different card values would complete a straight that uses
+/// the hole cards. That's an open ended draw or a double gutshot.
+fn has_straight_draw(hole_cards: &[Card], board: &[Card]) -> bool {
+    let board_values = value_set(board);
+    let all_values = board_values | value_set(hole_cards);
+    let outs = (0..13)
+        .map(|value| 1 << value)
+        .filter(|bit| all_values & bit == 0)
+        .filter(|bit| is_straight(all_values | bit) && !is_straight(board_values | bit))
+        .count();
+    outs >= 2
+}
+
+/// The hands a tight aggressive player plays from one position, written
+/// the way `RangeParser` reads them.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct PositionRanges {
+    /// Hands to raise with when nobody has raised yet.
+    pub open: String,
+    /// Hands to re-raise a single raise with.
+    pub three_bet: String,
+    /// Hands to call a single raise with.
+    pub call: String,
+}
+
+impl PositionRanges {
+    fn new(open: &str, three_bet: &str, call: &str) -> Self {
+        Self {
+            open: open.to_string(),
+            three_bet: three_bet.to_string(),
+            call: call.to_string(),
+        }
+    }
+}
+
+/// Everything that decides how a `TagAgent` plays.
+///
+/// The defaults are a fairly standard tight aggressive full ring style.
+/// Bet sizes are in big blinds preflop and fractions of the pot after.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct TagConfig {
+    pub early: PositionRanges,
+    pub middle: PositionRanges,
+    pub late: PositionRanges,
+    pub small_blind: PositionRanges,
+    pub big_blind: PositionRanges,
+    /// Hands to go all in with after a re-raise. Hands in the position's
+    /// `three_bet` range call instead and everything else folds.
+    pub four_bet: String,
+
+    /// Open raise size in big blinds.
+    pub open_size_bb: f32,
+    /// A re-raise is to this many times the current bet.
+    pub three_bet_multiplier: f32,
+
+    /// Continuation bet size as a fraction of the pot.
+    pub cbet_fraction: f32,
+    /// How often to continuation bet the flop after raising preflop.
+    pub cbet_frequency: f64,
+    /// Don't continuation bet into more opponents than this.
+    pub cbet_max_opponents: usize,
+    /// Value bet size as a fraction of the pot.
+    pub value_bet_fraction: f32,
+    /// Raises after the flop are to this many times the current bet.
+    pub raise_multiplier: f32,
+
+    /// Bet when checked to with at least this.
+    pub value_bet_strength: HandStrength,
+    /// Raise a bet with at least this.
+    pub raise_strength: HandStrength,
+    /// Call any bet with at least this.
+    pub call_strength: HandStrength,
+    /// Call bets up to this fraction of the pot with a medium pair.
+    pub medium_call_pot_fraction: f32,
+    /// Call bets up to this fraction of the pot with a draw.
+    pub draw_call_pot_fraction: f32,
+}
+
+impl Default for TagConfig {
+    fn default() -> Self {
+        Self {
+            early: PositionRanges::new(
+                "77+,ATs+,KJs+,QJs,AJo+,KQo",
+                "QQ+,AKs,AKo",
+                "JJ,TT,99,AQs,AJs,KQs",
+            ),
+            middle: PositionRanges::new(
+                "55+,A9s+,KTs+,QTs+,JTs,T9s,ATo+,KJo+",
+                "QQ+,AKs,AKo,AQs",
+                "JJ,TT,99,88,AJs,KQs,AQo",
+            ),
+            late: PositionRanges::new(
+                "22+,A2s+,K8s+,Q9s+,J9s+,T9s,98s,87s,76s,65s,A8o+,KTo+,QTo+,JTo",
+                "JJ+,AQs+,AKo",
+                "TT,99,88,77,66,55,ATs,AJs,KQs,QJs,JTs,T9s,AJo,AQo,KQo",
+            ),
+            small_blind: PositionRanges::new(
+                "22+,A2s+,K9s+,Q9s+,J9s+,T9s,98s,A9o+,KJo+,QJo",
+                "QQ+,AQs+,AKo",
+                "JJ,TT,99,88,77,ATs,AJs,KQs,QJs,AQo",
+            ),
+            big_blind: PositionRanges::new(
+                "88+,ATs+,KQs,AJo+",
+                "QQ+,AKs,AKo",
+                "22+,A2s+,K9s+,Q9s+,J9s+,T9s,98s,87s,76s,65s,A9o+,KTo+,QJo",
+            ),
+            four_bet: "KK+,AKs".to_string(),
+            open_size_bb: 2.5,
+            three_bet_multiplier: 3.0,
+            cbet_fraction: 0.5,
+            cbet_frequency: 0.75,
+            cbet_max_opponents: 2,
+            value_bet_fraction: 0.66,
+            raise_multiplier: 3.0,
+            value_bet_strength: HandStrength::Strong,
+            raise_strength: HandStrength::Monster,
+            call_strength: HandStrength::Strong,
+            medium_call_pot_fraction: 0.5,
+            draw_call_pot_fraction: 0.35,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ParsedPositionRanges {
+    open: HandRange,
+    three_bet: HandRange,
+    call: HandRange,
+}
+
+impl ParsedPositionRanges {
+    fn parse(ranges: &PositionRanges) -> Result<Self, RSPokerError> {
+        Ok(Self {
+            open: HandRange::parse(&ranges.open)?,
+            three_bet: HandRange::parse(&ranges.three_bet)?,
+            call: HandRange::parse(&ranges.call)?,
+        })
+    }
+}
+
+#[derive(Debug)]
+struct ParsedRanges {
+    early: ParsedPositionRanges,
+    middle: ParsedPositionRanges,
+    late: ParsedPositionRanges,
+    small_blind: ParsedPositionRanges,
+    big_blind: ParsedPositionRanges,
+    four_bet: HandRange,
+}
+
+impl ParsedRanges {
+    fn parse(config: &TagConfig) -> Result<Self, RSPokerError> {
+        Ok(Self {
+            early: ParsedPositionRanges::parse(&config.early)?,
+            middle: ParsedPositionRanges::parse(&config.middle)?,
+            late: ParsedPositionRanges::parse(&config.late)?,
+            small_blind: ParsedPositionRanges::parse(&config.small_blind)?,
+            big_blind: ParsedPositionRanges::parse(&config.big_blind)?,
+            four_bet: HandRange::parse(&config.four_bet)?,
+        })
+    }
+
+    fn for_position(&self, position: Position) -> &ParsedPositionRanges {
+        match position {
+            Position::Early => &self.early,
+            Position::Middle => &self.middle,
+            Position::Late => &self.late,
+            Position::SmallBlind => &self.small_blind,
+            Position::BigBlind => &self.big_blind,
+        }
+    }
+}
+
+/// A rule based tight aggressive (TAG) player.
+///
+/// Preflop it plays from per position range charts: open raise, re-raise,
+/// or call a raise, and only continue after a re-raise with the very best
+/// hands. After the flop it buckets its hand into a `HandStrength`, value
+/// bets good hands, continuation bets the flop after raising preflop, and
+/// calls with medium pairs and draws only when the price is right.
+///
+/// It doesn't try to be unexploitable. It's meant to be a sensible, human
+/// like opponent to benchmark other agents against.
+///
+/// Preflop ranges only make sense for two card hands. With more hole cards
+/// (Omaha) it just checks or limps preflop and folds to raises.
+#[derive(Debug, Clone)]
+pub struct TagAgent {
+    config: Arc<TagConfig>,
+    ranges: Arc<ParsedRanges>,
+}
+
+impl TagAgent {
+    /// Create an agent, failing if any of the ranges can't be parsed.
+    pub fn new(config: TagConfig) -> Result<Self, RSPokerError> {
+        let ranges = ParsedRanges::parse(&config)?;
+        Ok(Self {
+            config: Arc::new(config),
+            ranges: Arc::new(ranges),
+        })
+    }
+
+    pub fn config(&self) -> &TagConfig {
+        &self.config
+    }
+
+    fn preflop(&self, view: &PlayerView) -> AgentAction {
+        let hole_cards = view.hole_cards();
+        let to_call = to_call(view);
+        if hole_cards.len() != 2 {
+            return if view.current_round_bet() <= view.big_blind {
+                AgentAction::Call
+            } else {
+                check_or_fold(to_call)
+            };
+        }
+
+        let ranges = self
+            .ranges
+            .for_position(Position::of(view, view.player_idx()));
+        match view.round_data.total_raise_count {
+            0 if ranges.open.contains(&hole_cards) => {
+                raise_to(view, self.config.open_size_bb * view.big_blind)
+            }
+            0 => check_or_fold(to_call),
+            1 if ranges.three_bet.contains(&hole_cards) => raise_to(
+                view,
+                self.config.three_bet_multiplier * view.current_round_bet(),
+            ),
+            1 if ranges.call.contains(&hole_cards) => AgentAction::Call,
+            1 => check_or_fold(to_call),
+            _ if self.ranges.four_bet.contains(&hole_cards) => AgentAction::AllIn,
+            _ if ranges.three_bet.contains(&hole_cards) => AgentAction::Call,
+            _ => check_or_fold(to_call),
+        }
+    }
+
+    fn postflop(&self, view: &PlayerView) -> AgentAction {
+        let config = &self.config;
+        let strength = HandStrength::of(view, view.player_idx());
+        let to_call = to_call(view);
+        let pot = view.total_pot();
+
+        if to_call <= 0.0 {
+            if strength >= config.value_bet_strength {
+                return raise_to(
+                    view,
+                    view.current_round_bet() + pot * config.value_bet_fraction,
+                );
+            }
+            if self.should_cbet(view) {
+                return raise_to(view, view.current_round_bet() + pot * config.cbet_fraction);
+            }
+            return AgentAction::Call;
+        }
+
+        if strength >= config.raise_strength {
+            raise_to(view, view.current_round_bet() * config.raise_multiplier)
+        } else if strength >= config.call_strength
+            || (strength == HandStrength::Medium
+                && to_call <= pot * config.medium_call_pot_fraction)
+            || (strength == HandStrength::Draw && to_call <= pot * config.draw_call_pot_fraction)
+        {
+            AgentAction::Call
+        } else {
+            AgentAction::Fold
+        }
+    }
+
+    fn should_cbet(&self, view: &PlayerView) -> bool {
+        view.round == Round::Flop
+            && view.num_active_players() - 1 <= self.config.cbet_max_opponents
+            && preflop_aggressor(view.actions()) == Some(view.player_idx())
+            && rng().random_bool(self.config.cbet_frequency.clamp(0.0, 1.0))
+    }
+}
+
+impl Default for TagAgent {
+    fn default() -> Self {
+        Self::new(TagConfig::default()).expect("Default ranges are valid")
+    }
+}
+
+impl Agent for TagAgent {
+    fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+        // Everyone else is all in so there's nobody left to bet against.
+        if view.num_active_players() == 1 && to_call(view) <= 0.0 {
+            return AgentAction::Call;
+        }
+        if view.round == Round::Preflop {
+            self.preflop(view)
+        } else {
+            self.postflop(view)
+        }
+    }
+}
+
+/// The last player to raise preflop.
+fn preflop_aggressor(actions: &[Action]) -> Option<usize> {
+    actions
+        .iter()
+        .filter_map(|action| match action {
+            Action::PlayedAction(payload)
+                if payload.round == Round::Preflop && payload.final_bet > payload.starting_bet =>
+            {
+                Some(payload.idx)
+            }
+            _ => None,
+        })
+        .next_back()
+}
+
+/// Generates `TagAgent`s that all share the same parsed ranges.
+#[derive(Debug, Clone, Default)]
+pub struct TagAgentGenerator {
+    agent: TagAgent,
+}
+
+impl TagAgentGenerator {
+    pub fn new(config: TagConfig) -> Result<Self, RSPokerError> {
+        Ok(Self {
+            agent: TagAgent::new(config)?,
+        })
+    }
+}
+
+impl AgentGenerator for TagAgentGenerator {
+    fn generate(&self, _game_state: &GameState) -> Box<dyn Agent> {
+        Box::new(self.agent.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::{
+        HoldemSimulationBuilder,
+        agent::{CallingAgent, RandomAgent},
+        test_util::assert_valid_game_state,
+    };
+
+    use super::*;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.as_bytes()
+            .chunks(2)
+            .map(|c| Card::try_from(std::str::from_utf8(c).unwrap()).unwrap())
+            .collect()
+    }
+
+    /// A six handed game with the blinds posted and `hole` dealt to the
+    /// player first to act.
+    fn preflop_state(hole: &str) -> GameState {
+        let mut game_state = GameState::new_starting(vec![100.0; 6], 2.0, 1.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.do_bet(1.0, true).unwrap();
+        game_state.do_bet(2.0, true).unwrap();
+        let idx = game_state.to_act_idx();
+        game_state.hands[idx].extend(cards(hole));
+        game_state
+    }
+
+    fn strength(hole: &str, board: &str) -> HandStrength {
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 0);
+        game_state.board = cards(board);
+        game_state.hands[0].extend(cards(hole));
+        game_state.hands[0].extend(cards(board));
+        HandStrength::of(&game_state, 0)
+    }
+
+    #[test]
+    fn test_default_ranges_parse() {
+        let agent = TagAgent::default();
+        assert!(agent.ranges.early.open.len() < agent.ranges.late.open.len());
+        assert!(!agent.ranges.four_bet.is_empty());
+    }
+
+    #[test]
+    fn test_bad_range_is_an_error() {
+        let config = TagConfig {
+            four_bet: "not a range".to_string(),
+            ..TagConfig::default()
+        };
+        assert!(TagAgent::new(config).is_err());
+    }
+
+    #[test]
+    fn test_positions() {
+        let game_state = GameState::new_starting(vec![100.0; 6], 2.0, 1.0, 0.0, 0);
+        let positions: Vec<Position> = (0..6).map(|i| Position::of(&game_state, i)).collect();
+        assert_eq!(
+            vec![
+                Position::Late,
+                Position::SmallBlind,
+                Position::BigBlind,
+                Position::Early,
+                Position::Middle,
+                Position::Late
+            ],
+            positions
+        );
+
+        let game_state = GameState::new_starting(vec![100.0; 2], 2.0, 1.0, 0.0, 1);
+        assert_eq!(Position::BigBlind, Position::of(&game_state, 0));
+        assert_eq!(Position::Late, Position::of(&game_state, 1));
+    }
+
+    #[test]
+    fn test_hand_strength() {
+        assert_eq!(HandStrength::Monster, strength("AhKd", "AsKc2d"));
+        assert_eq!(HandStrength::Monster, strength("7h7d", "7s2c9d"));
+        assert_eq!(HandStrength::Strong, strength("AhQd", "As7c2d"));
+        assert_eq!(HandStrength::Strong, strength("QhQd", "Js7c2d"));
+        assert_eq!(HandStrength::Medium, strength("8h8d", "Js7c2d"));
+        assert_eq!(HandStrength::Draw, strength("AhKh", "Qh7h2d"));
+        assert_eq!(HandStrength::Draw, strength("9h8d", "Ts7c2d"));
+        assert_eq!(HandStrength::Air, strength("9h8d", "KsKc2d"));
+        // Draws don't count once the river is out.
+        assert_eq!(HandStrength::Air, strength("AhKh", "Qh7h2d3c4s"));
+    }
+
+    #[test]
+    fn test_preflop_chart() {
+        let mut agent = TagAgent::default();
+
+        let game_state = preflop_state("AsAh");
+        let view = PlayerView::new(game_state.to_act_idx(), &game_state, &[]);
+        assert_eq!(AgentAction::Bet(5.0), agent.act(0, &view));
+
+        let game_state = preflop_state("7s2h");
+        let view = PlayerView::new(game_state.to_act_idx(), &game_state, &[]);
+        assert_eq!(AgentAction::Fold, agent.act(0, &view));
+    }
+
+    #[test]
+    fn test_preflop_facing_raise() {
+        let mut agent = TagAgent::default();
+
+        let mut game_state = preflop_state("AsKh");
+        game_state.do_bet(6.0, false).unwrap();
+        let idx = game_state.to_act_idx();
+        game_state.hands[idx].extend(cards("KsKd"));
+        let view = PlayerView::new(idx, &game_state, &[]);
+        assert_eq!(AgentAction::Bet(18.0), agent.act(0, &view));
+
+        game_state.do_bet(18.0, false).unwrap();
+        let idx = game_state.to_act_idx();
+        game_state.hands[idx].extend(cards("9s8s"));
+        let view = PlayerView::new(idx, &game_state, &[]);
+        assert_eq!(AgentAction::Fold, agent.act(0, &view));
+    }
+
+    #[test_log::test]
+    fn test_tag_agents_play_valid_games() {
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let game_state = GameState::new_starting(vec![100.0; 6], 2.0, 1.0, 0.0, 0);
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::<TagAgent>::default(),
+                Box::<RandomAgent>::default(),
+                Box::<TagAgent>::default(),
+                Box::<CallingAgent>::default(),
+                Box::<TagAgent>::default(),
+                Box::<RandomAgent>::default(),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+
+            assert!(sim.game_state.is_complete());
+            assert_valid_game_state(&sim.game_state);
+            for action in &sim.actions {
+                if let Action::FailedAction(failed) = action {
+                    assert!(
+                        failed.result.idx % 2 == 1,
+                        "Tag agent made an invalid action {failed:?}"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -4,7 +4,9 @@ use little_sorry::RegretMatcher;
 use ndarray::ArrayView1;
 use tracing::event;
 
-use crate::arena::{Agent, GameState, Historian, HoldemSimulationBuilder, action::AgentAction};
+use crate::arena::{
+    Agent, GameState, Historian, HoldemSimulationBuilder, PlayerView, action::AgentAction,
+};
 
 use super::{
     CFRHistorian, GameStateIteratorGen, NodeData,
@@ -283,18 +285,21 @@ where
     T: ActionGenerator + 'static,
     I: GameStateIteratorGen + Clone + 'static,
 {
-    fn act(&mut self, id: u128, game_state: &GameState) -> crate::arena::action::AgentAction {
+    fn act(&mut self, id: u128, view: &PlayerView) -> crate::arena::action::AgentAction {
         event!(tracing::Level::TRACE, ?id, "Agent acting");
+        // CFR simulates from the true game state so it's always omniscient.
+        let game_state = view.game_state();
         assert!(
             game_state.round_data.to_act_idx == self.traversal_state.player_idx(),
             "Agent should only be called when it's the player's turn"
         );
 
-        // make sure that we have at least 2 cards
+        // make sure that we have at least our hole cards
+        let num_hole_cards = game_state.game_type.num_hole_cards();
+        let num_cards = game_state.hands[self.traversal_state.player_idx()].count();
         assert!(
-            game_state.hands[self.traversal_state.player_idx()].count() == 2
-                || game_state.hands[self.traversal_state.player_idx()].count() >= 5,
-            "Agent should only be called when it has at least 2 cards"
+            num_cards == num_hole_cards || num_cards >= num_hole_cards + 3,
+            "Agent should only be called when it has at least its hole cards"
         );
 
         // Make sure that the CFR state has a regret matcher for this node
@@ -320,6 +325,11 @@ where
         }
     }
 
+    /// CFR needs every player's cards to explore the game tree.
+    fn omniscient(&self) -> bool {
+        true
+    }
+
     /// CFRAgent should always have a historian
     /// since it needs to keep track of the game state
     /// and the actions taken.
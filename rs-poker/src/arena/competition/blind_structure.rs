@@ -0,0 +1,190 @@
+/// How much the blinds grow at each level of the presets, in big blinds.
+const PRESET_MULTIPLIERS: [f32; 25] = [
+    1.0, 1.5, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 80.0, 100.0,
+    150.0, 200.0, 300.0, 400.0, 500.0, 600.0, 800.0, 1000.0,
+];
+/// The preset level that antes start at.
+const PRESET_ANTE_LEVEL: usize = 4;
+/// How many levels `doubling` creates. By then the blinds are over a
+/// billion times bigger than they started.
+const DOUBLING_LEVELS: usize = 31;
+
+/// The blinds and ante for a number of hands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindLevel {
+    pub small_blind: f32,
+    pub big_blind: f32,
+    pub ante: f32,
+    /// How many hands the level lasts.
+    pub hands: usize,
+}
+
+/// A list of blind levels that a tournament steps through so that the
+/// blinds keep going up and the tournament ends, however passively the
+/// agents play. The last level lasts forever.
+///
+/// ```
+/// use rs_poker::arena::competition::{BlindLevel, BlindStructure};
+///
+/// let structure = BlindStructure::new(vec![
+///     BlindLevel {
+///         small_blind: 5.0,
+///         big_blind: 10.0,
+///         ante: 0.0,
+///         hands: 10,
+///     },
+///     BlindLevel {
+///         small_blind: 10.0,
+///         big_blind: 20.0,
+///         ante: 2.0,
+///         hands: 10,
+///     },
+/// ]);
+///
+/// assert_eq!(10.0, structure.level(9).unwrap().big_blind);
+/// assert_eq!(20.0, structure.level(10).unwrap().big_blind);
+/// assert_eq!(20.0, structure.level(1000).unwrap().big_blind);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlindStructure {
+    pub levels: Vec<BlindLevel>,
+}
+
+impl BlindStructure {
+    pub fn new(levels: Vec<BlindLevel>) -> Self {
+        Self { levels }
+    }
+
+    /// The same blinds for the whole tournament.
+    pub fn fixed(small_blind: f32, big_blind: f32, ante: f32) -> Self {
+        Self::new(vec![BlindLevel {
+            small_blind,
+            big_blind,
+            ante,
+            hands: usize::MAX,
+        }])
+    }
+
+    /// Blinds that double every `hands_per_level` hands, with no ante.
+    pub fn doubling(small_blind: f32, big_blind: f32, hands_per_level: usize) -> Self {
+        let levels = (0..DOUBLING_LEVELS)
+            .map(|level| {
+                let multiplier = 2f32.powi(level as i32);
+                BlindLevel {
+                    small_blind: small_blind * multiplier,
+                    big_blind: big_blind * multiplier,
+                    ante: 0.0,
+                    hands: hands_per_level,
+                }
+            })
+            .collect();
+        Self::new(levels)
+    }
+
+    /// A slow structure with 20 hands per level, for deep stacked
+    /// tournaments.
+    pub fn standard(big_blind: f32) -> Self {
+        Self::preset(big_blind, 20)
+    }
+
+    /// 10 hands per level.
+    pub fn turbo(big_blind: f32) -> Self {
+        Self::preset(big_blind, 10)
+    }
+
+    /// 5 hands per level. Even agents that never put chips in voluntarily
+    /// are blinded out quickly.
+    pub fn hyper(big_blind: f32) -> Self {
+        Self::preset(big_blind, 5)
+    }
+
+    /// The preset levels, starting from `big_blind`. The small blind is
+    /// always half the big blind and an ante of a tenth of the big blind
+    /// starts at the fifth level.
+    fn preset(big_blind: f32, hands: usize) -> Self {
+        let levels = PRESET_MULTIPLIERS
+            .iter()
+            .enumerate()
+            .map(|(idx, multiplier)| {
+                let level_big_blind = big_blind * multiplier;
+                BlindLevel {
+                    small_blind: level_big_blind / 2.0,
+                    big_blind: level_big_blind,
+                    ante: if idx >= PRESET_ANTE_LEVEL {
+                        level_big_blind / 10.0
+                    } else {
+                        0.0
+                    },
+                    hands,
+                }
+            })
+            .collect();
+        Self::new(levels)
+    }
+
+    /// The index of the level that the hand, counting from 0, is played at.
+    /// Returns `None` if there are no levels.
+    pub fn level_idx(&self, hand: usize) -> Option<usize> {
+        let mut start = 0usize;
+        for (idx, level) in self.levels.iter().enumerate() {
+            start = start.saturating_add(level.hands);
+            if hand < start {
+                return Some(idx);
+            }
+        }
+        self.levels.len().checked_sub(1)
+    }
+
+    /// The level that the hand, counting from 0, is played at.
+    pub fn level(&self, hand: usize) -> Option<&BlindLevel> {
+        self.level_idx(hand).map(|idx| &self.levels[idx])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doubling() {
+        let structure = BlindStructure::doubling(5.0, 10.0, 3);
+        assert_eq!(5.0, structure.level(0).unwrap().small_blind);
+        assert_eq!(5.0, structure.level(2).unwrap().small_blind);
+        assert_eq!(10.0, structure.level(3).unwrap().small_blind);
+        assert_eq!(40.0, structure.level(6).unwrap().big_blind);
+    }
+
+    #[test]
+    fn test_presets_get_faster() {
+        let standard = BlindStructure::standard(10.0);
+        let turbo = BlindStructure::turbo(10.0);
+        let hyper = BlindStructure::hyper(10.0);
+
+        assert_eq!(Some(0), standard.level_idx(19));
+        assert_eq!(Some(1), turbo.level_idx(19));
+        assert_eq!(Some(3), hyper.level_idx(19));
+
+        let level = hyper.level(20).unwrap();
+        assert_eq!(40.0, level.big_blind);
+        assert_eq!(20.0, level.small_blind);
+        assert_eq!(4.0, level.ante);
+        assert_eq!(0.0, hyper.level(0).unwrap().ante);
+    }
+
+    #[test]
+    fn test_last_level_lasts_forever() {
+        let structure = BlindStructure::fixed(1.0, 2.0, 0.5);
+        assert_eq!(Some(0), structure.level_idx(usize::MAX - 1));
+        assert_eq!(None, BlindStructure::default().level(0));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let structure = BlindStructure::turbo(2.0);
+        let json = serde_json::to_string(&structure).unwrap();
+        assert_eq!(structure, serde_json::from_str(&json).unwrap());
+    }
+}
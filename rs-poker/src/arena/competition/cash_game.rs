@@ -0,0 +1,593 @@
+use std::{collections::VecDeque, fmt::Display};
+
+use rand::Rng;
+use tracing::{event, trace_span};
+
+use crate::arena::{
+    Agent, GameState, GameType, HoldemSimulationBuilder,
+    agent::{AgentGenerator, FoldingAgent},
+    errors::HoldemSimulationError,
+    historian::HistorianGenerator,
+};
+
+use super::MAX_TABLE_SIZE;
+
+/// The default most an agent can buy in for, in big blinds.
+const DEFAULT_MAX_BUY_IN_BB: f32 = 100.0;
+
+/// Builder for a `CashGame`.
+#[derive(Default)]
+pub struct CashGameBuilder {
+    agent_generators: Option<Vec<Box<dyn AgentGenerator>>>,
+    historian_generators: Option<Vec<Box<dyn HistorianGenerator>>>,
+    big_blind: Option<f32>,
+    small_blind: Option<f32>,
+    ante: f32,
+    game_type: GameType,
+    table_size: Option<usize>,
+    max_buy_in: Option<f32>,
+    rebuy_below: Option<f32>,
+    sit_out_chance: f64,
+    leave_chance: f64,
+    panic_on_historian_error: bool,
+}
+
+/// A cash game session at one table.
+///
+/// Unlike `HoldemCompetition`, which starts every hand from fresh stacks,
+/// agents keep their stacks from hand to hand. Every agent buys in for the
+/// max buy in and tops back up to it whenever their stack drops below the
+/// rebuy threshold. Before each hand an agent can sit out, keeping their
+/// seat and stack, or leave the table, cashing out. Empty seats are filled
+/// from the agents waiting for a seat, starting with the agents that didn't
+/// fit at the table and then those that left, in the order they left.
+///
+/// The button moves to the seat that posted the small blind last hand. If
+/// that seat is now empty or sitting out the button is dead: the hand is
+/// dealt with the button on that seat and the blinds posted by the next
+/// players dealt in, so the big blind still moves one player around the
+/// table. After the hand the button moves on to whoever posted the small
+/// blind.
+///
+/// ```
+/// use rs_poker::arena::AgentGenerator;
+/// use rs_poker::arena::agent::{CallingAgentGenerator, RandomAgentGenerator};
+/// use rs_poker::arena::competition::CashGameBuilder;
+///
+/// let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
+///     Box::<RandomAgentGenerator>::default(),
+///     Box::<CallingAgentGenerator>::default(),
+///     Box::<RandomAgentGenerator>::default(),
+/// ];
+/// let mut cash_game = CashGameBuilder::default()
+///     .agent_generators(agent_gens)
+///     .big_blind(2.0)
+///     .build()
+///     .unwrap();
+///
+/// cash_game.run(50).unwrap();
+/// assert_eq!(3, cash_game.stats().len());
+/// println!("{}", cash_game);
+/// ```
+pub struct CashGame {
+    agent_generators: Vec<Box<dyn AgentGenerator>>,
+    historian_generators: Vec<Box<dyn HistorianGenerator>>,
+    big_blind: f32,
+    small_blind: f32,
+    ante: f32,
+    game_type: GameType,
+    max_buy_in: f32,
+    rebuy_below: f32,
+    sit_out_chance: f64,
+    leave_chance: f64,
+    panic_on_historian_error: bool,
+
+    /// The agent in each seat, if any.
+    seats: Vec<Option<usize>>,
+    /// Agents without a seat, in the order they get one.
+    waiting: VecDeque<usize>,
+    /// The seat with the button, which can be empty.
+    dealer_idx: usize,
+    stacks: Vec<f32>,
+    stats: Vec<SessionStats>,
+    /// The number of hands that have been dealt.
+    pub num_hands: usize,
+}
+
+/// How one agent did over a cash game session.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionStats {
+    /// The number of hands the agent was dealt in.
+    pub hands: usize,
+    /// How many times the agent bought chips: sitting down, which includes
+    /// coming back after leaving, and every rebuy.
+    pub buy_ins: usize,
+    /// The chips the agent has bought, over every buy in and rebuy.
+    pub total_buy_in: f32,
+    /// The chips the agent took with them when leaving the table.
+    pub cashed_out: f32,
+    /// The chips won or lost in big blinds, over every hand played.
+    pub won_bb: f32,
+    /// The sum of the squares of each hand's result in big blinds.
+    won_bb_squared: f32,
+    /// The stack after each hand the agent played, by hand number.
+    pub stack_graph: Vec<(usize, f32)>,
+}
+
+impl SessionStats {
+    /// Big blinds won per 100 hands.
+    pub fn bb_per_100(&self) -> f32 {
+        if self.hands == 0 {
+            return 0.0;
+        }
+        self.won_bb / self.hands as f32 * 100.0
+    }
+
+    /// The standard deviation of the result of a hand, in big blinds.
+    pub fn std_dev(&self) -> f32 {
+        if self.hands < 2 {
+            return 0.0;
+        }
+        let n = self.hands as f32;
+        let mean = self.won_bb / n;
+        let variance = (self.won_bb_squared - n * mean * mean) / (n - 1.0);
+        variance.max(0.0).sqrt()
+    }
+
+    /// The standard deviation of the result over 100 hands, in big blinds.
+    /// This is how much `bb_per_100` is expected to swing from luck alone.
+    pub fn std_dev_per_100(&self) -> f32 {
+        self.std_dev() * 10.0
+    }
+
+    fn record_hand(&mut self, hand: usize, change_bb: f32, stack: f32) {
+        self.hands += 1;
+        self.won_bb += change_bb;
+        self.won_bb_squared += change_bb * change_bb;
+        self.stack_graph.push((hand, stack));
+    }
+}
+
+impl CashGameBuilder {
+    /// Sets the agent generators. The first agents fill the table and the
+    /// rest wait for a seat.
+    pub fn agent_generators(mut self, agent_generators: Vec<Box<dyn AgentGenerator>>) -> Self {
+        self.agent_generators = Some(agent_generators);
+        self
+    }
+
+    /// Sets the historian generators. New historians are made for every
+    /// hand.
+    pub fn historian_generators(
+        mut self,
+        historian_generators: Vec<Box<dyn HistorianGenerator>>,
+    ) -> Self {
+        self.historian_generators = Some(historian_generators);
+        self
+    }
+
+    pub fn big_blind(mut self, big_blind: f32) -> Self {
+        self.big_blind = Some(big_blind);
+        self
+    }
+
+    /// Sets the small blind. Defaults to half the big blind.
+    pub fn small_blind(mut self, small_blind: f32) -> Self {
+        self.small_blind = Some(small_blind);
+        self
+    }
+
+    pub fn ante(mut self, ante: f32) -> Self {
+        self.ante = ante;
+        self
+    }
+
+    pub fn game_type(mut self, game_type: GameType) -> Self {
+        self.game_type = game_type;
+        self
+    }
+
+    /// Sets the number of seats, from 2 to `MAX_TABLE_SIZE`. Defaults to
+    /// the number of agents, up to `MAX_TABLE_SIZE`.
+    pub fn table_size(mut self, table_size: usize) -> Self {
+        self.table_size = Some(table_size);
+        self
+    }
+
+    /// Sets what agents buy in for and top up to. Defaults to 100 big
+    /// blinds.
+    pub fn max_buy_in(mut self, max_buy_in: f32) -> Self {
+        self.max_buy_in = Some(max_buy_in);
+        self
+    }
+
+    /// Sets the stack that agents top up below. Defaults to the big blind,
+    /// so agents only rebuy when they can't cover it.
+    pub fn rebuy_below(mut self, rebuy_below: f32) -> Self {
+        self.rebuy_below = Some(rebuy_below);
+        self
+    }
+
+    /// Sets the chance that a seated agent sits out each hand.
+    pub fn sit_out_chance(mut self, sit_out_chance: f64) -> Self {
+        self.sit_out_chance = sit_out_chance;
+        self
+    }
+
+    /// Sets the chance that a seated agent leaves the table before each
+    /// hand.
+    pub fn leave_chance(mut self, leave_chance: f64) -> Self {
+        self.leave_chance = leave_chance;
+        self
+    }
+
+    /// Sets whether the underlying `HoldemSimulation` should panic if a
+    /// historian errors.
+    pub fn panic_on_historian_error(mut self, panic_on_historian_error: bool) -> Self {
+        self.panic_on_historian_error = panic_on_historian_error;
+        self
+    }
+
+    pub fn build(self) -> Result<CashGame, HoldemSimulationError> {
+        let agent_generators = self
+            .agent_generators
+            .ok_or(HoldemSimulationError::NeedAgents)?;
+        let big_blind = self.big_blind.ok_or(HoldemSimulationError::NeedBlinds)?;
+        let table_size = self
+            .table_size
+            .unwrap_or(agent_generators.len().min(MAX_TABLE_SIZE));
+        if !(2..=MAX_TABLE_SIZE).contains(&table_size) {
+            return Err(HoldemSimulationError::InvalidTableSize);
+        }
+        let num_agents = agent_generators.len();
+        let mut seats = vec![None; table_size];
+        for (seat, idx) in seats.iter_mut().zip(0..num_agents) {
+            *seat = Some(idx);
+        }
+        Ok(CashGame {
+            agent_generators,
+            historian_generators: self.historian_generators.unwrap_or_default(),
+            big_blind,
+            small_blind: self.small_blind.unwrap_or(big_blind / 2.0),
+            ante: self.ante,
+            game_type: self.game_type,
+            max_buy_in: self.max_buy_in.unwrap_or(big_blind * DEFAULT_MAX_BUY_IN_BB),
+            rebuy_below: self.rebuy_below.unwrap_or(big_blind),
+            sit_out_chance: self.sit_out_chance,
+            leave_chance: self.leave_chance,
+            panic_on_historian_error: self.panic_on_historian_error,
+            seats,
+            waiting: (table_size..num_agents).collect(),
+            dealer_idx: 0,
+            stacks: vec![0.0; num_agents],
+            stats: vec![SessionStats::default(); num_agents],
+            num_hands: 0,
+        })
+    }
+}
+
+impl CashGame {
+    /// Deal `num_hands` more hands. Stacks and stats carry on from any
+    /// earlier calls. Hands where fewer than two agents are in their seats
+    /// aren't dealt.
+    pub fn run(&mut self, num_hands: usize) -> Result<(), HoldemSimulationError> {
+        self.run_with_rng(num_hands, &mut rand::rng())
+    }
+
+    /// `run` with the cards and the seat churn coming from `rng`.
+    pub fn run_with_rng<R: Rng>(
+        &mut self,
+        num_hands: usize,
+        rng: &mut R,
+    ) -> Result<(), HoldemSimulationError> {
+        let span = trace_span!("CashGame::run");
+        let _enter = span.enter();
+
+        for _ in 0..num_hands {
+            let playing = self.seat_agents(rng);
+            if playing.iter().filter(|p| **p).count() < 2 {
+                continue;
+            }
+            self.play_hand(&playing, rng)?;
+        }
+        Ok(())
+    }
+
+    /// How each agent did, indexed the same as the agent generators.
+    pub fn stats(&self) -> &[SessionStats] {
+        &self.stats
+    }
+
+    /// Each agent's current stack. Agents without a seat have 0.
+    pub fn stacks(&self) -> &[f32] {
+        &self.stacks
+    }
+
+    /// The agent in each seat, if any.
+    pub fn seats(&self) -> &[Option<usize>] {
+        &self.seats
+    }
+
+    /// The seat with the button, which can be empty.
+    pub fn dealer_idx(&self) -> usize {
+        self.dealer_idx
+    }
+
+    /// Agents leave, empty seats are filled, everyone seated tops up and
+    /// some sit out. Returns whether each seat is dealt in.
+    fn seat_agents<R: Rng>(&mut self, rng: &mut R) -> Vec<bool> {
+        for seat in self.seats.iter_mut() {
+            let Some(idx) = *seat else {
+                continue;
+            };
+            if self.leave_chance > 0.0 && rng.random_bool(self.leave_chance) {
+                event!(tracing::Level::DEBUG, "Agent {} leaves the table", idx);
+                self.stats[idx].cashed_out += std::mem::take(&mut self.stacks[idx]);
+                self.waiting.push_back(idx);
+                *seat = None;
+            }
+        }
+        for seat in self.seats.iter_mut().filter(|seat| seat.is_none()) {
+            *seat = self.waiting.pop_front();
+            if let Some(idx) = seat {
+                event!(tracing::Level::DEBUG, "Agent {} sits down", idx);
+            }
+        }
+        // Sitting down with no chips buys in for the first time.
+        for idx in self.seats.iter().flatten() {
+            let stack = &mut self.stacks[*idx];
+            if *stack < self.rebuy_below || *stack == 0.0 {
+                self.stats[*idx].buy_ins += 1;
+                self.stats[*idx].total_buy_in += self.max_buy_in - *stack;
+                *stack = self.max_buy_in;
+            }
+        }
+        self.seats
+            .iter()
+            .map(|seat| {
+                seat.is_some()
+                    && !(self.sit_out_chance > 0.0 && rng.random_bool(self.sit_out_chance))
+            })
+            .collect()
+    }
+
+    fn play_hand<R: Rng>(
+        &mut self,
+        playing: &[bool],
+        rng: &mut R,
+    ) -> Result<(), HoldemSimulationError> {
+        let stacks: Vec<f32> = self
+            .seats
+            .iter()
+            .zip(playing)
+            .map(|(seat, playing)| match seat {
+                Some(idx) if *playing => self.stacks[*idx],
+                _ => 0.0,
+            })
+            .collect();
+        let game_state = GameState::new_starting(
+            stacks,
+            self.big_blind,
+            self.small_blind,
+            self.ante,
+            self.dealer_idx,
+        )
+        .with_game_type(self.game_type);
+        // Empty seats and agents sitting out have no chips so never act.
+        let agents: Vec<Box<dyn Agent>> = self
+            .seats
+            .iter()
+            .map(|seat| match seat {
+                Some(idx) => self.agent_generators[*idx].generate(&game_state),
+                None => Box::new(FoldingAgent) as Box<dyn Agent>,
+            })
+            .collect();
+        let historians = self
+            .historian_generators
+            .iter()
+            .map(|builder| builder.generate(&game_state))
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .historians(historians)
+            .panic_on_historian_error(self.panic_on_historian_error)
+            .build()?;
+        sim.run(rng);
+
+        let hand = self.num_hands;
+        for (seat, agent) in self.seats.iter().enumerate() {
+            let Some(idx) = *agent else {
+                continue;
+            };
+            if !playing[seat] {
+                continue;
+            }
+            let stack = sim.game_state.stacks[seat];
+            let change = stack - sim.game_state.starting_stacks[seat];
+            self.stacks[idx] = stack;
+            self.stats[idx].record_hand(hand, change / self.big_blind, stack);
+        }
+        self.num_hands += 1;
+
+        // The button moves to whoever posted the small blind, the first seat
+        // dealt in after the button.
+        let num_seats = self.seats.len();
+        self.dealer_idx = (1..=num_seats)
+            .map(|offset| (self.dealer_idx + offset) % num_seats)
+            .find(|seat| playing[*seat])
+            .unwrap_or(self.dealer_idx);
+        Ok(())
+    }
+}
+
+impl Display for CashGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{:<6} {:>6} {:>10} {:>10} {:>8} {:>10} {:>10}",
+            "agent", "hands", "bb/100", "sd/100", "buy ins", "bought", "net"
+        )?;
+        for (idx, stats) in self.stats.iter().enumerate() {
+            let net = self.stacks[idx] + stats.cashed_out - stats.total_buy_in;
+            writeln!(
+                f,
+                "{:<6} {:>6} {:>10.2} {:>10.2} {:>8} {:>10.2} {:>10.2}",
+                idx,
+                stats.hands,
+                stats.bb_per_100(),
+                stats.std_dev_per_100(),
+                stats.buy_ins,
+                stats.total_buy_in,
+                net
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::arena::agent::{AllInAgentGenerator, CallingAgentGenerator, FoldingAgentGenerator};
+
+    use super::*;
+
+    #[test]
+    fn test_chips_are_conserved() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<AllInAgentGenerator>::default(),
+            Box::<CallingAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+        ];
+        let mut cash_game = CashGameBuilder::default()
+            .agent_generators(agent_gens)
+            .big_blind(2.0)
+            .build()
+            .unwrap();
+        cash_game
+            .run_with_rng(100, &mut StdRng::seed_from_u64(42))
+            .unwrap();
+
+        assert_eq!(100, cash_game.num_hands);
+        let mut net = 0.0;
+        for (idx, stats) in cash_game.stats().iter().enumerate() {
+            assert_eq!(100, stats.hands);
+            assert_eq!(100, stats.stack_graph.len());
+            assert!(stats.buy_ins >= 1);
+            net += cash_game.stacks()[idx] + stats.cashed_out - stats.total_buy_in;
+            // The stats agree with the stacks.
+            let won = cash_game.stacks()[idx] - stats.total_buy_in;
+            assert!((won / 2.0 - stats.won_bb).abs() < 1e-2);
+        }
+        assert!(net.abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_rebuy_below_threshold() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+        ];
+        let mut cash_game = CashGameBuilder::default()
+            .agent_generators(agent_gens)
+            .big_blind(10.0)
+            .max_buy_in(100.0)
+            .rebuy_below(100.0)
+            .build()
+            .unwrap();
+        cash_game
+            .run_with_rng(20, &mut StdRng::seed_from_u64(7))
+            .unwrap();
+
+        // Heads up all in every hand someone loses their stack and tops up,
+        // the winner stays above the threshold.
+        let buy_ins: usize = cash_game.stats().iter().map(|s| s.buy_ins).sum();
+        assert!(buy_ins > 2);
+        for (stack, stats) in cash_game.stacks().iter().zip(cash_game.stats()) {
+            assert!(*stack >= 0.0);
+            assert!(stats.total_buy_in >= 100.0);
+        }
+    }
+
+    #[test]
+    fn test_seat_churn() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..6)
+            .map(|_| Box::<CallingAgentGenerator>::default() as Box<dyn AgentGenerator>)
+            .collect();
+        let mut cash_game = CashGameBuilder::default()
+            .agent_generators(agent_gens)
+            .big_blind(2.0)
+            .table_size(4)
+            .sit_out_chance(0.2)
+            .leave_chance(0.1)
+            .build()
+            .unwrap();
+        assert_eq!(&[Some(0), Some(1), Some(2), Some(3)], cash_game.seats());
+
+        cash_game
+            .run_with_rng(200, &mut StdRng::seed_from_u64(3))
+            .unwrap();
+
+        // Agents that started off waiting got to play, nobody played every
+        // hand and nobody is seated twice.
+        assert!(cash_game.stats()[4].hands > 0);
+        assert!(cash_game.stats()[5].hands > 0);
+        assert!(cash_game.stats().iter().all(|s| s.hands < 200));
+        let mut seated: Vec<usize> = cash_game.seats().iter().flatten().copied().collect();
+        seated.sort();
+        seated.dedup();
+        assert_eq!(4, seated.len());
+    }
+
+    #[test]
+    fn test_dead_button() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..3)
+            .map(|_| Box::<FoldingAgentGenerator>::default() as Box<dyn AgentGenerator>)
+            .collect();
+        let mut cash_game = CashGameBuilder::default()
+            .agent_generators(agent_gens)
+            .big_blind(2.0)
+            .table_size(4)
+            .build()
+            .unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Seat 3 is empty. The button goes from seat 0 to the small blind in
+        // seat 1, then seat 2 and skips the empty seat back to seat 0.
+        let mut dealers = vec![];
+        for _ in 0..4 {
+            cash_game.run_with_rng(1, &mut rng).unwrap();
+            dealers.push(cash_game.dealer_idx());
+        }
+        assert_eq!(vec![1, 2, 0, 1], dealers);
+
+        // Seat 2 leaving with the button on it leaves a dead button there
+        // for a hand, then the button moves to seat 0 which posted the small
+        // blind.
+        cash_game.seats[2] = None;
+        cash_game.waiting.clear();
+        cash_game.dealer_idx = 2;
+        cash_game.run_with_rng(1, &mut rng).unwrap();
+        assert_eq!(0, cash_game.dealer_idx());
+    }
+
+    #[test]
+    fn test_build_errors() {
+        assert!(matches!(
+            CashGameBuilder::default().big_blind(2.0).build(),
+            Err(HoldemSimulationError::NeedAgents)
+        ));
+        let agent_gens: Vec<Box<dyn AgentGenerator>> =
+            vec![Box::<CallingAgentGenerator>::default()];
+        assert!(matches!(
+            CashGameBuilder::default()
+                .agent_generators(agent_gens)
+                .big_blind(2.0)
+                .build(),
+            Err(HoldemSimulationError::InvalidTableSize)
+        ));
+    }
+}
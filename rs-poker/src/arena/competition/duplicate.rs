@@ -0,0 +1,348 @@
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use tracing::{event, trace_span};
+
+use crate::arena::{
+    AgentGenerator, GameState, HoldemSimulationBuilder, errors::HoldemSimulationError,
+    historian::HistorianGenerator, sim_builder::build_deck,
+};
+
+/// The z score for a 95% confidence interval.
+const Z_95: f32 = 1.96;
+
+/// How one agent did over a `DuplicateCompetition`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuplicateAgentResult {
+    /// Total winnings in big blinds over every hand played.
+    pub total_change: f32,
+    /// Average winnings in big blinds per 100 hands.
+    pub bb_per_100: f32,
+    /// The standard error of `bb_per_100`. Needs at least two deals.
+    pub std_error: Option<f32>,
+}
+
+impl DuplicateAgentResult {
+    /// The interval `bb_per_100` is in with the confidence given by the z
+    /// score, for example 1.96 for 95%.
+    pub fn confidence_interval(&self, z: f32) -> Option<(f32, f32)> {
+        self.std_error.map(|std_error| {
+            (
+                self.bb_per_100 - z * std_error,
+                self.bb_per_100 + z * std_error,
+            )
+        })
+    }
+
+    /// The 95% confidence interval of `bb_per_100`.
+    pub fn confidence_interval_95(&self) -> Option<(f32, f32)> {
+        self.confidence_interval(Z_95)
+    }
+}
+
+/// The results of a `DuplicateCompetition` so far, indexed the same as the
+/// agent generators.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DuplicateResults {
+    /// The number of distinct deals played.
+    pub num_deals: usize,
+    /// The number of hands played, one per deal and seat rotation.
+    pub num_hands: usize,
+    pub agents: Vec<DuplicateAgentResult>,
+}
+
+/// The duplicate version of `HoldemCompetition`.
+///
+/// Every deal is played once for each seat rotation of the agents, with
+/// the same cards going to the same seats each time. Summing an agent's
+/// results over the rotations cancels out most of the luck of the cards,
+/// so far fewer hands are needed to tell which agent is better.
+///
+/// Agent `i` sits in seat `(i + rotation) % num_seats`. The game states
+/// must have exactly one seat per agent generator.
+///
+/// ```
+/// use rs_poker::arena::AgentGenerator;
+/// use rs_poker::arena::CloneGameStateGenerator;
+/// use rs_poker::arena::GameState;
+/// use rs_poker::arena::agent::{CallingAgentGenerator, RandomAgentGenerator};
+/// use rs_poker::arena::competition::DuplicateCompetition;
+///
+/// let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
+///     Box::<CallingAgentGenerator>::default(),
+///     Box::<RandomAgentGenerator>::default(),
+/// ];
+/// let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+/// let mut competition =
+///     DuplicateCompetition::new(agent_gens, vec![], CloneGameStateGenerator::new(game_state));
+///
+/// let results = competition.run(10).unwrap();
+/// assert_eq!(20, results.num_hands);
+/// println!("{:?}", results.agents[0].confidence_interval_95());
+/// ```
+pub struct DuplicateCompetition<G>
+where
+    G: Iterator<Item = GameState>,
+{
+    agent_generators: Vec<Box<dyn AgentGenerator>>,
+    historian_generators: Vec<Box<dyn HistorianGenerator>>,
+    game_state_iterator: G,
+
+    num_deals: usize,
+    num_hands: usize,
+    // Per agent, in big blinds
+    total_change: Vec<f64>,
+    // Per agent sums of each deal's average result per hand, and of their
+    // squares, for the standard error.
+    deal_sum: Vec<f64>,
+    deal_sum_squares: Vec<f64>,
+}
+
+impl<G> DuplicateCompetition<G>
+where
+    G: Iterator<Item = GameState>,
+{
+    pub fn new(
+        agent_generators: Vec<Box<dyn AgentGenerator>>,
+        historian_generators: Vec<Box<dyn HistorianGenerator>>,
+        game_state_iterator: G,
+    ) -> Self {
+        let num_agents = agent_generators.len();
+        Self {
+            agent_generators,
+            historian_generators,
+            game_state_iterator,
+            num_deals: 0,
+            num_hands: 0,
+            total_change: vec![0.0; num_agents],
+            deal_sum: vec![0.0; num_agents],
+            deal_sum_squares: vec![0.0; num_agents],
+        }
+    }
+
+    /// Play `num_deals` more deals, each once per seat rotation, and return
+    /// the results of every deal so far.
+    ///
+    /// Stops early if the game state iterator runs out.
+    pub fn run(&mut self, num_deals: usize) -> Result<DuplicateResults, HoldemSimulationError> {
+        self.run_with_rng(num_deals, &mut rand::rng())
+    }
+
+    /// `run` with the cards coming from `rng`.
+    pub fn run_with_rng<R: Rng>(
+        &mut self,
+        num_deals: usize,
+        rng: &mut R,
+    ) -> Result<DuplicateResults, HoldemSimulationError> {
+        for _ in 0..num_deals {
+            let Some(game_state) = self.game_state_iterator.next() else {
+                break;
+            };
+            self.play_deal(game_state, rng.random())?;
+        }
+        Ok(self.results())
+    }
+
+    /// The results of every deal so far.
+    pub fn results(&self) -> DuplicateResults {
+        let deals = self.num_deals as f64;
+        let agents = (0..self.agent_generators.len())
+            .map(|idx| {
+                let bb_per_100 = if self.num_hands == 0 {
+                    0.0
+                } else {
+                    self.total_change[idx] / self.num_hands as f64 * 100.0
+                };
+                let std_error = (self.num_deals >= 2).then(|| {
+                    let mean = self.deal_sum[idx] / deals;
+                    let variance =
+                        (self.deal_sum_squares[idx] - deals * mean * mean).max(0.0) / (deals - 1.0);
+                    ((variance / deals).sqrt() * 100.0) as f32
+                });
+                DuplicateAgentResult {
+                    total_change: self.total_change[idx] as f32,
+                    bb_per_100: bb_per_100 as f32,
+                    std_error,
+                }
+            })
+            .collect();
+        DuplicateResults {
+            num_deals: self.num_deals,
+            num_hands: self.num_hands,
+            agents,
+        }
+    }
+
+    /// Play one deal in every seat rotation. Each rotation deals from the
+    /// same deck with an rng seeded the same way, so the cards are too.
+    fn play_deal(&mut self, game_state: GameState, seed: u64) -> Result<(), HoldemSimulationError> {
+        let span = trace_span!("duplicate_deal", seed);
+        let _enter = span.enter();
+
+        let num_agents = self.agent_generators.len();
+        if game_state.num_players != num_agents {
+            return Err(HoldemSimulationError::AgentsDontMatchSeats);
+        }
+        let deck = build_deck(&game_state);
+        let mut deal_change = vec![0.0; num_agents];
+
+        for rotation in 0..num_agents {
+            let agent_idx = |seat: usize| (seat + num_agents - rotation) % num_agents;
+            let agents = (0..num_agents)
+                .map(|seat| self.agent_generators[agent_idx(seat)].generate(&game_state))
+                .collect();
+            let historians = self
+                .historian_generators
+                .iter()
+                .map(|g| g.generate(&game_state))
+                .collect();
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state.clone())
+                .agents(agents)
+                .historians(historians)
+                .deck(deck)
+                .build()?;
+            sim.run(&mut StdRng::seed_from_u64(seed));
+
+            let big_blind = sim.game_state.big_blind as f64;
+            for (seat, (starting, ending)) in sim
+                .game_state
+                .starting_stacks
+                .iter()
+                .zip(sim.game_state.stacks.iter())
+                .enumerate()
+            {
+                deal_change[agent_idx(seat)] += (*ending - *starting) as f64 / big_blind;
+            }
+            self.num_hands += 1;
+        }
+
+        event!(
+            tracing::Level::TRACE,
+            ?deal_change,
+            "duplicate deal complete"
+        );
+        self.num_deals += 1;
+        for (idx, change) in deal_change.into_iter().enumerate() {
+            self.total_change[idx] += change;
+            let per_hand = change / num_agents as f64;
+            self.deal_sum[idx] += per_hand;
+            self.deal_sum_squares[idx] += per_hand * per_hand;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use crate::arena::{
+        CloneGameStateGenerator,
+        agent::{
+            AllInAgentGenerator, CallingAgentGenerator, FoldingAgentGenerator, RandomAgentGenerator,
+        },
+    };
+
+    use super::*;
+
+    fn competition(
+        agent_gens: Vec<Box<dyn AgentGenerator>>,
+        num_players: usize,
+    ) -> DuplicateCompetition<CloneGameStateGenerator> {
+        let game_state = GameState::new_starting(vec![100.0; num_players], 10.0, 5.0, 0.0, 0);
+        DuplicateCompetition::new(agent_gens, vec![], CloneGameStateGenerator::new(game_state))
+    }
+
+    #[test]
+    fn test_identical_agents_cancel_out() {
+        let mut competition = competition(
+            vec![
+                Box::<CallingAgentGenerator>::default(),
+                Box::<CallingAgentGenerator>::default(),
+            ],
+            2,
+        );
+
+        let results = competition.run(20).unwrap();
+
+        assert_eq!(20, results.num_deals);
+        assert_eq!(40, results.num_hands);
+        for agent in &results.agents {
+            assert_relative_eq!(0.0, agent.total_change, epsilon = 1e-3);
+            assert_relative_eq!(0.0, agent.std_error.unwrap(), epsilon = 1e-3);
+        }
+    }
+
+    #[test]
+    fn test_all_in_beats_folding() {
+        let mut competition = competition(
+            vec![
+                Box::<AllInAgentGenerator>::default(),
+                Box::<FoldingAgentGenerator>::default(),
+            ],
+            2,
+        );
+
+        let results = competition.run(5).unwrap();
+
+        // Shoving from the button wins the big blind, and folding from the
+        // button gives up the small blind.
+        let all_in = results.agents[0];
+        assert_relative_eq!(75.0, all_in.bb_per_100, epsilon = 1e-3);
+        assert_relative_eq!(-75.0, results.agents[1].bb_per_100, epsilon = 1e-3);
+        assert_relative_eq!(7.5, all_in.total_change, epsilon = 1e-3);
+        let (low, high) = all_in.confidence_interval_95().unwrap();
+        assert_relative_eq!(75.0, low, epsilon = 1e-3);
+        assert_relative_eq!(75.0, high, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn test_three_handed_results_add_up() {
+        let mut competition = competition(
+            vec![
+                Box::<RandomAgentGenerator>::default(),
+                Box::<CallingAgentGenerator>::default(),
+                Box::<AllInAgentGenerator>::default(),
+            ],
+            3,
+        );
+
+        let results = competition.run(10).unwrap();
+        assert_eq!(30, results.num_hands);
+        let total: f32 = results.agents.iter().map(|a| a.total_change).sum();
+        assert_relative_eq!(0.0, total, epsilon = 1e-2);
+
+        // Results keep adding up over more calls to run.
+        let results = competition.run(5).unwrap();
+        assert_eq!(15, results.num_deals);
+    }
+
+    #[test]
+    fn test_one_deal_has_no_error_estimate() {
+        let mut competition = competition(
+            vec![
+                Box::<CallingAgentGenerator>::default(),
+                Box::<RandomAgentGenerator>::default(),
+            ],
+            2,
+        );
+        let results = competition.run(1).unwrap();
+        assert_eq!(None, results.agents[0].confidence_interval_95());
+    }
+
+    #[test]
+    fn test_seats_must_match_agents() {
+        let mut competition = competition(
+            vec![
+                Box::<CallingAgentGenerator>::default(),
+                Box::<CallingAgentGenerator>::default(),
+            ],
+            3,
+        );
+        assert_eq!(
+            Err(HoldemSimulationError::AgentsDontMatchSeats),
+            competition.run(1)
+        );
+    }
+}
@@ -0,0 +1,471 @@
+use rand::Rng;
+
+use crate::arena::{
+    GameState, GameType,
+    action::{Action, PlayedActionPayload},
+    sim_builder::build_deck,
+};
+use crate::core::{Card, Deck, Hand, PlayerBitSet, Rank, Rankable};
+use crate::omaha::rank_omaha;
+
+/// Estimates how much each seat won in a finished hand with less luck than
+/// the actual result. Averaged over many hands the estimates should come
+/// out the same as the real winnings, but get there with far fewer hands.
+///
+/// Add them to a `HoldemCompetition` with `with_estimator` and the
+/// estimates show up next to `total_change`.
+pub trait ChangeEstimator {
+    /// The name the results are reported under.
+    fn name(&self) -> &str;
+
+    /// The estimated change in each seat's stack, in chips.
+    fn estimate(&self, game_state: &GameState, actions: &[Action]) -> Vec<f32>;
+}
+
+/// An estimate of what each seat expects to take from the pot from some
+/// point in a hand, for example from a reference agent.
+pub trait ValueEstimator {
+    /// The chips each seat expects to be awarded from the pot. The game
+    /// state has every player's cards, the board so far, and each player's
+    /// total bets for the hand in `player_bet`.
+    fn values(&self, game_state: &GameState) -> Vec<f32>;
+}
+
+/// Replaces the result of hands where the players were all in before the
+/// river with what they would win on average over every runout.
+#[derive(Debug, Clone)]
+pub struct AllInAdjustedEstimator {
+    /// How many runouts to sample when more than one card is to come. With
+    /// one card to come every card is tried.
+    pub iterations: usize,
+}
+
+impl Default for AllInAdjustedEstimator {
+    fn default() -> Self {
+        Self { iterations: 1000 }
+    }
+}
+
+impl ChangeEstimator for AllInAdjustedEstimator {
+    fn name(&self) -> &str {
+        "all_in_adjusted"
+    }
+
+    fn estimate(&self, game_state: &GameState, actions: &[Action]) -> Vec<f32> {
+        let actual = actual_change(game_state);
+        // Cards dealt after the last bet are the runout.
+        let Some(last_bet) = actions.iter().rposition(|action| {
+            matches!(
+                action,
+                Action::PlayedAction(_) | Action::FailedAction(_) | Action::ForcedBet(_)
+            )
+        }) else {
+            return actual;
+        };
+        let has_runout = actions[last_bet..]
+            .iter()
+            .any(|action| matches!(action, Action::DealCommunity(_)));
+
+        let mut spot = Spot::new(game_state);
+        actions[..=last_bet]
+            .iter()
+            .for_each(|action| spot.apply(action));
+        if !has_runout || spot.in_hand.count() < 2 {
+            return actual;
+        }
+
+        let expected = expected_awards(
+            &spot.game_state(game_state),
+            self.iterations,
+            &mut rand::rng(),
+        );
+        let mut awarded = vec![0.0; game_state.num_players];
+        for action in actions {
+            if let Action::Award(payload) = action {
+                awarded[payload.idx] += payload.award_amount;
+            }
+        }
+        actual
+            .iter()
+            .zip(awarded)
+            .zip(expected)
+            .map(|((change, awarded), expected)| change - awarded + expected)
+            .collect()
+    }
+}
+
+/// A simplified AIVAT estimator.
+///
+/// Every time community cards are dealt it takes out the luck of the deal:
+/// how much the value estimates changed because of the cards that came,
+/// compared to the average over every card that could have come. With good
+/// value estimates that removes most of the variance from the board while
+/// staying unbiased, since the corrections average out to zero.
+///
+/// Unlike full AIVAT it doesn't correct for the hole cards or for the
+/// players' own decisions, since that needs to know their strategies.
+#[derive(Debug, Clone)]
+pub struct AivatEstimator<V: ValueEstimator> {
+    value: V,
+    /// How many deals to sample when working out the average value of a
+    /// deal of more than one card.
+    pub iterations: usize,
+}
+
+impl<V: ValueEstimator> AivatEstimator<V> {
+    pub fn new(value: V, iterations: usize) -> Self {
+        Self { value, iterations }
+    }
+}
+
+impl Default for AivatEstimator<ShowdownValue> {
+    fn default() -> Self {
+        Self::new(ShowdownValue::default(), 100)
+    }
+}
+
+impl<V: ValueEstimator> ChangeEstimator for AivatEstimator<V> {
+    fn name(&self) -> &str {
+        "aivat"
+    }
+
+    fn estimate(&self, game_state: &GameState, actions: &[Action]) -> Vec<f32> {
+        let mut rng = rand::rng();
+        let mut correction = vec![0.0; game_state.num_players];
+        let mut spot = Spot::new(game_state);
+
+        let mut i = 0;
+        while i < actions.len() {
+            if !matches!(actions[i], Action::DealCommunity(_)) {
+                spot.apply(&actions[i]);
+                i += 1;
+                continue;
+            }
+
+            let before = spot.clone();
+            let mut num_cards = 0;
+            while let Some(action @ Action::DealCommunity(_)) = actions.get(i) {
+                spot.apply(action);
+                num_cards += 1;
+                i += 1;
+            }
+
+            let after = self.value.values(&spot.game_state(game_state));
+            let before_state = before.game_state(game_state);
+            let mut expected = vec![0.0; game_state.num_players];
+            let deals = possible_deals(&before_state, num_cards, self.iterations, &mut rng);
+            for cards in &deals {
+                let mut dealt = before.clone();
+                dealt.board.extend(cards);
+                let values = self.value.values(&dealt.game_state(game_state));
+                for (total, value) in expected.iter_mut().zip(values) {
+                    *total += value / deals.len() as f32;
+                }
+            }
+            for ((correction, after), expected) in correction.iter_mut().zip(after).zip(expected) {
+                *correction += after - expected;
+            }
+        }
+
+        actual_change(game_state)
+            .into_iter()
+            .zip(correction)
+            .map(|(change, correction)| change - correction)
+            .collect()
+    }
+}
+
+/// Values a spot by the pot share each player would win if there was no
+/// more betting and the rest of the board was dealt out.
+#[derive(Debug, Clone)]
+pub struct ShowdownValue {
+    /// How many runouts to sample when more than one card is to come.
+    pub iterations: usize,
+}
+
+impl Default for ShowdownValue {
+    fn default() -> Self {
+        Self { iterations: 100 }
+    }
+}
+
+impl ValueEstimator for ShowdownValue {
+    fn values(&self, game_state: &GameState) -> Vec<f32> {
+        expected_awards(game_state, self.iterations, &mut rand::rng())
+    }
+}
+
+fn actual_change(game_state: &GameState) -> Vec<f32> {
+    game_state
+        .stacks
+        .iter()
+        .zip(game_state.starting_stacks.iter())
+        .map(|(ending, starting)| ending - starting)
+        .collect()
+}
+
+/// The state of a hand part way through, rebuilt from its actions.
+#[derive(Debug, Clone)]
+struct Spot {
+    board: Vec<Card>,
+    contributions: Vec<f32>,
+    in_hand: PlayerBitSet,
+    all_in: PlayerBitSet,
+}
+
+impl Spot {
+    fn new(game_state: &GameState) -> Self {
+        let mut in_hand = PlayerBitSet::default();
+        for idx in 0..game_state.num_players {
+            if !game_state.hole_cards(idx).is_empty() {
+                in_hand.enable(idx);
+            }
+        }
+        Self {
+            board: vec![],
+            contributions: vec![0.0; game_state.num_players],
+            in_hand,
+            all_in: PlayerBitSet::default(),
+        }
+    }
+
+    fn apply(&mut self, action: &Action) {
+        match action {
+            Action::ForcedBet(payload) => {
+                self.contributions[payload.idx] += payload.bet;
+                if payload.player_stack <= 0.0 {
+                    self.all_in.enable(payload.idx);
+                }
+            }
+            Action::PlayedAction(payload) => self.apply_payload(payload),
+            Action::FailedAction(payload) => self.apply_payload(&payload.result),
+            Action::DealCommunity(card) => self.board.push(*card),
+            _ => {}
+        }
+    }
+
+    fn apply_payload(&mut self, payload: &PlayedActionPayload) {
+        self.contributions[payload.idx] += payload.final_pot - payload.starting_pot;
+        self.in_hand = payload.players_active | payload.players_all_in;
+        self.all_in = payload.players_all_in;
+    }
+
+    /// The hand's final state with the board and bets from this spot.
+    fn game_state(&self, final_state: &GameState) -> GameState {
+        let mut game_state = final_state.clone();
+        for idx in 0..game_state.num_players {
+            let mut cards = final_state.hole_cards(idx);
+            if !cards.is_empty() {
+                cards.extend(&self.board);
+            }
+            game_state.hands[idx] = Hand::new_with_cards(cards);
+        }
+        game_state.board = self.board.clone();
+        game_state.total_pot = self.contributions.iter().sum();
+        game_state.player_bet = self.contributions.clone();
+        game_state.player_all_in = self.all_in;
+        game_state.player_active = PlayerBitSet::default();
+        for idx in self.in_hand.ones().filter(|idx| !self.all_in.get(*idx)) {
+            game_state.player_active.enable(idx);
+        }
+        game_state
+    }
+}
+
+/// Every card when dealing one, otherwise `iterations` random deals.
+fn possible_deals<R: Rng>(
+    game_state: &GameState,
+    num_cards: usize,
+    iterations: usize,
+    rng: &mut R,
+) -> Vec<Vec<Card>> {
+    let deck = build_deck(game_state);
+    if num_cards == 1 {
+        deck.iter().map(|card| vec![card]).collect()
+    } else {
+        (0..iterations.max(1))
+            .map(|_| deal(deck, num_cards, rng))
+            .collect()
+    }
+}
+
+fn deal<R: Rng>(mut deck: Deck, num_cards: usize, rng: &mut R) -> Vec<Card> {
+    (0..num_cards).filter_map(|_| deck.deal(rng)).collect()
+}
+
+/// What each seat would be awarded on average if the rest of the board was
+/// dealt out and everyone still in went to showdown.
+fn expected_awards<R: Rng>(game_state: &GameState, iterations: usize, rng: &mut R) -> Vec<f32> {
+    let contenders = game_state.player_active | game_state.player_all_in;
+    let to_come = 5usize.saturating_sub(game_state.board.len());
+    if contenders.count() < 2 || to_come == 0 {
+        return showdown_awards(game_state, &game_state.board);
+    }
+
+    let deals = possible_deals(game_state, to_come, iterations, rng);
+    let mut expected = vec![0.0; game_state.num_players];
+    let mut board = game_state.board.clone();
+    for cards in &deals {
+        board.truncate(game_state.board.len());
+        board.extend(cards);
+        let awards = showdown_awards(game_state, &board);
+        for (total, award) in expected.iter_mut().zip(awards) {
+            *total += award / deals.len() as f32;
+        }
+    }
+    expected
+}
+
+/// Split the pot, and any side pots, between the best hands still in.
+fn showdown_awards(game_state: &GameState, board: &[Card]) -> Vec<f32> {
+    let contenders = game_state.player_active | game_state.player_all_in;
+    let contributions = &game_state.player_bet;
+    let ranks: Vec<Option<Rank>> = (0..game_state.num_players)
+        .map(|idx| {
+            contenders
+                .get(idx)
+                .then(|| rank(game_state, &game_state.hole_cards(idx), board))
+        })
+        .collect();
+
+    let mut levels: Vec<f32> = contenders.ones().map(|idx| contributions[idx]).collect();
+    levels.sort_by(|a, b| a.total_cmp(b));
+    levels.dedup();
+
+    let mut awards = vec![0.0; game_state.num_players];
+    let mut previous = 0.0;
+    for (i, level) in levels.iter().enumerate() {
+        let is_last = i + 1 == levels.len();
+        let pot: f32 = contributions
+            .iter()
+            .map(|bet| {
+                let top = if is_last { *bet } else { bet.min(*level) };
+                (top - bet.min(previous)).max(0.0)
+            })
+            .sum();
+        let eligible = contenders
+            .ones()
+            .filter(|idx| contributions[*idx] >= *level)
+            .collect::<Vec<_>>();
+        let best = eligible.iter().filter_map(|idx| ranks[*idx]).max();
+        let winners = eligible
+            .iter()
+            .filter(|idx| ranks[**idx] == best)
+            .collect::<Vec<_>>();
+        for idx in &winners {
+            awards[**idx] += pot / winners.len() as f32;
+        }
+        previous = *level;
+    }
+    awards
+}
+
+fn rank(game_state: &GameState, hole_cards: &[Card], board: &[Card]) -> Rank {
+    let cards: Vec<Card> = hole_cards.iter().chain(board).copied().collect();
+    match game_state.game_type {
+        GameType::NoLimitHoldem => cards.rank(),
+        GameType::PotLimitOmaha => rank_omaha(hole_cards, board).unwrap_or_else(|| cards.rank()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use crate::arena::{
+        Agent, HoldemSimulationBuilder,
+        agent::{AllInAgent, CallingAgent},
+    };
+
+    use super::*;
+
+    /// A preflop game state where seat 0 has `first` and seat 1 has `second`.
+    fn dealt(first: &str, second: &str) -> GameState {
+        let mut game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.hands[0] = Hand::new_from_str(first).unwrap();
+        game_state.hands[1] = Hand::new_from_str(second).unwrap();
+        game_state.advance_round();
+        game_state
+    }
+
+    fn play(game_state: GameState, agents: Vec<Box<dyn Agent>>) -> (GameState, Vec<Action>) {
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .build()
+            .unwrap();
+        sim.run(&mut rand::rng());
+        (sim.game_state, sim.actions)
+    }
+
+    #[test]
+    fn test_showdown_awards_side_pots() {
+        let mut game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+        game_state.hands[0] = Hand::new_from_str("AsAh").unwrap();
+        game_state.hands[1] = Hand::new_from_str("KsKh").unwrap();
+        game_state.hands[2] = Hand::new_from_str("2c7d").unwrap();
+        game_state.player_bet = vec![20.0, 100.0, 100.0];
+        game_state.player_all_in = PlayerBitSet::new(3);
+        game_state.player_active = PlayerBitSet::default();
+        let board = Hand::new_from_str("3c8d9hJsQd")
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>();
+
+        let awards = showdown_awards(&game_state, &board);
+
+        // Aces take the main pot, kings take the side pot.
+        assert_eq!(vec![60.0, 160.0, 0.0], awards);
+    }
+
+    #[test]
+    fn test_no_all_in_is_unchanged() {
+        let (game_state, actions) = play(
+            dealt("AsAh", "7c2d"),
+            vec![
+                Box::<CallingAgent>::default(),
+                Box::<CallingAgent>::default(),
+            ],
+        );
+        let estimate = AllInAdjustedEstimator::default().estimate(&game_state, &actions);
+        assert_eq!(actual_change(&game_state), estimate);
+    }
+
+    #[test]
+    fn test_all_in_preflop_uses_equity() {
+        let estimator = AllInAdjustedEstimator { iterations: 4000 };
+        for _ in 0..3 {
+            let (game_state, actions) = play(
+                dealt("AsAh", "7c2d"),
+                vec![Box::<AllInAgent>::default(), Box::<AllInAgent>::default()],
+            );
+            let estimate = estimator.estimate(&game_state, &actions);
+
+            // Aces are about 88% against seven deuce, for a pot of 200.
+            assert!(estimate[0] > 65.0 && estimate[0] < 85.0, "{estimate:?}");
+            assert_relative_eq!(0.0, estimate[0] + estimate[1], epsilon = 1e-2);
+        }
+    }
+
+    #[test]
+    fn test_aivat_removes_board_luck() {
+        let estimator = AivatEstimator::new(ShowdownValue { iterations: 200 }, 200);
+        for _ in 0..3 {
+            let (game_state, actions) = play(
+                dealt("AsAh", "7c2d"),
+                vec![
+                    Box::<CallingAgent>::default(),
+                    Box::<CallingAgent>::default(),
+                ],
+            );
+            let estimate = estimator.estimate(&game_state, &actions);
+
+            // Checked down after calling preflop, so the pot of 20 is shared
+            // by equity whichever board came.
+            assert!(estimate[0] > 5.0 && estimate[0] < 10.0, "{estimate:?}");
+            assert_relative_eq!(0.0, estimate[0] + estimate[1], epsilon = 1e-2);
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use std::{
-    collections::{HashMap, VecDeque},
+    collections::{BTreeMap, HashMap, VecDeque},
     fmt::Debug,
 };
 
 use crate::arena::{HoldemSimulation, errors::HoldemSimulationError, game_state::Round};
 
+use super::ChangeEstimator;
+
 /// A  struct to help seeing which agent is likely to do well
 ///
 /// Each competition is a series of `HoldemSimulations`
@@ -28,6 +30,11 @@ pub struct HoldemCompetition<T: Iterator<Item = HoldemSimulation>> {
     // Count of the round before the simulation stopped
     pub before_count: HashMap<Round, usize>,
 
+    /// Running totals from each `ChangeEstimator` by name, normalized in
+    /// big blinds like `total_change`.
+    pub estimated_change: BTreeMap<String, Vec<f32>>,
+    estimators: Vec<Box<dyn ChangeEstimator>>,
+
     /// Maximum number of HoldemSimulation's to
     /// keep in a long call to `run`
     max_sim_history: usize,
@@ -55,9 +62,20 @@ impl<T: Iterator<Item = HoldemSimulation>> HoldemCompetition<T> {
             zero_count: vec![0; MAX_PLAYERS],
             // Round before stopping
             before_count: HashMap::new(),
+            estimated_change: BTreeMap::new(),
+            estimators: vec![],
         }
     }
 
+    /// Also keep a running total of what `estimator` thinks each agent won,
+    /// in `estimated_change`.
+    pub fn with_estimator(mut self, estimator: impl ChangeEstimator + 'static) -> Self {
+        self.estimated_change
+            .insert(estimator.name().to_string(), vec![0.0; MAX_PLAYERS]);
+        self.estimators.push(Box::new(estimator));
+        self
+    }
+
     pub fn run(
         &mut self,
         num_rounds: usize,
@@ -121,6 +139,17 @@ impl<T: Iterator<Item = HoldemSimulation>> HoldemCompetition<T> {
                 self.zero_count[idx] += 1;
             }
         }
+        for estimator in &self.estimators {
+            let estimate = estimator.estimate(&running_sim.game_state, &running_sim.actions);
+            let totals = self
+                .estimated_change
+                .entry(estimator.name().to_string())
+                .or_insert_with(|| vec![0.0; MAX_PLAYERS]);
+            for (idx, change) in estimate.into_iter().enumerate() {
+                totals[idx] += change / running_sim.game_state.big_blind;
+            }
+        }
+
         // Update the count
         let count = self
             .before_count
@@ -141,6 +170,7 @@ impl<T: Iterator<Item = HoldemSimulation>> Debug for HoldemCompetition<T> {
             .field("zero_count", &self.zero_count)
             .field("loss_count", &self.loss_count)
             .field("round_before", &self.before_count)
+            .field("estimated_change", &self.estimated_change)
             .finish()
     }
 }
@@ -149,8 +179,10 @@ impl<T: Iterator<Item = HoldemSimulation>> Debug for HoldemCompetition<T> {
 mod tests {
     use crate::arena::{
         AgentGenerator, CloneGameStateGenerator, GameState,
-        agent::{CallingAgentGenerator, RandomAgentGenerator},
-        competition::StandardSimulationIterator,
+        agent::{AllInAgentGenerator, CallingAgentGenerator, RandomAgentGenerator},
+        competition::{
+            AivatEstimator, AllInAdjustedEstimator, ShowdownValue, StandardSimulationIterator,
+        },
     };
 
     use super::*;
@@ -173,4 +205,30 @@ mod tests {
 
         let _first_results = competition.run(100).unwrap();
     }
+
+    #[test]
+    fn test_estimated_change() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<AllInAgentGenerator>::default(),
+            Box::<CallingAgentGenerator>::default(),
+        ];
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        let sim_gen = StandardSimulationIterator::new(
+            agent_gens,
+            vec![],
+            CloneGameStateGenerator::new(game_state),
+        );
+        let mut competition = HoldemCompetition::new(sim_gen)
+            .with_estimator(AllInAdjustedEstimator { iterations: 100 })
+            .with_estimator(AivatEstimator::new(ShowdownValue { iterations: 20 }, 20));
+
+        competition.run(5).unwrap();
+
+        for name in ["all_in_adjusted", "aivat"] {
+            let totals = &competition.estimated_change[name];
+            // Every hand is all in preflop for 10 big blinds each.
+            assert!(totals[0].abs() <= 50.0, "{name}: {totals:?}");
+            approx::assert_relative_eq!(0.0, totals[0] + totals[1], epsilon = 1e-2);
+        }
+    }
 }
@@ -1,7 +1,27 @@
+mod blind_structure;
+mod cash_game;
+mod duplicate;
+mod estimators;
 mod holdem_competition;
+mod multi_table_tournament;
+mod payouts;
 mod sim_iterator;
 mod tournament;
+mod tournament_format;
 
+pub use blind_structure::{BlindLevel, BlindStructure};
+pub use cash_game::{CashGame, CashGameBuilder, SessionStats};
+pub use duplicate::{DuplicateAgentResult, DuplicateCompetition, DuplicateResults};
+pub use estimators::{
+    AivatEstimator, AllInAdjustedEstimator, ChangeEstimator, ShowdownValue, ValueEstimator,
+};
 pub use holdem_competition::HoldemCompetition;
+pub use multi_table_tournament::{
+    MAX_TABLE_SIZE, MultiTableTournament, MultiTableTournamentBuilder,
+};
+pub use payouts::{PayoutStructure, icm, split_prizes};
 pub use sim_iterator::StandardSimulationIterator;
 pub use tournament::{SingleTableTournament, SingleTableTournamentBuilder, TournamentResults};
+pub use tournament_format::{
+    AddOn, Bounties, BountyFormat, Knockout, Rebuys, TournamentFormat, knocked_out_by,
+};
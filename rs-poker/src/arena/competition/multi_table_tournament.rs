@@ -0,0 +1,567 @@
+use rand::Rng;
+use tracing::{event, trace_span};
+
+use crate::arena::{
+    GameState, GameType, HoldemSimulationBuilder, agent::AgentGenerator,
+    errors::HoldemSimulationError, historian::HistorianGenerator,
+};
+
+use super::{BlindLevel, BlindStructure, PayoutStructure, TournamentResults};
+
+/// The most players that can sit at one table.
+pub const MAX_TABLE_SIZE: usize = 9;
+
+/// Builder for a `MultiTableTournament`.
+#[derive(Default)]
+pub struct MultiTableTournamentBuilder {
+    agent_generators: Option<Vec<Box<dyn AgentGenerator>>>,
+    historian_generators: Option<Vec<Box<dyn HistorianGenerator>>>,
+    starting_stack: Option<f32>,
+    big_blind: Option<f32>,
+    small_blind: Option<f32>,
+    ante: f32,
+    blind_structure: Option<BlindStructure>,
+    game_type: GameType,
+    table_size: Option<usize>,
+    payout_structure: Option<PayoutStructure>,
+    buy_in: Option<f32>,
+    panic_on_historian_error: bool,
+}
+
+/// A tournament with more agents than fit at one table.
+///
+/// Agents are dealt out over as few tables as possible and every table
+/// plays one hand at a time, hand for hand, each as its own
+/// `HoldemSimulation`. After every hand busted agents are given their
+/// places, tables that are no longer needed are broken up and the rest are
+/// balanced so no table has more than one player more than any other. Once
+/// everyone left fits at one table they play down to a winner at the final
+/// table.
+///
+/// ```
+/// use rs_poker::arena::AgentGenerator;
+/// use rs_poker::arena::agent::AllInAgentGenerator;
+/// use rs_poker::arena::competition::MultiTableTournamentBuilder;
+///
+/// let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..30)
+///     .map(|_| Box::<AllInAgentGenerator>::default() as Box<dyn AgentGenerator>)
+///     .collect();
+/// let tournament = MultiTableTournamentBuilder::default()
+///     .agent_generators(agent_gens)
+///     .starting_stack(100.0)
+///     .big_blind(10.0)
+///     .build()
+///     .unwrap();
+///
+/// let results = tournament.run().unwrap();
+/// assert_eq!(30, results.places().len());
+/// ```
+pub struct MultiTableTournament {
+    agent_generators: Vec<Box<dyn AgentGenerator>>,
+    historian_generators: Vec<Box<dyn HistorianGenerator>>,
+    starting_stack: f32,
+    blind_structure: BlindStructure,
+    game_type: GameType,
+    table_size: usize,
+    payout_structure: Option<PayoutStructure>,
+    buy_in: Option<f32>,
+    panic_on_historian_error: bool,
+}
+
+/// One table, by the agents sitting at it.
+#[derive(Debug, Clone, PartialEq)]
+struct Table {
+    /// The index of the agent in each seat.
+    seats: Vec<usize>,
+    /// The seat with the dealer button.
+    dealer_idx: usize,
+}
+
+impl MultiTableTournamentBuilder {
+    /// Sets the agent generators for the tournament, one per entrant.
+    pub fn agent_generators(mut self, agent_generators: Vec<Box<dyn AgentGenerator>>) -> Self {
+        self.agent_generators = Some(agent_generators);
+        self
+    }
+
+    /// Sets the historian generators. A new historian is made for every
+    /// hand at every table.
+    pub fn historian_generators(
+        mut self,
+        historian_generators: Vec<Box<dyn HistorianGenerator>>,
+    ) -> Self {
+        self.historian_generators = Some(historian_generators);
+        self
+    }
+
+    /// Sets the stack every agent starts with.
+    pub fn starting_stack(mut self, starting_stack: f32) -> Self {
+        self.starting_stack = Some(starting_stack);
+        self
+    }
+
+    pub fn big_blind(mut self, big_blind: f32) -> Self {
+        self.big_blind = Some(big_blind);
+        self
+    }
+
+    /// Sets the small blind. Defaults to half the big blind.
+    pub fn small_blind(mut self, small_blind: f32) -> Self {
+        self.small_blind = Some(small_blind);
+        self
+    }
+
+    pub fn ante(mut self, ante: f32) -> Self {
+        self.ante = ante;
+        self
+    }
+
+    /// Sets blind levels that go up as the tournament goes on, in place of
+    /// fixed blinds. Levels count rounds of hands, with every table
+    /// playing one hand in each.
+    pub fn blind_structure(mut self, blind_structure: BlindStructure) -> Self {
+        self.blind_structure = Some(blind_structure);
+        self
+    }
+
+    pub fn game_type(mut self, game_type: GameType) -> Self {
+        self.game_type = game_type;
+        self
+    }
+
+    /// Sets the most players at each table, from 2 to `MAX_TABLE_SIZE`.
+    /// Defaults to `MAX_TABLE_SIZE`.
+    pub fn table_size(mut self, table_size: usize) -> Self {
+        self.table_size = Some(table_size);
+        self
+    }
+
+    /// Sets how the prize pool is paid out at the end of the tournament.
+    pub fn payout_structure(mut self, payout_structure: PayoutStructure) -> Self {
+        self.payout_structure = Some(payout_structure);
+        self
+    }
+
+    /// Sets what every agent paid to enter. The prize pool is the buy in
+    /// times the number of agents. Without a buy in it's the starting
+    /// stack.
+    pub fn buy_in(mut self, buy_in: f32) -> Self {
+        self.buy_in = Some(buy_in);
+        self
+    }
+
+    /// Sets whether the underlying `HoldemSimulation` should panic if a
+    /// historian errors.
+    pub fn panic_on_historian_error(mut self, panic_on_historian_error: bool) -> Self {
+        self.panic_on_historian_error = panic_on_historian_error;
+        self
+    }
+
+    pub fn build(self) -> Result<MultiTableTournament, HoldemSimulationError> {
+        let agent_generators = self
+            .agent_generators
+            .ok_or(HoldemSimulationError::NeedAgents)?;
+        let starting_stack = self
+            .starting_stack
+            .ok_or(HoldemSimulationError::NeedStartingStack)?;
+        let blind_structure = match (self.blind_structure, self.big_blind) {
+            (Some(blind_structure), _) => blind_structure,
+            (None, Some(big_blind)) => BlindStructure::fixed(
+                self.small_blind.unwrap_or(big_blind / 2.0),
+                big_blind,
+                self.ante,
+            ),
+            (None, None) => return Err(HoldemSimulationError::NeedBlinds),
+        };
+        if blind_structure.levels.is_empty() {
+            return Err(HoldemSimulationError::NeedBlinds);
+        }
+        let table_size = self.table_size.unwrap_or(MAX_TABLE_SIZE);
+        if !(2..=MAX_TABLE_SIZE).contains(&table_size) {
+            return Err(HoldemSimulationError::InvalidTableSize);
+        }
+        Ok(MultiTableTournament {
+            agent_generators,
+            historian_generators: self.historian_generators.unwrap_or_default(),
+            starting_stack,
+            blind_structure,
+            game_type: self.game_type,
+            table_size,
+            payout_structure: self.payout_structure,
+            buy_in: self.buy_in,
+            panic_on_historian_error: self.panic_on_historian_error,
+        })
+    }
+}
+
+impl MultiTableTournament {
+    /// Run the tournament to completion.
+    ///
+    /// The places are indexed the same as the agent generators, from 1 for
+    /// the winner to N for the first agent to bust. Agents that bust on the
+    /// same round of hands are placed by the stack they started the hand
+    /// with. `rounds` counts the rounds of hands, with every table playing
+    /// one hand in each.
+    pub fn run(self) -> Result<TournamentResults, HoldemSimulationError> {
+        self.run_with_rng(&mut rand::rng())
+    }
+
+    /// `run` with the cards coming from `rng`.
+    pub fn run_with_rng<R: Rng>(
+        self,
+        rng: &mut R,
+    ) -> Result<TournamentResults, HoldemSimulationError> {
+        let span = trace_span!("MultiTableTournament::run");
+        let _enter = span.enter();
+
+        let num_agents = self.agent_generators.len();
+        let mut stacks = vec![self.starting_stack; num_agents];
+        let mut results = TournamentResults::new(&stacks);
+        let mut place = num_agents;
+        let mut tables = seat_agents(num_agents, self.table_size);
+
+        while place > 1 {
+            let level = *self
+                .blind_structure
+                .level(results.rounds())
+                .ok_or(HoldemSimulationError::NeedBlinds)?;
+            let mut busted = vec![];
+            for table in tables.iter_mut() {
+                // With heads up tables an odd number of players can leave
+                // someone waiting on their own for a table to open up.
+                if table.seats.len() < 2 {
+                    continue;
+                }
+                let starting: Vec<f32> = table.seats.iter().map(|idx| stacks[*idx]).collect();
+                let ending = self.play_hand(table, &starting, &level, rng)?;
+                for (seat, stack) in ending.iter().enumerate() {
+                    let idx = table.seats[seat];
+                    stacks[idx] = *stack;
+                    if *stack == 0.0 {
+                        busted.push((idx, starting[seat]));
+                    }
+                }
+                table.move_button(&ending);
+            }
+            results.update_max(&stacks);
+
+            // The shortest stack going into the hand gets the worst place.
+            busted.sort_by(|a, b| a.1.total_cmp(&b.1));
+            for (idx, _) in busted {
+                event!(
+                    tracing::Level::INFO,
+                    "Agent {} finished in place {}",
+                    idx,
+                    place
+                );
+                results.set_place(idx, place);
+                place -= 1;
+            }
+
+            for table in tables.iter_mut() {
+                table.remove_busted(&stacks);
+            }
+            balance(&mut tables, self.table_size);
+        }
+
+        let winners: Vec<usize> = (0..num_agents).filter(|idx| stacks[*idx] > 0.0).collect();
+        if winners.len() != 1 {
+            return Err(HoldemSimulationError::NoWinner);
+        }
+        results.set_place(winners[0], 1);
+        event!(
+            tracing::Level::INFO,
+            "Agent {} finished in place 1",
+            winners[0]
+        );
+        if let Some(payout_structure) = &self.payout_structure {
+            let buy_in = self.buy_in.unwrap_or(self.starting_stack);
+            results.add_to_prize_pool(buy_in * num_agents as f32);
+            results.pay(payout_structure);
+        }
+        Ok(results)
+    }
+
+    /// Play one hand at a table, returning the stacks after it.
+    fn play_hand<R: Rng>(
+        &self,
+        table: &Table,
+        stacks: &[f32],
+        level: &BlindLevel,
+        rng: &mut R,
+    ) -> Result<Vec<f32>, HoldemSimulationError> {
+        let game_state = GameState::new_starting(
+            stacks.to_vec(),
+            level.big_blind,
+            level.small_blind,
+            level.ante,
+            table.dealer_idx,
+        )
+        .with_game_type(self.game_type);
+        let agents = table
+            .seats
+            .iter()
+            .map(|idx| self.agent_generators[*idx].generate(&game_state))
+            .collect();
+        let historians = self
+            .historian_generators
+            .iter()
+            .map(|builder| builder.generate(&game_state))
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .historians(historians)
+            .panic_on_historian_error(self.panic_on_historian_error)
+            .build()?;
+        sim.run(rng);
+        Ok(sim.game_state.stacks)
+    }
+}
+
+impl Table {
+    /// Move the button to the next seat that still has chips.
+    fn move_button(&mut self, stacks: &[f32]) {
+        let num_seats = self.seats.len();
+        let mut dealer_idx = (self.dealer_idx + 1) % num_seats;
+        while stacks[dealer_idx] == 0.0 && dealer_idx != self.dealer_idx {
+            dealer_idx = (dealer_idx + 1) % num_seats;
+        }
+        self.dealer_idx = dealer_idx;
+    }
+
+    /// Stand up every agent without chips, keeping the button in place.
+    fn remove_busted(&mut self, stacks: &[f32]) {
+        let dealer = self.seats.get(self.dealer_idx).copied();
+        self.seats.retain(|idx| stacks[*idx] > 0.0);
+        self.dealer_idx = dealer
+            .and_then(|dealer| self.seats.iter().position(|idx| *idx == dealer))
+            .unwrap_or(0);
+    }
+
+    /// The seat that posts the big blind next hand. Moving that player when
+    /// balancing means nobody gets to skip paying the blinds.
+    fn big_blind_seat(&self) -> usize {
+        let offset = if self.seats.len() == 2 { 1 } else { 2 };
+        (self.dealer_idx + offset) % self.seats.len()
+    }
+}
+
+/// Spread the agents over as few tables as possible, as evenly as possible.
+fn seat_agents(num_agents: usize, table_size: usize) -> Vec<Table> {
+    let num_tables = num_agents.div_ceil(table_size).max(1);
+    let mut tables = vec![
+        Table {
+            seats: vec![],
+            dealer_idx: 0,
+        };
+        num_tables
+    ];
+    for idx in 0..num_agents {
+        tables[idx % num_tables].seats.push(idx);
+    }
+    tables
+}
+
+/// Break tables that are no longer needed, smallest first, and then move
+/// players from the biggest table to the smallest until they are within one
+/// player of each other.
+fn balance(tables: &mut Vec<Table>, table_size: usize) {
+    let remaining: usize = tables.iter().map(|t| t.seats.len()).sum();
+    let needed = remaining.div_ceil(table_size).max(1);
+
+    while tables.len() > needed {
+        let smallest = smallest_table(tables);
+        let broken = tables.remove(smallest);
+        event!(
+            tracing::Level::DEBUG,
+            players = ?broken.seats,
+            "breaking table {}",
+            smallest
+        );
+        for idx in broken.seats {
+            let to = smallest_table(tables);
+            tables[to].seats.push(idx);
+        }
+    }
+
+    loop {
+        let smallest = smallest_table(tables);
+        let biggest = (0..tables.len())
+            .max_by_key(|t| tables[*t].seats.len())
+            .unwrap_or(smallest);
+        if tables[biggest].seats.len() <= tables[smallest].seats.len() + 1 {
+            break;
+        }
+        let seat = tables[biggest].big_blind_seat();
+        let idx = tables[biggest].seats.remove(seat);
+        if seat < tables[biggest].dealer_idx {
+            tables[biggest].dealer_idx -= 1;
+        }
+        tables[biggest].dealer_idx %= tables[biggest].seats.len();
+        event!(
+            tracing::Level::DEBUG,
+            "moving agent {} from table {} to table {}",
+            idx,
+            biggest,
+            smallest
+        );
+        tables[smallest].seats.push(idx);
+    }
+}
+
+fn smallest_table(tables: &[Table]) -> usize {
+    (0..tables.len())
+        .min_by_key(|t| tables[*t].seats.len())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::agent::{AllInAgentGenerator, CallingAgentGenerator};
+
+    use super::*;
+
+    fn sizes(tables: &[Table]) -> Vec<usize> {
+        tables.iter().map(|t| t.seats.len()).collect()
+    }
+
+    #[test]
+    fn test_seat_agents_evenly() {
+        assert_eq!(vec![7, 7, 6], sizes(&seat_agents(20, 9)));
+        assert_eq!(vec![9], sizes(&seat_agents(9, 9)));
+        assert_eq!(vec![5, 5], sizes(&seat_agents(10, 9)));
+    }
+
+    #[test]
+    fn test_balance_moves_and_breaks() {
+        let mut tables = seat_agents(27, 9);
+        // Six busts from the first table.
+        tables[0].seats.truncate(3);
+        balance(&mut tables, 9);
+        assert_eq!(vec![7, 7, 7], sizes(&tables));
+
+        // Down to 18 so one table has to go.
+        tables[1].seats.truncate(4);
+        balance(&mut tables, 9);
+        assert_eq!(vec![9, 9], sizes(&tables));
+
+        // Everyone is still seated exactly once.
+        let mut seated: Vec<usize> = tables.iter().flat_map(|t| t.seats.clone()).collect();
+        seated.sort();
+        seated.dedup();
+        assert_eq!(18, seated.len());
+    }
+
+    #[test]
+    fn test_remove_busted_keeps_button() {
+        let mut table = Table {
+            seats: vec![3, 4, 5, 6],
+            dealer_idx: 2,
+        };
+        let mut stacks = vec![10.0; 7];
+        stacks[4] = 0.0;
+        table.remove_busted(&stacks);
+        assert_eq!(vec![3, 5, 6], table.seats);
+        assert_eq!(1, table.dealer_idx);
+    }
+
+    #[test]
+    fn test_all_in_tournament_places_everyone() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..40)
+            .map(|_| Box::<AllInAgentGenerator>::default() as Box<dyn AgentGenerator>)
+            .collect();
+        let tournament = MultiTableTournamentBuilder::default()
+            .agent_generators(agent_gens)
+            .starting_stack(100.0)
+            .big_blind(10.0)
+            .ante(1.0)
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+
+        let mut places = results.places().to_vec();
+        places.sort();
+        assert_eq!((1..=40).collect::<Vec<_>>(), places);
+        assert_eq!(vec![0.0; 40], results.prizes());
+        approx::assert_relative_eq!(
+            4000.0,
+            results.max_stacks().iter().cloned().fold(0.0, f32::max),
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn test_small_tables() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..7)
+            .map(|i| {
+                if i % 2 == 0 {
+                    Box::<AllInAgentGenerator>::default() as Box<dyn AgentGenerator>
+                } else {
+                    Box::<CallingAgentGenerator>::default() as Box<dyn AgentGenerator>
+                }
+            })
+            .collect();
+        let tournament = MultiTableTournamentBuilder::default()
+            .agent_generators(agent_gens)
+            .starting_stack(50.0)
+            .big_blind(10.0)
+            .table_size(2)
+            .payout_structure(PayoutStructure::DoubleOrNothing)
+            .buy_in(10.0)
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+        assert_eq!(1, results.places().iter().filter(|p| **p == 1).count());
+        for (place, prize) in results.places().iter().zip(results.prizes()) {
+            let expected = if *place <= 3 { 70.0 / 3.0 } else { 0.0 };
+            assert_eq!(expected, *prize);
+        }
+        assert!(!results.places().contains(&0));
+    }
+
+    #[test]
+    fn test_blind_structure_ends_passive_tournament() {
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..12)
+            .map(|_| Box::<CallingAgentGenerator>::default() as Box<dyn AgentGenerator>)
+            .collect();
+        let tournament = MultiTableTournamentBuilder::default()
+            .agent_generators(agent_gens)
+            .starting_stack(500.0)
+            .blind_structure(BlindStructure::hyper(2.0))
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+        assert_eq!(1, results.places().iter().filter(|p| **p == 1).count());
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        assert!(matches!(
+            MultiTableTournamentBuilder::default()
+                .starting_stack(100.0)
+                .big_blind(10.0)
+                .build(),
+            Err(HoldemSimulationError::NeedAgents)
+        ));
+        assert!(matches!(
+            MultiTableTournamentBuilder::default()
+                .agent_generators(vec![])
+                .starting_stack(100.0)
+                .build(),
+            Err(HoldemSimulationError::NeedBlinds)
+        ));
+        assert!(matches!(
+            MultiTableTournamentBuilder::default()
+                .agent_generators(vec![])
+                .starting_stack(100.0)
+                .big_blind(10.0)
+                .table_size(12)
+                .build(),
+            Err(HoldemSimulationError::InvalidTableSize)
+        ));
+    }
+}
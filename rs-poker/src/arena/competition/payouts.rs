@@ -0,0 +1,237 @@
+/// How the prize pool of a tournament is paid out by finishing place.
+///
+/// ```
+/// use rs_poker::arena::competition::PayoutStructure;
+///
+/// let payouts = PayoutStructure::percentages(vec![50.0, 30.0, 20.0]);
+/// assert_eq!(
+///     vec![500.0, 300.0, 200.0, 0.0, 0.0],
+///     payouts.prizes(5, 1000.0)
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum PayoutStructure {
+    /// First place gets everything.
+    WinnerTakeAll,
+    /// The top half of the field each get the same share, about double
+    /// their buy in.
+    DoubleOrNothing,
+    /// The percentage of the prize pool for each place, starting with
+    /// first. If there are fewer players than paid places the percentages
+    /// that are left are scaled up to pay out the whole pool.
+    Percentages { percentages: Vec<f32> },
+    /// Fixed amounts for each place, starting with first, whatever the
+    /// prize pool.
+    Fixed { amounts: Vec<f32> },
+}
+
+impl PayoutStructure {
+    pub fn percentages(percentages: Vec<f32>) -> Self {
+        PayoutStructure::Percentages { percentages }
+    }
+
+    pub fn fixed(amounts: Vec<f32>) -> Self {
+        PayoutStructure::Fixed { amounts }
+    }
+
+    /// A typical payout table for the size of the field. Small fields pay
+    /// one to three places, bigger fields pay about 15% of the players with
+    /// each place worth less than the one above it.
+    pub fn standard(num_players: usize) -> Self {
+        let percentages = match num_players {
+            0..=3 => vec![100.0],
+            4..=6 => vec![65.0, 35.0],
+            7..=10 => vec![50.0, 30.0, 20.0],
+            11..=20 => vec![40.0, 25.0, 15.0, 12.0, 8.0],
+            21..=50 => vec![30.0, 20.0, 14.0, 10.0, 8.0, 6.0, 5.0, 4.0, 3.0],
+            _ => {
+                let paid = num_players * 15 / 100;
+                let weights: Vec<f32> = (1..=paid).map(|place| 1.0 / place as f32).collect();
+                let total: f32 = weights.iter().sum();
+                weights.iter().map(|w| w / total * 100.0).collect()
+            }
+        };
+        Self::percentages(percentages)
+    }
+
+    /// The prize for each place, starting with first, for a field of
+    /// `num_players`. Places that aren't paid get 0.
+    pub fn prizes(&self, num_players: usize, prize_pool: f32) -> Vec<f32> {
+        let mut prizes = vec![0.0; num_players];
+        match self {
+            PayoutStructure::WinnerTakeAll => {
+                if let Some(first) = prizes.first_mut() {
+                    *first = prize_pool;
+                }
+            }
+            PayoutStructure::DoubleOrNothing => {
+                let paid = (num_players / 2).max(1).min(num_players);
+                for prize in prizes.iter_mut().take(paid) {
+                    *prize = prize_pool / paid as f32;
+                }
+            }
+            PayoutStructure::Percentages { percentages } => {
+                let total: f32 = percentages.iter().take(num_players).sum();
+                if total > 0.0 {
+                    for (prize, percentage) in prizes.iter_mut().zip(percentages) {
+                        *prize = prize_pool * percentage / total;
+                    }
+                }
+            }
+            PayoutStructure::Fixed { amounts } => {
+                for (prize, amount) in prizes.iter_mut().zip(amounts) {
+                    *prize = *amount;
+                }
+            }
+        }
+        prizes
+    }
+
+    /// The prize for each player given the place they finished in, from 1.
+    /// Players that share a place split the prizes for the places they
+    /// cover.
+    pub fn payouts(&self, places: &[usize], prize_pool: f32) -> Vec<f32> {
+        split_prizes(places, &self.prizes(places.len(), prize_pool))
+    }
+}
+
+/// Give each player the prize for their place, from 1, with tied players
+/// splitting the prizes for the places they cover equally.
+pub fn split_prizes(places: &[usize], prizes: &[f32]) -> Vec<f32> {
+    places
+        .iter()
+        .map(|place| {
+            let tied = places.iter().filter(|other| *other == place).count();
+            let start = place.saturating_sub(1);
+            let total: f32 = (start..start + tied)
+                .map(|idx| prizes.get(idx).copied().unwrap_or_default())
+                .sum();
+            total / tied as f32
+        })
+        .collect()
+}
+
+/// The Independent Chip Model equity of each stack.
+///
+/// Uses the Malmuth-Harville model: the chance of a player finishing
+/// first is their share of the chips, and the chance of each finishing
+/// order follows from removing the players above them and repeating. Only
+/// the places that are paid are expanded, trailing places paying nothing
+/// are left out, but the work still grows quickly with the number of
+/// players and paid places, so this is meant for a single table or a final
+/// table.
+///
+/// ```
+/// use rs_poker::arena::competition::icm;
+///
+/// // Equal stacks have equal equity.
+/// let equity = icm(&[100.0, 100.0, 100.0], &[70.0, 30.0]);
+/// for e in equity {
+///     assert!((e - 100.0 / 3.0).abs() < 1e-3);
+/// }
+/// ```
+pub fn icm(stacks: &[f32], prizes: &[f32]) -> Vec<f32> {
+    let stacks: Vec<f64> = stacks.iter().map(|s| f64::from(s.max(0.0))).collect();
+    let paid = prizes
+        .iter()
+        .rposition(|p| *p > 0.0)
+        .map_or(0, |i| i + 1)
+        .min(stacks.len());
+    let mut equity = vec![0.0; stacks.len()];
+    let mut finished = vec![false; stacks.len()];
+    icm_place(&stacks, &prizes[..paid], 1.0, &mut finished, &mut equity);
+    equity.into_iter().map(|e| e as f32).collect()
+}
+
+/// Share out the prize for the next place over every player still in,
+/// then recurse for the places below.
+fn icm_place(
+    stacks: &[f64],
+    prizes: &[f32],
+    probability: f64,
+    finished: &mut [bool],
+    equity: &mut [f64],
+) {
+    let Some((prize, rest)) = prizes.split_first() else {
+        return;
+    };
+    let total: f64 = stacks
+        .iter()
+        .zip(finished.iter())
+        .filter(|(_, finished)| !**finished)
+        .map(|(stack, _)| stack)
+        .sum();
+    if total <= 0.0 {
+        return;
+    }
+    for idx in 0..stacks.len() {
+        if finished[idx] || stacks[idx] <= 0.0 {
+            continue;
+        }
+        let p = probability * stacks[idx] / total;
+        equity[idx] += p * f64::from(*prize);
+        finished[idx] = true;
+        icm_place(stacks, rest, p, finished, equity);
+        finished[idx] = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use approx::assert_relative_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_winner_take_all_and_double_or_nothing() {
+        assert_eq!(
+            vec![90.0, 0.0, 0.0],
+            PayoutStructure::WinnerTakeAll.prizes(3, 90.0)
+        );
+        assert_eq!(
+            vec![50.0, 50.0, 50.0, 0.0, 0.0, 0.0],
+            PayoutStructure::DoubleOrNothing.prizes(6, 150.0)
+        );
+    }
+
+    #[test]
+    fn test_percentages_scale_for_small_fields() {
+        let payouts = PayoutStructure::percentages(vec![50.0, 30.0, 20.0]);
+        let prizes = payouts.prizes(2, 80.0);
+        assert_relative_eq!(50.0, prizes[0], epsilon = 1e-4);
+        assert_relative_eq!(30.0, prizes[1], epsilon = 1e-4);
+    }
+
+    #[test]
+    fn test_standard_pays_whole_pool() {
+        for num_players in [2, 6, 9, 18, 45, 100, 500] {
+            let prizes = PayoutStructure::standard(num_players).prizes(num_players, 1000.0);
+            assert_relative_eq!(1000.0, prizes.iter().sum::<f32>(), epsilon = 1e-2);
+            assert!(prizes.windows(2).all(|w| w[0] >= w[1]));
+        }
+    }
+
+    #[test]
+    fn test_ties_split() {
+        let payouts = PayoutStructure::fixed(vec![100.0, 50.0, 20.0]);
+        assert_eq!(
+            vec![100.0, 35.0, 35.0, 0.0],
+            payouts.payouts(&[1, 2, 2, 4], 0.0)
+        );
+    }
+
+    #[test]
+    fn test_icm() {
+        // The classic example: 50/30/20 with stacks of 5000/3000/2000.
+        let equity = icm(&[5000.0, 3000.0, 2000.0], &[50.0, 30.0, 20.0]);
+        assert_relative_eq!(38.393, equity[0], epsilon = 1e-3);
+        assert_relative_eq!(32.750, equity[1], epsilon = 1e-3);
+        assert_relative_eq!(28.857, equity[2], epsilon = 1e-3);
+
+        // A busted player gets nothing and someone with every chip gets
+        // first place.
+        assert_eq!(vec![0.0, 10.0], icm(&[0.0, 500.0], &[10.0, 5.0]));
+    }
+}
@@ -4,6 +4,10 @@ use crate::arena::{
     GameState, agent::AgentGenerator, errors::HoldemSimulationError, historian::HistorianGenerator,
 };
 
+use super::{
+    BlindStructure, Bounties, Knockout, PayoutStructure, TournamentFormat, knocked_out_by,
+};
+
 /// A `SingleTableTournament` is a tournament that has multiple agents
 /// playing holdem poker at a single table. The tournament is played
 /// until a single agent has all the money.
@@ -14,6 +18,10 @@ pub struct SingleTableTournamentBuilder {
     agent_generators: Option<Vec<Box<dyn AgentGenerator>>>,
     historian_generators: Option<Vec<Box<dyn HistorianGenerator>>>,
     starting_game_state: Option<GameState>,
+    blind_structure: Option<BlindStructure>,
+    payout_structure: Option<PayoutStructure>,
+    buy_in: Option<f32>,
+    format: TournamentFormat,
     panic_on_historian_error: bool,
 }
 
@@ -21,18 +29,29 @@ pub struct SingleTableTournamentBuilder {
 /// This includes the places that each agent finished in.
 /// The max stack that each agent had at any point in the tournament.
 /// And the number of rounds that the tournament took to complete.
+/// Along with the prize money each agent won if there were payouts, and
+/// the rebuys, add-ons and bounties for formats that have them.
 #[derive(Debug, Clone)]
 pub struct TournamentResults {
     places: Vec<usize>,
     max_stacks: Vec<f32>,
     rounds: usize,
+    prizes: Vec<f32>,
+    prize_pool: f32,
+    rebuys: Vec<usize>,
+    add_ons: Vec<usize>,
+    bounties: Vec<f32>,
+    knockouts: Vec<Knockout>,
 }
 pub struct SingleTableTournament {
     agent_generators: Vec<Box<dyn AgentGenerator>>,
     historian_generators: Vec<Box<dyn HistorianGenerator>>,
     starting_game_state: GameState,
+    blind_structure: Option<BlindStructure>,
+    payout_structure: Option<PayoutStructure>,
+    buy_in: Option<f32>,
+    format: TournamentFormat,
     panic_on_historian_error: bool,
-    // TODO should this include payouts?
 }
 impl TournamentResults {
     pub fn new(starting_stacks: &[f32]) -> Self {
@@ -40,6 +59,12 @@ impl TournamentResults {
             places: vec![0; starting_stacks.len()],
             max_stacks: starting_stacks.to_vec(),
             rounds: 0,
+            prizes: vec![0.0; starting_stacks.len()],
+            prize_pool: 0.0,
+            rebuys: vec![0; starting_stacks.len()],
+            add_ons: vec![0; starting_stacks.len()],
+            bounties: vec![0.0; starting_stacks.len()],
+            knockouts: vec![],
         }
     }
 
@@ -71,6 +96,65 @@ impl TournamentResults {
     pub fn max_stacks(&self) -> &[f32] {
         &self.max_stacks
     }
+
+    /// Add buy ins, rebuys or add-ons to the prize pool.
+    pub fn add_to_prize_pool(&mut self, amount: f32) {
+        self.prize_pool += amount;
+    }
+
+    /// Pay out the prize pool by the places set so far.
+    pub fn pay(&mut self, payout_structure: &PayoutStructure) {
+        self.prizes = payout_structure.payouts(&self.places, self.prize_pool);
+    }
+
+    /// The prize money each agent won. All 0 if nothing was paid out.
+    pub fn prizes(&self) -> &[f32] {
+        &self.prizes
+    }
+
+    pub fn prize_pool(&self) -> f32 {
+        self.prize_pool
+    }
+
+    /// Record an agent rebuying, paying `cost` into the prize pool.
+    pub fn add_rebuy(&mut self, idx: usize, cost: f32) {
+        self.rebuys[idx] += 1;
+        self.prize_pool += cost;
+    }
+
+    /// How many times each agent rebought.
+    pub fn rebuys(&self) -> &[usize] {
+        &self.rebuys
+    }
+
+    /// Record an agent taking an add-on, paying `cost` into the prize pool.
+    pub fn add_add_on(&mut self, idx: usize, cost: f32) {
+        self.add_ons[idx] += 1;
+        self.prize_pool += cost;
+    }
+
+    /// How many add-ons each agent took.
+    pub fn add_ons(&self) -> &[usize] {
+        &self.add_ons
+    }
+
+    pub fn add_knockout(&mut self, knockout: Knockout) {
+        self.knockouts.push(knockout);
+    }
+
+    /// Every knockout in a bounty tournament, in the order they happened.
+    pub fn knockouts(&self) -> &[Knockout] {
+        &self.knockouts
+    }
+
+    pub fn set_bounties(&mut self, bounties: &[f32]) {
+        self.bounties = bounties.to_vec();
+    }
+
+    /// The bounty money each agent won. All 0 without bounties.
+    pub fn bounties(&self) -> &[f32] {
+        &self.bounties
+    }
 }
 
 impl SingleTableTournamentBuilder {
@@ -97,6 +181,36 @@ impl SingleTableTournamentBuilder {
         self
     }
 
+    /// Sets the blind levels for the tournament. Every hand is played with
+    /// the blinds and ante of its level rather than those of the starting
+    /// game state.
+    pub fn blind_structure(mut self, blind_structure: BlindStructure) -> Self {
+        self.blind_structure = Some(blind_structure);
+        self
+    }
+
+    /// Sets how the prize pool is paid out at the end of the tournament.
+    pub fn payout_structure(mut self, payout_structure: PayoutStructure) -> Self {
+        self.payout_structure = Some(payout_structure);
+        self
+    }
+
+    /// Sets what every agent paid to enter. The prize pool is the buy in
+    /// times the number of agents. Without a buy in the prize pool is all
+    /// the chips in the starting stacks.
+    pub fn buy_in(mut self, buy_in: f32) -> Self {
+        self.buy_in = Some(buy_in);
+        self
+    }
+
+    /// Sets the tournament format, for rebuys, an add-on or bounties.
+    /// Defaults to a freezeout. Agents always rebuy if they can and always
+    /// take the add-on.
+    pub fn format(mut self, format: TournamentFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Sets whether the underlying `HoldemSimulation` should panic if a
     /// historian errors.
     pub fn panic_on_historian_error(mut self, panic_on_historian_error: bool) -> Self {
@@ -120,6 +234,10 @@ impl SingleTableTournamentBuilder {
             agent_generators: agent_builders,
             historian_generators: historian_builders,
             starting_game_state,
+            blind_structure: self.blind_structure,
+            payout_structure: self.payout_structure,
+            buy_in: self.buy_in,
+            format: self.format,
             panic_on_historian_error: self.panic_on_historian_error,
         })
     }
@@ -139,14 +257,57 @@ impl SingleTableTournament {
         let _enter = span.enter();
 
         let mut rand = rand::rng();
+        let num_agents = self.agent_generators.len();
         // The place that we are about to assign to the next agent to bust out.
-        let mut place = self.agent_generators.len();
+        let mut place = num_agents;
         // Holds the results of the tournament.
         let mut results = TournamentResults::new(&self.starting_game_state.stacks);
+        let prize_pool = match self.buy_in {
+            Some(buy_in) => buy_in * num_agents as f32,
+            None => self.starting_game_state.stacks.iter().sum(),
+        };
+        results.add_to_prize_pool(prize_pool);
+        let mut bounties = self
+            .format
+            .bounty
+            .map(|format| Bounties::new(format, num_agents));
         let mut game_state = self.starting_game_state;
 
         // While there is still more than one player left in the tournament
         while place > 1 {
+            let hand = results.rounds();
+            let add_on = self.format.add_on_before(hand);
+            let level = self
+                .blind_structure
+                .as_ref()
+                .and_then(|structure| structure.level(hand));
+            if add_on.is_some() || level.is_some() {
+                let mut stacks = game_state.stacks;
+                if let Some(add_on) = add_on {
+                    for (idx, stack) in stacks.iter_mut().enumerate() {
+                        if *stack > 0.0 {
+                            *stack += add_on.chips;
+                            results.add_add_on(idx, add_on.cost);
+                        }
+                    }
+                }
+                let (big_blind, small_blind, ante) = match level {
+                    Some(level) => (level.big_blind, level.small_blind, level.ante),
+                    None => (
+                        game_state.big_blind,
+                        game_state.small_blind,
+                        game_state.ante,
+                    ),
+                };
+                game_state = GameState::new_starting(
+                    stacks,
+                    big_blind,
+                    small_blind,
+                    ante,
+                    game_state.dealer_idx,
+                )
+                .with_game_type(game_state.game_type);
+            }
             let agents = self
                 .agent_generators
                 .iter()
@@ -170,20 +331,42 @@ impl SingleTableTournament {
             // Update the results
             results.update_max(&sim.game_state.stacks);
 
-            let mut out = sim
-                .game_state
-                .stacks
-                .iter()
-                .enumerate()
-                .filter(|(_, stack)| **stack == 0.0)
-                .filter(|(idx, _)| sim.game_state.starting_stacks[*idx] != 0.0)
-                .map(|(idx, _)| idx)
+            let starting_stacks = &sim.game_state.starting_stacks;
+            let mut stacks = sim.game_state.stacks.clone();
+            let busted = (0..num_agents)
+                .filter(|idx| stacks[*idx] == 0.0 && starting_stacks[*idx] != 0.0)
                 .collect::<Vec<_>>();
 
+            // Whoever knocked the busted agents out collects their bounties,
+            // even if they rebuy.
+            if let Some(bounties) = bounties.as_mut() {
+                for idx in &busted {
+                    let knocked_out_by = knocked_out_by(&sim.actions, *idx);
+                    for knockout in bounties.knock_out(hand, &knocked_out_by, *idx) {
+                        results.add_knockout(knockout);
+                    }
+                }
+            }
+
+            let mut out = vec![];
+            for idx in busted {
+                match self.format.rebuys {
+                    Some(rebuys) if self.format.can_rebuy(hand, results.rebuys()[idx]) => {
+                        event!(tracing::Level::INFO, "Agent {} rebuys", idx);
+                        stacks[idx] = rebuys.chips;
+                        results.add_rebuy(idx, rebuys.cost);
+                        if let Some(bounties) = bounties.as_mut() {
+                            bounties.rebuy(idx);
+                        }
+                    }
+                    _ => out.push(idx),
+                }
+            }
+
             // Sort by the starting stack going into the hand
             out.sort_by(|a, b| {
-                sim.game_state.starting_stacks[*b]
-                    .partial_cmp(&sim.game_state.starting_stacks[*a])
+                starting_stacks[*b]
+                    .partial_cmp(&starting_stacks[*a])
                     .unwrap()
                     .reverse()
             });
@@ -201,18 +384,19 @@ impl SingleTableTournament {
             }
             // Move the dealer button
             // Find the next player with a stack
-            let mut dealer_idx = (sim.game_state.dealer_idx + 1) % sim.game_state.stacks.len();
-            while sim.game_state.stacks[dealer_idx] == 0.0 {
-                dealer_idx = (dealer_idx + 1) % sim.game_state.stacks.len();
+            let mut dealer_idx = (sim.game_state.dealer_idx + 1) % stacks.len();
+            while stacks[dealer_idx] == 0.0 {
+                dealer_idx = (dealer_idx + 1) % stacks.len();
             }
 
             game_state = GameState::new_starting(
-                sim.game_state.stacks,
+                stacks,
                 sim.game_state.big_blind,
                 sim.game_state.small_blind,
                 sim.game_state.ante,
                 dealer_idx,
-            );
+            )
+            .with_game_type(sim.game_state.game_type);
         }
 
         // Assign the winner
@@ -233,6 +417,15 @@ impl SingleTableTournament {
             let idx = winners[0];
             results.set_place(idx, 1);
             event!(tracing::Level::INFO, "Agent {} finished in place 1", idx);
+            if let Some(bounties) = bounties.as_mut() {
+                bounties.finish(idx);
+            }
+        }
+        if let Some(bounties) = bounties {
+            results.set_bounties(bounties.won());
+        }
+        if let Some(payout_structure) = &self.payout_structure {
+            results.pay(payout_structure);
         }
         Ok(results)
     }
@@ -240,7 +433,8 @@ impl SingleTableTournament {
 
 #[cfg(test)]
 mod tests {
-    use crate::arena::agent::{AllInAgentGenerator, FoldingAgentGenerator};
+    use crate::arena::agent::{AllInAgentGenerator, CallingAgentGenerator, FoldingAgentGenerator};
+    use crate::arena::competition::{AddOn, BountyFormat, Rebuys};
 
     use super::*;
 
@@ -268,6 +462,118 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_blind_structure_ends_passive_tournament() {
+        // Calling agents never raise, so with fixed blinds this could take a
+        // very long time.
+        let gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<CallingAgentGenerator>::default(),
+            Box::<CallingAgentGenerator>::default(),
+            Box::<CallingAgentGenerator>::default(),
+        ];
+        let game_state = GameState::new_starting(vec![1000.0; 3], 2.0, 1.0, 0.0, 0);
+        let tournament = SingleTableTournamentBuilder::default()
+            .agent_generators(gens)
+            .starting_game_state(game_state)
+            .blind_structure(BlindStructure::hyper(2.0))
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+
+        let mut places = results.places().to_vec();
+        places.sort();
+        assert_eq!(vec![1, 2, 3], places);
+        approx::assert_relative_eq!(
+            3000.0,
+            results.max_stacks().iter().cloned().fold(0.0, f32::max),
+            epsilon = 1e-2
+        );
+    }
+
+    #[test]
+    fn test_payouts() {
+        let gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+        ];
+        let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+        let tournament = SingleTableTournamentBuilder::default()
+            .agent_generators(gens)
+            .starting_game_state(game_state)
+            .payout_structure(PayoutStructure::percentages(vec![70.0, 30.0]))
+            .buy_in(10.0)
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+
+        for (place, prize) in results.places().iter().zip(results.prizes()) {
+            let expected = match place {
+                1 => 21.0,
+                2 => 9.0,
+                _ => 0.0,
+            };
+            assert_eq!(expected, *prize);
+        }
+    }
+
+    #[test]
+    fn test_rebuys_add_on_and_progressive_knockout() {
+        let gens: Vec<Box<dyn AgentGenerator>> = vec![
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+            Box::<AllInAgentGenerator>::default(),
+        ];
+        let game_state = GameState::new_starting(vec![100.0; 4], 10.0, 5.0, 0.0, 0);
+        let format = TournamentFormat {
+            rebuys: Some(Rebuys {
+                chips: 100.0,
+                cost: 10.0,
+                until_hand: 3,
+                max_per_player: Some(1),
+            }),
+            add_on: Some(AddOn {
+                chips: 50.0,
+                cost: 5.0,
+                at_hand: 3,
+            }),
+            bounty: Some(BountyFormat::Progressive { bounty: 10.0 }),
+        };
+        let tournament = SingleTableTournamentBuilder::default()
+            .agent_generators(gens)
+            .starting_game_state(game_state)
+            .payout_structure(PayoutStructure::WinnerTakeAll)
+            .buy_in(10.0)
+            .format(format)
+            .build()
+            .unwrap();
+
+        let results = tournament.run().unwrap();
+
+        let mut places = results.places().to_vec();
+        places.sort();
+        assert_eq!(vec![1, 2, 3, 4], places);
+
+        let rebuys: usize = results.rebuys().iter().sum();
+        let add_ons: usize = results.add_ons().iter().sum();
+        assert!(results.rebuys().iter().all(|r| *r <= 1));
+        assert!(results.add_ons().iter().all(|a| *a <= 1));
+        assert_eq!(
+            40.0 + 10.0 * rebuys as f32 + 5.0 * add_ons as f32,
+            results.prize_pool()
+        );
+        assert_eq!(results.prize_pool(), results.prizes().iter().sum::<f32>());
+
+        // Every bounty that was put up, including for rebuys, is won by
+        // someone.
+        let bounties: f32 = results.bounties().iter().sum();
+        assert!((bounties - 10.0 * (4 + rebuys) as f32).abs() < 1e-3);
+        assert!(results.knockouts().len() >= 3);
+    }
+
     #[test]
     fn test_headsup_tournament_folding_never_wins() {
         let stacks = vec![50.0; 4];
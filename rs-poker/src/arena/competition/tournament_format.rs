@@ -0,0 +1,365 @@
+use crate::arena::action::{Action, AwardPayload};
+
+/// Chips that a busted player can buy back in with during the rebuy
+/// period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rebuys {
+    /// The chips each rebuy gives.
+    pub chips: f32,
+    /// What each rebuy costs. It all goes into the prize pool.
+    pub cost: f32,
+    /// Players who bust before this hand, counting from 0, rebuy.
+    pub until_hand: usize,
+    /// The most times one player can rebuy, or `None` for no limit.
+    pub max_per_player: Option<usize>,
+}
+
+/// Extra chips that every player still in buys once, at the end of the
+/// rebuy period for example.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AddOn {
+    /// The chips the add-on gives.
+    pub chips: f32,
+    /// What the add-on costs. It all goes into the prize pool.
+    pub cost: f32,
+    /// The add-on is taken before this hand, counting from 0.
+    pub at_hand: usize,
+}
+
+/// Money put on every player's head that goes to whoever knocks them out.
+/// Bounties are paid for on top of the buy in and aren't part of the prize
+/// pool. The winner of the tournament collects their own bounty.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum BountyFormat {
+    /// Knocking a player out wins their whole bounty.
+    Standard { bounty: f32 },
+    /// Progressive knockout. Knocking a player out wins half their bounty
+    /// and the other half goes onto the knocker's own head.
+    Progressive { bounty: f32 },
+}
+
+impl BountyFormat {
+    /// The bounty every player starts with.
+    pub fn starting_bounty(&self) -> f32 {
+        match *self {
+            BountyFormat::Standard { bounty } | BountyFormat::Progressive { bounty } => bounty,
+        }
+    }
+}
+
+/// Everything about a tournament beyond a freezeout: rebuys, an add-on and
+/// bounties. The default is a plain freezeout.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TournamentFormat {
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub rebuys: Option<Rebuys>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub add_on: Option<AddOn>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub bounty: Option<BountyFormat>,
+}
+
+impl TournamentFormat {
+    /// Can a player who has already rebought `rebuys` times rebuy after
+    /// busting in `hand`?
+    pub fn can_rebuy(&self, hand: usize, rebuys: usize) -> bool {
+        self.rebuys
+            .is_some_and(|r| hand < r.until_hand && r.max_per_player.is_none_or(|max| rebuys < max))
+    }
+
+    /// The add-on if it is taken before `hand`.
+    pub fn add_on_before(&self, hand: usize) -> Option<AddOn> {
+        self.add_on.filter(|add_on| add_on.at_hand == hand)
+    }
+
+    /// The hand history flags for the format.
+    #[cfg(feature = "open-hand-history")]
+    pub fn flags(&self) -> Vec<crate::open_hand_history::TournamentFlag> {
+        use crate::open_hand_history::TournamentFlag;
+
+        let mut flags = vec![];
+        if self.rebuys.is_some() || self.add_on.is_some() {
+            flags.push(TournamentFlag::Rebuy);
+        }
+        match self.bounty {
+            Some(BountyFormat::Standard { .. }) => flags.push(TournamentFlag::Bounty),
+            Some(BountyFormat::Progressive { .. }) => flags.push(TournamentFlag::ProgressiveBounty),
+            None => {}
+        }
+        flags
+    }
+}
+
+/// One player knocking out another.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Knockout {
+    /// The hand it happened in, counting from 0.
+    pub hand: usize,
+    pub player_idx: usize,
+    pub defeated_idx: usize,
+    /// The bounty money won.
+    pub bounty_won: f32,
+}
+
+#[cfg(feature = "open-hand-history")]
+impl From<&Knockout> for crate::open_hand_history::TournamentBountyObj {
+    fn from(knockout: &Knockout) -> Self {
+        Self {
+            player_id: knockout.player_idx as u64,
+            bounty_won: knockout.bounty_won,
+            defeated_player_id: knockout.defeated_idx as u64,
+        }
+    }
+}
+
+/// The bounties on everyone's heads and what each player has won.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bounties {
+    format: BountyFormat,
+    heads: Vec<f32>,
+    won: Vec<f32>,
+}
+
+impl Bounties {
+    pub fn new(format: BountyFormat, num_players: usize) -> Self {
+        Self {
+            format,
+            heads: vec![format.starting_bounty(); num_players],
+            won: vec![0.0; num_players],
+        }
+    }
+
+    /// The bounty on each player's head.
+    pub fn heads(&self) -> &[f32] {
+        &self.heads
+    }
+
+    /// The bounty money each player has won.
+    pub fn won(&self) -> &[f32] {
+        &self.won
+    }
+
+    /// Pay out the bounty of `defeated` to the players that knocked them
+    /// out, split evenly if there is more than one.
+    pub fn knock_out(&mut self, hand: usize, players: &[usize], defeated: usize) -> Vec<Knockout> {
+        if players.is_empty() {
+            return vec![];
+        }
+        let share = std::mem::take(&mut self.heads[defeated]) / players.len() as f32;
+        players
+            .iter()
+            .map(|idx| {
+                let bounty_won = match self.format {
+                    BountyFormat::Standard { .. } => share,
+                    BountyFormat::Progressive { .. } => {
+                        self.heads[*idx] += share / 2.0;
+                        share / 2.0
+                    }
+                };
+                self.won[*idx] += bounty_won;
+                Knockout {
+                    hand,
+                    player_idx: *idx,
+                    defeated_idx: defeated,
+                    bounty_won,
+                }
+            })
+            .collect()
+    }
+
+    /// A player rebought and has a new bounty on their head.
+    pub fn rebuy(&mut self, idx: usize) {
+        self.heads[idx] += self.format.starting_bounty();
+    }
+
+    /// The tournament is over and the winner collects the bounty on their
+    /// own head.
+    pub fn finish(&mut self, winner: usize) {
+        self.won[winner] += std::mem::take(&mut self.heads[winner]);
+    }
+}
+
+/// The players that knocked `busted` out of a hand: the winners of the
+/// highest pot `busted` had chips in.
+///
+/// Only players that put in at least as much as `busted` could win that pot.
+/// The simulation awards pots best hand first, so it went to the first of
+/// them to be awarded chips, along with anyone holding the same hand.
+pub fn knocked_out_by(actions: &[Action], busted: usize) -> Vec<usize> {
+    let mut sat: Vec<f32> = vec![];
+    let mut stacks: Vec<f32> = vec![];
+    let mut awards = vec![];
+    for action in actions {
+        let (idx, stack) = match action {
+            Action::PlayerSit(payload) => {
+                set_seat(&mut sat, payload.idx, payload.player_stack);
+                (payload.idx, payload.player_stack)
+            }
+            Action::ForcedBet(payload) => (payload.idx, payload.player_stack),
+            Action::PlayedAction(payload) => (payload.idx, payload.player_stack),
+            Action::FailedAction(payload) => (payload.result.idx, payload.result.player_stack),
+            Action::Award(payload) => {
+                if payload.idx != busted && payload.award_amount > 0.0 {
+                    awards.push(payload);
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        set_seat(&mut stacks, idx, stack);
+    }
+
+    let invested =
+        |idx: usize| sat.get(idx).copied().unwrap_or(0.0) - stacks.get(idx).copied().unwrap_or(0.0);
+    let needed = invested(busted);
+    let contenders: Vec<&AwardPayload> = awards
+        .into_iter()
+        .filter(|award| invested(award.idx) >= needed)
+        .collect();
+    let Some(first) = contenders.first() else {
+        return vec![];
+    };
+    let mut players: Vec<usize> = contenders
+        .iter()
+        .filter(|award| award.rank == first.rank)
+        .map(|award| award.idx)
+        .collect();
+    players.sort_unstable();
+    players.dedup();
+    players
+}
+
+fn set_seat(values: &mut Vec<f32>, idx: usize, value: f32) {
+    if values.len() <= idx {
+        values.resize(idx + 1, 0.0);
+    }
+    values[idx] = value;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        arena::action::{ForcedBetPayload, ForcedBetType, PlayerSitPayload},
+        core::Rank,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_can_rebuy() {
+        let format = TournamentFormat {
+            rebuys: Some(Rebuys {
+                chips: 100.0,
+                cost: 10.0,
+                until_hand: 20,
+                max_per_player: Some(2),
+            }),
+            ..Default::default()
+        };
+        assert!(format.can_rebuy(19, 1));
+        assert!(!format.can_rebuy(19, 2));
+        assert!(!format.can_rebuy(20, 0));
+        assert!(!TournamentFormat::default().can_rebuy(0, 0));
+    }
+
+    #[test]
+    fn test_progressive_knockout() {
+        let mut bounties = Bounties::new(BountyFormat::Progressive { bounty: 10.0 }, 3);
+
+        let knockouts = bounties.knock_out(4, &[0], 2);
+        assert_eq!(1, knockouts.len());
+        assert_eq!(5.0, knockouts[0].bounty_won);
+        assert_eq!(&[15.0, 10.0, 0.0], bounties.heads());
+
+        bounties.knock_out(9, &[1], 0);
+        assert_eq!(&[0.0, 17.5, 0.0], bounties.heads());
+        bounties.finish(1);
+        assert_eq!(&[5.0, 25.0, 0.0], bounties.won());
+        // Every bounty paid in has been paid out.
+        assert_eq!(30.0, bounties.won().iter().sum::<f32>());
+    }
+
+    #[test]
+    fn test_standard_bounty_split() {
+        let mut bounties = Bounties::new(BountyFormat::Standard { bounty: 10.0 }, 3);
+        bounties.knock_out(0, &[0, 1], 2);
+        assert_eq!(&[5.0, 5.0, 0.0], bounties.won());
+        bounties.finish(0);
+        assert_eq!(&[15.0, 5.0, 0.0], bounties.won());
+    }
+
+    /// A hand where each player puts in `invested` and the awards are
+    /// made in order.
+    fn hand(stacks: &[f32], invested: &[f32], awards: &[(usize, f32, Rank)]) -> Vec<Action> {
+        let mut actions: Vec<Action> = stacks
+            .iter()
+            .enumerate()
+            .map(|(idx, stack)| {
+                Action::PlayerSit(PlayerSitPayload {
+                    idx,
+                    player_stack: *stack,
+                })
+            })
+            .collect();
+        // Forced bets are the shortest way to put chips in.
+        actions.extend(invested.iter().enumerate().map(|(idx, bet)| {
+            Action::ForcedBet(ForcedBetPayload {
+                bet: *bet,
+                player_stack: stacks[idx] - bet,
+                idx,
+                forced_bet_type: ForcedBetType::Ante,
+            })
+        }));
+        actions.extend(awards.iter().map(|(idx, amount, rank)| {
+            Action::Award(AwardPayload {
+                total_pot: *amount,
+                award_amount: *amount,
+                rank: Some(*rank),
+                hand: None,
+                idx: *idx,
+            })
+        }));
+        actions
+    }
+
+    #[test]
+    fn test_knocked_out_by_side_pot_winner() {
+        // A is all in for 20 and C wins the main pot. B wins the bigger side
+        // pot but A had no chips in it.
+        let actions = hand(
+            &[20.0, 100.0, 100.0],
+            &[20.0, 100.0, 100.0],
+            &[(2, 60.0, Rank::TwoPair(0)), (1, 160.0, Rank::OnePair(0))],
+        );
+        assert_eq!(vec![2], knocked_out_by(&actions, 0));
+    }
+
+    #[test]
+    fn test_knocked_out_by() {
+        // The short stacks A and C are all in. C has the best hand and takes
+        // the main pot, B the side pot from D.
+        let actions = hand(
+            &[20.0, 200.0, 20.0, 200.0],
+            &[20.0, 200.0, 20.0, 200.0],
+            &[(2, 80.0, Rank::Flush(0)), (1, 360.0, Rank::Straight(0))],
+        );
+        assert_eq!(vec![2], knocked_out_by(&actions, 0));
+        assert_eq!(vec![1], knocked_out_by(&actions, 3));
+
+        // A chopped pot is split between everyone with the best hand.
+        let actions = hand(
+            &[50.0, 100.0, 50.0],
+            &[50.0, 50.0, 50.0],
+            &[(0, 75.0, Rank::Straight(0)), (1, 75.0, Rank::Straight(0))],
+        );
+        assert_eq!(vec![0, 1], knocked_out_by(&actions, 2));
+        assert!(knocked_out_by(&[], 2).is_empty());
+    }
+}
@@ -10,6 +10,8 @@ pub enum GameStateError {
     BetSizeDoesntCallSelf,
     #[error("The raise is below the minimum raise size")]
     RaiseSizeTooSmall,
+    #[error("The raise is above the pot limit")]
+    RaiseSizeTooLarge,
     #[error("Can't advance after showdown")]
     CantAdvanceRound,
 }
@@ -24,6 +26,18 @@ pub enum HoldemSimulationError {
 
     #[error("Expected GameState to contain a winner (agent with all the money)")]
     NoWinner,
+
+    #[error("Expected one agent for every seat")]
+    AgentsDontMatchSeats,
+
+    #[error("Builder needs a starting stack")]
+    NeedStartingStack,
+
+    #[error("Builder needs blinds")]
+    NeedBlinds,
+
+    #[error("Tables must seat between 2 and 9 players")]
+    InvalidTableSize,
 }
 
 #[derive(Error, Debug)]
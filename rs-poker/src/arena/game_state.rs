@@ -3,7 +3,8 @@ use std::fmt::Display;
 
 use rand::{Rng, rng};
 
-use crate::core::{Card, Hand, PlayerBitSet};
+use crate::core::{Card, Hand, PlayerBitSet, Rank, Rankable};
+use crate::omaha::{OMAHA_HOLE_CARDS, rank_omaha};
 
 use super::errors::GameStateError;
 
@@ -76,7 +77,47 @@ impl Round {
     }
 }
 
+/// The poker variant being played.
+///
+/// This controls how many hole cards are dealt, how hands are ranked at
+/// showdown, and how large a bet is allowed to be.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameType {
+    /// No limit Texas Holdem. Two hole cards, best five of seven.
+    #[default]
+    NoLimitHoldem,
+    /// Pot limit Omaha. Four hole cards, exactly two of them have to be used
+    /// at showdown, and no bet can be larger than the pot.
+    PotLimitOmaha,
+}
+
+impl GameType {
+    /// How many hole cards each player is dealt.
+    pub fn num_hole_cards(&self) -> usize {
+        match self {
+            GameType::NoLimitHoldem => 2,
+            GameType::PotLimitOmaha => OMAHA_HOLE_CARDS,
+        }
+    }
+
+    /// Are bets capped at the size of the pot.
+    pub fn is_pot_limit(&self) -> bool {
+        matches!(self, GameType::PotLimitOmaha)
+    }
+}
+
+impl Display for GameType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameType::NoLimitHoldem => write!(f, "No Limit Holdem"),
+            GameType::PotLimitOmaha => write!(f, "Pot Limit Omaha"),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RoundData {
     // Which players were active starting this round.
     pub starting_player_active: PlayerBitSet,
@@ -212,6 +253,7 @@ impl RoundData {
 }
 
 #[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
     /// The number of players that started
     pub num_players: usize,
@@ -252,6 +294,8 @@ pub struct GameState {
     // on sim restarts.
     pub bb_posted: bool,
     pub sb_posted: bool,
+    /// The variant being played. Defaults to no limit holdem.
+    pub game_type: GameType,
 }
 
 impl GameState {
@@ -319,9 +363,26 @@ impl GameState {
             // if the game is just starting.
             bb_posted: round != Round::Starting,
             sb_posted: round != Round::Starting,
+            game_type: GameType::default(),
         }
     }
 
+    /// Play a different variant than the default no limit holdem.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rs_poker::arena::game_state::{GameState, GameType};
+    ///
+    /// let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0)
+    ///     .with_game_type(GameType::PotLimitOmaha);
+    /// assert_eq!(4, game_state.game_type.num_hole_cards());
+    /// ```
+    pub fn with_game_type(mut self, game_type: GameType) -> Self {
+        self.game_type = game_type;
+        self
+    }
+
     pub fn new_starting(
         stacks: Vec<f32>,
         big_blind: f32,
@@ -422,6 +483,41 @@ impl GameState {
         self.round_data.min_raise
     }
 
+    /// The largest total bet for this round that the player to act can make.
+    ///
+    /// In no limit games that's going all in. In pot limit games it's a pot
+    /// sized raise: calling and then raising by everything in the pot
+    /// (including the call), or all in if that's smaller.
+    pub fn current_round_max_bet(&self) -> f32 {
+        let all_in = self.current_round_current_player_bet() + self.current_player_stack();
+        if self.game_type.is_pot_limit() {
+            let bet = self.current_round_bet();
+            let to_call = (bet - self.current_round_current_player_bet()).max(0.0);
+            (bet + self.total_pot + to_call).min(all_in)
+        } else {
+            all_in
+        }
+    }
+
+    /// The hole cards of a player. These are the cards in the player's hand
+    /// that are not on the board.
+    pub fn hole_cards(&self, idx: usize) -> Vec<Card> {
+        self.hands[idx]
+            .iter()
+            .filter(|c| !self.board.contains(c))
+            .collect()
+    }
+
+    /// Rank a player's hand according to the rules of the game type.
+    pub fn player_rank(&self, idx: usize) -> Rank {
+        match self.game_type {
+            GameType::NoLimitHoldem => self.hands[idx].rank(),
+            GameType::PotLimitOmaha => rank_omaha(&self.hole_cards(idx), &self.board)
+                // Without enough cards for an omaha hand fall back to using all of them.
+                .unwrap_or_else(|| self.hands[idx].rank()),
+        }
+    }
+
     pub fn advance_round(&mut self) {
         match self.round {
             Round::Complete => (),
@@ -592,6 +688,11 @@ impl GameState {
             } else if is_raise && !is_all_in && raise < self.round_data.min_raise {
                 // There's a raise the raise is less than the min bet and it's not an all in
                 Err(GameStateError::RaiseSizeTooSmall)
+            } else if self.game_type.is_pot_limit()
+                && capped_new_player_bet > self.current_round_max_bet()
+            {
+                // Pot limit games can't bet more than the pot.
+                Err(GameStateError::RaiseSizeTooLarge)
             } else {
                 // Yeah this looks ok.
                 Ok(capped_extra)
@@ -814,6 +915,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pot_limit_caps_raise() {
+        let stacks = vec![500.0; 5];
+        let mut game_state = GameState::new_starting(stacks, 20.0, 10.0, 0.0, 0)
+            .with_game_type(GameType::PotLimitOmaha);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.advance_round();
+
+        game_state.do_bet(10.0, true).unwrap();
+        game_state.do_bet(20.0, true).unwrap();
+
+        // UTG can call 20 and then raise the 50 in the pot.
+        assert_eq!(70.0, game_state.current_round_max_bet());
+        assert_eq!(
+            Err(GameStateError::RaiseSizeTooLarge),
+            game_state.do_bet(71.0, false)
+        );
+        game_state.do_bet(70.0, false).unwrap();
+
+        // Next player calls 70 and raises the 170 pot.
+        assert_eq!(240.0, game_state.current_round_max_bet());
+    }
+
+    #[test]
+    fn test_no_limit_max_bet_is_all_in() {
+        let stacks = vec![500.0; 5];
+        let mut game_state = GameState::new_starting(stacks, 20.0, 10.0, 0.0, 0);
+        game_state.advance_round();
+        game_state.advance_round();
+        game_state.advance_round();
+
+        game_state.do_bet(10.0, true).unwrap();
+        game_state.do_bet(20.0, true).unwrap();
+
+        assert_eq!(500.0, game_state.current_round_max_bet());
+        game_state.do_bet(500.0, false).unwrap();
+    }
+
     #[test]
     fn test_gamestate_keeps_round_before_complete() {
         let stacks = vec![100.0; 3];
@@ -0,0 +1,524 @@
+use crate::arena::{
+    action::{
+        Action, AgentAction, AwardPayload, DealStartingHandPayload, FailedActionPayload,
+        ForcedBetPayload, ForcedBetType, GameStartPayload, PlayedActionPayload, PlayerSitPayload,
+    },
+    game_state::Round,
+};
+use crate::core::{Card, Hand, PlayerBitSet, Rank};
+
+use super::HandLogError;
+
+/// Amounts at or above this are always stored as raw floats.
+const MAX_WHOLE_AMOUNT: f32 = 1e15;
+
+/// Encode a sequence of actions.
+pub fn encode_actions(actions: &[Action]) -> Vec<u8> {
+    let mut encoder = Encoder::default();
+    encoder.varint(actions.len() as u128);
+    for action in actions {
+        encoder.action(action);
+    }
+    encoder.bytes
+}
+
+/// Decode a sequence of actions written by [`encode_actions`].
+pub fn decode_actions(bytes: &[u8]) -> Result<Vec<Action>, HandLogError> {
+    let mut decoder = Decoder { bytes, pos: 0 };
+    let len = decoder.usize()?;
+    // Every action takes at least two bytes.
+    let mut actions = Vec::with_capacity(len.min(bytes.len() / 2));
+    for _ in 0..len {
+        actions.push(decoder.action()?);
+    }
+    if decoder.pos != bytes.len() {
+        return Err(HandLogError::Corrupt(format!(
+            "{} bytes left after the actions",
+            bytes.len() - decoder.pos
+        )));
+    }
+    Ok(actions)
+}
+
+/// Append `value` as a LEB128 varint.
+pub(super) fn write_varint(bytes: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        bytes.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    bytes.push(value as u8);
+}
+
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    fn varint(&mut self, value: u128) {
+        write_varint(&mut self.bytes, value);
+    }
+
+    /// Whole amounts are a varint shifted left one bit, anything else is a
+    /// `1` followed by the little endian bits of the float.
+    fn amount(&mut self, amount: f32) {
+        if (0.0..MAX_WHOLE_AMOUNT).contains(&amount) && amount.fract() == 0.0 {
+            self.varint((amount as u128) << 1);
+        } else {
+            self.varint(1);
+            self.bytes.extend_from_slice(&amount.to_le_bytes());
+        }
+    }
+
+    fn card(&mut self, card: Card) {
+        self.bytes.push(u8::from(card));
+    }
+
+    fn round(&mut self, round: Round) {
+        self.bytes.push(match round {
+            Round::Starting => 0,
+            Round::Ante => 1,
+            Round::DealPreflop => 2,
+            Round::Preflop => 3,
+            Round::DealFlop => 4,
+            Round::Flop => 5,
+            Round::DealTurn => 6,
+            Round::Turn => 7,
+            Round::DealRiver => 8,
+            Round::River => 9,
+            Round::Showdown => 10,
+            Round::Complete => 11,
+        });
+    }
+
+    fn player_set(&mut self, set: PlayerBitSet) {
+        let bits = set.ones().fold(0u128, |bits, idx| bits | (1 << idx));
+        self.varint(bits);
+    }
+
+    fn agent_action(&mut self, action: &AgentAction) {
+        match action {
+            AgentAction::Fold => self.bytes.push(0),
+            AgentAction::Call => self.bytes.push(1),
+            AgentAction::Bet(amount) => {
+                self.bytes.push(2);
+                self.amount(*amount);
+            }
+            AgentAction::AllIn => self.bytes.push(3),
+        }
+    }
+
+    fn played(&mut self, payload: &PlayedActionPayload) {
+        self.agent_action(&payload.action);
+        self.varint(payload.idx as u128);
+        self.round(payload.round);
+        self.amount(payload.player_stack);
+        self.amount(payload.starting_pot);
+        self.amount(payload.final_pot);
+        self.amount(payload.starting_bet);
+        self.amount(payload.final_bet);
+        self.amount(payload.starting_min_raise);
+        self.amount(payload.final_min_raise);
+        self.amount(payload.starting_player_bet);
+        self.amount(payload.final_player_bet);
+        self.player_set(payload.players_active);
+        self.player_set(payload.players_all_in);
+    }
+
+    fn rank(&mut self, rank: Option<Rank>) {
+        let (tag, value) = match rank {
+            None => {
+                self.bytes.push(0);
+                return;
+            }
+            Some(Rank::HighCard(value)) => (1, value),
+            Some(Rank::OnePair(value)) => (2, value),
+            Some(Rank::TwoPair(value)) => (3, value),
+            Some(Rank::ThreeOfAKind(value)) => (4, value),
+            Some(Rank::Straight(value)) => (5, value),
+            Some(Rank::Flush(value)) => (6, value),
+            Some(Rank::FullHouse(value)) => (7, value),
+            Some(Rank::FourOfAKind(value)) => (8, value),
+            Some(Rank::StraightFlush(value)) => (9, value),
+        };
+        self.bytes.push(tag);
+        self.varint(value as u128);
+    }
+
+    fn hand(&mut self, hand: Option<&Hand>) {
+        match hand {
+            None => self.bytes.push(0),
+            Some(hand) => {
+                self.bytes.push(1);
+                self.varint(hand.count() as u128);
+                for card in hand.iter() {
+                    self.card(card);
+                }
+            }
+        }
+    }
+
+    fn action(&mut self, action: &Action) {
+        match action {
+            Action::GameStart(payload) => {
+                self.bytes.push(0);
+                self.amount(payload.ante);
+                self.amount(payload.small_blind);
+                self.amount(payload.big_blind);
+            }
+            Action::PlayerSit(payload) => {
+                self.bytes.push(1);
+                self.varint(payload.idx as u128);
+                self.amount(payload.player_stack);
+            }
+            Action::DealStartingHand(payload) => {
+                self.bytes.push(2);
+                self.varint(payload.idx as u128);
+                self.card(payload.card);
+            }
+            Action::RoundAdvance(round) => {
+                self.bytes.push(3);
+                self.round(*round);
+            }
+            Action::PlayedAction(payload) => {
+                self.bytes.push(4);
+                self.played(payload);
+            }
+            Action::FailedAction(payload) => {
+                self.bytes.push(5);
+                self.agent_action(&payload.action);
+                self.played(&payload.result);
+            }
+            Action::ForcedBet(payload) => {
+                self.bytes.push(6);
+                self.varint(payload.idx as u128);
+                self.amount(payload.bet);
+                self.amount(payload.player_stack);
+                self.bytes.push(match payload.forced_bet_type {
+                    ForcedBetType::Ante => 0,
+                    ForcedBetType::SmallBlind => 1,
+                    ForcedBetType::BigBlind => 2,
+                });
+            }
+            Action::DealCommunity(card) => {
+                self.bytes.push(7);
+                self.card(*card);
+            }
+            Action::Award(payload) => {
+                self.bytes.push(8);
+                self.varint(payload.idx as u128);
+                self.amount(payload.total_pot);
+                self.amount(payload.award_amount);
+                self.rank(payload.rank);
+                self.hand(payload.hand.as_ref());
+            }
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Decoder<'_> {
+    fn byte(&mut self) -> Result<u8, HandLogError> {
+        let byte = *self
+            .bytes
+            .get(self.pos)
+            .ok_or_else(|| HandLogError::Corrupt("hand ends early".to_string()))?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    fn varint(&mut self) -> Result<u128, HandLogError> {
+        let mut value = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.byte()?;
+            value |= ((byte & 0x7f) as u128) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(HandLogError::Corrupt("varint is too long".to_string()))
+    }
+
+    fn usize(&mut self) -> Result<usize, HandLogError> {
+        let value = self.varint()?;
+        usize::try_from(value)
+            .map_err(|_| HandLogError::Corrupt(format!("{value} is too large for an index")))
+    }
+
+    fn amount(&mut self) -> Result<f32, HandLogError> {
+        let value = self.varint()?;
+        if value == 1 {
+            let mut bits = [0u8; 4];
+            for bit in bits.iter_mut() {
+                *bit = self.byte()?;
+            }
+            Ok(f32::from_le_bytes(bits))
+        } else if value & 1 == 0 {
+            Ok((value >> 1) as f32)
+        } else {
+            Err(HandLogError::Corrupt(format!("invalid amount tag {value}")))
+        }
+    }
+
+    fn card(&mut self) -> Result<Card, HandLogError> {
+        let byte = self.byte()?;
+        if byte >= 52 {
+            return Err(HandLogError::Corrupt(format!("invalid card {byte}")));
+        }
+        Ok(Card::from(byte))
+    }
+
+    fn round(&mut self) -> Result<Round, HandLogError> {
+        Ok(match self.byte()? {
+            0 => Round::Starting,
+            1 => Round::Ante,
+            2 => Round::DealPreflop,
+            3 => Round::Preflop,
+            4 => Round::DealFlop,
+            5 => Round::Flop,
+            6 => Round::DealTurn,
+            7 => Round::Turn,
+            8 => Round::DealRiver,
+            9 => Round::River,
+            10 => Round::Showdown,
+            11 => Round::Complete,
+            tag => return Err(HandLogError::Corrupt(format!("invalid round {tag}"))),
+        })
+    }
+
+    fn player_set(&mut self) -> Result<PlayerBitSet, HandLogError> {
+        let bits = self.varint()?;
+        let mut set = PlayerBitSet::default();
+        for idx in 0..u16::BITS as usize {
+            if bits & (1 << idx) != 0 {
+                set.enable(idx);
+            }
+        }
+        if bits >> u16::BITS != 0 {
+            return Err(HandLogError::Corrupt(format!("invalid player set {bits}")));
+        }
+        Ok(set)
+    }
+
+    fn agent_action(&mut self) -> Result<AgentAction, HandLogError> {
+        Ok(match self.byte()? {
+            0 => AgentAction::Fold,
+            1 => AgentAction::Call,
+            2 => AgentAction::Bet(self.amount()?),
+            3 => AgentAction::AllIn,
+            tag => return Err(HandLogError::Corrupt(format!("invalid agent action {tag}"))),
+        })
+    }
+
+    fn played(&mut self) -> Result<PlayedActionPayload, HandLogError> {
+        Ok(PlayedActionPayload {
+            action: self.agent_action()?,
+            idx: self.usize()?,
+            round: self.round()?,
+            player_stack: self.amount()?,
+            starting_pot: self.amount()?,
+            final_pot: self.amount()?,
+            starting_bet: self.amount()?,
+            final_bet: self.amount()?,
+            starting_min_raise: self.amount()?,
+            final_min_raise: self.amount()?,
+            starting_player_bet: self.amount()?,
+            final_player_bet: self.amount()?,
+            players_active: self.player_set()?,
+            players_all_in: self.player_set()?,
+        })
+    }
+
+    fn rank(&mut self) -> Result<Option<Rank>, HandLogError> {
+        let tag = self.byte()?;
+        if tag == 0 {
+            return Ok(None);
+        }
+        let value = u32::try_from(self.varint()?)
+            .map_err(|_| HandLogError::Corrupt("rank is too large".to_string()))?;
+        Ok(Some(match tag {
+            1 => Rank::HighCard(value),
+            2 => Rank::OnePair(value),
+            3 => Rank::TwoPair(value),
+            4 => Rank::ThreeOfAKind(value),
+            5 => Rank::Straight(value),
+            6 => Rank::Flush(value),
+            7 => Rank::FullHouse(value),
+            8 => Rank::FourOfAKind(value),
+            9 => Rank::StraightFlush(value),
+            tag => return Err(HandLogError::Corrupt(format!("invalid rank {tag}"))),
+        }))
+    }
+
+    fn hand(&mut self) -> Result<Option<Hand>, HandLogError> {
+        match self.byte()? {
+            0 => Ok(None),
+            1 => {
+                let len = self.usize()?;
+                if len > 52 {
+                    return Err(HandLogError::Corrupt(format!("hand of {len} cards")));
+                }
+                let cards = (0..len)
+                    .map(|_| self.card())
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(Some(Hand::new_with_cards(cards)))
+            }
+            tag => Err(HandLogError::Corrupt(format!("invalid hand tag {tag}"))),
+        }
+    }
+
+    fn action(&mut self) -> Result<Action, HandLogError> {
+        Ok(match self.byte()? {
+            0 => Action::GameStart(GameStartPayload {
+                ante: self.amount()?,
+                small_blind: self.amount()?,
+                big_blind: self.amount()?,
+            }),
+            1 => Action::PlayerSit(PlayerSitPayload {
+                idx: self.usize()?,
+                player_stack: self.amount()?,
+            }),
+            2 => Action::DealStartingHand(DealStartingHandPayload {
+                idx: self.usize()?,
+                card: self.card()?,
+            }),
+            3 => Action::RoundAdvance(self.round()?),
+            4 => Action::PlayedAction(self.played()?),
+            5 => Action::FailedAction(FailedActionPayload {
+                action: self.agent_action()?,
+                result: self.played()?,
+            }),
+            6 => Action::ForcedBet(ForcedBetPayload {
+                idx: self.usize()?,
+                bet: self.amount()?,
+                player_stack: self.amount()?,
+                forced_bet_type: match self.byte()? {
+                    0 => ForcedBetType::Ante,
+                    1 => ForcedBetType::SmallBlind,
+                    2 => ForcedBetType::BigBlind,
+                    tag => {
+                        return Err(HandLogError::Corrupt(format!(
+                            "invalid forced bet type {tag}"
+                        )));
+                    }
+                },
+            }),
+            7 => Action::DealCommunity(self.card()?),
+            8 => Action::Award(AwardPayload {
+                idx: self.usize()?,
+                total_pot: self.amount()?,
+                award_amount: self.amount()?,
+                rank: self.rank()?,
+                hand: self.hand()?,
+            }),
+            tag => return Err(HandLogError::Corrupt(format!("invalid action {tag}"))),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::arena::{
+        Agent, GameState, HoldemSimulationBuilder,
+        agent::{AllInAgent, CallingAgent, RandomAgent},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_amounts() {
+        for amount in [
+            0.0,
+            1.0,
+            127.0,
+            128.0,
+            1e9,
+            0.5,
+            33.333,
+            -2.0,
+            1e20,
+            f32::MAX,
+        ] {
+            let mut encoder = Encoder::default();
+            encoder.amount(amount);
+            let mut decoder = Decoder {
+                bytes: &encoder.bytes,
+                pos: 0,
+            };
+            assert_eq!(amount, decoder.amount().unwrap());
+            assert_eq!(encoder.bytes.len(), decoder.pos);
+        }
+
+        // Small whole amounts fit in a byte.
+        let mut encoder = Encoder::default();
+        encoder.amount(50.0);
+        assert_eq!(1, encoder.bytes.len());
+    }
+
+    #[test]
+    fn test_simulations_round_trip() {
+        for seed in 0..20 {
+            let num_players = 2 + (seed as usize % 8);
+            let stacks = (0..num_players).map(|i| 100.0 + 0.5 * i as f32).collect();
+            let game_state = GameState::new_starting(stacks, 10.0, 5.0, 1.0, 0);
+            let agents: Vec<Box<dyn Agent>> = (0..num_players)
+                .map(|i| -> Box<dyn Agent> {
+                    match i % 3 {
+                        0 => Box::<RandomAgent>::default(),
+                        1 => Box::new(CallingAgent),
+                        _ => Box::new(AllInAgent),
+                    }
+                })
+                .collect();
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .build()
+                .unwrap();
+            sim.run(&mut StdRng::seed_from_u64(seed));
+
+            let bytes = encode_actions(&sim.actions);
+            assert_eq!(sim.actions, decode_actions(&bytes).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_award_round_trip() {
+        let actions = vec![Action::Award(AwardPayload {
+            total_pot: 30.0,
+            award_amount: 15.5,
+            rank: Some(Rank::TwoPair(1234)),
+            hand: Some(Hand::new_from_str("AsAdKcKh2c").unwrap()),
+            idx: 3,
+        })];
+        assert_eq!(actions, decode_actions(&encode_actions(&actions)).unwrap());
+    }
+
+    #[test]
+    fn test_corrupt() {
+        let bytes = encode_actions(&[Action::RoundAdvance(Round::Flop)]);
+        assert!(matches!(
+            decode_actions(&bytes[..bytes.len() - 1]),
+            Err(HandLogError::Corrupt(_))
+        ));
+
+        let mut extra = bytes.clone();
+        extra.push(0);
+        assert!(matches!(
+            decode_actions(&extra),
+            Err(HandLogError::Corrupt(_))
+        ));
+
+        // A round that doesn't exist.
+        assert!(matches!(
+            decode_actions(&[1, 3, 99]),
+            Err(HandLogError::Corrupt(_))
+        ));
+    }
+}
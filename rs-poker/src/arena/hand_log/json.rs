@@ -0,0 +1,104 @@
+use std::{
+    fs::{self, File},
+    io::BufReader,
+    path::Path,
+};
+
+use crate::arena::action::Action;
+
+use super::{HandLogError, HandLogReader, HandLogWriter};
+
+/// Convert a directory of `<id>.json` files, as written by
+/// `DirectoryHistorian`, into a new hand log at `log_path`.
+///
+/// Hands are written in order of their ids. Files that aren't named with
+/// an id are skipped. Returns the number of hands written.
+pub fn json_dir_to_hand_log(json_dir: &Path, log_path: &Path) -> Result<usize, HandLogError> {
+    let mut games = vec![];
+    for entry in fs::read_dir(json_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u128>().ok());
+        if let Some(id) = id {
+            games.push((id, path));
+        }
+    }
+    games.sort();
+
+    let mut writer = HandLogWriter::create(log_path)?;
+    for (id, path) in &games {
+        let actions: Vec<Action> = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        writer.write_hand(*id, &actions)?;
+    }
+    writer.flush()?;
+    Ok(games.len())
+}
+
+/// Write every hand in the log at `log_path` to `json_dir` as one pretty
+/// printed `<id>.json` file per hand, the same as `DirectoryHistorian`.
+///
+/// Returns the number of hands written.
+pub fn hand_log_to_json_dir(log_path: &Path, json_dir: &Path) -> Result<usize, HandLogError> {
+    fs::create_dir_all(json_dir)?;
+    let mut count = 0;
+    for hand in HandLogReader::open(log_path)? {
+        let hand = hand?;
+        let file = File::create(json_dir.join(hand.id.to_string()).with_extension("json"))?;
+        serde_json::to_writer_pretty(file, &hand.actions)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::arena::{
+        Agent, GameState, HoldemSimulationBuilder, agent::RandomAgent, hand_log::IndexedHandLog,
+        historian::DirectoryHistorian,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let dir = TempDir::new().unwrap();
+        let json_dir = dir.path().join("json");
+        for _ in 0..5 {
+            let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+            let agents: Vec<Box<dyn Agent>> = (0..3)
+                .map(|_| -> Box<dyn Agent> { Box::<RandomAgent>::default() })
+                .collect();
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(DirectoryHistorian::new(json_dir.clone()))])
+                .build()
+                .unwrap();
+            sim.run(&mut rand::rng());
+        }
+
+        let log_path = dir.path().join("hands.log");
+        assert_eq!(5, json_dir_to_hand_log(&json_dir, &log_path).unwrap());
+        let mut log = IndexedHandLog::open(&log_path).unwrap();
+        assert_eq!(5, log.len());
+
+        let out_dir = dir.path().join("out");
+        assert_eq!(5, hand_log_to_json_dir(&log_path, &out_dir).unwrap());
+        for id in log.ids().collect::<Vec<_>>() {
+            let name = format!("{id}.json");
+            let original: Vec<Action> =
+                serde_json::from_str(&fs::read_to_string(json_dir.join(&name)).unwrap()).unwrap();
+            let converted: Vec<Action> =
+                serde_json::from_str(&fs::read_to_string(out_dir.join(&name)).unwrap()).unwrap();
+            assert_eq!(original, converted);
+            assert_eq!(original, log.find(id).unwrap().unwrap().actions);
+        }
+    }
+}
@@ -0,0 +1,158 @@
+//! A compact binary log of played hands.
+//!
+//! `DirectoryHistorian` writes a pretty printed JSON file per game which
+//! doesn't scale to the millions of hands that CFR training or a long
+//! competition produce. A hand log keeps every hand in one append only file
+//! with a small binary encoding of the `Action` stream:
+//!
+//! - Counts, indexes and whole chip amounts are LEB128 varints. Amounts that
+//!   aren't whole numbers are stored as the raw `f32` so nothing is lost.
+//! - Cards are packed into a single byte.
+//! - Rounds, forced bet types and agent actions are single byte tags.
+//!
+//! Next to the log an index file (the log's path with `.idx` appended)
+//! stores the game id and byte offset of every hand so that any hand can be
+//! read without scanning the log.
+//!
+//! - [`HandLogWriter`] appends hands to a log and its index.
+//! - [`HandLogReader`] iterates over every hand in a log.
+//! - [`IndexedHandLog`] reads hands by their position in the log or by id.
+//! - [`HandLogHistorian`](crate::arena::historian::HandLogHistorian) writes
+//!   simulations straight to a log.
+//!
+//! With the `serde` feature logs can be converted to and from the JSON
+//! files that `DirectoryHistorian` writes, see [`json_dir_to_hand_log`] and
+//! [`hand_log_to_json_dir`].
+//!
+//! # Example
+//!
+//! ```
+//! use rs_poker::arena::action::Action;
+//! use rs_poker::arena::game_state::Round;
+//! use rs_poker::arena::hand_log::{HandLogReader, HandLogWriter, IndexedHandLog};
+//!
+//! let dir = std::env::temp_dir().join(format!("hand-log-doc-{}", std::process::id()));
+//! std::fs::create_dir_all(&dir).unwrap();
+//! let path = dir.join("hands.log");
+//!
+//! let mut writer = HandLogWriter::create(&path).unwrap();
+//! for id in 0..3 {
+//!     writer
+//!         .write_hand(id, &[Action::RoundAdvance(Round::Complete)])
+//!         .unwrap();
+//! }
+//! writer.flush().unwrap();
+//!
+//! let hands: Vec<_> = HandLogReader::open(&path)
+//!     .unwrap()
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//! assert_eq!(3, hands.len());
+//!
+//! let mut log = IndexedHandLog::open(&path).unwrap();
+//! assert_eq!(2, log.find(2).unwrap().unwrap().id);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+mod encoding;
+#[cfg(feature = "serde")]
+mod json;
+mod reader;
+mod writer;
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use super::action::Action;
+
+pub use encoding::{decode_actions, encode_actions};
+#[cfg(feature = "serde")]
+pub use json::{hand_log_to_json_dir, json_dir_to_hand_log};
+pub use reader::{HandLogReader, IndexedHandLog};
+pub use writer::HandLogWriter;
+
+/// The first bytes of every hand log.
+const LOG_MAGIC: &[u8; 4] = b"RSHL";
+/// The first bytes of every hand log index.
+const INDEX_MAGIC: &[u8; 4] = b"RSHI";
+/// The version of the encoding that is written.
+const VERSION: u8 = 1;
+/// Bytes in the header of both files.
+const HEADER_LEN: u64 = 5;
+/// Bytes in each index entry, a `u128` id and a `u64` offset.
+const INDEX_ENTRY_LEN: u64 = 24;
+
+#[derive(Error, Debug)]
+pub enum HandLogError {
+    #[error("IO Error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a hand log file")]
+    BadMagic,
+    #[error("Unsupported hand log version {0}")]
+    UnsupportedVersion(u8),
+    #[error("Corrupt hand log: {0}")]
+    Corrupt(String),
+    #[error("No hand number {0} in the log")]
+    HandOutOfRange(usize),
+    #[cfg(feature = "serde")]
+    #[error("JSON Error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// One hand read back from a log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedHand {
+    /// The id of the simulation that played the hand.
+    pub id: u128,
+    pub actions: Vec<Action>,
+}
+
+/// The path of the index for the log at `path`.
+pub fn index_path(path: &Path) -> PathBuf {
+    let mut index: OsString = path.as_os_str().to_owned();
+    index.push(".idx");
+    PathBuf::from(index)
+}
+
+fn header(magic: &[u8; 4]) -> [u8; HEADER_LEN as usize] {
+    [magic[0], magic[1], magic[2], magic[3], VERSION]
+}
+
+fn check_header(magic: &[u8; 4], header: &[u8]) -> Result<(), HandLogError> {
+    if header.len() != HEADER_LEN as usize || &header[..4] != magic {
+        return Err(HandLogError::BadMagic);
+    }
+    if header[4] != VERSION {
+        return Err(HandLogError::UnsupportedVersion(header[4]));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(
+            PathBuf::from("/tmp/hands.log.idx"),
+            index_path(Path::new("/tmp/hands.log"))
+        );
+    }
+
+    #[test]
+    fn test_check_header() {
+        assert!(check_header(LOG_MAGIC, &header(LOG_MAGIC)).is_ok());
+        assert!(matches!(
+            check_header(INDEX_MAGIC, &header(LOG_MAGIC)),
+            Err(HandLogError::BadMagic)
+        ));
+        assert!(matches!(
+            check_header(LOG_MAGIC, b"RSHL\x07"),
+            Err(HandLogError::UnsupportedVersion(7))
+        ));
+    }
+}
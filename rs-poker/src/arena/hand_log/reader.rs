@@ -0,0 +1,321 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use super::{
+    HEADER_LEN, HandLogError, INDEX_ENTRY_LEN, INDEX_MAGIC, LOG_MAGIC, LoggedHand, check_header,
+    decode_actions, index_path,
+};
+
+/// Iterates over every hand in a log in the order they were written.
+///
+/// Stops at the first error, which is returned as the last item.
+#[derive(Debug)]
+pub struct HandLogReader<R = BufReader<File>> {
+    reader: R,
+    done: bool,
+}
+
+impl HandLogReader {
+    /// Open the log at `path`.
+    pub fn open(path: &Path) -> Result<Self, HandLogError> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> HandLogReader<R> {
+    /// Read a log from `reader`, which must be at the start of the log.
+    pub fn new(mut reader: R) -> Result<Self, HandLogError> {
+        read_header(&mut reader, LOG_MAGIC)?;
+        Ok(Self {
+            reader,
+            done: false,
+        })
+    }
+}
+
+impl<R: Read> Iterator for HandLogReader<R> {
+    type Item = Result<LoggedHand, HandLogError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let hand = read_hand(&mut self.reader).transpose();
+        if !matches!(hand, Some(Ok(_))) {
+            self.done = true;
+        }
+        hand
+    }
+}
+
+/// Random access to the hands of a log through its index.
+///
+/// Hand numbers are the position of the hand in the log, starting at zero.
+#[derive(Debug)]
+pub struct IndexedHandLog {
+    log: BufReader<File>,
+    /// The id and offset of every hand.
+    entries: Vec<(u128, u64)>,
+    by_id: HashMap<u128, usize>,
+}
+
+impl IndexedHandLog {
+    /// Open the log at `path` and load its index.
+    pub fn open(path: &Path) -> Result<Self, HandLogError> {
+        let mut log = File::open(path)?;
+        read_header(&mut log, LOG_MAGIC)?;
+
+        let mut index = BufReader::new(File::open(index_path(path))?);
+        read_header(&mut index, INDEX_MAGIC)?;
+        let mut entries = vec![];
+        let mut entry = [0u8; INDEX_ENTRY_LEN as usize];
+        while read_or_eof(&mut index, &mut entry)? {
+            let (id, offset) = entry.split_at(16);
+            entries.push((
+                u128::from_le_bytes(id.try_into().unwrap()),
+                u64::from_le_bytes(offset.try_into().unwrap()),
+            ));
+        }
+
+        let mut by_id = HashMap::with_capacity(entries.len());
+        for (number, (id, _)) in entries.iter().enumerate() {
+            by_id.entry(*id).or_insert(number);
+        }
+        Ok(Self {
+            log: BufReader::new(log),
+            entries,
+            by_id,
+        })
+    }
+
+    /// The number of hands in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The ids of the hands in the order they were written.
+    pub fn ids(&self) -> impl Iterator<Item = u128> + '_ {
+        self.entries.iter().map(|(id, _)| *id)
+    }
+
+    /// Read the hand with hand number `number`.
+    pub fn get(&mut self, number: usize) -> Result<LoggedHand, HandLogError> {
+        let (id, offset) = *self
+            .entries
+            .get(number)
+            .ok_or(HandLogError::HandOutOfRange(number))?;
+        self.log.seek(SeekFrom::Start(offset))?;
+        let hand = read_hand(&mut self.log)?
+            .ok_or_else(|| HandLogError::Corrupt(format!("hand {number} is past the end")))?;
+        if hand.id != id {
+            return Err(HandLogError::Corrupt(format!(
+                "hand {number} has id {} but the index says {id}",
+                hand.id
+            )));
+        }
+        Ok(hand)
+    }
+
+    /// Read the first hand played by the simulation with id `id`.
+    pub fn find(&mut self, id: u128) -> Result<Option<LoggedHand>, HandLogError> {
+        match self.by_id.get(&id) {
+            Some(number) => self.get(*number).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+pub(super) fn read_header<R: Read>(reader: &mut R, magic: &[u8; 4]) -> Result<(), HandLogError> {
+    let mut header = [0u8; HEADER_LEN as usize];
+    if !read_or_eof(reader, &mut header)? {
+        return Err(HandLogError::BadMagic);
+    }
+    check_header(magic, &header)
+}
+
+/// The number of hands in an index file of `len` bytes.
+pub(super) fn index_entries(len: u64) -> Result<usize, HandLogError> {
+    let entries = len.saturating_sub(HEADER_LEN);
+    if !entries.is_multiple_of(INDEX_ENTRY_LEN) {
+        return Err(HandLogError::Corrupt(format!(
+            "index of {len} bytes ends part way through an entry"
+        )));
+    }
+    Ok((entries / INDEX_ENTRY_LEN) as usize)
+}
+
+/// Read the next hand, or `None` if the log ends cleanly.
+fn read_hand<R: Read>(reader: &mut R) -> Result<Option<LoggedHand>, HandLogError> {
+    let mut first = [0u8; 1];
+    if !read_or_eof(reader, &mut first)? {
+        return Ok(None);
+    }
+    let id = read_varint(reader, first[0])?;
+    let mut first_len = [0u8; 1];
+    reader.read_exact(&mut first_len)?;
+    let len = read_varint(reader, first_len[0])?;
+    let len =
+        usize::try_from(len).map_err(|_| HandLogError::Corrupt(format!("hand of {len} bytes")))?;
+
+    let mut payload = vec![];
+    reader.take(len as u64).read_to_end(&mut payload)?;
+    if payload.len() != len {
+        return Err(HandLogError::Io(io::ErrorKind::UnexpectedEof.into()));
+    }
+    Ok(Some(LoggedHand {
+        id,
+        actions: decode_actions(&payload)?,
+    }))
+}
+
+/// Finish reading a varint whose first byte is `first`.
+fn read_varint<R: Read>(reader: &mut R, first: u8) -> Result<u128, HandLogError> {
+    let mut value = (first & 0x7f) as u128;
+    let mut byte = first;
+    let mut shift = 7;
+    while byte & 0x80 != 0 {
+        if shift >= 128 {
+            return Err(HandLogError::Corrupt("varint is too long".to_string()));
+        }
+        let mut next = [0u8; 1];
+        reader.read_exact(&mut next)?;
+        byte = next[0];
+        value |= ((byte & 0x7f) as u128) << shift;
+        shift += 7;
+    }
+    Ok(value)
+}
+
+/// Fill `buf`, returning `false` if the reader was already at the end.
+fn read_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> Result<bool, HandLogError> {
+    let mut read = 0;
+    while read < buf.len() {
+        match reader.read(&mut buf[read..]) {
+            Ok(0) if read == 0 => return Ok(false),
+            Ok(0) => return Err(HandLogError::Io(io::ErrorKind::UnexpectedEof.into())),
+            Ok(n) => read += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use rand::{SeedableRng, rngs::StdRng};
+    use tempfile::TempDir;
+
+    use crate::arena::{
+        Agent, GameState, HoldemSimulationBuilder,
+        action::Action,
+        agent::RandomAgent,
+        game_state::Round,
+        hand_log::{HandLogWriter, encode_actions},
+    };
+
+    use super::*;
+
+    fn simulate(seed: u64) -> Vec<Action> {
+        let game_state = GameState::new_starting(vec![100.0; 4], 10.0, 5.0, 0.0, 0);
+        let agents: Vec<Box<dyn Agent>> = (0..4)
+            .map(|_| -> Box<dyn Agent> { Box::<RandomAgent>::default() })
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .build()
+            .unwrap();
+        sim.run(&mut StdRng::seed_from_u64(seed));
+        sim.actions
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hands.log");
+        let hands: Vec<Vec<Action>> = (0..10).map(simulate).collect();
+
+        let mut writer = HandLogWriter::create(&path).unwrap();
+        for (id, actions) in hands.iter().enumerate() {
+            assert_eq!(id, writer.write_hand(id as u128 * 1000, actions).unwrap());
+        }
+        writer.flush().unwrap();
+
+        let read: Vec<LoggedHand> = HandLogReader::open(&path)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(hands.len(), read.len());
+        for (id, (actions, hand)) in hands.iter().zip(&read).enumerate() {
+            assert_eq!(id as u128 * 1000, hand.id);
+            assert_eq!(actions, &hand.actions);
+        }
+
+        let mut log = IndexedHandLog::open(&path).unwrap();
+        assert_eq!(10, log.len());
+        assert_eq!(read[7], log.get(7).unwrap());
+        assert_eq!(read[2], log.get(2).unwrap());
+        assert_eq!(Some(read[5].clone()), log.find(5000).unwrap());
+        assert_eq!(None, log.find(5).unwrap());
+        assert!(matches!(log.get(10), Err(HandLogError::HandOutOfRange(10))));
+    }
+
+    #[test]
+    fn test_append() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hands.log");
+        let hands: Vec<Vec<Action>> = (0..3).map(simulate).collect();
+        for (id, actions) in hands.iter().enumerate() {
+            let mut writer = HandLogWriter::append(&path).unwrap();
+            assert_eq!(id, writer.len());
+            writer.write_hand(id as u128, actions).unwrap();
+        }
+
+        let mut log = IndexedHandLog::open(&path).unwrap();
+        assert_eq!(vec![0, 1, 2], log.ids().collect::<Vec<_>>());
+        assert_eq!(hands[1], log.find(1).unwrap().unwrap().actions);
+    }
+
+    #[test]
+    fn test_truncated_log() {
+        let mut bytes = vec![];
+        bytes.extend_from_slice(b"RSHL\x01");
+        // Hand id 1 with 3 bytes of actions, but only 2 are there.
+        bytes.extend_from_slice(&[1, 3, 1, 3]);
+        let mut reader = HandLogReader::new(Cursor::new(bytes)).unwrap();
+        assert!(matches!(reader.next(), Some(Err(HandLogError::Io(_)))));
+        assert!(reader.next().is_none());
+
+        let actions = [Action::RoundAdvance(Round::Complete)];
+        bytes = b"RSHL\x01".to_vec();
+        bytes.extend_from_slice(&[7, 3]);
+        bytes.extend_from_slice(&encode_actions(&actions));
+        let hands: Vec<LoggedHand> = HandLogReader::new(Cursor::new(bytes))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            vec![LoggedHand {
+                id: 7,
+                actions: actions.to_vec()
+            }],
+            hands
+        );
+
+        assert!(matches!(
+            HandLogReader::new(Cursor::new(b"{\"json\": true}".to_vec())),
+            Err(HandLogError::BadMagic)
+        ));
+    }
+}
@@ -0,0 +1,102 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufWriter, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+use crate::arena::action::Action;
+
+use super::{
+    HEADER_LEN, HandLogError, INDEX_MAGIC, LOG_MAGIC, encode_actions,
+    encoding::write_varint,
+    header, index_path,
+    reader::{index_entries, read_header},
+};
+
+/// Appends hands to a hand log and its index.
+///
+/// Writes are buffered, call [`HandLogWriter::flush`] to make sure
+/// everything written so far is on disk. Dropping the writer flushes it
+/// too, ignoring any errors.
+#[derive(Debug)]
+pub struct HandLogWriter {
+    log: BufWriter<File>,
+    index: BufWriter<File>,
+    /// Where the next hand starts in the log.
+    offset: u64,
+    hands: usize,
+}
+
+impl HandLogWriter {
+    /// Create a new log at `path` along with its index, replacing any log
+    /// that was already there.
+    pub fn create(path: &Path) -> Result<Self, HandLogError> {
+        let mut log = File::create(path)?;
+        let mut index = File::create(index_path(path))?;
+        log.write_all(&header(LOG_MAGIC))?;
+        index.write_all(&header(INDEX_MAGIC))?;
+        Ok(Self {
+            log: BufWriter::new(log),
+            index: BufWriter::new(index),
+            offset: HEADER_LEN,
+            hands: 0,
+        })
+    }
+
+    /// Open the log at `path` to add more hands to it, creating it if it
+    /// doesn't exist.
+    pub fn append(path: &Path) -> Result<Self, HandLogError> {
+        if !path.exists() {
+            return Self::create(path);
+        }
+        let mut log = OpenOptions::new().read(true).append(true).open(path)?;
+        let mut index = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(index_path(path))?;
+        read_header(&mut log, LOG_MAGIC)?;
+        read_header(&mut index, INDEX_MAGIC)?;
+        let offset = log.seek(SeekFrom::End(0))?;
+        let index_len = index.seek(SeekFrom::End(0))?;
+        let hands = index_entries(index_len)?;
+        Ok(Self {
+            log: BufWriter::new(log),
+            index: BufWriter::new(index),
+            offset,
+            hands,
+        })
+    }
+
+    /// Append one hand, returning its hand number in the log.
+    pub fn write_hand(&mut self, id: u128, actions: &[Action]) -> Result<usize, HandLogError> {
+        let payload = encode_actions(actions);
+        let mut record = Vec::with_capacity(payload.len() + 24);
+        write_varint(&mut record, id);
+        write_varint(&mut record, payload.len() as u128);
+        record.extend_from_slice(&payload);
+        self.log.write_all(&record)?;
+
+        self.index.write_all(&id.to_le_bytes())?;
+        self.index.write_all(&self.offset.to_le_bytes())?;
+
+        self.offset += record.len() as u64;
+        self.hands += 1;
+        Ok(self.hands - 1)
+    }
+
+    /// The number of hands in the log.
+    pub fn len(&self) -> usize {
+        self.hands
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hands == 0
+    }
+
+    /// Write everything buffered to the log and its index.
+    pub fn flush(&mut self) -> Result<(), HandLogError> {
+        self.log.flush()?;
+        self.index.flush()?;
+        Ok(())
+    }
+}
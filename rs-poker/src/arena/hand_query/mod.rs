@@ -0,0 +1,537 @@
+//! Search recorded hands for interesting spots.
+//!
+//! Every way of recording a hand ends up as the same stream of [`Action`]s,
+//! so queries run over [`RecordedHand`]s built from any of them:
+//!
+//! - the records of a [`VecHistorian`](crate::arena::historian::VecHistorian)
+//!   with [`RecordedHand::from_records`]
+//! - a [`TournamentReplayData`] with [`RecordedHand::from_tournament`]
+//! - a hand log, since a [`LoggedHand`] converts into a [`RecordedHand`]
+//! - a directory of `DirectoryHistorian` JSON files with
+//!   [`RecordedHand::read_json_dir`]
+//! - Open Hand History files with `RecordedHand::from_ohh_file` when the
+//!   `open-hand-history` feature is enabled
+//!
+//! Each hand is summarized into a [`HandSummary`] of who raised, who went to
+//! showdown, what they held, the board and the pot. A [`HandQuery`] keeps
+//! the hands that match all of its [`HandPredicate`]s and returns their ids
+//! with [`QueryStats`].
+//!
+//! # Example
+//!
+//! ```
+//! use rs_poker::arena::hand_query::{
+//!     HandPredicate, HandQuery, PlayerPredicate, PlayerSelector, RecordedHand,
+//! };
+//! use rs_poker::arena::historian::VecHistorian;
+//! use rs_poker::arena::{Agent, GameState, HoldemSimulationBuilder, agent::RandomAgent};
+//!
+//! let records = VecHistorian::new().get_storage();
+//! for _ in 0..10 {
+//!     let agents: Vec<Box<dyn Agent>> = (0..3)
+//!         .map(|_| -> Box<dyn Agent> { Box::<RandomAgent>::default() })
+//!         .collect();
+//!     let mut sim = HoldemSimulationBuilder::default()
+//!         .game_state(GameState::new_starting(vec![100.0; 3], 2.0, 1.0, 0.0, 0))
+//!         .agents(agents)
+//!         .historians(vec![Box::new(VecHistorian::new_with_actions(
+//!             records.clone(),
+//!         ))])
+//!         .build()
+//!         .unwrap();
+//!     sim.run(&mut rand::rng());
+//! }
+//! let hands = RecordedHand::from_records(&records.borrow());
+//! assert_eq!(10, hands.len());
+//!
+//! // Player 0 raised preflop and lost at showdown in a pot over 20 big blinds.
+//! let result = HandQuery::new()
+//!     .filter(HandPredicate::Player(
+//!         PlayerSelector::Idx(0),
+//!         vec![
+//!             PlayerPredicate::RaisedPreflop,
+//!             PlayerPredicate::LostAtShowdown,
+//!         ],
+//!     ))
+//!     .filter(HandPredicate::PotOverBigBlinds(20.0))
+//!     .run(&hands);
+//! assert_eq!(10, result.stats.searched);
+//! assert_eq!(result.ids().len(), result.stats.hands);
+//! ```
+mod predicate;
+mod summary;
+
+use std::collections::BTreeMap;
+#[cfg(feature = "serde")]
+use std::{fs, io, path::Path};
+
+use super::{TournamentReplayData, action::Action, hand_log::LoggedHand, historian::HistoryRecord};
+
+pub use predicate::{HandPredicate, PlayerPredicate, PlayerSelector};
+pub use summary::{HandSummary, PlayerSummary};
+
+/// The actions of one hand along with what to call it and its players.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedHand {
+    pub id: String,
+    /// The name of the player in each seat index. Seats without a name are
+    /// called `Player {idx}`.
+    pub player_names: Vec<String>,
+    pub actions: Vec<Action>,
+}
+
+impl RecordedHand {
+    pub fn new(id: impl Into<String>, actions: Vec<Action>) -> Self {
+        Self {
+            id: id.into(),
+            player_names: vec![],
+            actions,
+        }
+    }
+
+    pub fn with_player_names(mut self, player_names: Vec<String>) -> Self {
+        self.player_names = player_names;
+        self
+    }
+
+    /// Split historian records into hands, starting a new hand at every
+    /// `GameStart`. Hands are numbered in the order they were recorded.
+    pub fn from_records(records: &[HistoryRecord]) -> Vec<Self> {
+        let mut hands: Vec<Vec<Action>> = vec![];
+        for record in records {
+            match (&record.action, hands.last_mut()) {
+                (Action::GameStart(_), _) | (_, None) => hands.push(vec![record.action.clone()]),
+                (action, Some(hand)) => hand.push(action.clone()),
+            }
+        }
+        hands
+            .into_iter()
+            .enumerate()
+            .map(|(number, actions)| Self::new(number.to_string(), actions))
+            .collect()
+    }
+
+    /// Every hand of a tournament, numbered by hand.
+    pub fn from_tournament(data: &TournamentReplayData) -> Vec<Self> {
+        data.hands
+            .iter()
+            .enumerate()
+            .map(|(number, actions)| Self::new(number.to_string(), actions.clone()))
+            .collect()
+    }
+
+    /// Read a directory of `<id>.json` files as written by
+    /// `DirectoryHistorian`, in order of their file names.
+    #[cfg(feature = "serde")]
+    pub fn read_json_dir(dir: &Path) -> io::Result<Vec<Self>> {
+        let mut paths = vec![];
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| -> io::Result<Self> {
+                let id = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let actions = serde_json::from_reader(io::BufReader::new(fs::File::open(&path)?))?;
+                Ok(Self::new(id, actions))
+            })
+            .collect()
+    }
+
+    /// Import every hand in a `.ohh` file, naming the players as the
+    /// history does.
+    #[cfg(feature = "open-hand-history")]
+    pub fn from_ohh_file(path: &Path) -> Result<Vec<Self>, super::ImportError> {
+        crate::open_hand_history::read_hands(path)?
+            .iter()
+            .map(|history| {
+                let imported = super::ImportedHand::try_from(history)?;
+                let names = imported
+                    .player_ids
+                    .iter()
+                    .map(|id| {
+                        history
+                            .players
+                            .iter()
+                            .find(|player| player.id == *id)
+                            .map(|player| player.name.clone())
+                            .unwrap_or_else(|| format!("Player {id}"))
+                    })
+                    .collect();
+                Ok(Self::from(imported).with_player_names(names))
+            })
+            .collect()
+    }
+}
+
+impl From<LoggedHand> for RecordedHand {
+    fn from(hand: LoggedHand) -> Self {
+        Self::new(hand.id.to_string(), hand.actions)
+    }
+}
+
+#[cfg(feature = "open-hand-history")]
+impl From<super::ImportedHand> for RecordedHand {
+    fn from(hand: super::ImportedHand) -> Self {
+        Self::new(hand.game_number, hand.actions)
+    }
+}
+
+/// Totals for one player over the hands a query matched.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerQueryStats {
+    pub hands: usize,
+    pub showdowns: usize,
+    /// Hands where the player was awarded more than they put in.
+    pub hands_won: usize,
+    pub net: f32,
+    pub net_big_blinds: f32,
+}
+
+/// Totals over the hands a query matched.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryStats {
+    /// The number of hands looked at.
+    pub searched: usize,
+    /// The number of hands that matched.
+    pub hands: usize,
+    pub showdowns: usize,
+    pub total_pot: f32,
+    pub average_pot: f32,
+    pub average_pot_big_blinds: f32,
+    /// Totals for each player, by name.
+    pub players: BTreeMap<String, PlayerQueryStats>,
+}
+
+/// The hands that matched a query.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QueryResult {
+    pub hands: Vec<HandSummary>,
+    pub stats: QueryStats,
+}
+
+impl QueryResult {
+    /// The ids of the matching hands, in the order they were searched.
+    pub fn ids(&self) -> Vec<&str> {
+        self.hands.iter().map(|hand| hand.id.as_str()).collect()
+    }
+}
+
+/// Finds the hands matching every one of a list of predicates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HandQuery {
+    predicates: Vec<HandPredicate>,
+}
+
+impl HandQuery {
+    /// A query that matches every hand.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only keep hands that also match `predicate`.
+    pub fn filter(mut self, predicate: HandPredicate) -> Self {
+        self.predicates.push(predicate);
+        self
+    }
+
+    pub fn matches(&self, hand: &HandSummary) -> bool {
+        self.predicates.iter().all(|p| p.matches(hand))
+    }
+
+    /// Summarize every hand and keep the ones that match.
+    pub fn run<'a>(&self, hands: impl IntoIterator<Item = &'a RecordedHand>) -> QueryResult {
+        self.run_summaries(hands.into_iter().map(HandSummary::new))
+    }
+
+    /// Keep the matching hands from ones already summarized, which is
+    /// quicker when running many queries over the same hands.
+    pub fn run_summaries(&self, hands: impl IntoIterator<Item = HandSummary>) -> QueryResult {
+        let mut result = QueryResult::default();
+        for hand in hands {
+            result.stats.searched += 1;
+            if !self.matches(&hand) {
+                continue;
+            }
+
+            let stats = &mut result.stats;
+            stats.hands += 1;
+            stats.showdowns += usize::from(hand.showdown);
+            stats.total_pot += hand.total_pot;
+            stats.average_pot_big_blinds += hand.pot_in_big_blinds();
+            for player in &hand.players {
+                let player_stats = stats.players.entry(player.name.clone()).or_default();
+                player_stats.hands += 1;
+                player_stats.showdowns += usize::from(player.showdown);
+                player_stats.hands_won += usize::from(player.net() > 0.0);
+                player_stats.net += player.net();
+                if hand.big_blind > 0.0 {
+                    player_stats.net_big_blinds += player.net() / hand.big_blind;
+                }
+            }
+            result.hands.push(hand);
+        }
+
+        let stats = &mut result.stats;
+        if stats.hands > 0 {
+            stats.average_pot = stats.total_pot / stats.hands as f32;
+            stats.average_pot_big_blinds /= stats.hands as f32;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+
+    use crate::{
+        arena::{
+            Agent, GameState, Historian, HoldemSimulationBuilder, action::AgentAction,
+            agent::VecReplayAgent, game_state::Round, historian::VecHistorian,
+        },
+        core::Card,
+    };
+
+    use super::*;
+
+    /// Play a three handed hand where each player makes the actions then
+    /// calls, named alice, bob and one left unnamed.
+    pub(crate) fn play_hand(actions: Vec<Vec<AgentAction>>) -> RecordedHand {
+        play_hand_with_historians(actions, vec![])
+    }
+
+    fn play_hand_with_historians(
+        actions: Vec<Vec<AgentAction>>,
+        mut historians: Vec<Box<dyn Historian>>,
+    ) -> RecordedHand {
+        let game_state = GameState::new_starting(vec![1000.0; 3], 10.0, 5.0, 0.0, 0);
+        let historian = VecHistorian::new();
+        let records = historian.get_storage();
+        historians.push(Box::new(historian));
+        let agents: Vec<Box<dyn Agent>> = actions
+            .into_iter()
+            .map(|actions| -> Box<dyn Agent> {
+                Box::new(VecReplayAgent::new_with_default(actions, AgentAction::Call))
+            })
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .historians(historians)
+            .panic_on_historian_error(true)
+            .build()
+            .unwrap();
+        // Seeded so every hand gets the same cards each run.
+        sim.run(&mut StdRng::seed_from_u64(42));
+        let actions = records.borrow().iter().map(|r| r.action.clone()).collect();
+        RecordedHand::new(sim.id.to_string(), actions)
+            .with_player_names(vec!["alice".into(), "bob".into()])
+    }
+
+    fn cards(cards: &[&str]) -> Vec<Card> {
+        cards.iter().map(|c| Card::try_from(*c).unwrap()).collect()
+    }
+
+    fn hands() -> Vec<RecordedHand> {
+        vec![
+            // Alice opens and bob 3-bets, then they check it down.
+            play_hand(vec![
+                vec![AgentAction::Bet(30.0), AgentAction::Call],
+                vec![AgentAction::Bet(90.0)],
+                vec![AgentAction::Fold],
+            ]),
+            // Everyone folds to the big blind.
+            play_hand(vec![
+                vec![AgentAction::Fold],
+                vec![AgentAction::Fold],
+                vec![],
+            ]),
+            // Bob opens and alice 3-bets.
+            play_hand(vec![
+                vec![AgentAction::Call, AgentAction::Bet(60.0)],
+                vec![AgentAction::Bet(20.0)],
+                vec![AgentAction::Fold],
+            ]),
+        ]
+    }
+
+    #[test]
+    fn test_query() {
+        let hands = hands();
+        let all = HandQuery::new().run(&hands);
+        assert_eq!(3, all.stats.hands);
+        assert_eq!(3, all.stats.players["alice"].hands);
+        let net: f32 = all.stats.players.values().map(|p| p.net).sum();
+        assert_eq!(0.0, net);
+
+        let three_bets = HandQuery::new()
+            .filter(HandPredicate::Player(
+                PlayerSelector::Any,
+                vec![PlayerPredicate::THREE_BET],
+            ))
+            .run(&hands);
+        assert_eq!(
+            vec![hands[0].id.as_str(), hands[2].id.as_str()],
+            three_bets.ids()
+        );
+        assert_eq!(3, three_bets.stats.searched);
+        assert_eq!(2, three_bets.stats.showdowns);
+        assert_eq!(
+            (190.0 + 130.0) / 2.0,
+            three_bets.stats.average_pot,
+            "each 3-bet is called with the big blind folding"
+        );
+
+        let bob_three_bet_and_lost = HandPredicate::Player(
+            PlayerSelector::Name("bob".into()),
+            vec![PlayerPredicate::THREE_BET, PlayerPredicate::LostAtShowdown],
+        );
+        let result = HandQuery::new().filter(bob_three_bet_and_lost).run(&hands);
+        // Alice's nines beat bob's threes.
+        let summary = HandSummary::new(&hands[0]);
+        assert_eq!(cards(&["3s", "Tc", "Ad", "8h", "7c"]), summary.board);
+        assert_eq!(
+            cards(&["9h", "9d"]),
+            summary.player("alice").unwrap().hole_cards
+        );
+        assert_eq!(
+            cards(&["3h", "9s"]),
+            summary.player("bob").unwrap().hole_cards
+        );
+        assert_eq!(vec![hands[0].id.as_str()], result.ids());
+        assert_eq!(-90.0, result.stats.players["bob"].net);
+
+        // Alice's 3-bet in the last hand wins with the same cards.
+        let alice_three_bet_and_won = HandPredicate::Player(
+            PlayerSelector::Name("alice".into()),
+            vec![PlayerPredicate::THREE_BET, PlayerPredicate::WonAtShowdown],
+        );
+        let result = HandQuery::new().filter(alice_three_bet_and_won).run(&hands);
+        assert_eq!(vec![hands[2].id.as_str()], result.ids());
+
+        let big_pots = HandQuery::new()
+            .filter(HandPredicate::PotOverBigBlinds(15.0))
+            .filter(HandPredicate::ReachedRound(Round::Flop).or(HandPredicate::NumPlayers(2)))
+            .filter(!HandPredicate::NumPlayers(2))
+            .run(&hands);
+        assert_eq!(vec![hands[0].id.as_str()], big_pots.ids());
+    }
+
+    #[test]
+    fn test_from_records() {
+        let records = VecHistorian::new().get_storage();
+        for _ in 0..3 {
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::new(VecReplayAgent::new(vec![])),
+                Box::new(VecReplayAgent::new(vec![])),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0))
+                .agents(agents)
+                .historians(vec![Box::new(VecHistorian::new_with_actions(
+                    records.clone(),
+                ))])
+                .build()
+                .unwrap();
+            sim.run(&mut rand::rng());
+        }
+
+        let hands = RecordedHand::from_records(&records.borrow());
+        assert_eq!(vec!["0", "1", "2"], HandQuery::new().run(&hands).ids());
+        assert!(
+            hands
+                .iter()
+                .all(|hand| matches!(hand.actions[0], Action::GameStart(_)))
+        );
+    }
+
+    #[test]
+    fn test_from_tournament() {
+        let mut data =
+            TournamentReplayData::new(GameState::new_starting(vec![1000.0; 3], 10.0, 5.0, 0.0, 0));
+        let actions = play_hand(vec![vec![], vec![], vec![]]).actions;
+        data.add_hand(actions.clone());
+        data.add_hand(actions);
+
+        let hands = RecordedHand::from_tournament(&data);
+        let result = HandQuery::new()
+            .filter(HandPredicate::NumPlayers(3))
+            .run(&hands);
+        assert_eq!(vec!["0", "1"], result.ids());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_read_json_dir() {
+        use crate::arena::historian::DirectoryHistorian;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(VecReplayAgent::new(vec![])),
+            Box::new(VecReplayAgent::new(vec![])),
+        ];
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0))
+            .agents(agents)
+            .historians(vec![Box::new(DirectoryHistorian::new(
+                dir.path().to_path_buf(),
+            ))])
+            .build()
+            .unwrap();
+        sim.run(&mut rand::rng());
+
+        let hands = RecordedHand::read_json_dir(dir.path()).unwrap();
+        let result = HandQuery::new()
+            .filter(HandPredicate::Player(
+                PlayerSelector::Any,
+                vec![PlayerPredicate::FoldedIn(Round::Preflop)],
+            ))
+            .run(&hands);
+        assert_eq!(vec![sim.id.to_string()], result.ids());
+    }
+
+    #[cfg(feature = "open-hand-history")]
+    #[test]
+    fn test_from_ohh_file() {
+        use crate::arena::historian::OpenHandHistoryHistorian;
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("hands.ohh");
+        let historian = OpenHandHistoryHistorian::new(path.clone()).with_player_names(vec![
+            "alice".into(),
+            "bob".into(),
+            "carol".into(),
+        ]);
+        play_hand_with_historians(
+            vec![
+                vec![AgentAction::Bet(30.0), AgentAction::Call],
+                vec![AgentAction::Bet(90.0)],
+                vec![AgentAction::Fold],
+            ],
+            vec![Box::new(historian)],
+        );
+
+        let hands = RecordedHand::from_ohh_file(&path).unwrap();
+        let result = HandQuery::new()
+            .filter(HandPredicate::Player(
+                PlayerSelector::Name("bob".into()),
+                vec![PlayerPredicate::THREE_BET, PlayerPredicate::WentToShowdown],
+            ))
+            .filter(HandPredicate::PotOverBigBlinds(18.0))
+            .run(&hands);
+        assert_eq!(1, result.stats.hands);
+        assert_eq!(1, result.stats.players["carol"].hands);
+    }
+}
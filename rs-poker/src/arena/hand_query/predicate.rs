@@ -0,0 +1,282 @@
+use std::ops::Not;
+
+use crate::{
+    arena::game_state::Round,
+    core::{FlatHand, RSPokerError},
+    holdem::RangeParser,
+};
+
+use super::{HandSummary, PlayerSummary};
+
+/// Which players a [`HandPredicate::Player`] looks at.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerSelector {
+    /// Any player in the hand.
+    Any,
+    /// The player in a seat index.
+    Idx(usize),
+    /// The player with a name.
+    Name(String),
+}
+
+impl PlayerSelector {
+    pub fn matches(&self, player: &PlayerSummary) -> bool {
+        match self {
+            PlayerSelector::Any => true,
+            PlayerSelector::Idx(idx) => player.idx == *idx,
+            PlayerSelector::Name(name) => player.name == *name,
+        }
+    }
+}
+
+/// Something a single player did in a hand.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PlayerPredicate {
+    /// Made any preflop raise.
+    RaisedPreflop,
+    /// Made the preflop raise to this bet level. The big blind is level 1
+    /// so 2 is an open raise, 3 a 3-bet and 4 a 4-bet.
+    PreflopBetLevel(usize),
+    /// Folded in the round.
+    FoldedIn(Round),
+    AllIn,
+    WentToShowdown,
+    /// Went to showdown and was awarded chips.
+    WonAtShowdown,
+    /// Went to showdown and got back less than they put in.
+    LostAtShowdown,
+    /// Was awarded more chips than they put in.
+    Won,
+    /// Was awarded less than they put in.
+    Lost,
+    /// Was dealt one of the hands. Build this with
+    /// [`PlayerPredicate::held`].
+    Held(Vec<FlatHand>),
+}
+
+impl PlayerPredicate {
+    /// A 3-bet preflop.
+    pub const THREE_BET: Self = PlayerPredicate::PreflopBetLevel(3);
+
+    /// Held a hand in a range like `"AK"`, `"AKs"` or `"TT+,AQs+"`.
+    ///
+    /// For games with more than two hole cards the player matches if any
+    /// two of their cards are in the range.
+    pub fn held(range: &str) -> Result<Self, RSPokerError> {
+        Ok(PlayerPredicate::Held(RangeParser::parse_many(range)?))
+    }
+
+    pub fn matches(&self, player: &PlayerSummary) -> bool {
+        match self {
+            PlayerPredicate::RaisedPreflop => player.raised_preflop(),
+            PlayerPredicate::PreflopBetLevel(level) => player.raised_to_level(*level),
+            PlayerPredicate::FoldedIn(round) => player.folded == Some(*round),
+            PlayerPredicate::AllIn => player.all_in,
+            PlayerPredicate::WentToShowdown => player.showdown,
+            PlayerPredicate::WonAtShowdown => player.won_at_showdown(),
+            PlayerPredicate::LostAtShowdown => player.lost_at_showdown(),
+            PlayerPredicate::Won => player.net() > 0.0,
+            PlayerPredicate::Lost => player.net() < 0.0,
+            PlayerPredicate::Held(hands) => hands
+                .iter()
+                .any(|hand| hand.iter().all(|card| player.hole_cards.contains(card))),
+        }
+    }
+}
+
+/// A condition on a whole hand.
+///
+/// Predicates combine with [`HandPredicate::and`], [`HandPredicate::or`]
+/// and `!`.
+///
+/// ```
+/// use rs_poker::arena::hand_query::{HandPredicate, PlayerPredicate, PlayerSelector};
+///
+/// // Alice 3-bet preflop and lost at showdown.
+/// let three_bet_and_lost = HandPredicate::Player(
+///     PlayerSelector::Name("alice".to_string()),
+///     vec![PlayerPredicate::THREE_BET, PlayerPredicate::LostAtShowdown],
+/// );
+///
+/// // Anyone held AK on a paired board, in a pot over 50 big blinds.
+/// let ak_on_paired_board = HandPredicate::Player(
+///     PlayerSelector::Any,
+///     vec![PlayerPredicate::held("AK").unwrap()],
+/// )
+/// .and(HandPredicate::PairedBoard)
+/// .and(HandPredicate::PotOverBigBlinds(50.0));
+/// # let _ = (three_bet_and_lost, ak_on_paired_board);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum HandPredicate {
+    /// Some selected player matches every player predicate.
+    Player(PlayerSelector, Vec<PlayerPredicate>),
+    /// More than this many chips went in the pot.
+    PotOver(f32),
+    /// More than this many big blinds went in the pot.
+    PotOverBigBlinds(f32),
+    /// Two cards on the board share a value.
+    PairedBoard,
+    /// The hand advanced to the round.
+    ReachedRound(Round),
+    /// A pot was awarded by comparing hands.
+    Showdown,
+    /// The number of players seated.
+    NumPlayers(usize),
+    And(Vec<HandPredicate>),
+    Or(Vec<HandPredicate>),
+    Not(Box<HandPredicate>),
+}
+
+impl HandPredicate {
+    pub fn matches(&self, hand: &HandSummary) -> bool {
+        match self {
+            HandPredicate::Player(selector, predicates) => hand
+                .players
+                .iter()
+                .filter(|player| selector.matches(player))
+                .any(|player| predicates.iter().all(|p| p.matches(player))),
+            HandPredicate::PotOver(chips) => hand.total_pot > *chips,
+            HandPredicate::PotOverBigBlinds(big_blinds) => hand.pot_in_big_blinds() > *big_blinds,
+            HandPredicate::PairedBoard => hand.paired_board(),
+            HandPredicate::ReachedRound(round) => hand.rounds.contains(round),
+            HandPredicate::Showdown => hand.showdown,
+            HandPredicate::NumPlayers(num_players) => hand.players.len() == *num_players,
+            HandPredicate::And(predicates) => predicates.iter().all(|p| p.matches(hand)),
+            HandPredicate::Or(predicates) => predicates.iter().any(|p| p.matches(hand)),
+            HandPredicate::Not(predicate) => !predicate.matches(hand),
+        }
+    }
+
+    pub fn and(self, other: HandPredicate) -> Self {
+        match self {
+            HandPredicate::And(mut predicates) => {
+                predicates.push(other);
+                HandPredicate::And(predicates)
+            }
+            predicate => HandPredicate::And(vec![predicate, other]),
+        }
+    }
+
+    pub fn or(self, other: HandPredicate) -> Self {
+        match self {
+            HandPredicate::Or(mut predicates) => {
+                predicates.push(other);
+                HandPredicate::Or(predicates)
+            }
+            predicate => HandPredicate::Or(vec![predicate, other]),
+        }
+    }
+}
+
+impl Not for HandPredicate {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        HandPredicate::Not(Box::new(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        arena::hand_query::RecordedHand,
+        core::{Card, Value},
+    };
+
+    use super::*;
+
+    fn cards(cards: &[&str]) -> Vec<Card> {
+        cards.iter().map(|c| Card::try_from(*c).unwrap()).collect()
+    }
+
+    fn summary() -> HandSummary {
+        let mut hand = HandSummary::new(&RecordedHand::new("1", vec![]));
+        hand.big_blind = 10.0;
+        hand.total_pot = 600.0;
+        hand.board = cards(&["Kd", "7c", "7h", "2s", "9d"]);
+        hand.players = (0..2)
+            .map(|idx| PlayerSummary {
+                idx,
+                name: format!("Player {idx}"),
+                starting_stack: 1000.0,
+                hole_cards: vec![],
+                invested: 300.0,
+                won: 0.0,
+                preflop_raises: vec![],
+                folded: None,
+                all_in: false,
+                showdown: true,
+            })
+            .collect();
+        hand.players[0].hole_cards = cards(&["As", "Kh"]);
+        hand.players[0].preflop_raises = vec![3];
+        hand.players[1].hole_cards = cards(&["Qs", "Qh"]);
+        hand.players[1].won = 600.0;
+        hand
+    }
+
+    #[test]
+    fn test_player_predicates() {
+        let hand = summary();
+        let ak = PlayerPredicate::held("AK").unwrap();
+        assert!(ak.matches(&hand.players[0]));
+        assert!(!ak.matches(&hand.players[1]));
+        assert!(
+            !PlayerPredicate::held("AKs")
+                .unwrap()
+                .matches(&hand.players[0])
+        );
+        assert!(
+            PlayerPredicate::held("TT+")
+                .unwrap()
+                .matches(&hand.players[1])
+        );
+        assert!(PlayerPredicate::held("Zz").is_err());
+
+        assert!(PlayerPredicate::THREE_BET.matches(&hand.players[0]));
+        assert!(!PlayerPredicate::PreflopBetLevel(2).matches(&hand.players[0]));
+        assert!(PlayerPredicate::LostAtShowdown.matches(&hand.players[0]));
+        assert!(PlayerPredicate::WonAtShowdown.matches(&hand.players[1]));
+        assert!(PlayerPredicate::Won.matches(&hand.players[1]));
+    }
+
+    #[test]
+    fn test_hand_predicates() {
+        let hand = summary();
+        let three_bet_lost = |selector| {
+            HandPredicate::Player(
+                selector,
+                vec![PlayerPredicate::THREE_BET, PlayerPredicate::LostAtShowdown],
+            )
+        };
+        assert!(three_bet_lost(PlayerSelector::Any).matches(&hand));
+        assert!(three_bet_lost(PlayerSelector::Idx(0)).matches(&hand));
+        assert!(!three_bet_lost(PlayerSelector::Name("Player 1".into())).matches(&hand));
+
+        let ak_paired = HandPredicate::Player(
+            PlayerSelector::Any,
+            vec![PlayerPredicate::held("AK").unwrap()],
+        )
+        .and(HandPredicate::PairedBoard);
+        assert!(ak_paired.matches(&hand));
+        assert!(
+            ak_paired
+                .clone()
+                .and(HandPredicate::PotOverBigBlinds(50.0))
+                .matches(&hand)
+        );
+        assert!(!ak_paired.and(HandPredicate::PotOver(600.0)).matches(&hand));
+
+        let mut unpaired = hand.clone();
+        unpaired.board[2] = Card::new(Value::Three, unpaired.board[2].suit);
+        assert!(!HandPredicate::PairedBoard.matches(&unpaired));
+        assert!((!HandPredicate::PairedBoard).matches(&unpaired));
+        assert!(
+            HandPredicate::PairedBoard
+                .or(HandPredicate::NumPlayers(2))
+                .matches(&unpaired)
+        );
+        assert!(!HandPredicate::Showdown.matches(&hand));
+    }
+}
@@ -0,0 +1,304 @@
+use crate::{
+    arena::{
+        action::{Action, AgentAction, ForcedBetType, PlayedActionPayload},
+        game_state::Round,
+    },
+    core::Card,
+};
+
+use super::RecordedHand;
+
+/// What one player did in a hand.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerSummary {
+    pub idx: usize,
+    pub name: String,
+    pub starting_stack: f32,
+    /// The cards dealt to the player, without the board.
+    pub hole_cards: Vec<Card>,
+    /// Every chip the player put in the pot, forced bets included.
+    pub invested: f32,
+    /// Every chip awarded to the player.
+    pub won: f32,
+    /// The bet level of each preflop raise the player made. The big blind
+    /// is the first bet, so an open raise is level 2 and a 3-bet is level 3.
+    pub preflop_raises: Vec<usize>,
+    /// The round the player folded in, if they folded.
+    pub folded: Option<Round>,
+    pub all_in: bool,
+    pub showdown: bool,
+}
+
+impl PlayerSummary {
+    /// Chips won minus chips put in.
+    pub fn net(&self) -> f32 {
+        self.won - self.invested
+    }
+
+    pub fn raised_preflop(&self) -> bool {
+        !self.preflop_raises.is_empty()
+    }
+
+    /// Whether the player made the raise to preflop bet level `level`.
+    pub fn raised_to_level(&self, level: usize) -> bool {
+        self.preflop_raises.contains(&level)
+    }
+
+    pub fn won_at_showdown(&self) -> bool {
+        self.showdown && self.won > 0.0
+    }
+
+    /// Went to showdown and got back less than they put in.
+    pub fn lost_at_showdown(&self) -> bool {
+        self.showdown && self.net() < 0.0
+    }
+}
+
+/// The facts about a recorded hand that queries filter on.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HandSummary {
+    pub id: String,
+    pub small_blind: f32,
+    pub big_blind: f32,
+    pub ante: f32,
+    pub players: Vec<PlayerSummary>,
+    pub board: Vec<Card>,
+    /// Every round the hand advanced to, in order.
+    pub rounds: Vec<Round>,
+    /// The chips put in by every player.
+    pub total_pot: f32,
+    /// Whether a pot was awarded by comparing hands.
+    pub showdown: bool,
+}
+
+impl HandSummary {
+    /// Summarize a hand from its actions.
+    ///
+    /// Players are named from `hand.player_names`, falling back to
+    /// `Player {idx}` for seats without a name.
+    pub fn new(hand: &RecordedHand) -> Self {
+        let mut summary = Self {
+            id: hand.id.clone(),
+            small_blind: 0.0,
+            big_blind: 0.0,
+            ante: 0.0,
+            players: vec![],
+            board: vec![],
+            rounds: vec![],
+            total_pot: 0.0,
+            showdown: false,
+        };
+        // The current stack of each player, to work out what each action
+        // put in the pot.
+        let mut stacks: Vec<f32> = vec![];
+        // The bet level preflop, one once the big blind is posted.
+        let mut level = 0;
+        let mut round = Round::Starting;
+
+        for action in &hand.actions {
+            match action {
+                Action::GameStart(payload) => {
+                    summary.small_blind = payload.small_blind;
+                    summary.big_blind = payload.big_blind;
+                    summary.ante = payload.ante;
+                }
+                Action::PlayerSit(payload) => {
+                    let player = summary.player_mut(payload.idx, hand);
+                    player.starting_stack = payload.player_stack;
+                    if stacks.len() <= payload.idx {
+                        stacks.resize(payload.idx + 1, 0.0);
+                    }
+                    stacks[payload.idx] = payload.player_stack;
+                }
+                Action::DealStartingHand(payload) => {
+                    summary
+                        .player_mut(payload.idx, hand)
+                        .hole_cards
+                        .push(payload.card);
+                }
+                Action::RoundAdvance(new_round) => {
+                    round = *new_round;
+                    summary.rounds.push(round);
+                }
+                Action::ForcedBet(payload) => {
+                    let before = stacks.get(payload.idx).copied().unwrap_or(0.0);
+                    let player = summary.player_mut(payload.idx, hand);
+                    player.invested += before - payload.player_stack;
+                    player.all_in |= payload.player_stack <= 0.0;
+                    if payload.idx < stacks.len() {
+                        stacks[payload.idx] = payload.player_stack;
+                    }
+                    if payload.forced_bet_type == ForcedBetType::BigBlind {
+                        level = 1;
+                    }
+                }
+                Action::PlayedAction(payload) => {
+                    summary.played(payload, round, &mut level, &mut stacks, hand);
+                }
+                Action::FailedAction(payload) => {
+                    summary.played(&payload.result, round, &mut level, &mut stacks, hand);
+                }
+                Action::DealCommunity(card) => summary.board.push(*card),
+                Action::Award(payload) => {
+                    summary.showdown |= payload.rank.is_some();
+                    summary.player_mut(payload.idx, hand).won += payload.award_amount;
+                }
+            }
+        }
+
+        let reached_showdown = summary.rounds.contains(&Round::Showdown);
+        for player in summary.players.iter_mut() {
+            player.showdown =
+                reached_showdown && player.folded.is_none() && !player.hole_cards.is_empty();
+        }
+        summary.total_pot = summary.players.iter().map(|p| p.invested).sum();
+        summary
+    }
+
+    /// The pot measured in big blinds, or the raw pot if there was no big
+    /// blind.
+    pub fn pot_in_big_blinds(&self) -> f32 {
+        if self.big_blind > 0.0 {
+            self.total_pot / self.big_blind
+        } else {
+            self.total_pot
+        }
+    }
+
+    /// The player with `name`.
+    pub fn player(&self, name: &str) -> Option<&PlayerSummary> {
+        self.players.iter().find(|p| p.name == name)
+    }
+
+    /// The players that were awarded chips.
+    pub fn winners(&self) -> impl Iterator<Item = &PlayerSummary> {
+        self.players.iter().filter(|p| p.won > 0.0)
+    }
+
+    /// Whether two cards on the board share a value.
+    pub fn paired_board(&self) -> bool {
+        self.board
+            .iter()
+            .enumerate()
+            .any(|(i, a)| self.board[i + 1..].iter().any(|b| a.value == b.value))
+    }
+
+    fn played(
+        &mut self,
+        payload: &PlayedActionPayload,
+        round: Round,
+        level: &mut usize,
+        stacks: &mut [f32],
+        hand: &RecordedHand,
+    ) {
+        let before = stacks.get(payload.idx).copied().unwrap_or(0.0);
+        let player = self.player_mut(payload.idx, hand);
+        player.invested += before - payload.player_stack;
+        player.all_in |= payload.player_stack <= 0.0;
+        if payload.action == AgentAction::Fold {
+            player.folded = Some(round);
+        }
+        if round == Round::Preflop && payload.final_bet > payload.starting_bet {
+            *level += 1;
+            player.preflop_raises.push(*level);
+        }
+        if payload.idx < stacks.len() {
+            stacks[payload.idx] = payload.player_stack;
+        }
+    }
+
+    /// The summary for the player in seat `idx`, adding seats as needed.
+    fn player_mut(&mut self, idx: usize, hand: &RecordedHand) -> &mut PlayerSummary {
+        while self.players.len() <= idx {
+            let seat = self.players.len();
+            self.players.push(PlayerSummary {
+                idx: seat,
+                name: hand
+                    .player_names
+                    .get(seat)
+                    .cloned()
+                    .unwrap_or_else(|| format!("Player {seat}")),
+                starting_stack: 0.0,
+                hole_cards: vec![],
+                invested: 0.0,
+                won: 0.0,
+                preflop_raises: vec![],
+                folded: None,
+                all_in: false,
+                showdown: false,
+            });
+        }
+        &mut self.players[idx]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::arena::hand_query::tests::play_hand;
+
+    use super::*;
+
+    #[test]
+    fn test_summarize_three_bet() {
+        let summary = HandSummary::new(&play_hand(vec![
+            vec![AgentAction::Bet(30.0), AgentAction::Call],
+            vec![AgentAction::Bet(90.0)],
+            vec![AgentAction::Fold],
+        ]));
+        assert_eq!(10.0, summary.big_blind);
+        assert_eq!(3, summary.players.len());
+        assert_eq!(5, summary.board.len());
+        assert!(summary.rounds.contains(&Round::Showdown));
+
+        let alice = summary.player("alice").unwrap();
+        let bob = summary.player("bob").unwrap();
+        let carol = &summary.players[2];
+        assert_eq!("Player 2", carol.name);
+        assert_eq!(vec![2], alice.preflop_raises);
+        assert!(bob.raised_to_level(3));
+        assert!(!carol.raised_preflop());
+        assert_eq!(Some(Round::Preflop), carol.folded);
+
+        assert_eq!(2, alice.hole_cards.len());
+        assert_eq!(90.0, alice.invested);
+        assert_eq!(90.0, bob.invested);
+        assert!(alice.showdown && bob.showdown && !carol.showdown);
+        assert_eq!(190.0, summary.total_pot);
+        assert_eq!(19.0, summary.pot_in_big_blinds());
+
+        let won: f32 = summary.players.iter().map(|p| p.won).sum();
+        assert_eq!(summary.total_pot, won);
+        assert!(summary.showdown);
+        assert!(summary.winners().all(|p| p.showdown));
+    }
+
+    #[test]
+    fn test_fold_to_big_blind() {
+        let summary = HandSummary::new(&play_hand(vec![
+            vec![AgentAction::Fold],
+            vec![AgentAction::Fold],
+            vec![],
+        ]));
+        assert!(summary.board.is_empty());
+        assert!(!summary.showdown);
+        assert!(summary.players.iter().all(|p| !p.showdown));
+        assert_eq!(15.0, summary.total_pot);
+        assert_eq!(5.0, summary.players[2].net());
+        assert_eq!(-5.0, summary.players[1].net());
+    }
+
+    #[test]
+    fn test_paired_board() {
+        let mut summary = HandSummary::new(&RecordedHand::new("1", vec![]));
+        summary.board = vec![
+            Card::try_from("Ah").unwrap(),
+            Card::try_from("7c").unwrap(),
+            Card::try_from("2d").unwrap(),
+        ];
+        assert!(!summary.paired_board());
+        summary.board.push(Card::try_from("7s").unwrap());
+        assert!(summary.paired_board());
+    }
+}
@@ -0,0 +1,330 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::arena::{GameState, action::Action};
+
+use super::{Historian, HistorianError};
+
+/// One action sent from a [`ChannelHistorian`], with the game state after
+/// the action was played.
+#[derive(Debug, Clone)]
+pub struct HistoryEvent {
+    pub id: u128,
+    pub game_state: GameState,
+    pub action: Action,
+}
+
+/// What a [`ChannelHistorian`] does when the channel is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChannelFullPolicy {
+    /// Wait for the collector to catch up, slowing the simulation down.
+    #[default]
+    Block,
+    /// Drop the action and keep going. Historians behind the channel will
+    /// see incomplete games, so only use this where losing some history is
+    /// better than slowing down.
+    Drop,
+    /// Fail with [`HistorianError::ChannelFull`], which removes the
+    /// historian from the simulation.
+    Fail,
+}
+
+/// Create a bounded channel that holds up to `capacity` events and a
+/// historian that sends into it.
+pub fn history_channel(capacity: usize) -> (ChannelHistorian, Receiver<HistoryEvent>) {
+    let (sender, receiver) = mpsc::sync_channel(capacity);
+    (ChannelHistorian::new(sender), receiver)
+}
+
+/// A historian that sends every action over a channel so that simulations
+/// running on other threads can all be recorded in one place.
+///
+/// Unlike most historians this one is `Send`, and clones send into the same
+/// channel, so a clone can be handed to every worker thread. Pair it with a
+/// [`HistoryCollector`] to feed the actions into historians that have to
+/// stay on one thread.
+#[derive(Debug, Clone)]
+pub struct ChannelHistorian {
+    sender: SyncSender<HistoryEvent>,
+    policy: ChannelFullPolicy,
+    dropped: Arc<AtomicUsize>,
+}
+
+impl ChannelHistorian {
+    pub fn new(sender: SyncSender<HistoryEvent>) -> Self {
+        Self {
+            sender,
+            policy: ChannelFullPolicy::default(),
+            dropped: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: ChannelFullPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The number of actions dropped because the channel was full, across
+    /// this historian and all its clones.
+    pub fn dropped(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Historian for ChannelHistorian {
+    fn record_action(
+        &mut self,
+        id: u128,
+        game_state: &GameState,
+        action: Action,
+    ) -> Result<(), HistorianError> {
+        let event = HistoryEvent {
+            id,
+            game_state: game_state.clone(),
+            action,
+        };
+        match self.policy {
+            ChannelFullPolicy::Block => self
+                .sender
+                .send(event)
+                .map_err(|_| HistorianError::ChannelClosed),
+            ChannelFullPolicy::Drop | ChannelFullPolicy::Fail => {
+                match self.sender.try_send(event) {
+                    Ok(()) => Ok(()),
+                    Err(TrySendError::Disconnected(_)) => Err(HistorianError::ChannelClosed),
+                    Err(TrySendError::Full(_)) if self.policy == ChannelFullPolicy::Drop => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        Ok(())
+                    }
+                    Err(TrySendError::Full(_)) => Err(HistorianError::ChannelFull),
+                }
+            }
+        }
+    }
+}
+
+/// What a [`HistoryCollector`] did once every sender is gone.
+#[derive(Debug)]
+pub struct CollectedHistory<T> {
+    /// The value returned by the collector's finish function.
+    pub value: T,
+    /// The number of events received.
+    pub events: usize,
+    /// The errors from historians that failed. Like in a simulation, a
+    /// historian that fails is dropped.
+    pub errors: Vec<HistorianError>,
+}
+
+/// Receives events from [`ChannelHistorian`]s on its own thread and records
+/// them with other historians.
+///
+/// The historians are created on the collector thread, so they don't need
+/// to be `Send`. The collector runs until every `ChannelHistorian` sending
+/// to it has been dropped.
+///
+/// ```
+/// use std::thread;
+///
+/// use rs_poker::arena::historian::{HistoryCollector, VecHistorian, history_channel};
+/// use rs_poker::arena::{
+///     Agent, GameState, Historian, HoldemSimulationBuilder, agent::CallingAgent,
+/// };
+///
+/// let (historian, receiver) = history_channel(1024);
+/// let collector = HistoryCollector::spawn(receiver, || {
+///     let historian = VecHistorian::new();
+///     let storage = historian.get_storage();
+///     let historians: Vec<Box<dyn Historian>> = vec![Box::new(historian)];
+///     (historians, move || storage.take().len())
+/// });
+///
+/// let workers: Vec<_> = (0..2)
+///     .map(|_| {
+///         let historian = historian.clone();
+///         thread::spawn(move || {
+///             let agents: Vec<Box<dyn Agent>> =
+///                 vec![Box::new(CallingAgent), Box::new(CallingAgent)];
+///             let mut sim = HoldemSimulationBuilder::default()
+///                 .game_state(GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0))
+///                 .agents(agents)
+///                 .historians(vec![Box::new(historian)])
+///                 .build()
+///                 .unwrap();
+///             sim.run(&mut rand::rng());
+///         })
+///     })
+///     .collect();
+/// drop(historian);
+/// for worker in workers {
+///     worker.join().unwrap();
+/// }
+///
+/// let collected = collector.join().unwrap();
+/// assert_eq!(collected.events, collected.value);
+/// ```
+#[derive(Debug)]
+pub struct HistoryCollector<T> {
+    handle: JoinHandle<CollectedHistory<T>>,
+}
+
+impl<T: Send + 'static> HistoryCollector<T> {
+    /// Start collecting from `receiver`.
+    ///
+    /// `setup` runs on the collector thread and returns the historians to
+    /// record with, along with a function that's called after the last
+    /// event to produce the collector's result, usually by reading the
+    /// storage of those historians.
+    pub fn spawn<S, F>(receiver: Receiver<HistoryEvent>, setup: S) -> Self
+    where
+        S: FnOnce() -> (Vec<Box<dyn Historian>>, F) + Send + 'static,
+        F: FnOnce() -> T,
+    {
+        let handle = thread::spawn(move || {
+            let (mut historians, finish) = setup();
+            let mut events = 0;
+            let mut errors = vec![];
+            for event in receiver {
+                events += 1;
+                // Record with every historian, dropping the ones that fail.
+                let mut idx = 0;
+                while idx < historians.len() {
+                    match historians[idx].record_action(
+                        event.id,
+                        &event.game_state,
+                        event.action.clone(),
+                    ) {
+                        Ok(()) => idx += 1,
+                        Err(error) => {
+                            historians.remove(idx);
+                            errors.push(error);
+                        }
+                    }
+                }
+            }
+            // Let historians that write on drop finish before reading them.
+            drop(historians);
+            CollectedHistory {
+                value: finish(),
+                events,
+                errors,
+            }
+        });
+        Self { handle }
+    }
+
+    /// Wait for every sender to be dropped and the collector to record the
+    /// last event.
+    pub fn join(self) -> thread::Result<CollectedHistory<T>> {
+        self.handle.join()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+    use crate::arena::{
+        Agent, HoldemSimulationBuilder,
+        agent::{CallingAgent, RandomAgent},
+        game_state::Round,
+        historian::{FailingHistorian, FnHistorian},
+    };
+
+    use super::*;
+
+    fn run_sim(historian: ChannelHistorian) -> u128 {
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::<RandomAgent>::default(),
+            Box::new(CallingAgent),
+            Box::<RandomAgent>::default(),
+        ];
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0))
+            .agents(agents)
+            .historians(vec![Box::new(historian)])
+            .panic_on_historian_error(true)
+            .build()
+            .unwrap();
+        sim.run(&mut rand::rng());
+        sim.id
+    }
+
+    #[test]
+    fn test_collect_from_threads() {
+        let (historian, receiver) = history_channel(8);
+        let collector = HistoryCollector::spawn(receiver, || {
+            let games: Rc<RefCell<HashMap<u128, Vec<Action>>>> = Rc::default();
+            let storage = games.clone();
+            let historian = FnHistorian::new(move |id, _game_state, action| {
+                games.borrow_mut().entry(id).or_default().push(action);
+                Ok(())
+            });
+            let historians: Vec<Box<dyn Historian>> =
+                vec![Box::new(historian), Box::new(FailingHistorian)];
+            (historians, move || storage.take())
+        });
+
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                let historian = historian.clone();
+                thread::spawn(move || -> Vec<u128> {
+                    (0..5).map(|_| run_sim(historian.clone())).collect()
+                })
+            })
+            .collect();
+        drop(historian);
+        let ids: Vec<u128> = workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect();
+
+        let collected = collector.join().unwrap();
+        assert_eq!(1, collected.errors.len());
+        assert_eq!(20, collected.value.len());
+        let actions: usize = collected.value.values().map(|a| a.len()).sum();
+        assert_eq!(collected.events, actions);
+        for id in ids {
+            let game = &collected.value[&id];
+            assert_eq!(
+                Some(&Action::RoundAdvance(Round::Complete)),
+                game.last(),
+                "every game arrives complete and in order"
+            );
+        }
+    }
+
+    #[test]
+    fn test_full_policies() {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let mut dropping = ChannelHistorian::new(sender).with_policy(ChannelFullPolicy::Drop);
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        for _ in 0..3 {
+            let action = Action::RoundAdvance(Round::Preflop);
+            dropping.record_action(1, &game_state, action).unwrap();
+        }
+        assert_eq!(2, dropping.clone().dropped());
+
+        let mut failing = dropping.clone().with_policy(ChannelFullPolicy::Fail);
+        assert!(matches!(
+            failing.record_action(1, &game_state, Action::RoundAdvance(Round::Flop)),
+            Err(HistorianError::ChannelFull)
+        ));
+
+        assert_eq!(
+            Action::RoundAdvance(Round::Preflop),
+            receiver.recv().unwrap().action
+        );
+        drop(receiver);
+        let mut blocking = failing.with_policy(ChannelFullPolicy::Block);
+        assert!(matches!(
+            blocking.record_action(1, &game_state, Action::RoundAdvance(Round::Flop)),
+            Err(HistorianError::ChannelClosed)
+        ));
+    }
+}
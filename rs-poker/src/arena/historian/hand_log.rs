@@ -0,0 +1,130 @@
+use std::{cell::RefCell, collections::HashMap, path::Path, rc::Rc};
+
+use crate::arena::{
+    GameState,
+    action::Action,
+    game_state::Round,
+    hand_log::{HandLogError, HandLogWriter},
+};
+
+use super::{Historian, HistorianError};
+
+/// A historian that streams every completed game into a compact binary
+/// hand log, see [`crate::arena::hand_log`].
+///
+/// Actions are buffered per game and the whole game is written when it
+/// completes. Clones share the same writer so one log can collect the games
+/// of a whole competition or tournament.
+#[derive(Debug, Clone)]
+pub struct HandLogHistorian {
+    writer: Rc<RefCell<HandLogWriter>>,
+    games: HashMap<u128, Vec<Action>>,
+}
+
+impl HandLogHistorian {
+    /// Create a new log at `path`, replacing any that was there.
+    pub fn create(path: &Path) -> Result<Self, HandLogError> {
+        Ok(Self::new_with_writer(Rc::new(RefCell::new(
+            HandLogWriter::create(path)?,
+        ))))
+    }
+
+    /// Add games to the end of the log at `path`.
+    pub fn append(path: &Path) -> Result<Self, HandLogError> {
+        Ok(Self::new_with_writer(Rc::new(RefCell::new(
+            HandLogWriter::append(path)?,
+        ))))
+    }
+
+    /// Write games with a shared writer.
+    pub fn new_with_writer(writer: Rc<RefCell<HandLogWriter>>) -> Self {
+        Self {
+            writer,
+            games: HashMap::new(),
+        }
+    }
+
+    /// The writer, for flushing it or seeing how many games were written.
+    pub fn get_writer(&self) -> Rc<RefCell<HandLogWriter>> {
+        self.writer.clone()
+    }
+}
+
+impl Historian for HandLogHistorian {
+    fn record_action(
+        &mut self,
+        id: u128,
+        _game_state: &GameState,
+        action: Action,
+    ) -> Result<(), HistorianError> {
+        let complete = action == Action::RoundAdvance(Round::Complete);
+        let actions = self.games.entry(id).or_default();
+        actions.push(action);
+
+        if complete {
+            let actions = self
+                .games
+                .remove(&id)
+                .ok_or(HistorianError::UnableToRecordAction)?;
+            self.writer.try_borrow_mut()?.write_hand(id, &actions)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use crate::arena::{
+        Agent, CloneHistorianGenerator, HistorianGenerator, HoldemSimulationBuilder,
+        agent::{CallingAgent, RandomAgent},
+        hand_log::IndexedHandLog,
+        historian::VecHistorian,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_hand_log_historian() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hands.log");
+        let historian = HandLogHistorian::create(&path).unwrap();
+        let writer = historian.get_writer();
+        let generator = CloneHistorianGenerator::new(historian);
+
+        let mut expected = vec![];
+        for _ in 0..5 {
+            let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+            let vec_historian = VecHistorian::new();
+            let records = vec_historian.get_storage();
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::<RandomAgent>::default(),
+                Box::new(CallingAgent),
+                Box::<RandomAgent>::default(),
+            ];
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state.clone())
+                .agents(agents)
+                .historians(vec![
+                    generator.generate(&game_state),
+                    Box::new(vec_historian),
+                ])
+                .panic_on_historian_error(true)
+                .build()
+                .unwrap();
+            sim.run(&mut rand::rng());
+            let actions: Vec<Action> = records.borrow().iter().map(|r| r.action.clone()).collect();
+            expected.push((sim.id, actions));
+        }
+        assert_eq!(5, writer.borrow().len());
+        writer.borrow_mut().flush().unwrap();
+
+        let mut log = IndexedHandLog::open(&path).unwrap();
+        for (number, (id, actions)) in expected.into_iter().enumerate() {
+            let hand = log.get(number).unwrap();
+            assert_eq!(id, hand.id);
+            assert_eq!(actions, hand.actions);
+        }
+    }
+}
@@ -15,6 +15,12 @@ pub enum HistorianError {
     #[cfg(any(test, feature = "serde"))]
     #[error("JSON Error: {0}")]
     JSONError(#[from] serde_json::Error),
+    #[error("The channel to the collector is closed")]
+    ChannelClosed,
+    #[error("The channel to the collector is full")]
+    ChannelFull,
+    #[error("Hand Log Error: {0}")]
+    HandLogError(#[from] crate::arena::hand_log::HandLogError),
     #[error("Unexpected CFR Node: {0}")]
     CFRUnexpectedNode(String),
     #[error("Expected Node not found in tree")]
@@ -90,8 +96,10 @@ where
     }
 }
 
+mod channel;
 mod failing;
 mod fn_historian;
+mod hand_log;
 mod null;
 mod stats_tracking;
 mod vec;
@@ -99,8 +107,16 @@ mod vec;
 #[cfg(any(test, feature = "serde"))]
 mod directory_historian;
 
+#[cfg(feature = "open-hand-history")]
+mod open_hand_history;
+
+pub use channel::{
+    ChannelFullPolicy, ChannelHistorian, CollectedHistory, HistoryCollector, HistoryEvent,
+    history_channel,
+};
 pub use failing::FailingHistorian;
 pub use fn_historian::FnHistorian;
+pub use hand_log::HandLogHistorian;
 pub use null::NullHistorian;
 pub use vec::HistoryRecord;
 pub use vec::VecHistorian;
@@ -108,4 +124,12 @@ pub use vec::VecHistorian;
 #[cfg(any(test, feature = "serde"))]
 pub use directory_historian::DirectoryHistorian;
 
-pub use stats_tracking::StatsTrackingHistorian;
+#[cfg(feature = "open-hand-history")]
+pub use open_hand_history::{
+    HandHistoryBuilder, HandHistoryFormat, OHH_SPEC_VERSION, OpenHandHistoryHistorian,
+};
+
+pub use stats_tracking::{
+    PlayerStats, PlayerSummary, StatCount, StatLine, StatValue, StatsStorage, StatsSummary,
+    StatsTrackingHistorian, StreetLine, StreetStats,
+};
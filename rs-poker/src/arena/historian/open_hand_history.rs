@@ -0,0 +1,732 @@
+use std::{cell::RefCell, path::PathBuf, rc::Rc};
+
+use chrono::Utc;
+
+use crate::arena::{
+    GameState, GameType,
+    action::{Action, AgentAction, ForcedBetType, PlayedActionPayload},
+    competition::{Bounties, Knockout, TournamentFormat, knocked_out_by},
+    game_state::Round,
+};
+use crate::core::Card;
+use crate::open_hand_history::{
+    Action as OhhAction, ActionObj, BetLimitObj, BetType, GameType as OhhGameType, HandHistory,
+    PlayerObj, PlayerWinsObj, PotObj, RoundObj, TournamentInfoObj, append_hand,
+    append_pokerstars_hand,
+};
+
+use super::{Historian, HistorianError};
+
+/// The version of the Open Hand History spec that is written.
+pub const OHH_SPEC_VERSION: &str = "1.4.7";
+
+/// Turns the actions of one game into an Open Hand History.
+///
+/// Blinds, antes and dealt cards go in the preflop round. Every player's
+/// hole cards are dealt face up since the simulation knows them all, and
+/// everyone still in at showdown shows. Each award from the simulation is
+/// a winner of a pot, with tied players sharing the same pot.
+///
+/// ```
+/// use rand::{SeedableRng, rngs::StdRng};
+/// use rs_poker::arena::HoldemSimulationBuilder;
+/// use rs_poker::arena::agent::{AllInAgent, CallingAgent};
+/// use rs_poker::arena::game_state::GameState;
+/// use rs_poker::arena::historian::HandHistoryBuilder;
+///
+/// let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+/// let mut builder = HandHistoryBuilder::new("1".to_string(), &game_state);
+/// let mut sim = HoldemSimulationBuilder::default()
+///     .game_state(game_state)
+///     .agents(vec![Box::new(AllInAgent), Box::new(CallingAgent)])
+///     .build()
+///     .unwrap();
+/// sim.run(&mut StdRng::seed_from_u64(1));
+/// for action in &sim.actions {
+///     builder.record(action);
+/// }
+///
+/// let hand = builder.build();
+/// assert_eq!(2, hand.players.len());
+/// assert_eq!(200.0, hand.pots.iter().map(|p| p.amount).sum::<f32>());
+/// ```
+#[derive(Debug, Clone)]
+pub struct HandHistoryBuilder {
+    hand: HandHistory,
+    /// Each player's stack as of the last action, to work out how much a
+    /// forced bet really put in.
+    stacks: Vec<f32>,
+    hole_cards: Vec<Vec<Card>>,
+    folded: Vec<bool>,
+    round: Round,
+}
+
+impl HandHistoryBuilder {
+    /// Start a hand for a game about to be played from `game_state`.
+    pub fn new(game_number: String, game_state: &GameState) -> Self {
+        let (game_type, bet_type) = match game_state.game_type {
+            GameType::NoLimitHoldem => (OhhGameType::Holdem, BetType::NoLimit),
+            GameType::PotLimitOmaha => (OhhGameType::Omaha, BetType::PotLimit),
+        };
+        let num_players = game_state.num_players;
+        let hand = HandHistory {
+            spec_version: OHH_SPEC_VERSION.to_string(),
+            site_name: "rs_poker".to_string(),
+            network_name: "rs_poker".to_string(),
+            internal_version: env!("CARGO_PKG_VERSION").to_string(),
+            tournament: false,
+            tournament_info: None,
+            game_number,
+            start_date_utc: Some(Utc::now()),
+            table_name: "arena".to_string(),
+            table_handle: None,
+            table_skin: None,
+            game_type,
+            bet_limit: Some(BetLimitObj {
+                bet_type,
+                bet_cap: 0.0,
+            }),
+            table_size: num_players as u64,
+            currency: "".to_string(),
+            dealer_seat: game_state.dealer_idx as u64 + 1,
+            small_blind_amount: game_state.small_blind,
+            big_blind_amount: game_state.big_blind,
+            ante_amount: game_state.ante,
+            hero_player_id: None,
+            players: vec![],
+            rounds: vec![],
+            pots: vec![],
+            tournament_bounties: None,
+        };
+        Self {
+            hand,
+            stacks: vec![0.0; num_players],
+            hole_cards: vec![vec![]; num_players],
+            folded: vec![false; num_players],
+            round: Round::Starting,
+        }
+    }
+
+    /// Add the next action of the game to the hand.
+    pub fn record(&mut self, action: &Action) {
+        match action {
+            Action::GameStart(_) => {}
+            Action::PlayerSit(payload) => {
+                self.stacks[payload.idx] = payload.player_stack;
+                self.hand.players.push(PlayerObj {
+                    id: payload.idx as u64,
+                    seat: payload.idx as u64 + 1,
+                    name: format!("Player {}", payload.idx),
+                    display: None,
+                    starting_stack: payload.player_stack,
+                    player_bounty: None,
+                    is_sitting_out: None,
+                });
+                self.hand.players.sort_by_key(|player| player.seat);
+            }
+            Action::DealStartingHand(payload) => {
+                self.hole_cards[payload.idx].push(payload.card);
+                let round = self.current_round();
+                let dealt = round.actions.last_mut().filter(|last| {
+                    last.action == OhhAction::DealtCards && last.player_id == payload.idx as u64
+                });
+                if let Some(dealt) = dealt {
+                    dealt.cards.get_or_insert_with(Vec::new).push(payload.card);
+                } else {
+                    Self::push_action(
+                        round,
+                        payload.idx,
+                        OhhAction::DealtCards,
+                        0.0,
+                        false,
+                        Some(vec![payload.card]),
+                    );
+                }
+            }
+            Action::RoundAdvance(round) => {
+                self.round = *round;
+                if *round == Round::Showdown {
+                    self.show_cards();
+                }
+            }
+            Action::ForcedBet(payload) => {
+                let amount = self.stacks[payload.idx] - payload.player_stack;
+                self.stacks[payload.idx] = payload.player_stack;
+                let action = match payload.forced_bet_type {
+                    ForcedBetType::Ante => OhhAction::PostAnte,
+                    ForcedBetType::SmallBlind => OhhAction::PostSmallBlind,
+                    ForcedBetType::BigBlind => OhhAction::PostBigBlind,
+                };
+                let round = self.current_round();
+                Self::push_action(
+                    round,
+                    payload.idx,
+                    action,
+                    amount,
+                    payload.player_stack <= 0.0,
+                    None,
+                );
+            }
+            Action::PlayedAction(payload) => self.played(payload),
+            Action::FailedAction(payload) => self.played(&payload.result),
+            Action::DealCommunity(card) => {
+                self.current_round()
+                    .cards
+                    .get_or_insert_with(Vec::new)
+                    .push(*card);
+            }
+            Action::Award(payload) => {
+                let win = PlayerWinsObj {
+                    player_id: payload.idx as u64,
+                    win_amount: payload.award_amount,
+                    cashout_amount: None,
+                    cashout_fee: None,
+                    bonus_amount: None,
+                    contributed_rake: None,
+                };
+                // Players that tie split the same pot.
+                let number = self.hand.pots.len() as u64;
+                let tied = self.hand.pots.last_mut().filter(|pot| {
+                    pot.amount == payload.total_pot
+                        && pot.player_wins.iter().all(|w| w.player_id != win.player_id)
+                });
+                if let Some(pot) = tied {
+                    pot.player_wins.push(win);
+                } else {
+                    self.hand.pots.push(PotObj {
+                        number,
+                        amount: payload.total_pot,
+                        rake: None,
+                        jackpot: None,
+                        player_wins: vec![win],
+                    });
+                }
+            }
+        }
+    }
+
+    /// The finished hand.
+    pub fn build(self) -> HandHistory {
+        self.hand
+    }
+
+    fn played(&mut self, payload: &PlayedActionPayload) {
+        let amount = payload.final_player_bet - payload.starting_player_bet;
+        self.stacks[payload.idx] -= amount;
+        let action = if payload.action == AgentAction::Fold {
+            self.folded[payload.idx] = true;
+            OhhAction::Fold
+        } else if payload.final_bet > payload.starting_bet {
+            if payload.starting_bet == 0.0 {
+                OhhAction::Bet
+            } else {
+                OhhAction::Raise
+            }
+        } else if amount > 0.0 {
+            OhhAction::Call
+        } else {
+            OhhAction::Check
+        };
+        let is_allin = payload.players_all_in.get(payload.idx);
+        let round = self.current_round();
+        Self::push_action(round, payload.idx, action, amount, is_allin, None);
+    }
+
+    /// Everyone still in at showdown shows their hole cards.
+    fn show_cards(&mut self) {
+        let showing: Vec<usize> = self
+            .hand
+            .players
+            .iter()
+            .map(|player| player.id as usize)
+            .filter(|idx| !self.folded[*idx])
+            .collect();
+        for idx in showing {
+            let cards = self.hole_cards[idx].clone();
+            let round = self.current_round();
+            Self::push_action(round, idx, OhhAction::ShowsCards, 0.0, false, Some(cards));
+        }
+    }
+
+    /// The round for the street being played, starting it if needed.
+    fn current_round(&mut self) -> &mut RoundObj {
+        let street = match self.round {
+            Round::Starting | Round::Ante | Round::DealPreflop | Round::Preflop => "Preflop",
+            Round::DealFlop | Round::Flop => "Flop",
+            Round::DealTurn | Round::Turn => "Turn",
+            Round::DealRiver | Round::River => "River",
+            Round::Showdown | Round::Complete => "Showdown",
+        };
+        let rounds = &mut self.hand.rounds;
+        if rounds.last().is_none_or(|round| round.street != street) {
+            rounds.push(RoundObj {
+                id: rounds.len() as u64,
+                street: street.to_string(),
+                cards: None,
+                actions: vec![],
+            });
+        }
+        rounds.last_mut().unwrap()
+    }
+
+    fn push_action(
+        round: &mut RoundObj,
+        idx: usize,
+        action: OhhAction,
+        amount: f32,
+        is_allin: bool,
+        cards: Option<Vec<Card>>,
+    ) {
+        round.actions.push(ActionObj {
+            action_number: round.actions.len() as u64 + 1,
+            player_id: idx as u64,
+            action,
+            amount,
+            is_allin,
+            cards,
+        });
+    }
+}
+
+/// The format hands are written in by [`OpenHandHistoryHistorian`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HandHistoryFormat {
+    /// One Open Hand History JSON object per hand.
+    #[default]
+    OpenHandHistory,
+    /// PokerStars style plain text, hands separated by blank lines.
+    PokerStars,
+}
+
+/// A historian that appends every game to a file so simulations can be
+/// loaded into standard hand history tools.
+///
+/// By default each hand is written to a `.ohh` file as an Open Hand
+/// History, one JSON object per hand. With
+/// `with_format(HandHistoryFormat::PokerStars)` hands are written as
+/// PokerStars style text instead, separated by blank lines.
+///
+/// The hand is written when the game completes. The game number is the id
+/// of the simulation.
+///
+/// For tournaments, give the tournament's info and format and hand out
+/// clones with a `CloneHistorianGenerator`. The format's flags are added
+/// to the tournament info, and with bounties the clones share who has
+/// which bounty so each hand lists its knockouts.
+#[derive(Debug, Clone)]
+pub struct OpenHandHistoryHistorian {
+    path: PathBuf,
+    table_name: Option<String>,
+    player_names: Option<Vec<String>>,
+    hero_player_id: Option<u64>,
+    tournament_info: Option<TournamentInfoObj>,
+    tournament_format: TournamentFormat,
+    bounties: Option<Rc<RefCell<BountyTracker>>>,
+    format: HandHistoryFormat,
+    builder: Option<HandHistoryBuilder>,
+    /// The actions of the current hand, kept to find knockouts.
+    actions: Vec<Action>,
+}
+
+/// The bounties of a tournament as of the last hand written.
+#[derive(Debug)]
+struct BountyTracker {
+    /// Started with the first hand, once the number of players is known.
+    bounties: Option<Bounties>,
+    /// The number of hands written, counting from 0.
+    hand: usize,
+    /// Each player's stack at the end of the last hand, to spot rebuys.
+    stacks: Vec<f32>,
+}
+
+impl BountyTracker {
+    /// Pay out the bounties of everyone busted in a hand.
+    fn knockouts(
+        &mut self,
+        format: TournamentFormat,
+        game_state: &GameState,
+        actions: &[Action],
+    ) -> Vec<Knockout> {
+        let Some(bounty) = format.bounty else {
+            return vec![];
+        };
+        let bounties = self
+            .bounties
+            .get_or_insert_with(|| Bounties::new(bounty, game_state.num_players));
+        // Players that busted last hand and are back with chips rebought.
+        for (idx, stack) in game_state.starting_stacks.iter().enumerate() {
+            if *stack > 0.0 && self.stacks.get(idx).is_some_and(|last| *last == 0.0) {
+                bounties.rebuy(idx);
+            }
+        }
+        let knockouts = (0..game_state.num_players)
+            .filter(|idx| game_state.stacks[*idx] == 0.0 && game_state.starting_stacks[*idx] != 0.0)
+            .flat_map(|idx| bounties.knock_out(self.hand, &knocked_out_by(actions, idx), idx))
+            .collect();
+        self.stacks = game_state.stacks.clone();
+        self.hand += 1;
+        knockouts
+    }
+}
+
+impl OpenHandHistoryHistorian {
+    /// Creates a historian that appends hands to the file at `path`,
+    /// creating it if needed.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            table_name: None,
+            player_names: None,
+            hero_player_id: None,
+            tournament_info: None,
+            tournament_format: TournamentFormat::default(),
+            bounties: None,
+            format: HandHistoryFormat::default(),
+            builder: None,
+            actions: vec![],
+        }
+    }
+
+    pub fn with_table_name(mut self, table_name: String) -> Self {
+        self.table_name = Some(table_name);
+        self
+    }
+
+    /// Name the players by seat index. Defaults to `Player {idx}`.
+    pub fn with_player_names(mut self, player_names: Vec<String>) -> Self {
+        self.player_names = Some(player_names);
+        self
+    }
+
+    /// The player the hand is written from the point of view of.
+    pub fn with_hero(mut self, idx: usize) -> Self {
+        self.hero_player_id = Some(idx as u64);
+        self
+    }
+
+    /// Mark every hand as part of a tournament.
+    pub fn with_tournament_info(mut self, tournament_info: TournamentInfoObj) -> Self {
+        self.tournament_info = Some(tournament_info);
+        self
+    }
+
+    /// The rebuys, add-on and bounties of the tournament. Use it with
+    /// `with_tournament_info`.
+    pub fn with_tournament_format(mut self, tournament_format: TournamentFormat) -> Self {
+        self.tournament_format = tournament_format;
+        self.bounties = tournament_format.bounty.map(|_| {
+            Rc::new(RefCell::new(BountyTracker {
+                bounties: None,
+                hand: 0,
+                stacks: vec![],
+            }))
+        });
+        self
+    }
+
+    /// Write hands in `format` rather than as JSON.
+    pub fn with_format(mut self, format: HandHistoryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    fn finish(&self, mut hand: HandHistory) -> HandHistory {
+        if let Some(table_name) = &self.table_name {
+            hand.table_name = table_name.clone();
+        }
+        if let Some(names) = &self.player_names {
+            for player in hand.players.iter_mut() {
+                if let Some(name) = names.get(player.id as usize) {
+                    player.name = name.clone();
+                }
+            }
+        }
+        hand.hero_player_id = self.hero_player_id;
+        if let Some(tournament_info) = &self.tournament_info {
+            let mut tournament_info = tournament_info.clone();
+            let mut flags = tournament_info.flags.unwrap_or_default();
+            for flag in self.tournament_format.flags() {
+                if !flags.contains(&flag) {
+                    flags.push(flag);
+                }
+            }
+            tournament_info.flags = (!flags.is_empty()).then_some(flags);
+            hand.tournament = true;
+            hand.tournament_info = Some(tournament_info);
+        }
+        hand
+    }
+}
+
+impl Historian for OpenHandHistoryHistorian {
+    fn record_action(
+        &mut self,
+        id: u128,
+        game_state: &GameState,
+        action: Action,
+    ) -> Result<(), HistorianError> {
+        let builder = self
+            .builder
+            .get_or_insert_with(|| HandHistoryBuilder::new(id.to_string(), game_state));
+        builder.record(&action);
+        if self.bounties.is_some() {
+            self.actions.push(action.clone());
+        }
+
+        if action == Action::RoundAdvance(Round::Complete) {
+            let builder = self
+                .builder
+                .take()
+                .ok_or(HistorianError::UnableToRecordAction)?;
+            let mut hand = self.finish(builder.build());
+            if let Some(bounties) = &self.bounties {
+                let actions = std::mem::take(&mut self.actions);
+                let knockouts =
+                    bounties
+                        .borrow_mut()
+                        .knockouts(self.tournament_format, game_state, &actions);
+                if !knockouts.is_empty() {
+                    hand.tournament_bounties = Some(knockouts.iter().map(Into::into).collect());
+                }
+            }
+            match self.format {
+                HandHistoryFormat::OpenHandHistory => append_hand(&self.path, hand)?,
+                HandHistoryFormat::PokerStars => append_pokerstars_hand(&self.path, &hand)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::NamedTempFile;
+
+    use crate::arena::{
+        Agent, AgentGenerator, HoldemSimulationBuilder,
+        agent::{AllInAgentGenerator, CallingAgent, RandomAgent},
+        competition::{BountyFormat, Rebuys, SingleTableTournamentBuilder},
+        historian::CloneHistorianGenerator,
+    };
+    use crate::open_hand_history::{
+        OpenHandHistoryWrapper, SpeedObj, SpeedType, TournamentFlag, TournamentType,
+        parse_pokerstars_hands,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_hands_round_trip() {
+        let file = NamedTempFile::new().unwrap();
+        let mut rng = rand::rng();
+        for _ in 0..5 {
+            let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 1.0, 0);
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::<RandomAgent>::default(),
+                Box::new(CallingAgent),
+                Box::<RandomAgent>::default(),
+            ];
+            let historian = OpenHandHistoryHistorian::new(file.path().to_path_buf())
+                .with_table_name("Test Table".to_string())
+                .with_hero(1);
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(historian)])
+                .panic_on_historian_error(true)
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+        }
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        let hands: Vec<OpenHandHistoryWrapper> = contents
+            .split("\n\n")
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(5, hands.len());
+
+        for wrapper in hands {
+            let hand = &wrapper.ohh;
+            assert_eq!("Test Table", hand.table_name);
+            assert_eq!(Some(1), hand.hero_player_id);
+            assert_eq!(3, hand.players.len());
+            assert_eq!("Preflop", hand.rounds[0].street);
+
+            let preflop = &hand.rounds[0].actions;
+            let antes = preflop
+                .iter()
+                .filter(|a| a.action == OhhAction::PostAnte)
+                .count();
+            assert_eq!(3, antes);
+            for action in preflop.iter().filter(|a| a.action == OhhAction::DealtCards) {
+                assert_eq!(2, action.cards.as_ref().unwrap().len());
+            }
+
+            // Everything put in is won back.
+            let put_in: f32 = hand
+                .rounds
+                .iter()
+                .flat_map(|round| &round.actions)
+                .map(|action| action.amount)
+                .sum();
+            let won: f32 = hand
+                .pots
+                .iter()
+                .flat_map(|pot| &pot.player_wins)
+                .map(|win| win.win_amount)
+                .sum();
+            assert!((put_in - won).abs() < 1e-3, "{put_in} != {won}");
+
+            let json = serde_json::to_string(&wrapper).unwrap();
+            let parsed: OpenHandHistoryWrapper = serde_json::from_str(&json).unwrap();
+            assert_eq!(wrapper, parsed);
+        }
+    }
+
+    #[test]
+    fn test_pokerstars_format() {
+        let file = NamedTempFile::new().unwrap();
+        for _ in 0..3 {
+            let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+            let historian = OpenHandHistoryHistorian::new(file.path().to_path_buf())
+                .with_player_names(vec!["alice".to_string(), "bob".to_string()])
+                .with_format(HandHistoryFormat::PokerStars);
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(vec![Box::new(CallingAgent), Box::new(CallingAgent)])
+                .historians(vec![Box::new(historian)])
+                .panic_on_historian_error(true)
+                .build()
+                .unwrap();
+            sim.run(&mut rand::rng());
+        }
+
+        let contents = fs::read_to_string(file.path()).unwrap();
+        assert!(contents.contains("Seat 1: alice (100 in chips)"));
+        let hands = parse_pokerstars_hands(&contents).unwrap();
+        assert_eq!(3, hands.len());
+        for hand in hands {
+            assert_eq!("bob", hand.players[1].name);
+            assert_eq!(20.0, hand.pots.iter().map(|p| p.amount).sum::<f32>());
+        }
+    }
+
+    #[test]
+    fn test_bounty_tournament() {
+        let file = NamedTempFile::new().unwrap();
+        let format = TournamentFormat {
+            rebuys: Some(Rebuys {
+                chips: 100.0,
+                cost: 10.0,
+                until_hand: 3,
+                max_per_player: Some(1),
+            }),
+            add_on: None,
+            bounty: Some(BountyFormat::Progressive { bounty: 10.0 }),
+        };
+        let tournament_info = TournamentInfoObj {
+            tournament_number: "1".to_string(),
+            name: "Knockout".to_string(),
+            start_date_utc: None,
+            currency: String::new(),
+            buyin_amount: 10.0,
+            fee_amount: 0.0,
+            bounty_fee_amount: 10.0,
+            initial_stack: 100,
+            tournament_type: TournamentType::SingleTableTournament,
+            flags: None,
+            speed: SpeedObj {
+                speed_type: SpeedType::Normal,
+                round_time: 0,
+            },
+        };
+        let historian = OpenHandHistoryHistorian::new(file.path().to_path_buf())
+            .with_tournament_info(tournament_info)
+            .with_tournament_format(format);
+        let agent_gens: Vec<Box<dyn AgentGenerator>> = (0..4)
+            .map(|_| Box::<AllInAgentGenerator>::default() as Box<dyn AgentGenerator>)
+            .collect();
+        let results = SingleTableTournamentBuilder::default()
+            .agent_generators(agent_gens)
+            .historian_generators(vec![Box::new(CloneHistorianGenerator::new(historian))])
+            .starting_game_state(GameState::new_starting(vec![100.0; 4], 10.0, 5.0, 0.0, 0))
+            .format(format)
+            .panic_on_historian_error(true)
+            .build()
+            .unwrap()
+            .run()
+            .unwrap();
+
+        let hands: Vec<HandHistory> = fs::read_to_string(file.path())
+            .unwrap()
+            .split("\n\n")
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str::<OpenHandHistoryWrapper>(line)
+                    .unwrap()
+                    .ohh
+            })
+            .collect();
+        assert_eq!(results.rounds(), hands.len());
+        for hand in &hands {
+            let flags = hand.tournament_info.as_ref().unwrap().flags.as_ref();
+            assert_eq!(
+                Some(&vec![
+                    TournamentFlag::Rebuy,
+                    TournamentFlag::ProgressiveBounty
+                ]),
+                flags
+            );
+        }
+
+        // The knockouts written match the tournament's, rebuys included.
+        let written: Vec<(u64, u64, f32)> = hands
+            .iter()
+            .flat_map(|hand| hand.tournament_bounties.iter().flatten())
+            .map(|b| (b.player_id, b.defeated_player_id, b.bounty_won))
+            .collect();
+        let knockouts: Vec<(u64, u64, f32)> = results
+            .knockouts()
+            .iter()
+            .map(|k| (k.player_idx as u64, k.defeated_idx as u64, k.bounty_won))
+            .collect();
+        assert!(knockouts.len() >= 3);
+        assert_eq!(knockouts, written);
+    }
+
+    #[test]
+    fn test_showdown() {
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 1);
+        let mut builder = HandHistoryBuilder::new("2".to_string(), &game_state);
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(vec![Box::new(CallingAgent), Box::new(CallingAgent)])
+            .build()
+            .unwrap();
+        sim.run(&mut rand::rng());
+        for action in &sim.actions {
+            builder.record(action);
+        }
+        let hand = builder.build();
+
+        assert_eq!(2, hand.dealer_seat);
+        let streets: Vec<&str> = hand.rounds.iter().map(|r| r.street.as_str()).collect();
+        assert_eq!(
+            vec!["Preflop", "Flop", "Turn", "River", "Showdown"],
+            streets
+        );
+        assert_eq!(3, hand.rounds[1].cards.as_ref().unwrap().len());
+        assert_eq!(1, hand.rounds[2].cards.as_ref().unwrap().len());
+        let shows = hand.rounds[4]
+            .actions
+            .iter()
+            .filter(|a| a.action == OhhAction::ShowsCards)
+            .count();
+        assert_eq!(2, shows);
+        assert_eq!(20.0, hand.pots.iter().map(|p| p.amount).sum::<f32>());
+    }
+}
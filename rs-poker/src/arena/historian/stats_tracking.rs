@@ -1,10 +1,163 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{cell::RefCell, collections::BTreeMap, fmt, rc::Rc};
 
 use super::Historian;
 
 use crate::arena::GameState;
-use crate::arena::action::{Action, AgentAction, PlayedActionPayload};
-use crate::core::Rankable;
+use crate::arena::action::{Action, AgentAction, AwardPayload, PlayedActionPayload};
+use crate::arena::agent::Position;
+use crate::arena::game_state::Round;
+use crate::core::{PlayerBitSet, Rankable};
+
+/// How many times something happened out of how many times it could have.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatCount {
+    pub count: usize,
+    pub opportunities: usize,
+}
+
+impl StatCount {
+    /// Record one opportunity, and whether the player took it.
+    pub fn record(&mut self, hit: bool) {
+        self.opportunities += 1;
+        if hit {
+            self.count += 1;
+        }
+    }
+
+    /// `count / opportunities`, or `None` without any opportunities.
+    pub fn frequency(&self) -> Option<f32> {
+        ratio(self.count, self.opportunities)
+    }
+
+    fn add(&mut self, other: &StatCount) {
+        self.count += other.count;
+        self.opportunities += other.opportunities;
+    }
+}
+
+/// What a player did on one betting round.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StreetStats {
+    /// Bets and raises
+    pub aggressive: usize,
+    pub calls: usize,
+    pub checks: usize,
+    pub folds: usize,
+    /// Betting again after being the last aggressor on the previous street.
+    /// Never filled in preflop.
+    pub cbet: StatCount,
+    /// Folding to someone else's continuation bet.
+    pub fold_to_cbet: StatCount,
+}
+
+impl StreetStats {
+    /// Bets and raises per call. Players who never call count as having
+    /// called once.
+    pub fn aggression_factor(&self) -> Option<f32> {
+        aggression_factor(self.aggressive, self.calls)
+    }
+
+    /// Fraction of bets and raises out of every bet, raise, call and fold.
+    pub fn aggression_frequency(&self) -> StatCount {
+        StatCount {
+            count: self.aggressive,
+            opportunities: self.aggressive + self.calls + self.folds,
+        }
+    }
+
+    fn add(&mut self, other: &StreetStats) {
+        self.aggressive += other.aggressive;
+        self.calls += other.calls;
+        self.checks += other.checks;
+        self.folds += other.folds;
+        self.cbet.add(&other.cbet);
+        self.fold_to_cbet.add(&other.fold_to_cbet);
+    }
+}
+
+/// The usual HUD statistics for one player, either in one position or
+/// summed over all of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayerStats {
+    /// Hands dealt into
+    pub hands: usize,
+    /// Put money in preflop without being forced to
+    pub vpip: StatCount,
+    /// Raised preflop
+    pub pfr: StatCount,
+    /// Re-raised when facing a single preflop raise
+    pub three_bet: StatCount,
+    /// Folded after opening and facing a re-raise
+    pub fold_to_three_bet: StatCount,
+    /// Raised from late position or the small blind when folded to
+    pub steal: StatCount,
+    /// Folded from the blinds when facing a steal
+    pub fold_to_steal: StatCount,
+    /// Went to showdown after seeing the flop
+    pub wtsd: StatCount,
+    /// Won money after going to showdown
+    pub wsd: StatCount,
+    pub preflop: StreetStats,
+    pub flop: StreetStats,
+    pub turn: StreetStats,
+    pub river: StreetStats,
+}
+
+impl PlayerStats {
+    /// The stats for a betting round, `None` for rounds without betting.
+    pub fn street(&self, round: Round) -> Option<&StreetStats> {
+        match round {
+            Round::Preflop => Some(&self.preflop),
+            Round::Flop => Some(&self.flop),
+            Round::Turn => Some(&self.turn),
+            Round::River => Some(&self.river),
+            _ => None,
+        }
+    }
+
+    fn street_mut(&mut self, round: Round) -> Option<&mut StreetStats> {
+        match round {
+            Round::Preflop => Some(&mut self.preflop),
+            Round::Flop => Some(&mut self.flop),
+            Round::Turn => Some(&mut self.turn),
+            Round::River => Some(&mut self.river),
+            _ => None,
+        }
+    }
+
+    fn postflop(&self) -> StreetStats {
+        let mut total = self.flop.clone();
+        total.add(&self.turn);
+        total.add(&self.river);
+        total
+    }
+
+    /// Postflop bets and raises per call.
+    pub fn aggression_factor(&self) -> Option<f32> {
+        self.postflop().aggression_factor()
+    }
+
+    /// Postflop bets and raises out of every bet, raise, call and fold.
+    pub fn aggression_frequency(&self) -> StatCount {
+        self.postflop().aggression_frequency()
+    }
+
+    fn add(&mut self, other: &PlayerStats) {
+        self.hands += other.hands;
+        self.vpip.add(&other.vpip);
+        self.pfr.add(&other.pfr);
+        self.three_bet.add(&other.three_bet);
+        self.fold_to_three_bet.add(&other.fold_to_three_bet);
+        self.steal.add(&other.steal);
+        self.fold_to_steal.add(&other.fold_to_steal);
+        self.wtsd.add(&other.wtsd);
+        self.wsd.add(&other.wsd);
+        self.preflop.add(&other.preflop);
+        self.flop.add(&other.flop);
+        self.turn.add(&other.turn);
+        self.river.add(&other.river);
+    }
+}
 
 /// Storage for tracking various poker player statistics
 ///
@@ -24,6 +177,9 @@ use crate::core::Rankable;
 ///   each player
 /// * `raise_ahead_count` - Vector storing the count of raise actions when ahead
 ///   in hand for each player
+/// * `by_position` - Vector storing the full HUD statistics of each player,
+///   split up by the position they were in
+#[derive(Debug, Clone)]
 pub struct StatsStorage {
     // The total number of actions each player has taken
     pub actions_count: Vec<usize>,
@@ -41,6 +197,9 @@ pub struct StatsStorage {
     pub raise_count: Vec<usize>,
 
     pub raise_ahead_count: Vec<usize>,
+
+    // Everything else, per player and position
+    pub by_position: Vec<BTreeMap<Position, PlayerStats>>,
 }
 
 impl StatsStorage {
@@ -57,8 +216,92 @@ impl StatsStorage {
             raise_count: vec![0; num_players],
 
             raise_ahead_count: vec![0; num_players],
+
+            by_position: vec![BTreeMap::new(); num_players],
         }
     }
+
+    /// The number of players being tracked.
+    pub fn num_players(&self) -> usize {
+        self.actions_count.len()
+    }
+
+    /// The player's stats from one position, if they have played there.
+    pub fn position_stats(&self, idx: usize, position: Position) -> Option<&PlayerStats> {
+        self.by_position.get(idx)?.get(&position)
+    }
+
+    /// The player's stats summed over every position.
+    pub fn player_stats(&self, idx: usize) -> PlayerStats {
+        let mut total = PlayerStats::default();
+        for stats in self
+            .by_position
+            .get(idx)
+            .into_iter()
+            .flat_map(|p| p.values())
+        {
+            total.add(stats);
+        }
+        total
+    }
+
+    /// Percentages and sample sizes for every player, ready to be shown
+    /// or serialized.
+    pub fn summary(&self) -> StatsSummary {
+        let players = self
+            .by_position
+            .iter()
+            .enumerate()
+            .map(|(idx, positions)| PlayerSummary {
+                idx,
+                overall: StatLine::from(&self.player_stats(idx)),
+                by_position: positions
+                    .iter()
+                    .map(|(position, stats)| (*position, StatLine::from(stats)))
+                    .collect(),
+            })
+            .collect();
+        StatsSummary { players }
+    }
+
+    fn stats_mut(&mut self, idx: usize, position: Position) -> &mut PlayerStats {
+        self.by_position[idx].entry(position).or_default()
+    }
+
+    /// Make room for at least `num_players` players.
+    fn ensure_players(&mut self, num_players: usize) {
+        if num_players <= self.num_players() {
+            return;
+        }
+        for counts in [
+            &mut self.actions_count,
+            &mut self.vpip_count,
+            &mut self.vpip_ahead_count,
+            &mut self.raise_count,
+            &mut self.raise_ahead_count,
+        ] {
+            counts.resize(num_players, 0);
+        }
+        self.vpip_total.resize(num_players, 0.0);
+        self.vpip_ahead_total.resize(num_players, 0.0);
+        self.by_position.resize(num_players, BTreeMap::new());
+    }
+}
+
+fn ratio(count: usize, total: usize) -> Option<f32> {
+    if total == 0 {
+        None
+    } else {
+        Some(count as f32 / total as f32)
+    }
+}
+
+fn aggression_factor(aggressive: usize, calls: usize) -> Option<f32> {
+    if aggressive + calls == 0 {
+        None
+    } else {
+        Some(aggressive as f32 / calls.max(1) as f32)
+    }
 }
 
 impl Default for StatsStorage {
@@ -67,20 +310,358 @@ impl Default for StatsStorage {
     }
 }
 
+/// A single statistic as a frequency plus the number of samples it came
+/// from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatValue {
+    pub value: Option<f32>,
+    pub samples: usize,
+}
+
+impl From<StatCount> for StatValue {
+    fn from(count: StatCount) -> Self {
+        Self {
+            value: count.frequency(),
+            samples: count.opportunities,
+        }
+    }
+}
+
+impl fmt::Display for StatValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.value {
+            Some(value) => write!(f, "{:.0}", value * 100.0),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+/// The summary of one betting round.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StreetLine {
+    pub aggression_factor: Option<f32>,
+    pub aggression_frequency: StatValue,
+    pub cbet: StatValue,
+    pub fold_to_cbet: StatValue,
+}
+
+impl From<&StreetStats> for StreetLine {
+    fn from(stats: &StreetStats) -> Self {
+        Self {
+            aggression_factor: stats.aggression_factor(),
+            aggression_frequency: stats.aggression_frequency().into(),
+            cbet: stats.cbet.into(),
+            fold_to_cbet: stats.fold_to_cbet.into(),
+        }
+    }
+}
+
+/// The summary of a `PlayerStats`.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatLine {
+    pub hands: usize,
+    pub vpip: StatValue,
+    pub pfr: StatValue,
+    pub three_bet: StatValue,
+    pub fold_to_three_bet: StatValue,
+    pub steal: StatValue,
+    pub fold_to_steal: StatValue,
+    pub wtsd: StatValue,
+    pub wsd: StatValue,
+    /// Postflop
+    pub aggression_factor: Option<f32>,
+    /// Postflop
+    pub aggression_frequency: StatValue,
+    pub preflop: StreetLine,
+    pub flop: StreetLine,
+    pub turn: StreetLine,
+    pub river: StreetLine,
+}
+
+impl From<&PlayerStats> for StatLine {
+    fn from(stats: &PlayerStats) -> Self {
+        Self {
+            hands: stats.hands,
+            vpip: stats.vpip.into(),
+            pfr: stats.pfr.into(),
+            three_bet: stats.three_bet.into(),
+            fold_to_three_bet: stats.fold_to_three_bet.into(),
+            steal: stats.steal.into(),
+            fold_to_steal: stats.fold_to_steal.into(),
+            wtsd: stats.wtsd.into(),
+            wsd: stats.wsd.into(),
+            aggression_factor: stats.aggression_factor(),
+            aggression_frequency: stats.aggression_frequency().into(),
+            preflop: (&stats.preflop).into(),
+            flop: (&stats.flop).into(),
+            turn: (&stats.turn).into(),
+            river: (&stats.river).into(),
+        }
+    }
+}
+
+/// Every stat for one seat.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerSummary {
+    pub idx: usize,
+    pub overall: StatLine,
+    pub by_position: BTreeMap<Position, StatLine>,
+}
+
+/// A snapshot of `StatsStorage` that can be serialized, or displayed as a
+/// HUD table with one row per seat.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatsSummary {
+    pub players: Vec<PlayerSummary>,
+}
+
+impl fmt::Display for StatsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4} {:>6} {:>4} {:>4} {:>4} {:>4} {:>5} {:>6} {:>4} {:>5} {:>4} {:>4} {:>4} {:>4}",
+            "Seat",
+            "Hands",
+            "VPIP",
+            "PFR",
+            "3B",
+            "F3B",
+            "Steal",
+            "FSteal",
+            "CB",
+            "FCB",
+            "WTSD",
+            "W$SD",
+            "AF",
+            "AFq"
+        )?;
+        for player in &self.players {
+            let line = &player.overall;
+            let af = line
+                .aggression_factor
+                .map_or_else(|| "-".to_string(), |af| format!("{af:.1}"));
+            writeln!(
+                f,
+                "{:>4} {:>6} {:>4} {:>4} {:>4} {:>4} {:>5} {:>6} {:>4} {:>5} {:>4} {:>4} {:>4} {:>4}",
+                player.idx,
+                line.hands,
+                line.vpip,
+                line.pfr,
+                line.three_bet,
+                line.fold_to_three_bet,
+                line.steal,
+                line.fold_to_steal,
+                line.flop.cbet,
+                line.flop.fold_to_cbet,
+                line.wtsd,
+                line.wsd,
+                af,
+                line.aggression_frequency
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// What has happened so far in the current hand.
+#[derive(Debug, Clone, Default)]
+struct HandTracker {
+    vpip: PlayerBitSet,
+    pfr: PlayerBitSet,
+
+    // Preflop raises so far, and who made the first one
+    preflop_raises: usize,
+    opener: Option<usize>,
+    // Someone has called or raised preflop
+    preflop_entered: bool,
+    // Who is trying to steal the blinds, until someone else calls or raises
+    stealer: Option<usize>,
+    three_bet_chance: PlayerBitSet,
+    fold_to_three_bet_chance: PlayerBitSet,
+    steal_chance: PlayerBitSet,
+    fold_to_steal_chance: PlayerBitSet,
+
+    // Last player to bet or raise this street and the street before
+    aggressor: Option<usize>,
+    previous_aggressor: Option<usize>,
+    cbet_chance: PlayerBitSet,
+    // The previous aggressor bet this street and nobody has raised yet.
+    cbet_live: bool,
+    faced_cbet: PlayerBitSet,
+
+    saw_flop: PlayerBitSet,
+    showdown: PlayerBitSet,
+    won_at_showdown: PlayerBitSet,
+}
+
 /// A historian implementation that tracks and stores poker game statistics
 ///
+/// The storage can be shared between many historians, one per hand, to
+/// keep statistics across a whole competition. Seats are tracked by index
+/// so that only makes sense when players keep their seats.
+///
+/// Besides the running totals in `StatsStorage` it keeps the usual HUD
+/// statistics (PFR, 3-bet, c-bet, WTSD, steals and so on) split up by
+/// position and street. `StatsStorage::summary` turns them into
+/// percentages with sample sizes.
+///
 /// # Fields
 /// * `storage` - A reference-counted, mutable reference to the statistics
 ///   storage
 pub struct StatsTrackingHistorian {
     storage: Rc<RefCell<StatsStorage>>,
+    hand: HandTracker,
 }
 
 impl StatsTrackingHistorian {
+    /// A historian that adds to existing storage.
+    pub fn new_with_storage(storage: Rc<RefCell<StatsStorage>>) -> Self {
+        Self {
+            storage,
+            hand: HandTracker::default(),
+        }
+    }
+
     pub fn get_storage(&self) -> Rc<RefCell<StatsStorage>> {
         self.storage.clone()
     }
 
+    fn start_hand(&mut self, game_state: &GameState) -> Result<(), super::HistorianError> {
+        self.hand = HandTracker::default();
+        let mut storage = self.storage.try_borrow_mut()?;
+        storage.ensure_players(game_state.num_players);
+        for idx in game_state.player_active.ones() {
+            let stats = storage.stats_mut(idx, Position::of(game_state, idx));
+            stats.hands += 1;
+            stats.vpip.opportunities += 1;
+            stats.pfr.opportunities += 1;
+        }
+        Ok(())
+    }
+
+    fn advance_street(
+        &mut self,
+        game_state: &GameState,
+        round: Round,
+    ) -> Result<(), super::HistorianError> {
+        let hand = &mut self.hand;
+        hand.previous_aggressor = hand.aggressor.take();
+        hand.cbet_chance = PlayerBitSet::default();
+        hand.cbet_live = false;
+        hand.faced_cbet = PlayerBitSet::default();
+
+        let in_hand = game_state.player_active | game_state.player_all_in;
+        let mut storage = self.storage.try_borrow_mut()?;
+        storage.ensure_players(game_state.num_players);
+        match round {
+            Round::Flop => {
+                hand.saw_flop = in_hand;
+                for idx in in_hand.ones() {
+                    let stats = storage.stats_mut(idx, Position::of(game_state, idx));
+                    stats.wtsd.opportunities += 1;
+                }
+            }
+            Round::Showdown => {
+                hand.showdown = in_hand;
+                for idx in in_hand.ones() {
+                    let saw_flop = hand.saw_flop.get(idx);
+                    let stats = storage.stats_mut(idx, Position::of(game_state, idx));
+                    stats.wsd.opportunities += 1;
+                    if saw_flop {
+                        stats.wtsd.count += 1;
+                    }
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn record_award(
+        &mut self,
+        game_state: &GameState,
+        payload: &AwardPayload,
+    ) -> Result<(), super::HistorianError> {
+        let idx = payload.idx;
+        let hand = &mut self.hand;
+        if payload.award_amount > 0.0 && hand.showdown.get(idx) && !hand.won_at_showdown.get(idx) {
+            hand.won_at_showdown.enable(idx);
+            let mut storage = self.storage.try_borrow_mut()?;
+            storage
+                .stats_mut(idx, Position::of(game_state, idx))
+                .wsd
+                .count += 1;
+        }
+        Ok(())
+    }
+
+    fn record_hand_stats(
+        &mut self,
+        game_state: &GameState,
+        payload: &PlayedActionPayload,
+    ) -> Result<(), super::HistorianError> {
+        let mut storage = self.storage.try_borrow_mut()?;
+        storage.ensure_players(game_state.num_players);
+        let idx = payload.idx;
+        let raised = payload.final_bet > payload.starting_bet;
+        let put_in_money = payload.final_player_bet > payload.starting_player_bet;
+        let folded = payload.action == AgentAction::Fold;
+        let position = Position::of(game_state, idx);
+        let hand = &mut self.hand;
+
+        if payload.round == Round::Preflop {
+            let stats = storage.stats_mut(idx, position);
+            if put_in_money && !hand.vpip.get(idx) {
+                hand.vpip.enable(idx);
+                stats.vpip.count += 1;
+            }
+            if raised && !hand.pfr.get(idx) {
+                hand.pfr.enable(idx);
+                stats.pfr.count += 1;
+            }
+            record_preflop_spots(hand, stats, position, payload);
+        }
+
+        let stats = storage.stats_mut(idx, position);
+        if let Some(street) = stats.street_mut(payload.round) {
+            if raised {
+                street.aggressive += 1;
+            } else if put_in_money {
+                street.calls += 1;
+            } else if folded {
+                street.folds += 1;
+            } else {
+                street.checks += 1;
+            }
+
+            if payload.round != Round::Preflop {
+                if hand.previous_aggressor == Some(idx) {
+                    if payload.starting_bet == 0.0 && !hand.cbet_chance.get(idx) {
+                        hand.cbet_chance.enable(idx);
+                        street.cbet.record(raised);
+                        hand.cbet_live = raised;
+                    }
+                } else if hand.cbet_live && !hand.faced_cbet.get(idx) {
+                    hand.faced_cbet.enable(idx);
+                    street.fold_to_cbet.record(folded);
+                    if raised {
+                        hand.cbet_live = false;
+                    }
+                }
+            }
+        }
+
+        if raised {
+            hand.aggressor = Some(idx);
+        }
+        Ok(())
+    }
+
     fn record_played_action(
         &self,
         games_state: &GameState,
@@ -127,19 +708,68 @@ impl StatsTrackingHistorian {
     }
 
     pub fn new_with_num_players(num_players: usize) -> Self {
-        Self {
-            storage: Rc::new(RefCell::new(StatsStorage::new_with_num_players(
-                num_players,
-            ))),
+        Self::new_with_storage(Rc::new(RefCell::new(StatsStorage::new_with_num_players(
+            num_players,
+        ))))
+    }
+}
+
+/// 3-bets and steals, along with folding to them.
+fn record_preflop_spots(
+    hand: &mut HandTracker,
+    stats: &mut PlayerStats,
+    position: Position,
+    payload: &PlayedActionPayload,
+) {
+    let idx = payload.idx;
+    let raised = payload.final_bet > payload.starting_bet;
+    let put_in_money = payload.final_player_bet > payload.starting_player_bet;
+    let folded = payload.action == AgentAction::Fold;
+
+    if hand.preflop_raises == 1 && hand.opener != Some(idx) && !hand.three_bet_chance.get(idx) {
+        hand.three_bet_chance.enable(idx);
+        stats.three_bet.record(raised);
+    }
+    if hand.preflop_raises == 2
+        && hand.opener == Some(idx)
+        && !hand.fold_to_three_bet_chance.get(idx)
+    {
+        hand.fold_to_three_bet_chance.enable(idx);
+        stats.fold_to_three_bet.record(folded);
+    }
+
+    let can_steal = matches!(position, Position::Late | Position::SmallBlind);
+    let in_blinds = matches!(position, Position::SmallBlind | Position::BigBlind);
+    if !hand.preflop_entered && can_steal && !hand.steal_chance.get(idx) {
+        hand.steal_chance.enable(idx);
+        stats.steal.record(raised);
+        if raised {
+            hand.stealer = Some(idx);
+        }
+    } else if hand.stealer.is_some_and(|stealer| stealer != idx) {
+        if in_blinds && !hand.fold_to_steal_chance.get(idx) {
+            hand.fold_to_steal_chance.enable(idx);
+            stats.fold_to_steal.record(folded);
+        }
+        if put_in_money {
+            hand.stealer = None;
+        }
+    }
+
+    if put_in_money {
+        hand.preflop_entered = true;
+    }
+    if raised {
+        hand.preflop_raises += 1;
+        if hand.opener.is_none() {
+            hand.opener = Some(idx);
         }
     }
 }
 
 impl Default for StatsTrackingHistorian {
     fn default() -> Self {
-        Self {
-            storage: Rc::new(RefCell::new(StatsStorage::default())),
-        }
+        Self::new_with_storage(Rc::new(RefCell::new(StatsStorage::default())))
     }
 }
 
@@ -151,10 +781,19 @@ impl Historian for StatsTrackingHistorian {
         action: Action,
     ) -> Result<(), super::HistorianError> {
         match action {
-            Action::PlayedAction(payload) => self.record_played_action(game_state, payload),
+            Action::RoundAdvance(Round::Preflop) => self.start_hand(game_state),
+            Action::RoundAdvance(
+                round @ (Round::Flop | Round::Turn | Round::River | Round::Showdown),
+            ) => self.advance_street(game_state, round),
+            Action::PlayedAction(payload) => {
+                self.record_hand_stats(game_state, &payload)?;
+                self.record_played_action(game_state, payload)
+            }
             Action::FailedAction(failed_action_payload) => {
+                self.record_hand_stats(game_state, &failed_action_payload.result)?;
                 self.record_played_action(game_state, failed_action_payload.result)
             }
+            Action::Award(payload) => self.record_award(game_state, &payload),
             _ => Ok(()),
         }
     }
@@ -300,4 +939,283 @@ mod tests {
 
         assert_eq!(storage.borrow().raise_count, vec![1, 1]);
     }
+
+    #[test]
+    fn test_preflop_stats_counted() {
+        let hist = Box::new(StatsTrackingHistorian::new_with_num_players(2));
+        let storage = hist.get_storage();
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::<AllInAgent>::default() as Box<dyn Agent>,
+            Box::<FoldingAgent>::default() as Box<dyn Agent>,
+        ];
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        let mut rng = rand::rng();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .historians(vec![hist])
+            .build()
+            .unwrap();
+
+        sim.run(&mut rng);
+
+        let storage = storage.borrow();
+        let (all_in, folder) = (storage.player_stats(0), storage.player_stats(1));
+        assert_eq!((1, 1), (all_in.hands, folder.hands));
+        assert_eq!(Some(1.0), all_in.vpip.frequency());
+        assert_eq!(Some(1.0), all_in.pfr.frequency());
+        assert_eq!(Some(0.0), folder.vpip.frequency());
+        assert_eq!(Some(0.0), folder.pfr.frequency());
+        assert_eq!(None, folder.flop.fold_to_cbet.frequency());
+    }
+
+    #[test]
+    fn test_fold_to_cbet_counted_across_hands() {
+        let hist = StatsTrackingHistorian::new_with_num_players(2);
+        let storage = hist.get_storage();
+        let mut rng = rand::rng();
+
+        for _ in 0..2 {
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::new(VecReplayAgent::new_with_default(
+                    vec![AgentAction::Bet(20.0), AgentAction::Bet(10.0)],
+                    AgentAction::Fold,
+                )),
+                Box::new(VecReplayAgent::new_with_default(
+                    vec![AgentAction::Bet(20.0), AgentAction::Bet(0.0)],
+                    AgentAction::Fold,
+                )),
+            ];
+            let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(StatsTrackingHistorian::new_with_storage(
+                    storage.clone(),
+                ))])
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+        }
+
+        let storage = storage.borrow();
+        let (raiser, caller) = (storage.player_stats(0), storage.player_stats(1));
+        assert_eq!((2, 2), (raiser.hands, caller.hands));
+        assert_eq!((2, 2), (raiser.vpip.count, caller.vpip.count));
+        assert_eq!((2, 0), (raiser.pfr.count, caller.pfr.count));
+        assert_eq!(2, raiser.flop.aggressive);
+        assert_eq!(0, raiser.flop.fold_to_cbet.opportunities);
+        assert_eq!(
+            StatCount {
+                count: 2,
+                opportunities: 2
+            },
+            caller.flop.fold_to_cbet
+        );
+        assert_eq!(Some(2.0), raiser.aggression_factor());
+    }
+
+    #[test]
+    fn test_steal_and_three_bet_counted() {
+        let hist = StatsTrackingHistorian::new_with_num_players(2);
+        let storage = hist.get_storage();
+        let agents: Vec<Box<dyn Agent>> = vec![
+            Box::new(VecReplayAgent::new_with_default(
+                vec![AgentAction::Bet(20.0)],
+                AgentAction::Fold,
+            )),
+            Box::new(VecReplayAgent::new_with_default(
+                vec![AgentAction::Bet(60.0)],
+                AgentAction::Fold,
+            )),
+        ];
+        let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .historians(vec![Box::new(hist)])
+            .build()
+            .unwrap();
+        sim.run(&mut rand::rng());
+
+        let storage = storage.borrow();
+        let button = storage.position_stats(0, Position::Late).unwrap();
+        assert_eq!(1, button.hands);
+        assert_eq!(
+            StatCount {
+                count: 1,
+                opportunities: 1
+            },
+            button.steal
+        );
+        assert_eq!(
+            StatCount {
+                count: 1,
+                opportunities: 1
+            },
+            button.fold_to_three_bet
+        );
+        assert_eq!(StatCount::default(), button.three_bet);
+
+        let big_blind = storage.position_stats(1, Position::BigBlind).unwrap();
+        assert_eq!(
+            StatCount {
+                count: 0,
+                opportunities: 1
+            },
+            big_blind.fold_to_steal
+        );
+        assert_eq!(
+            StatCount {
+                count: 1,
+                opportunities: 1
+            },
+            big_blind.three_bet
+        );
+        assert_eq!(1, big_blind.preflop.aggressive);
+        assert_eq!(None, storage.position_stats(1, Position::Late));
+        assert_eq!(StatCount::default(), storage.player_stats(1).wtsd);
+    }
+
+    #[test]
+    fn test_showdown_and_cbet_by_street() {
+        let storage = Rc::new(RefCell::new(StatsStorage::new_with_num_players(2)));
+        let mut rng = rand::rng();
+
+        for _ in 0..3 {
+            // The button raises and bets the flop and the turn, getting
+            // called every time. Then both check the river.
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::new(VecReplayAgent::new_with_default(
+                    vec![
+                        AgentAction::Bet(20.0),
+                        AgentAction::Bet(10.0),
+                        AgentAction::Bet(20.0),
+                    ],
+                    AgentAction::Bet(0.0),
+                )),
+                Box::new(VecReplayAgent::new_with_default(
+                    vec![
+                        AgentAction::Bet(20.0),
+                        AgentAction::Bet(0.0),
+                        AgentAction::Bet(10.0),
+                        AgentAction::Bet(0.0),
+                        AgentAction::Bet(20.0),
+                    ],
+                    AgentAction::Bet(0.0),
+                )),
+            ];
+            let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(StatsTrackingHistorian::new_with_storage(
+                    storage.clone(),
+                ))])
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+        }
+
+        let storage = storage.borrow();
+        let button = storage.player_stats(0);
+        let big_blind = storage.player_stats(1);
+        assert_eq!(
+            StatCount {
+                count: 3,
+                opportunities: 3
+            },
+            button.flop.cbet
+        );
+        assert_eq!(
+            StatCount {
+                count: 3,
+                opportunities: 3
+            },
+            button.turn.cbet
+        );
+        assert_eq!(
+            StatCount {
+                count: 0,
+                opportunities: 3
+            },
+            big_blind.flop.fold_to_cbet
+        );
+        assert_eq!(
+            StatCount {
+                count: 0,
+                opportunities: 3
+            },
+            big_blind.turn.fold_to_cbet
+        );
+
+        // Everyone checks the river down.
+        for stats in [&button, &big_blind] {
+            assert_eq!(
+                StatCount {
+                    count: 3,
+                    opportunities: 3
+                },
+                stats.wtsd
+            );
+            assert_eq!(3, stats.wsd.opportunities);
+            assert_eq!(3, stats.river.checks);
+        }
+        assert!(button.wsd.count + big_blind.wsd.count >= 3);
+        assert_eq!(Some(6.0), button.aggression_factor());
+        assert_eq!(Some(0.0), big_blind.aggression_factor());
+        assert_eq!(Some(1.0), button.aggression_frequency().frequency());
+    }
+
+    #[test]
+    fn test_summary() {
+        let storage = Rc::new(RefCell::new(StatsStorage::new_with_num_players(2)));
+        let mut rng = rand::rng();
+        for _ in 0..5 {
+            let agents: Vec<Box<dyn Agent>> = vec![
+                Box::<CallingAgent>::default() as Box<dyn Agent>,
+                Box::<CallingAgent>::default() as Box<dyn Agent>,
+            ];
+            let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(StatsTrackingHistorian::new_with_storage(
+                    storage.clone(),
+                ))])
+                .build()
+                .unwrap();
+            sim.run(&mut rng);
+        }
+
+        let summary = storage.borrow().summary();
+        assert_eq!(2, summary.players.len());
+        let line = &summary.players[0].overall;
+        assert_eq!(5, line.hands);
+        assert_eq!(Some(1.0), line.vpip.value);
+        assert_eq!(5, line.vpip.samples);
+        assert_eq!(Some(0.0), line.pfr.value);
+        assert_eq!(Some(1.0), line.wtsd.value);
+        assert_eq!(None, line.three_bet.value);
+        assert_eq!(0, line.flop.cbet.samples);
+        assert_eq!(
+            vec![Position::Late],
+            summary.players[0]
+                .by_position
+                .keys()
+                .copied()
+                .collect::<Vec<_>>()
+        );
+
+        let table = summary.to_string();
+        assert!(table.starts_with("Seat"));
+        assert_eq!(3, table.lines().count());
+
+        #[cfg(feature = "serde")]
+        {
+            let json = serde_json::to_string(&summary).unwrap();
+            let back: StatsSummary = serde_json::from_str(&json).unwrap();
+            assert_eq!(summary, back);
+        }
+    }
 }
@@ -120,14 +120,21 @@
 //! ```
 //! use rs_poker::arena::cfr::CFRAgent;
 //! ```
+pub mod acpc;
 pub mod action;
 pub mod agent;
 pub mod cfr;
 pub mod competition;
 pub mod errors;
 pub mod game_state;
+pub mod hand_log;
+pub mod hand_query;
 pub mod historian;
+pub mod player_view;
+pub mod rating;
 pub mod replay_game;
+#[cfg(feature = "open-hand-history")]
+pub mod replay_open_hand_history;
 pub mod replay_tournament;
 pub mod sim_builder;
 pub mod simulation;
@@ -136,9 +143,14 @@ pub mod simulation;
 pub mod test_util;
 
 pub use agent::{Agent, AgentGenerator, CloneAgentGenerator};
-pub use game_state::{CloneGameStateGenerator, GameState, GameStateGenerator};
+pub use game_state::{CloneGameStateGenerator, GameState, GameStateGenerator, GameType};
 pub use historian::{CloneHistorianGenerator, Historian, HistorianError, HistorianGenerator};
+pub use player_view::PlayerView;
 pub use replay_game::{GameReplay, ReplayError, ReplaySimulationBuilder};
+#[cfg(feature = "open-hand-history")]
+pub use replay_open_hand_history::{
+    ImportError, ImportedHand, import_hands, import_pokerstars_hands,
+};
 pub use replay_tournament::{
     ReplayTournamentBuilder, TournamentHistorian, TournamentReplay, TournamentReplayData,
 };
@@ -0,0 +1,282 @@
+use std::ops::Deref;
+
+use rand::Rng;
+
+use crate::core::{Card, Deck, Hand};
+
+use super::{action::Action, game_state::GameState};
+
+/// Everything a single seat at the table is allowed to know.
+///
+/// This is what agents are given when it's their turn to act. Other players'
+/// hole cards are removed from both the game state and the action history,
+/// so an agent can't cheat by looking at them. The board, stacks, bets, and
+/// pot are all public and are left as is.
+///
+/// `PlayerView` derefs to the redacted `GameState` so agents can use all the
+/// usual helpers like `current_round_bet`.
+///
+/// Tools that need the full game state (for example CFR, which simulates
+/// every possible future) can opt in to an omniscient view by overriding
+/// `Agent::omniscient`.
+///
+/// # Example
+///
+/// ```
+/// use rs_poker::arena::{GameState, PlayerView};
+/// use rs_poker::core::{Card, Suit, Value};
+///
+/// let mut game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+/// game_state.hands[0].insert(Card::new(Value::Ace, Suit::Spade));
+/// game_state.hands[1].insert(Card::new(Value::King, Suit::Spade));
+///
+/// let view = PlayerView::new(0, &game_state, &[]);
+/// assert_eq!(vec![Card::new(Value::Ace, Suit::Spade)], view.hole_cards());
+/// assert!(view.hands[1].is_empty());
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlayerView {
+    player_idx: usize,
+    game_state: GameState,
+    actions: Vec<Action>,
+    omniscient: bool,
+}
+
+impl PlayerView {
+    /// Create the view for `player_idx` hiding every other player's hole
+    /// cards.
+    pub fn new(player_idx: usize, game_state: &GameState, actions: &[Action]) -> Self {
+        let mut game_state = game_state.clone();
+        let board = Hand::new_with_cards(game_state.board.clone());
+        for (idx, hand) in game_state.hands.iter_mut().enumerate() {
+            if idx != player_idx {
+                *hand = board;
+            }
+        }
+
+        let actions = actions
+            .iter()
+            .filter(|action| match action {
+                Action::DealStartingHand(payload) => payload.idx == player_idx,
+                _ => true,
+            })
+            .cloned()
+            .collect();
+
+        Self {
+            player_idx,
+            game_state,
+            actions,
+            omniscient: false,
+        }
+    }
+
+    /// Create a view for `player_idx` that can see everything, including the
+    /// other players' hole cards.
+    pub fn omniscient(player_idx: usize, game_state: &GameState, actions: &[Action]) -> Self {
+        Self {
+            player_idx,
+            game_state: game_state.clone(),
+            actions: actions.to_vec(),
+            omniscient: true,
+        }
+    }
+
+    /// The seat this view is for.
+    pub fn player_idx(&self) -> usize {
+        self.player_idx
+    }
+
+    /// Can this view see other players' hole cards.
+    pub fn is_omniscient(&self) -> bool {
+        self.omniscient
+    }
+
+    /// The game state as seen by this seat.
+    pub fn game_state(&self) -> &GameState {
+        &self.game_state
+    }
+
+    /// This seat's own hole cards.
+    pub fn hole_cards(&self) -> Vec<Card> {
+        self.game_state.hole_cards(self.player_idx)
+    }
+
+    /// The community cards.
+    pub fn board(&self) -> &[Card] {
+        &self.game_state.board
+    }
+
+    /// Every player's stack.
+    pub fn stacks(&self) -> &[f32] {
+        &self.game_state.stacks
+    }
+
+    /// How much every player has put into the pot this hand.
+    pub fn player_bets(&self) -> &[f32] {
+        &self.game_state.player_bet
+    }
+
+    /// The total amount in all pots.
+    pub fn total_pot(&self) -> f32 {
+        self.game_state.total_pot
+    }
+
+    /// The actions that have happened so far this hand.
+    pub fn actions(&self) -> &[Action] {
+        &self.actions
+    }
+
+    /// A copy of the game state with a guess at every hidden hand.
+    ///
+    /// Each other player still in the hand is dealt random hole cards from
+    /// the cards this seat can't see. Omniscient views have nothing to
+    /// guess and get the game state as it is.
+    pub fn sample_game_state<R: Rng>(&self, rng: &mut R) -> GameState {
+        let mut game_state = self.game_state.clone();
+        if self.omniscient {
+            return game_state;
+        }
+        let mut deck = Deck::default();
+        for hand in &game_state.hands {
+            for card in hand.iter() {
+                deck.remove(&card);
+            }
+        }
+
+        let num_hole_cards = game_state.game_type.num_hole_cards();
+        let in_hand = game_state.player_active | game_state.player_all_in;
+        for idx in in_hand.ones().filter(|&idx| idx != self.player_idx) {
+            for _ in 0..num_hole_cards {
+                if let Some(card) = deck.deal(rng) {
+                    game_state.hands[idx].insert(card);
+                }
+            }
+        }
+        game_state
+    }
+}
+
+impl Deref for PlayerView {
+    type Target = GameState;
+
+    fn deref(&self) -> &Self::Target {
+        &self.game_state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use crate::arena::{Agent, HoldemSimulationBuilder, action::AgentAction};
+
+    use super::*;
+
+    /// Agent that remembers every view it was given.
+    #[derive(Clone)]
+    struct PeekingAgent {
+        omniscient: bool,
+        views: Rc<RefCell<Vec<PlayerView>>>,
+    }
+
+    impl Agent for PeekingAgent {
+        fn act(&mut self, _id: u128, view: &PlayerView) -> AgentAction {
+            self.views.borrow_mut().push(view.clone());
+            AgentAction::Call
+        }
+
+        fn omniscient(&self) -> bool {
+            self.omniscient
+        }
+    }
+
+    fn run_peeking(omniscient: bool) -> (Vec<PlayerView>, GameState) {
+        let views = Rc::new(RefCell::new(vec![]));
+        let agents: Vec<Box<dyn Agent>> = (0..3)
+            .map(|_| {
+                Box::new(PeekingAgent {
+                    omniscient,
+                    views: views.clone(),
+                }) as Box<dyn Agent>
+            })
+            .collect();
+        let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .build()
+            .unwrap();
+        let mut rng = rand::rng();
+        sim.run(&mut rng);
+
+        let views = views.borrow().clone();
+        (views, sim.game_state)
+    }
+
+    #[test]
+    fn test_agents_cant_see_other_hands() {
+        let (views, final_state) = run_peeking(false);
+        assert!(!views.is_empty());
+
+        for view in views {
+            let me = view.player_idx();
+            assert!(!view.is_omniscient());
+            assert_eq!(final_state.hole_cards(me), view.hole_cards());
+            for idx in (0..3).filter(|idx| *idx != me) {
+                assert!(view.game_state().hole_cards(idx).is_empty());
+            }
+            let dealt: Vec<usize> = view
+                .actions()
+                .iter()
+                .filter_map(|action| match action {
+                    Action::DealStartingHand(payload) => Some(payload.idx),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(vec![me, me], dealt);
+        }
+    }
+
+    #[test]
+    fn test_sample_game_state() {
+        let mut game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 0.0, 0);
+        game_state.hands[0] = Hand::new_from_str("AsAh").unwrap();
+        game_state.hands[1] = Hand::new_from_str("KsKh").unwrap();
+        game_state.hands[2] = Hand::new_from_str("QsQh").unwrap();
+        game_state.player_active.disable(2);
+        let view = PlayerView::new(0, &game_state, &[]);
+
+        let mut rng = rand::rng();
+        for _ in 0..20 {
+            let sampled = view.sample_game_state(&mut rng);
+            assert_eq!(view.hole_cards(), sampled.hole_cards(0));
+            // The folded player doesn't need a hand.
+            assert!(sampled.hole_cards(2).is_empty());
+
+            let guess = sampled.hole_cards(1);
+            assert_eq!(2, guess.len());
+            assert!(guess.iter().all(|card| !sampled.hands[0].contains(card)));
+        }
+
+        let omniscient = PlayerView::omniscient(0, &game_state, &[]);
+        assert_eq!(game_state, omniscient.sample_game_state(&mut rng));
+    }
+
+    #[test]
+    fn test_omniscient_agents_see_everything() {
+        let (views, final_state) = run_peeking(true);
+        assert!(!views.is_empty());
+
+        for view in views {
+            assert!(view.is_omniscient());
+            for idx in 0..3 {
+                assert_eq!(
+                    final_state.hole_cards(idx),
+                    view.game_state().hole_cards(idx)
+                );
+            }
+        }
+    }
+}
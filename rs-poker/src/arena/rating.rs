@@ -0,0 +1,464 @@
+//! Ratings for agents (or models) across many tournaments and competitions.
+//!
+//! Each result is a [`MatchResult`]: the place every player finished in.
+//! They can come from a `SingleTableTournament`, from the chip results of
+//! a `HoldemCompetition` or `DuplicateCompetition`, or from anything else
+//! that ranks players by name. [`Ratings`] folds them into one rating per
+//! name, with how uncertain that rating still is, and ranks everyone on a
+//! leaderboard.
+//!
+//! ```
+//! use rs_poker::arena::rating::{MatchResult, RatingSystem, Ratings};
+//!
+//! let mut ratings = Ratings::new(RatingSystem::default());
+//! for _ in 0..10 {
+//!     ratings.record(&MatchResult::from_places([
+//!         ("gpt", 1),
+//!         ("claude", 2),
+//!         ("random", 3),
+//!     ]));
+//! }
+//!
+//! let leaderboard = ratings.leaderboard();
+//! assert_eq!("gpt", leaderboard[0].name);
+//! assert_eq!("random", leaderboard[2].name);
+//! ```
+use std::collections::BTreeMap;
+use std::fmt;
+
+use super::competition::{DuplicateResults, TournamentResults};
+
+/// How ratings are updated after each result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum RatingSystem {
+    /// Multiplayer Elo. Every pair of players in a result counts as one
+    /// game, with the swing split over the number of opponents. Elo doesn't
+    /// track uncertainty so `sigma` is always zero.
+    Elo { initial_rating: f64, k_factor: f64 },
+    /// A TrueSkill style Bayesian rating, using the Weng-Lin Bradley-Terry
+    /// update which handles any number of players in closed form. The
+    /// leaderboard ranks by `mu - 3 * sigma`, so new players have to prove
+    /// themselves.
+    TrueSkill {
+        mu: f64,
+        sigma: f64,
+        /// How far apart two ratings are when the better player wins about
+        /// 76% of the time.
+        beta: f64,
+        /// Uncertainty added before every game so ratings can keep moving.
+        tau: f64,
+    },
+}
+
+impl RatingSystem {
+    /// The usual Elo constants, starting at 1500 with a K factor of 32.
+    pub fn elo() -> Self {
+        RatingSystem::Elo {
+            initial_rating: 1500.0,
+            k_factor: 32.0,
+        }
+    }
+
+    /// The usual TrueSkill constants, starting at 25 +/- 25/3.
+    pub fn true_skill() -> Self {
+        let mu = 25.0;
+        let sigma = mu / 3.0;
+        RatingSystem::TrueSkill {
+            mu,
+            sigma,
+            beta: sigma / 2.0,
+            tau: sigma / 100.0,
+        }
+    }
+
+    fn initial(&self) -> Rating {
+        match *self {
+            RatingSystem::Elo { initial_rating, .. } => Rating {
+                mu: initial_rating,
+                sigma: 0.0,
+                games: 0,
+            },
+            RatingSystem::TrueSkill { mu, sigma, .. } => Rating {
+                mu,
+                sigma,
+                games: 0,
+            },
+        }
+    }
+}
+
+impl Default for RatingSystem {
+    fn default() -> Self {
+        Self::true_skill()
+    }
+}
+
+/// One player's rating.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Rating {
+    /// The estimated skill.
+    pub mu: f64,
+    /// How uncertain `mu` still is.
+    pub sigma: f64,
+    /// How many results the player has been in.
+    pub games: usize,
+}
+
+impl Rating {
+    /// A rating the player is very likely to be at least as good as.
+    pub fn conservative(&self) -> f64 {
+        self.mu - 3.0 * self.sigma
+    }
+}
+
+/// Where every player finished in one tournament or competition. Lower
+/// places are better and players can share a place.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatchResult {
+    pub places: Vec<(String, usize)>,
+}
+
+impl MatchResult {
+    pub fn from_places<S: Into<String>>(places: impl IntoIterator<Item = (S, usize)>) -> Self {
+        Self {
+            places: places
+                .into_iter()
+                .map(|(name, place)| (name.into(), place))
+                .collect(),
+        }
+    }
+
+    /// Rank players by score, for example chips won, with the highest score
+    /// in first place. Equal scores share a place.
+    pub fn from_scores<S: Into<String>>(scores: impl IntoIterator<Item = (S, f32)>) -> Self {
+        let scores: Vec<(String, f32)> = scores
+            .into_iter()
+            .map(|(name, score)| (name.into(), score))
+            .collect();
+        let places = scores
+            .iter()
+            .map(|(name, score)| {
+                let better = scores.iter().filter(|(_, other)| other > score).count();
+                (name.clone(), better + 1)
+            })
+            .collect();
+        Self { places }
+    }
+
+    /// The places from a single table tournament. `names` are in the same
+    /// order as the agent generators.
+    pub fn from_tournament<S: AsRef<str>>(results: &TournamentResults, names: &[S]) -> Self {
+        Self::from_places(
+            names
+                .iter()
+                .zip(results.places())
+                .map(|(name, place)| (name.as_ref(), *place)),
+        )
+    }
+
+    /// Rank agents by the total chips they won in a `HoldemCompetition`,
+    /// `total_change` or one of the `estimated_change` totals.
+    pub fn from_changes<S: AsRef<str>>(changes: &[f32], names: &[S]) -> Self {
+        Self::from_scores(
+            names
+                .iter()
+                .zip(changes)
+                .map(|(name, change)| (name.as_ref(), *change)),
+        )
+    }
+
+    /// Rank agents by their results in a `DuplicateCompetition`.
+    pub fn from_duplicate<S: AsRef<str>>(results: &DuplicateResults, names: &[S]) -> Self {
+        Self::from_scores(
+            names
+                .iter()
+                .zip(&results.agents)
+                .map(|(name, agent)| (name.as_ref(), agent.total_change)),
+        )
+    }
+}
+
+/// A row of the leaderboard.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeaderboardEntry {
+    /// Starting from 1
+    pub rank: usize,
+    pub name: String,
+    /// What the leaderboard is sorted by, `mu - 3 * sigma`.
+    pub score: f64,
+    pub rating: Rating,
+}
+
+/// The ratings of everyone seen so far.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ratings {
+    pub system: RatingSystem,
+    pub players: BTreeMap<String, Rating>,
+}
+
+impl Ratings {
+    pub fn new(system: RatingSystem) -> Self {
+        Self {
+            system,
+            players: BTreeMap::new(),
+        }
+    }
+
+    /// A player's rating, or the starting rating if they haven't played.
+    pub fn rating(&self, name: &str) -> Rating {
+        self.players
+            .get(name)
+            .copied()
+            .unwrap_or_else(|| self.system.initial())
+    }
+
+    /// Update everyone in the result. Results with fewer than two players
+    /// don't say anything about skill and are ignored.
+    pub fn record(&mut self, result: &MatchResult) {
+        if result.places.len() < 2 {
+            return;
+        }
+        let before: Vec<Rating> = result
+            .places
+            .iter()
+            .map(|(name, _)| self.rating(name))
+            .collect();
+        let places: Vec<usize> = result.places.iter().map(|(_, place)| *place).collect();
+
+        let after = match self.system {
+            RatingSystem::Elo { k_factor, .. } => elo_update(&before, &places, k_factor),
+            RatingSystem::TrueSkill { beta, tau, .. } => {
+                bradley_terry_update(&before, &places, beta, tau)
+            }
+        };
+
+        for ((name, _), rating) in result.places.iter().zip(after) {
+            self.players.insert(name.clone(), rating);
+        }
+    }
+
+    /// Record every result in order.
+    pub fn record_all<'a>(&mut self, results: impl IntoIterator<Item = &'a MatchResult>) {
+        for result in results {
+            self.record(result);
+        }
+    }
+
+    /// Everyone sorted from best to worst.
+    pub fn leaderboard(&self) -> Vec<LeaderboardEntry> {
+        let mut players: Vec<(&String, &Rating)> = self.players.iter().collect();
+        players.sort_by(|a, b| b.1.conservative().total_cmp(&a.1.conservative()));
+        players
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (name, rating))| LeaderboardEntry {
+                rank: idx + 1,
+                name: name.clone(),
+                score: rating.conservative(),
+                rating: *rating,
+            })
+            .collect()
+    }
+
+    /// Load ratings saved with `save`.
+    #[cfg(feature = "serde")]
+    pub fn load(path: impl AsRef<std::path::Path>) -> Result<Self, RatingError> {
+        let file = std::fs::File::open(path)?;
+        Ok(serde_json::from_reader(std::io::BufReader::new(file))?)
+    }
+
+    /// Load ratings if the file exists, otherwise start new ones.
+    #[cfg(feature = "serde")]
+    pub fn load_or_new(
+        path: impl AsRef<std::path::Path>,
+        system: RatingSystem,
+    ) -> Result<Self, RatingError> {
+        if path.as_ref().exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::new(system))
+        }
+    }
+
+    /// Save the ratings as pretty printed JSON.
+    #[cfg(feature = "serde")]
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> Result<(), RatingError> {
+        let file = std::fs::File::create(path)?;
+        serde_json::to_writer_pretty(std::io::BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+impl fmt::Display for Ratings {
+    /// The leaderboard as a table.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "{:>4}  {:<24} {:>9} {:>9} {:>7} {:>6}",
+            "Rank", "Name", "Score", "Mu", "Sigma", "Games"
+        )?;
+        for entry in self.leaderboard() {
+            writeln!(
+                f,
+                "{:>4}  {:<24} {:>9.2} {:>9.2} {:>7.2} {:>6}",
+                entry.rank,
+                entry.name,
+                entry.score,
+                entry.rating.mu,
+                entry.rating.sigma,
+                entry.rating.games
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Debug, thiserror::Error)]
+pub enum RatingError {
+    #[error("Unable to read or write ratings")]
+    Io(#[from] std::io::Error),
+    #[error("Unable to parse ratings")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 1 for a win, 0.5 for a tie, 0 for a loss.
+fn pair_score(place: usize, other_place: usize) -> f64 {
+    match place.cmp(&other_place) {
+        std::cmp::Ordering::Less => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Greater => 0.0,
+    }
+}
+
+fn elo_update(before: &[Rating], places: &[usize], k_factor: f64) -> Vec<Rating> {
+    let opponents = (before.len() - 1) as f64;
+    before
+        .iter()
+        .enumerate()
+        .map(|(i, rating)| {
+            let delta: f64 = before
+                .iter()
+                .enumerate()
+                .filter(|(q, _)| *q != i)
+                .map(|(q, other)| {
+                    let expected = 1.0 / (1.0 + 10f64.powf((other.mu - rating.mu) / 400.0));
+                    pair_score(places[i], places[q]) - expected
+                })
+                .sum();
+            Rating {
+                mu: rating.mu + k_factor * delta / opponents,
+                sigma: rating.sigma,
+                games: rating.games + 1,
+            }
+        })
+        .collect()
+}
+
+/// Weng and Lin, "A Bayesian Approximation Method for Online Ranking",
+/// algorithm 1 with every player on their own team.
+fn bradley_terry_update(before: &[Rating], places: &[usize], beta: f64, tau: f64) -> Vec<Rating> {
+    // Smallest fraction sigma squared can shrink by in one update.
+    const KAPPA: f64 = 0.0001;
+
+    let variances: Vec<f64> = before
+        .iter()
+        .map(|rating| rating.sigma * rating.sigma + tau * tau)
+        .collect();
+    before
+        .iter()
+        .enumerate()
+        .map(|(i, rating)| {
+            let mut omega = 0.0;
+            let mut delta = 0.0;
+            for (q, other) in before.iter().enumerate().filter(|(q, _)| *q != i) {
+                let c = (variances[i] + variances[q] + 2.0 * beta * beta).sqrt();
+                let p = 1.0 / (1.0 + ((other.mu - rating.mu) / c).exp());
+                let share = variances[i] / c;
+                omega += share * (pair_score(places[i], places[q]) - p);
+                let gamma = variances[i].sqrt() / c;
+                delta += gamma * share / c * p * (1.0 - p);
+            }
+            Rating {
+                mu: rating.mu + omega,
+                sigma: (variances[i] * (1.0 - delta).max(KAPPA)).sqrt(),
+                games: rating.games + 1,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_scores_shares_places() {
+        let result = MatchResult::from_scores([("a", 10.0), ("b", -5.0), ("c", 10.0)]);
+        assert_eq!(
+            vec![
+                ("a".to_string(), 1),
+                ("b".to_string(), 3),
+                ("c".to_string(), 1)
+            ],
+            result.places
+        );
+    }
+
+    #[test]
+    fn test_true_skill_orders_and_shrinks_uncertainty() {
+        let mut ratings = Ratings::new(RatingSystem::true_skill());
+        let result = MatchResult::from_places([("a", 1), ("b", 2), ("c", 3), ("d", 4)]);
+        for _ in 0..20 {
+            ratings.record(&result);
+        }
+
+        let names: Vec<_> = ratings.leaderboard().into_iter().map(|e| e.name).collect();
+        assert_eq!(vec!["a", "b", "c", "d"], names);
+        let a = ratings.rating("a");
+        assert_eq!(20, a.games);
+        assert!(a.sigma < 25.0 / 3.0);
+        assert!(a.mu > 25.0 && ratings.rating("d").mu < 25.0);
+    }
+
+    #[test]
+    fn test_elo_is_zero_sum() {
+        let mut ratings = Ratings::new(RatingSystem::elo());
+        ratings.record(&MatchResult::from_places([("a", 1), ("b", 2), ("c", 2)]));
+
+        let total: f64 = ratings.players.values().map(|r| r.mu).sum();
+        approx::assert_relative_eq!(4500.0, total, epsilon = 1e-9);
+        // Beating two equal players with K 32.
+        approx::assert_relative_eq!(1516.0, ratings.rating("a").mu, epsilon = 1e-9);
+        assert_eq!(ratings.rating("b"), ratings.rating("c"));
+        assert_eq!(0.0, ratings.rating("a").sigma);
+    }
+
+    #[test]
+    fn test_single_player_results_are_ignored() {
+        let mut ratings = Ratings::default();
+        ratings.record(&MatchResult::from_places([("a", 1)]));
+        assert!(ratings.players.is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("ratings.json");
+
+        let mut ratings = Ratings::load_or_new(&path, RatingSystem::elo()).unwrap();
+        ratings.record(&MatchResult::from_changes(&[3.0, -3.0], &["x", "y"]));
+        ratings.save(&path).unwrap();
+
+        let loaded = Ratings::load(&path).unwrap();
+        assert_eq!(ratings, loaded);
+        assert!(loaded.to_string().contains("x"));
+    }
+}
@@ -2,9 +2,10 @@ use crate::arena::{
     Agent, GameState, HoldemSimulation, HoldemSimulationBuilder,
     action::Action,
     action::{
-        AwardPayload, DealStartingHandPayload, ForcedBetPayload, PlayedActionPayload,
-        PlayerSitPayload,
+        AgentAction, AwardPayload, DealStartingHandPayload, ForcedBetPayload, ForcedBetType,
+        PlayedActionPayload, PlayerSitPayload,
     },
+    game_state::Round,
 };
 use crate::core::{Card, Deck};
 use thiserror::Error;
@@ -19,6 +20,10 @@ pub enum ReplayError {
     GameStateInconsistency,
     #[error("Missing initial game state")]
     MissingInitialGameState,
+    #[error("Can't fork part way through dealing")]
+    ForkMidDeal,
+    #[error("Can't fork part way through awarding the pots")]
+    ForkMidAward,
 }
 
 /// A system for replaying poker games from recorded actions.
@@ -127,6 +132,101 @@ impl GameReplay {
         Ok(())
     }
 
+    /// Undo the last applied action, returning it. Returns `None` at the
+    /// start of the replay.
+    ///
+    /// Actions can't be unapplied, so this replays from the start up to the
+    /// action before.
+    pub fn step_back(&mut self) -> Result<Option<Action>, ReplayError> {
+        if self.current_action_index == 0 {
+            return Ok(None);
+        }
+        let action = self.actions[self.current_action_index - 1].clone();
+        self.step_to(self.current_action_index - 1)?;
+        Ok(Some(action))
+    }
+
+    /// Fork the hand at the current action into a live simulation played by
+    /// `agents`.
+    ///
+    /// The simulation picks up exactly where the replay is: the same hole
+    /// cards and board, with the cards not dealt yet as the deck, and the
+    /// actions so far in its history. From there it plays out with the new
+    /// agents and fresh cards. To see how a different action plays out, fork
+    /// just before it and pass the action to
+    /// [`HoldemSimulation::run_agent_action`] before running the rest.
+    ///
+    /// ```
+    /// use rand::{SeedableRng, rngs::StdRng};
+    /// use rs_poker::arena::action::{Action, AgentAction};
+    /// use rs_poker::arena::agent::CallingAgent;
+    /// use rs_poker::arena::{Agent, GameReplay, GameState, HoldemSimulationBuilder};
+    ///
+    /// let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+    /// let mut sim = HoldemSimulationBuilder::default()
+    ///     .game_state(game_state.clone())
+    ///     .agents(vec![Box::new(CallingAgent), Box::new(CallingAgent)])
+    ///     .build()
+    ///     .unwrap();
+    /// sim.run(&mut StdRng::seed_from_u64(7));
+    ///
+    /// // What if the first player to act had shoved instead?
+    /// let first_decision = sim
+    ///     .actions
+    ///     .iter()
+    ///     .position(|action| matches!(action, Action::PlayedAction(_)))
+    ///     .unwrap();
+    /// let mut replay = GameReplay::new(game_state, sim.actions.clone());
+    /// replay.step_to(first_decision).unwrap();
+    ///
+    /// let agents: Vec<Box<dyn Agent>> = vec![Box::new(CallingAgent), Box::new(CallingAgent)];
+    /// let mut what_if = replay.fork(agents).unwrap();
+    /// what_if.run_agent_action(AgentAction::AllIn);
+    /// what_if.run(&mut StdRng::seed_from_u64(8));
+    ///
+    /// assert!(what_if.game_state.is_complete());
+    /// assert_eq!(sim.actions[..first_decision], what_if.actions[..first_decision]);
+    /// assert!(matches!(
+    ///     &what_if.actions[first_decision],
+    ///     Action::PlayedAction(played) if played.final_player_bet == 100.0
+    /// ));
+    /// ```
+    pub fn fork(&self, agents: Vec<Box<dyn Agent>>) -> Result<HoldemSimulation, ReplayError> {
+        self.fork_builder()?
+            .agents(agents)
+            .build()
+            .map_err(|_| ReplayError::GameStateInconsistency)
+    }
+
+    /// A simulation builder with the game state, deck and history of a fork
+    /// at the current action, for adding agents and historians to.
+    pub fn fork_builder(&self) -> Result<HoldemSimulationBuilder, ReplayError> {
+        let played = &self.actions[..self.current_action_index];
+        if splits_deal(played.last(), self.actions.get(self.current_action_index)) {
+            return Err(ReplayError::ForkMidDeal);
+        }
+        // The simulation awards every pot at once, so it would award them all
+        // again.
+        if matches!(played.last(), Some(Action::Award(_))) {
+            return Err(ReplayError::ForkMidAward);
+        }
+
+        // The replay's own state is only good for looking at, so play the
+        // actions through a game state the way the simulation did.
+        let mut game_state = self.initial_state.clone();
+        for action in played {
+            play_live(&mut game_state, action)?;
+        }
+        // Seats are taken as the game starts.
+        if game_state.round == Round::Starting && !played.is_empty() {
+            return Err(ReplayError::ForkMidDeal);
+        }
+
+        Ok(HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .actions(played.to_vec()))
+    }
+
     /// Reset to the initial state
     pub fn reset_to_start(&mut self) {
         self.current_state = self.initial_state.clone();
@@ -229,6 +329,98 @@ impl GameReplay {
     }
 }
 
+/// Would forking between `last` and `next` split up the cards of a deal?
+///
+/// A street's community cards are dealt together and only then does the
+/// round advance, so forking after any of them would deal the street again.
+fn splits_deal(last: Option<&Action>, next: Option<&Action>) -> bool {
+    match (last, next) {
+        (Some(Action::DealCommunity(_)), _) => true,
+        (Some(Action::DealStartingHand(last)), Some(Action::DealStartingHand(next))) => {
+            last.idx == next.idx
+        }
+        _ => false,
+    }
+}
+
+/// Apply an action to a game state with the same transitions the
+/// simulation made when it recorded the action.
+fn play_live(game_state: &mut GameState, action: &Action) -> Result<(), ReplayError> {
+    let check_to_act = |game_state: &GameState, idx: usize| {
+        if game_state.to_act_idx() == idx {
+            Ok(())
+        } else {
+            Err(ReplayError::InvalidActionSequence)
+        }
+    };
+    let bet = |game_state: &mut GameState, amount: f32, is_forced: bool| {
+        game_state
+            .do_bet(amount, is_forced)
+            .map(|_| ())
+            .map_err(|_| ReplayError::GameStateInconsistency)
+    };
+
+    match action {
+        Action::GameStart(_) => {}
+        Action::PlayerSit(PlayerSitPayload { idx, .. }) => {
+            check_to_act(game_state, *idx)?;
+            game_state.round_data.needs_action.disable(*idx);
+            game_state.round_data.advance_action();
+        }
+        Action::RoundAdvance(round) => {
+            if *round == Round::Complete {
+                game_state.complete();
+            } else {
+                game_state.advance_round();
+            }
+            if game_state.round != *round {
+                return Err(ReplayError::InvalidActionSequence);
+            }
+        }
+        Action::ForcedBet(payload) => {
+            check_to_act(game_state, payload.idx)?;
+            bet(game_state, payload.bet, true)?;
+            match payload.forced_bet_type {
+                ForcedBetType::Ante => game_state.round_data.needs_action.disable(payload.idx),
+                ForcedBetType::SmallBlind => game_state.sb_posted = true,
+                ForcedBetType::BigBlind => game_state.bb_posted = true,
+            }
+        }
+        Action::DealStartingHand(DealStartingHandPayload { card, idx }) => {
+            check_to_act(game_state, *idx)?;
+            game_state.hands[*idx].insert(*card);
+            if game_state.hands[*idx].count() == game_state.game_type.num_hole_cards() {
+                game_state.round_data.needs_action.disable(*idx);
+                game_state.round_data.advance_action();
+            }
+        }
+        Action::DealCommunity(card) => {
+            game_state.board.push(*card);
+            for hand in &mut game_state.hands {
+                hand.insert(*card);
+            }
+        }
+        Action::PlayedAction(payload) => {
+            check_to_act(game_state, payload.idx)?;
+            match payload.action {
+                AgentAction::Fold => game_state.fold(),
+                _ => bet(game_state, payload.final_player_bet, false)?,
+            }
+        }
+        Action::FailedAction(payload) => {
+            check_to_act(game_state, payload.result.idx)?;
+            match payload.result.action {
+                AgentAction::Fold => game_state.fold(),
+                _ => bet(game_state, payload.result.final_player_bet, false)?,
+            }
+        }
+        Action::Award(payload) => {
+            game_state.award(payload.idx, payload.award_amount);
+        }
+    }
+    Ok(())
+}
+
 /// Create a simulation that will replay exact cards from recorded actions
 pub struct ReplaySimulationBuilder {
     actions: Vec<Action>,
@@ -329,7 +521,7 @@ mod tests {
     use crate::arena::{
         HoldemSimulationBuilder,
         action::{AgentAction, GameStartPayload},
-        agent::{Agent, VecReplayAgent},
+        agent::{Agent, CallingAgent, VecReplayAgent},
         historian::VecHistorian,
     };
     use crate::core::{Card, Suit, Value};
@@ -701,4 +893,131 @@ mod tests {
             panic!("Expected MissingInitialGameState error");
         }
     }
+
+    fn calling_game(num_players: usize, seed: u64) -> (GameState, Vec<Action>) {
+        let game_state = GameState::new_starting(vec![100.0; num_players], 10.0, 5.0, 1.0, 0);
+        let agents: Vec<Box<dyn Agent>> = (0..num_players)
+            .map(|_| -> Box<dyn Agent> { Box::new(CallingAgent) })
+            .collect();
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state.clone())
+            .agents(agents)
+            .build()
+            .unwrap();
+        sim.run(&mut StdRng::seed_from_u64(seed));
+        (game_state, sim.actions)
+    }
+
+    fn calling_agents(num_players: usize) -> Vec<Box<dyn Agent>> {
+        (0..num_players)
+            .map(|_| -> Box<dyn Agent> { Box::new(CallingAgent) })
+            .collect()
+    }
+
+    #[test]
+    fn test_step_back() {
+        let (game_state, actions) = calling_game(3, 1);
+        let mut replay = GameReplay::new(game_state.clone(), actions.clone());
+        replay.step_to(actions.len()).unwrap();
+
+        for idx in (0..actions.len()).rev() {
+            assert_eq!(Some(actions[idx].clone()), replay.step_back().unwrap());
+            assert_eq!(idx, replay.get_current_action_index());
+
+            let mut expected = GameReplay::new(game_state.clone(), actions.clone());
+            expected.step_to(idx).unwrap();
+            assert_eq!(expected.get_current_state(), replay.get_current_state());
+        }
+        assert_eq!(None, replay.step_back().unwrap());
+    }
+
+    #[test]
+    fn test_fork_everywhere() {
+        for seed in 0..5 {
+            let num_players = 2 + seed as usize;
+            let (game_state, actions) = calling_game(num_players, seed);
+            let mut replay = GameReplay::new(game_state, actions.clone());
+            let river = actions
+                .iter()
+                .rposition(|action| matches!(action, Action::DealCommunity(_)))
+                .unwrap();
+
+            for idx in 0..=actions.len() {
+                replay.step_to(idx).unwrap();
+                let mut sim = match replay.fork(calling_agents(num_players)) {
+                    Ok(sim) => sim,
+                    Err(ReplayError::ForkMidDeal | ReplayError::ForkMidAward) => continue,
+                    Err(error) => panic!("Can't fork at {idx}: {error}"),
+                };
+                let dealt = actions[..idx]
+                    .iter()
+                    .filter(|action| {
+                        matches!(
+                            action,
+                            Action::DealStartingHand(_) | Action::DealCommunity(_)
+                        )
+                    })
+                    .count();
+                assert_eq!(52 - dealt, sim.deck.len());
+                sim.run(&mut StdRng::seed_from_u64(seed + 100));
+
+                assert!(sim.game_state.is_complete());
+                assert_eq!(actions[..idx], sim.actions[..idx]);
+                assert_eq!(
+                    100.0 * num_players as f32,
+                    sim.game_state.stacks.iter().sum::<f32>()
+                );
+                // With every card dealt the same agents play the same hand.
+                if idx > river {
+                    assert_eq!(actions, sim.actions);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_fork_with_different_action() {
+        let (game_state, actions) = calling_game(3, 3);
+        let flop_decision = actions
+            .iter()
+            .position(|action| {
+                matches!(action, Action::PlayedAction(played) if played.round == Round::Flop)
+            })
+            .unwrap();
+        let mut replay = GameReplay::new(game_state, actions.clone());
+        replay.step_to(flop_decision).unwrap();
+
+        let mut sim = replay.fork(calling_agents(3)).unwrap();
+        let idx = sim.game_state.to_act_idx();
+        sim.run_agent_action(AgentAction::AllIn);
+        sim.run(&mut rand::rng());
+
+        match &sim.actions[flop_decision] {
+            Action::PlayedAction(played) => {
+                assert_eq!(idx, played.idx);
+                assert_eq!(0.0, played.player_stack);
+            }
+            action => panic!("Expected the shove, got {action:?}"),
+        }
+        assert_ne!(actions, sim.actions);
+    }
+
+    #[test]
+    fn test_fork_mid_deal() {
+        let (game_state, actions) = calling_game(2, 4);
+        let flop = actions
+            .iter()
+            .position(|action| matches!(action, Action::DealCommunity(_)))
+            .unwrap();
+        let mut replay = GameReplay::new(game_state, actions);
+        for idx in [1, 2, flop + 1, flop + 2, flop + 3] {
+            replay.step_to(idx).unwrap();
+            assert!(matches!(
+                replay.fork(calling_agents(2)),
+                Err(ReplayError::ForkMidDeal)
+            ));
+        }
+        replay.step_to(flop + 4).unwrap();
+        assert!(replay.fork(calling_agents(2)).is_ok());
+    }
 }
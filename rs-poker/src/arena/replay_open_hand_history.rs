@@ -0,0 +1,1021 @@
+//! Importing Open Hand Histories so that real world hands can be replayed
+//! and re-analysed with the arena.
+//!
+//! Each [`HandHistory`] is turned into the starting [`GameState`] and the
+//! `Vec<Action>` that the simulation would have recorded playing it, ready
+//! for [`GameReplay`] or [`ReplaySimulationBuilder`]. The hand is played
+//! through a game state as it's imported, so blinds, antes, bets and pots
+//! that don't follow the rules of the arena are rejected with an
+//! [`ImportError`] rather than producing a replay that doesn't add up.
+//!
+//! Players are given seat indexes in the order of their seats at the table.
+//! Players sitting out get a seat with an empty stack. Hole cards that the
+//! history never shows are filled in with cards no one else holds so every
+//! player has a full hand; the awards always come from the history's pots.
+//!
+//! ```
+//! use rand::{SeedableRng, rngs::StdRng};
+//! use rs_poker::arena::HoldemSimulationBuilder;
+//! use rs_poker::arena::agent::CallingAgent;
+//! use rs_poker::arena::game_state::GameState;
+//! use rs_poker::arena::historian::HandHistoryBuilder;
+//! use rs_poker::arena::replay_open_hand_history::ImportedHand;
+//!
+//! let game_state = GameState::new_starting(vec![100.0; 2], 10.0, 5.0, 0.0, 0);
+//! let mut builder = HandHistoryBuilder::new("1".to_string(), &game_state);
+//! let mut sim = HoldemSimulationBuilder::default()
+//!     .game_state(game_state)
+//!     .agents(vec![Box::new(CallingAgent), Box::new(CallingAgent)])
+//!     .build()
+//!     .unwrap();
+//! sim.run(&mut StdRng::seed_from_u64(1));
+//! for action in &sim.actions {
+//!     builder.record(action);
+//! }
+//!
+//! let imported = ImportedHand::try_from(&builder.build()).unwrap();
+//! let mut replay = imported.replay();
+//! while replay.has_more_actions() {
+//!     replay.step_forward().unwrap();
+//! }
+//! assert_eq!(sim.game_state.stacks, replay.get_current_state().stacks);
+//! ```
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::arena::{
+    GameReplay, GameState, GameType, ReplaySimulationBuilder,
+    action::{
+        Action, AgentAction, AwardPayload, DealStartingHandPayload, ForcedBetPayload,
+        ForcedBetType, GameStartPayload, PlayedActionPayload, PlayerSitPayload,
+    },
+    errors::GameStateError,
+    game_state::Round,
+};
+use crate::core::{Card, Deck};
+use crate::open_hand_history::{
+    Action as OhhAction, ActionObj, BetType, GameType as OhhGameType, HandHistory, PokerStarsError,
+    parse_pokerstars_hands, read_hands,
+};
+
+/// How far apart two amounts of chips can be and still be the same.
+const CHIP_TOLERANCE: f32 = 0.01;
+
+#[derive(Error, Debug)]
+pub enum ImportError {
+    #[error("Error reading hand histories")]
+    Io(#[from] std::io::Error),
+
+    #[error("Error parsing PokerStars hand history: {0}")]
+    PokerStars(#[from] PokerStarsError),
+
+    #[error("Unsupported game: {0}")]
+    UnsupportedGame(String),
+
+    #[error("Hand needs at least two players, it has {0}")]
+    NotEnoughPlayers(usize),
+
+    #[error("Player {0} isn't seated at the table")]
+    UnknownPlayer(u64),
+
+    #[error("Unsupported action {action:?} by player {player_id}")]
+    UnsupportedAction { player_id: u64, action: OhhAction },
+
+    #[error("Unknown street: {0}")]
+    UnknownStreet(String),
+
+    #[error("Player {player_id} has {found} cards, the game deals {expected}")]
+    WrongNumberOfHoleCards {
+        player_id: u64,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("The {street} has {found} cards, expected {expected}")]
+    WrongNumberOfBoardCards {
+        street: String,
+        expected: usize,
+        found: usize,
+    },
+
+    #[error("Card {0} is dealt more than once")]
+    DuplicateCard(Card),
+
+    #[error("Player {player_id} should post {expected} as {forced_bet:?} but posts {found}")]
+    ForcedBetMismatch {
+        player_id: u64,
+        forced_bet: ForcedBetType,
+        expected: f32,
+        found: f32,
+    },
+
+    #[error("Player {player_id} posts {forced_bet:?} but isn't due to")]
+    UnexpectedForcedBet {
+        player_id: u64,
+        forced_bet: ForcedBetType,
+    },
+
+    #[error("Player {found} acts on the {street} but it's player {expected} to act")]
+    OutOfTurn {
+        street: String,
+        expected: u64,
+        found: u64,
+    },
+
+    #[error("Player {0} is still to act when the history ends")]
+    MissingAction(u64),
+
+    #[error("Player {player_id} acts on the {street} after the betting is over")]
+    ExtraAction { player_id: u64, street: String },
+
+    #[error("Player {player_id} can't {action:?} facing a bet of {bet}")]
+    InvalidAction {
+        player_id: u64,
+        action: OhhAction,
+        bet: f32,
+    },
+
+    #[error("Player {player_id} {action:?} for {found} but that puts in {expected}")]
+    AmountMismatch {
+        player_id: u64,
+        action: OhhAction,
+        expected: f32,
+        found: f32,
+    },
+
+    #[error("Player {player_id} makes an invalid bet: {error}")]
+    InvalidBet {
+        player_id: u64,
+        error: GameStateError,
+    },
+
+    #[error("The pots pay out {awarded} but {total_pot} was put in")]
+    PotMismatch { total_pot: f32, awarded: f32 },
+}
+
+/// A hand from an Open Hand History ready to be replayed.
+#[derive(Debug, Clone)]
+pub struct ImportedHand {
+    pub game_number: String,
+    /// The history's id for the player in each seat index.
+    pub player_ids: Vec<u64>,
+    /// The game state before anything happens.
+    pub initial_state: GameState,
+    /// Everything that happens in the hand, as the simulation would record
+    /// it.
+    pub actions: Vec<Action>,
+}
+
+impl ImportedHand {
+    /// Step through the hand action by action.
+    pub fn replay(&self) -> GameReplay {
+        GameReplay::new(self.initial_state.clone(), self.actions.clone())
+    }
+
+    /// A builder for replays or simulations that deal the same cards.
+    pub fn replay_builder(&self) -> ReplaySimulationBuilder {
+        ReplaySimulationBuilder::new()
+            .with_initial_state(self.initial_state.clone())
+            .with_actions(self.actions.clone())
+    }
+
+    /// The seat index of a player in the history.
+    pub fn player_idx(&self, player_id: u64) -> Option<usize> {
+        self.player_ids.iter().position(|id| *id == player_id)
+    }
+}
+
+impl TryFrom<&HandHistory> for ImportedHand {
+    type Error = ImportError;
+
+    fn try_from(hand: &HandHistory) -> Result<Self, Self::Error> {
+        Importer::new(hand)?.import()
+    }
+}
+
+/// Read every hand in a `.ohh` file and import them.
+pub fn import_hands(path: &Path) -> Result<Vec<ImportedHand>, ImportError> {
+    read_hands(path)?
+        .iter()
+        .map(ImportedHand::try_from)
+        .collect()
+}
+
+/// Parse and import every hand in a PokerStars style text hand history.
+pub fn import_pokerstars_hands(text: &str) -> Result<Vec<ImportedHand>, ImportError> {
+    parse_pokerstars_hands(text)?
+        .iter()
+        .map(ImportedHand::try_from)
+        .collect()
+}
+
+/// Plays a hand history through a game state, recording the actions the
+/// simulation would have.
+struct Importer<'a> {
+    hand: &'a HandHistory,
+    player_ids: Vec<u64>,
+    game_state: GameState,
+    actions: Vec<Action>,
+    /// The forced bets posted by each player.
+    forced_bets: Vec<(usize, ForcedBetType, f32)>,
+    /// Folds, checks, bets, raises and calls in the order they happen.
+    played: Vec<(Round, &'a ActionObj)>,
+    next_played: usize,
+    hole_cards: Vec<Vec<Card>>,
+    /// Whether the history shows the player's hole cards.
+    known: Vec<bool>,
+    board: Vec<Card>,
+}
+
+impl<'a> Importer<'a> {
+    fn new(hand: &'a HandHistory) -> Result<Self, ImportError> {
+        let game_type = match (
+            &hand.game_type,
+            hand.bet_limit.as_ref().map(|l| &l.bet_type),
+        ) {
+            (OhhGameType::Holdem, None | Some(BetType::NoLimit)) => GameType::NoLimitHoldem,
+            (OhhGameType::Omaha, Some(BetType::PotLimit)) => GameType::PotLimitOmaha,
+            (game_type, bet_type) => {
+                return Err(ImportError::UnsupportedGame(format!(
+                    "{game_type:?} with {bet_type:?} betting"
+                )));
+            }
+        };
+
+        let mut players: Vec<_> = hand.players.iter().collect();
+        players.sort_by_key(|player| player.seat);
+        if players.len() < 2 {
+            return Err(ImportError::NotEnoughPlayers(players.len()));
+        }
+        let player_ids: Vec<u64> = players.iter().map(|player| player.id).collect();
+        let stacks: Vec<f32> = players
+            .iter()
+            .map(|player| {
+                if player.is_sitting_out == Some(true) {
+                    0.0
+                } else {
+                    player.starting_stack
+                }
+            })
+            .collect();
+        // With a dead button the dealer is the last player seated before it.
+        let dealer_idx = players
+            .iter()
+            .rposition(|player| player.seat <= hand.dealer_seat)
+            .unwrap_or(players.len() - 1);
+
+        let game_state = GameState::new_starting(
+            stacks,
+            hand.big_blind_amount,
+            hand.small_blind_amount,
+            hand.ante_amount,
+            dealer_idx,
+        )
+        .with_game_type(game_type);
+
+        let num_players = player_ids.len();
+        let mut importer = Self {
+            hand,
+            player_ids,
+            game_state,
+            actions: vec![],
+            forced_bets: vec![],
+            played: vec![],
+            next_played: 0,
+            hole_cards: vec![vec![]; num_players],
+            known: vec![false; num_players],
+            board: vec![],
+        };
+        importer.read_rounds()?;
+        Ok(importer)
+    }
+
+    /// Sort the history's actions into forced bets, cards and played
+    /// actions.
+    fn read_rounds(&mut self) -> Result<(), ImportError> {
+        let hand = self.hand;
+        for round in &hand.rounds {
+            let (street, board_cards) = match round.street.as_str() {
+                "Preflop" => (Round::Preflop, 0),
+                "Flop" => (Round::Flop, 3),
+                "Turn" => (Round::Turn, 1),
+                "River" => (Round::River, 1),
+                "Showdown" => (Round::Showdown, 0),
+                _ => return Err(ImportError::UnknownStreet(round.street.clone())),
+            };
+            let cards = round.cards.as_deref().unwrap_or_default();
+            if !cards.is_empty() && cards.len() != board_cards {
+                return Err(ImportError::WrongNumberOfBoardCards {
+                    street: round.street.clone(),
+                    expected: board_cards,
+                    found: cards.len(),
+                });
+            }
+            self.board.extend_from_slice(cards);
+
+            for action in &round.actions {
+                let idx = self.idx(action.player_id)?;
+                match action.action {
+                    OhhAction::DealtCards | OhhAction::ShowsCards | OhhAction::MucksCards => {
+                        self.hole_cards_shown(idx, action.cards.as_deref().unwrap_or_default())?;
+                    }
+                    OhhAction::PostAnte => self.posted(idx, ForcedBetType::Ante, action.amount),
+                    OhhAction::PostSmallBlind => {
+                        self.posted(idx, ForcedBetType::SmallBlind, action.amount)
+                    }
+                    OhhAction::PostBigBlind => {
+                        self.posted(idx, ForcedBetType::BigBlind, action.amount)
+                    }
+                    OhhAction::Fold
+                    | OhhAction::Check
+                    | OhhAction::Bet
+                    | OhhAction::Raise
+                    | OhhAction::Call => self.played.push((street, action)),
+                    _ => {
+                        return Err(ImportError::UnsupportedAction {
+                            player_id: action.player_id,
+                            action: action.action.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        let mut seen = Deck::new();
+        for card in self.hole_cards.iter().flatten().chain(&self.board) {
+            if !seen.insert(*card) {
+                return Err(ImportError::DuplicateCard(*card));
+            }
+        }
+        Ok(())
+    }
+
+    fn hole_cards_shown(&mut self, idx: usize, cards: &[Card]) -> Result<(), ImportError> {
+        for card in cards {
+            if !self.hole_cards[idx].contains(card) {
+                self.hole_cards[idx].push(*card);
+            }
+        }
+        let expected = self.game_state.game_type.num_hole_cards();
+        let found = self.hole_cards[idx].len();
+        if found > expected || (found > 0 && found < expected) {
+            return Err(ImportError::WrongNumberOfHoleCards {
+                player_id: self.player_ids[idx],
+                expected,
+                found,
+            });
+        }
+        self.known[idx] = found > 0;
+        Ok(())
+    }
+
+    fn posted(&mut self, idx: usize, forced_bet: ForcedBetType, amount: f32) {
+        self.forced_bets.push((idx, forced_bet, amount));
+    }
+
+    fn idx(&self, player_id: u64) -> Result<usize, ImportError> {
+        self.player_ids
+            .iter()
+            .position(|id| *id == player_id)
+            .ok_or(ImportError::UnknownPlayer(player_id))
+    }
+
+    fn import(mut self) -> Result<ImportedHand, ImportError> {
+        let initial_state = self.game_state.clone();
+        self.actions.push(Action::GameStart(GameStartPayload {
+            ante: self.game_state.ante,
+            small_blind: self.game_state.small_blind,
+            big_blind: self.game_state.big_blind,
+        }));
+
+        while self.game_state.round != Round::Complete {
+            match self.game_state.round {
+                Round::Starting => self.start(),
+                Round::Ante => self.ante()?,
+                Round::DealPreflop => self.deal_preflop(),
+                Round::Preflop => {
+                    self.blinds()?;
+                    self.betting_round()?;
+                }
+                Round::DealFlop => self.deal_community(3)?,
+                Round::DealTurn | Round::DealRiver => self.deal_community(1)?,
+                Round::Flop | Round::Turn | Round::River => self.betting_round()?,
+                Round::Showdown => {
+                    self.award_pots()?;
+                    self.end_game();
+                }
+                Round::Complete => {}
+            }
+            if self.game_state.round != Round::Complete {
+                self.advance_round();
+            }
+        }
+
+        if let Some((round, action)) = self.played.get(self.next_played) {
+            return Err(ImportError::ExtraAction {
+                player_id: action.player_id,
+                street: street_name(*round),
+            });
+        }
+        if let Some((idx, forced_bet, _)) = self.forced_bets.first() {
+            return Err(ImportError::UnexpectedForcedBet {
+                player_id: self.player_ids[*idx],
+                forced_bet: forced_bet.clone(),
+            });
+        }
+
+        Ok(ImportedHand {
+            game_number: self.hand.game_number.clone(),
+            player_ids: self.player_ids,
+            initial_state,
+            actions: self.actions,
+        })
+    }
+
+    fn start(&mut self) {
+        while self.game_state.current_round_num_active_players() > 0 {
+            let idx = self.game_state.to_act_idx();
+            self.actions.push(Action::PlayerSit(PlayerSitPayload {
+                idx,
+                player_stack: self.game_state.stacks[idx],
+            }));
+            self.game_state.round_data.needs_action.disable(idx);
+            self.game_state.round_data.advance_action();
+        }
+    }
+
+    fn ante(&mut self) -> Result<(), ImportError> {
+        if self.game_state.ante > 0.0 {
+            while self.game_state.current_round_num_active_players() > 0 {
+                let idx = self.game_state.to_act_idx();
+                self.forced_bet(ForcedBetType::Ante)?;
+                self.game_state.round_data.needs_action.disable(idx);
+            }
+        }
+        Ok(())
+    }
+
+    fn deal_preflop(&mut self) {
+        // Deal the cards the history never shows from what's left.
+        let mut deck = Deck::default();
+        for card in self.hole_cards.iter().flatten().chain(&self.board) {
+            deck.remove(card);
+        }
+        let mut unused = deck.iter();
+
+        let num_cards = self.game_state.game_type.num_hole_cards();
+        while self.game_state.current_round_num_active_players() > 0 {
+            let idx = self.game_state.to_act_idx();
+            if self.hole_cards[idx].is_empty() {
+                self.hole_cards[idx] = unused.by_ref().take(num_cards).collect();
+            }
+            let mut cards = self.hole_cards[idx].clone();
+            cards.sort();
+            for card in &cards {
+                self.actions
+                    .push(Action::DealStartingHand(DealStartingHandPayload {
+                        card: *card,
+                        idx,
+                    }));
+            }
+            self.game_state.hands[idx].extend(cards);
+            self.game_state.round_data.needs_action.disable(idx);
+            self.game_state.round_data.advance_action();
+        }
+    }
+
+    fn blinds(&mut self) -> Result<(), ImportError> {
+        if !self.game_state.sb_posted {
+            self.forced_bet(ForcedBetType::SmallBlind)?;
+            self.game_state.sb_posted = true;
+        }
+        if !self.game_state.bb_posted {
+            self.forced_bet(ForcedBetType::BigBlind)?;
+            self.game_state.bb_posted = true;
+        }
+        Ok(())
+    }
+
+    /// Force a bet from the player to act, checking the history agrees on
+    /// what they put in.
+    fn forced_bet(&mut self, forced_bet_type: ForcedBetType) -> Result<(), ImportError> {
+        let idx = self.game_state.to_act_idx();
+        let bet = match forced_bet_type {
+            ForcedBetType::Ante => self.game_state.ante,
+            ForcedBetType::SmallBlind => self.game_state.small_blind,
+            ForcedBetType::BigBlind => self.game_state.big_blind,
+        };
+        let expected = self.game_state.do_bet(bet, true).unwrap();
+        let found = self
+            .forced_bets
+            .iter()
+            .position(|(posted_idx, posted, _)| *posted_idx == idx && *posted == forced_bet_type)
+            .map(|pos| self.forced_bets.remove(pos).2)
+            .unwrap_or_default();
+        if !same_amount(expected, found) {
+            return Err(ImportError::ForcedBetMismatch {
+                player_id: self.player_ids[idx],
+                forced_bet: forced_bet_type.clone(),
+                expected,
+                found,
+            });
+        }
+        self.actions.push(Action::ForcedBet(ForcedBetPayload {
+            bet,
+            player_stack: self.game_state.stacks[idx],
+            idx,
+            forced_bet_type,
+        }));
+        Ok(())
+    }
+
+    fn deal_community(&mut self, num_cards: usize) -> Result<(), ImportError> {
+        let start = self.game_state.board.len();
+        let street = street_name(self.game_state.round);
+        let cards = self.board.get(start..start + num_cards).ok_or_else(|| {
+            ImportError::WrongNumberOfBoardCards {
+                street,
+                expected: num_cards,
+                found: self.board.len().saturating_sub(start),
+            }
+        })?;
+        let mut cards = cards.to_vec();
+        cards.sort();
+        for card in &cards {
+            self.actions.push(Action::DealCommunity(*card));
+        }
+        for hand in &mut self.game_state.hands {
+            hand.extend(cards.iter().cloned());
+        }
+        self.game_state.board.append(&mut cards);
+        Ok(())
+    }
+
+    fn betting_round(&mut self) -> Result<(), ImportError> {
+        let round = self.game_state.round;
+        while self.needs_action() && self.game_state.round == round {
+            let to_act = self.game_state.to_act_idx();
+            let (street, action) = self
+                .played
+                .get(self.next_played)
+                .copied()
+                .ok_or(ImportError::MissingAction(self.player_ids[to_act]))?;
+            let idx = self.idx(action.player_id)?;
+            if street != round || idx != to_act {
+                return Err(ImportError::OutOfTurn {
+                    street: street_name(round),
+                    expected: self.player_ids[to_act],
+                    found: action.player_id,
+                });
+            }
+            self.next_played += 1;
+            self.play(idx, action)?;
+        }
+        Ok(())
+    }
+
+    fn needs_action(&self) -> bool {
+        !(self.game_state.player_active & self.game_state.round_data.needs_action).empty()
+    }
+
+    fn play(&mut self, idx: usize, action: &ActionObj) -> Result<(), ImportError> {
+        let starting_bet = self.game_state.current_round_bet();
+        let starting_player_bet = self.game_state.current_round_player_bet(idx);
+        let starting_min_raise = self.game_state.current_round_min_raise();
+        let starting_pot = self.game_state.total_pot;
+        let to_call = starting_bet - starting_player_bet;
+
+        let invalid = || ImportError::InvalidAction {
+            player_id: action.player_id,
+            action: action.action.clone(),
+            bet: starting_bet,
+        };
+        let agent_action = match action.action {
+            OhhAction::Fold => AgentAction::Fold,
+            OhhAction::Check if to_call <= 0.0 => AgentAction::Call,
+            OhhAction::Call if to_call > 0.0 => AgentAction::Call,
+            OhhAction::Bet | OhhAction::Raise => {
+                AgentAction::Bet(starting_player_bet + action.amount)
+            }
+            _ => return Err(invalid()),
+        };
+
+        if agent_action != AgentAction::Fold {
+            let amount = match agent_action {
+                AgentAction::Bet(amount) => amount,
+                _ => starting_bet,
+            };
+            let put_in =
+                self.game_state
+                    .do_bet(amount, false)
+                    .map_err(|error| ImportError::InvalidBet {
+                        player_id: action.player_id,
+                        error,
+                    })?;
+            if !same_amount(put_in, action.amount) {
+                return Err(ImportError::AmountMismatch {
+                    player_id: action.player_id,
+                    action: action.action.clone(),
+                    expected: put_in,
+                    found: action.amount,
+                });
+            }
+        }
+
+        let final_player_bet = self.game_state.current_round_player_bet(idx);
+        self.actions.push(Action::PlayedAction(PlayedActionPayload {
+            action: match agent_action {
+                AgentAction::Bet(_) => AgentAction::Bet(final_player_bet),
+                _ => agent_action.clone(),
+            },
+            idx,
+            round: self.game_state.round,
+            player_stack: self.game_state.stacks[idx],
+            starting_pot,
+            final_pot: self.game_state.total_pot,
+            starting_bet,
+            final_bet: self.game_state.current_round_bet(),
+            starting_min_raise,
+            final_min_raise: self.game_state.current_round_min_raise(),
+            starting_player_bet,
+            final_player_bet,
+            players_active: self.game_state.player_active,
+            players_all_in: self.game_state.player_all_in,
+        }));
+
+        if agent_action == AgentAction::Fold {
+            self.game_state.fold();
+            let left = self.game_state.player_active | self.game_state.player_all_in;
+            if left.count() <= 1 {
+                self.award_pots()?;
+                self.end_game();
+            }
+        }
+        Ok(())
+    }
+
+    /// Pay out the pots the way the history says they were won.
+    fn award_pots(&mut self) -> Result<(), ImportError> {
+        let hand = self.hand;
+        let mut awarded = 0.0;
+        for pot in &hand.pots {
+            awarded += pot.rake.unwrap_or_default() + pot.jackpot.unwrap_or_default();
+            for win in &pot.player_wins {
+                let idx = self.idx(win.player_id)?;
+                awarded += win.win_amount;
+                self.game_state.award(idx, win.win_amount);
+                let showdown = self.game_state.round == Round::Showdown && self.known[idx];
+                self.actions.push(Action::Award(AwardPayload {
+                    total_pot: pot.amount,
+                    award_amount: win.win_amount,
+                    rank: showdown.then(|| self.game_state.player_rank(idx)),
+                    hand: showdown.then(|| self.game_state.hands[idx]),
+                    idx,
+                }));
+            }
+        }
+
+        let total_pot = self.game_state.total_pot;
+        if !same_amount(total_pot, awarded) {
+            return Err(ImportError::PotMismatch { total_pot, awarded });
+        }
+        Ok(())
+    }
+
+    fn advance_round(&mut self) {
+        let round = self.game_state.round;
+        self.game_state.advance_round();
+        if self.game_state.round != round {
+            self.actions
+                .push(Action::RoundAdvance(self.game_state.round));
+        }
+    }
+
+    fn end_game(&mut self) {
+        let round = self.game_state.round;
+        self.game_state.complete();
+        if self.game_state.round != round {
+            self.actions
+                .push(Action::RoundAdvance(self.game_state.round));
+        }
+    }
+}
+
+fn same_amount(a: f32, b: f32) -> bool {
+    (a - b).abs() < CHIP_TOLERANCE
+}
+
+fn street_name(round: Round) -> String {
+    match round {
+        Round::Flop | Round::DealFlop => "flop",
+        Round::Turn | Round::DealTurn => "turn",
+        Round::River | Round::DealRiver => "river",
+        Round::Showdown | Round::Complete => "showdown",
+        _ => "preflop",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{SeedableRng, rngs::StdRng};
+    use tempfile::NamedTempFile;
+
+    use crate::arena::{
+        Agent, HoldemSimulation, HoldemSimulationBuilder, PlayerView,
+        agent::{AllInAgent, CallingAgent, RandomAgent},
+        historian::{HandHistoryBuilder, OpenHandHistoryHistorian},
+    };
+    use crate::open_hand_history::{BetLimitObj, BetType, write_pokerstars_hand};
+
+    use super::*;
+
+    fn run_sim(game_state: GameState, agents: Vec<Box<dyn Agent>>, seed: u64) -> HoldemSimulation {
+        let mut sim = HoldemSimulationBuilder::default()
+            .game_state(game_state)
+            .agents(agents)
+            .build()
+            .unwrap();
+        sim.run(&mut StdRng::seed_from_u64(seed));
+        sim
+    }
+
+    /// Plays like a `RandomAgent` but bets whole chips, PokerStars text
+    /// only keeps hundredths.
+    #[derive(Clone, Default)]
+    struct WholeChipsAgent(RandomAgent);
+
+    impl Agent for WholeChipsAgent {
+        fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction {
+            match self.0.act(id, view) {
+                AgentAction::Bet(bet) => AgentAction::Bet(bet.round()),
+                action => action,
+            }
+        }
+    }
+
+    fn hand_history(sim: &HoldemSimulation, game_state: &GameState) -> HandHistory {
+        let mut builder = HandHistoryBuilder::new("1".to_string(), game_state);
+        for action in &sim.actions {
+            builder.record(action);
+        }
+        builder.build()
+    }
+
+    fn calling_hand() -> HandHistory {
+        let game_state = GameState::new_starting(vec![100.0; 3], 10.0, 5.0, 1.0, 0);
+        let agents: Vec<Box<dyn Agent>> = (0..3)
+            .map(|_| -> Box<dyn Agent> { Box::new(CallingAgent) })
+            .collect();
+        let sim = run_sim(game_state.clone(), agents, 7);
+        hand_history(&sim, &game_state)
+    }
+
+    /// Played actions only differ in how the agent asked for them.
+    fn normalize(actions: &[Action]) -> Vec<Action> {
+        actions
+            .iter()
+            .cloned()
+            .map(|action| match action {
+                Action::PlayedAction(mut payload) => {
+                    if payload.action != AgentAction::Fold {
+                        payload.action = if payload.final_bet > payload.starting_bet {
+                            AgentAction::Bet(payload.final_player_bet)
+                        } else {
+                            AgentAction::Call
+                        };
+                    }
+                    Action::PlayedAction(payload)
+                }
+                action => action,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_simulations_round_trip() {
+        for seed in 0..50 {
+            let num_players = 2 + (seed as usize % 5);
+            let game_type = if seed % 3 == 0 {
+                GameType::PotLimitOmaha
+            } else {
+                GameType::NoLimitHoldem
+            };
+            let stacks = (0..num_players).map(|i| 50.0 + 25.0 * i as f32).collect();
+            let game_state = GameState::new_starting(
+                stacks,
+                10.0,
+                5.0,
+                (seed % 2) as f32,
+                seed as usize % num_players,
+            )
+            .with_game_type(game_type);
+            let agents: Vec<Box<dyn Agent>> = (0..num_players)
+                .map(|i| -> Box<dyn Agent> {
+                    match i % 3 {
+                        0 => Box::<RandomAgent>::default(),
+                        1 => Box::new(CallingAgent),
+                        _ => Box::new(AllInAgent),
+                    }
+                })
+                .collect();
+            let sim = run_sim(game_state.clone(), agents, seed);
+
+            let imported = ImportedHand::try_from(&hand_history(&sim, &game_state)).unwrap();
+            assert_eq!(game_state, imported.initial_state);
+            if !sim
+                .actions
+                .iter()
+                .any(|action| matches!(action, Action::FailedAction(_)))
+            {
+                assert_eq!(normalize(&sim.actions), normalize(&imported.actions));
+            }
+
+            let mut replay = imported.replay();
+            while replay.has_more_actions() {
+                replay.step_forward().unwrap();
+            }
+            for (expected, replayed) in sim
+                .game_state
+                .stacks
+                .iter()
+                .zip(&replay.get_current_state().stacks)
+            {
+                assert!((expected - replayed).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pokerstars_round_trip() {
+        let mut text = String::new();
+        let mut expected = Vec::new();
+        for seed in 0..20 {
+            let num_players = 2 + (seed as usize % 5);
+            let game_state = GameState::new_starting(vec![100.0; num_players], 10.0, 5.0, 0.0, 0);
+            let agents: Vec<Box<dyn Agent>> = (0..num_players)
+                .map(|_| -> Box<dyn Agent> { Box::<WholeChipsAgent>::default() })
+                .collect();
+            let sim = run_sim(game_state.clone(), agents, seed);
+            text.push_str(&write_pokerstars_hand(&hand_history(&sim, &game_state)));
+            text.push_str("\n\n");
+            expected.push(sim.game_state.stacks.clone());
+        }
+
+        let hands = import_pokerstars_hands(&text).unwrap();
+        assert_eq!(expected.len(), hands.len());
+        for (stacks, imported) in expected.iter().zip(hands) {
+            let mut replay = imported.replay();
+            while replay.has_more_actions() {
+                replay.step_forward().unwrap();
+            }
+            for (expected, replayed) in stacks.iter().zip(&replay.get_current_state().stacks) {
+                assert!((expected - replayed).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_import_hands_from_file() {
+        let file = NamedTempFile::new().unwrap();
+        for seed in 0..3 {
+            let game_state = GameState::new_starting(vec![100.0; 4], 10.0, 5.0, 0.0, 1);
+            let historian = OpenHandHistoryHistorian::new(file.path().to_path_buf());
+            let agents: Vec<Box<dyn Agent>> = (0..4)
+                .map(|_| -> Box<dyn Agent> { Box::<RandomAgent>::default() })
+                .collect();
+            let mut sim = HoldemSimulationBuilder::default()
+                .game_state(game_state)
+                .agents(agents)
+                .historians(vec![Box::new(historian)])
+                .panic_on_historian_error(true)
+                .build()
+                .unwrap();
+            sim.run(&mut StdRng::seed_from_u64(seed));
+        }
+
+        let hands = import_hands(file.path()).unwrap();
+        assert_eq!(3, hands.len());
+        for hand in hands {
+            assert_eq!(vec![0, 1, 2, 3], hand.player_ids);
+            assert_eq!(Some(2), hand.player_idx(2));
+            assert_eq!(1, hand.initial_state.dealer_idx);
+            assert_eq!(
+                Some(&Action::RoundAdvance(Round::Complete)),
+                hand.actions.last()
+            );
+            hand.replay_builder().build_replay().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_seats_and_dead_button() {
+        let mut hand = calling_hand();
+        for (player, seat) in hand.players.iter_mut().zip([2, 5, 8]) {
+            player.seat = seat;
+        }
+        hand.players.swap(0, 2);
+
+        // With the button on seat 1 the blinds are in the wrong seats.
+        hand.dealer_seat = 1;
+        assert!(matches!(
+            ImportedHand::try_from(&hand),
+            Err(ImportError::ForcedBetMismatch { .. })
+        ));
+
+        // A dead button on the empty seat after the first player.
+        hand.dealer_seat = 3;
+        let imported = ImportedHand::try_from(&hand).unwrap();
+        assert_eq!(0, imported.initial_state.dealer_idx);
+        assert_eq!(vec![0, 1, 2], imported.player_ids);
+    }
+
+    #[test]
+    fn test_unknown_hole_cards() {
+        let mut hand = calling_hand();
+        for round in hand.rounds.iter_mut() {
+            round.actions.retain(|action| {
+                action.player_id == 0
+                    || !matches!(action.action, OhhAction::DealtCards | OhhAction::ShowsCards)
+            });
+        }
+        let imported = ImportedHand::try_from(&hand).unwrap();
+        let mut replay = imported.replay();
+        replay.step_to(imported.actions.len()).unwrap();
+        let state = replay.get_current_state();
+        for idx in 0..3 {
+            assert_eq!(2, state.hole_cards(idx).len());
+        }
+
+        // Only the player whose cards are known has a ranked award.
+        for action in &imported.actions {
+            if let Action::Award(payload) = action {
+                assert_eq!(payload.idx == 0, payload.rank.is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unsupported_game() {
+        let mut hand = calling_hand();
+        hand.bet_limit = Some(BetLimitObj {
+            bet_type: BetType::FixedLimit,
+            bet_cap: 0.0,
+        });
+        assert!(matches!(
+            ImportedHand::try_from(&hand),
+            Err(ImportError::UnsupportedGame(_))
+        ));
+    }
+
+    #[test]
+    fn test_inconsistent_hands() {
+        let hand = calling_hand();
+
+        let mut out_of_turn = hand.clone();
+        let preflop = &mut out_of_turn.rounds[0].actions;
+        let first = preflop
+            .iter()
+            .position(|action| action.action == OhhAction::Call)
+            .unwrap();
+        preflop.swap(first, first + 1);
+        assert!(matches!(
+            ImportedHand::try_from(&out_of_turn),
+            Err(ImportError::OutOfTurn { .. })
+        ));
+
+        let mut wrong_amount = hand.clone();
+        let call = wrong_amount.rounds[0]
+            .actions
+            .iter_mut()
+            .find(|action| action.action == OhhAction::Call)
+            .unwrap();
+        call.amount += 5.0;
+        assert!(matches!(
+            ImportedHand::try_from(&wrong_amount),
+            Err(ImportError::AmountMismatch { .. })
+        ));
+
+        let mut wrong_pot = hand.clone();
+        wrong_pot.pots[0].player_wins[0].win_amount += 10.0;
+        assert!(matches!(
+            ImportedHand::try_from(&wrong_pot),
+            Err(ImportError::PotMismatch { .. })
+        ));
+
+        let mut unknown_player = hand.clone();
+        unknown_player.rounds[1].actions[0].player_id = 42;
+        assert!(matches!(
+            ImportedHand::try_from(&unknown_player),
+            Err(ImportError::UnknownPlayer(42))
+        ));
+
+        let mut missing_turn = hand.clone();
+        missing_turn.rounds.truncate(2);
+        assert!(matches!(
+            ImportedHand::try_from(&missing_turn),
+            Err(ImportError::WrongNumberOfBoardCards { .. })
+        ));
+    }
+}
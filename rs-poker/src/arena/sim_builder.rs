@@ -3,13 +3,13 @@ use rand::Rng;
 use crate::core::{CardBitSet, Deck};
 
 use super::{
-    Agent, GameState, HoldemSimulation, agent::FoldingAgent, errors::HoldemSimulationError,
-    historian::Historian,
+    Agent, GameState, HoldemSimulation, action::Action, agent::FoldingAgent,
+    errors::HoldemSimulationError, historian::Historian,
 };
 
 // Some builder methods to help with turning a builder struct into a ready
 // simulation
-fn build_deck(game_state: &GameState) -> Deck {
+pub(crate) fn build_deck(game_state: &GameState) -> Deck {
     let mut d = CardBitSet::default();
 
     for hand in game_state.hands.iter() {
@@ -76,6 +76,7 @@ pub struct HoldemSimulationBuilder {
     historians: Vec<Box<dyn Historian>>,
     game_state: Option<GameState>,
     deck: Option<Deck>,
+    actions: Vec<Action>,
     panic_on_historian_error: bool,
 }
 
@@ -114,6 +115,14 @@ impl HoldemSimulationBuilder {
         self
     }
 
+    /// Set the actions already played, for a simulation that starts part way
+    /// through a hand. Agents see them in their `PlayerView`, historians
+    /// only see the actions played after the simulation starts.
+    pub fn actions(mut self, actions: Vec<Action>) -> Self {
+        self.actions = actions;
+        self
+    }
+
     /// Set the historians for the simulation created by this builder.
     pub fn historians(mut self, historians: Vec<Box<dyn Historian>>) -> Self {
         self.historians = historians;
@@ -166,6 +175,7 @@ impl HoldemSimulationBuilder {
             id,
             historians,
             panic_on_historian_error: self.panic_on_historian_error,
+            actions: self.actions,
         })
     }
 }
@@ -177,6 +187,7 @@ impl Default for HoldemSimulationBuilder {
             historians: vec![],
             game_state: None,
             deck: None,
+            actions: vec![],
             panic_on_historian_error: true,
         }
     }
@@ -6,7 +6,7 @@ use tracing::{Level, debug_span, event, instrument, trace_span};
 
 use crate::arena::action::{FailedActionPayload, PlayedActionPayload};
 use crate::arena::game_state::Round;
-use crate::core::{Card, Deck, Rank, Rankable};
+use crate::core::{Card, Deck, Rank};
 
 use super::action::{
     Action, AgentAction, AwardPayload, DealStartingHandPayload, ForcedBetPayload, GameStartPayload,
@@ -15,6 +15,7 @@ use super::action::{
 
 use super::Agent;
 use super::GameState;
+use super::PlayerView;
 use super::historian::Historian;
 
 /// # Description
@@ -58,6 +59,9 @@ pub struct HoldemSimulation {
     pub deck: Deck,
     pub historians: Vec<Box<dyn Historian>>,
     pub panic_on_historian_error: bool,
+    /// Every action that has happened so far. Agents see these
+    /// (with other players' cards removed) in their `PlayerView`.
+    pub actions: Vec<Action>,
 }
 
 impl HoldemSimulation {
@@ -181,7 +185,7 @@ impl HoldemSimulation {
         while self.game_state.current_round_num_active_players() > 0 {
             let idx = self.game_state.to_act_idx();
 
-            self.deal_player_cards(2, rand);
+            self.deal_player_cards(self.game_state.game_type.num_hole_cards(), rand);
 
             // This allows us to not deal to players that
             // are sitting out, while also going in the same
@@ -289,7 +293,7 @@ impl HoldemSimulation {
         // the values are vectors of player index, for players that had that hand
         let ranks = active
             .ones()
-            .map(|idx| (idx, self.game_state.hands[idx].rank()))
+            .map(|idx| (idx, self.game_state.player_rank(idx)))
             .fold(
                 BTreeMap::new(),
                 |mut map: BTreeMap<Rank, Vec<usize>>, (idx, rank)| {
@@ -451,7 +455,12 @@ impl HoldemSimulation {
         let idx = self.game_state.to_act_idx();
         let span = trace_span!("run_agent", idx);
         let _enter = span.enter();
-        let action = self.agents[idx].act(self.id, &self.game_state);
+        let view = if self.agents[idx].omniscient() {
+            PlayerView::omniscient(idx, &self.game_state, &self.actions)
+        } else {
+            PlayerView::new(idx, &self.game_state, &self.actions)
+        };
+        let action = self.agents[idx].act(self.id, &view);
 
         event!(parent: &span, Level::TRACE, ?action, idx);
         self.run_agent_action(action);
@@ -664,8 +673,8 @@ impl HoldemSimulation {
                 }
             }
             AgentAction::AllIn => {
-                let all_in_amount = self.game_state.current_round_current_player_bet()
-                    + self.game_state.current_player_stack();
+                // In pot limit games this is capped at a pot sized raise.
+                let all_in_amount = self.game_state.current_round_max_bet();
                 let bet_result = self.game_state.do_bet(all_in_amount, false);
 
                 match bet_result {
@@ -710,6 +719,10 @@ impl HoldemSimulation {
                             AgentAction::Bet(_) => AgentAction::Bet(player_bet),
                             AgentAction::Fold => AgentAction::Fold,
                             AgentAction::Call => AgentAction::Call,
+                            // A pot limit cap can leave chips behind, so that's a bet.
+                            AgentAction::AllIn if self.game_state.stacks[idx] > 0.0 => {
+                                AgentAction::Bet(player_bet)
+                            }
                             AgentAction::AllIn => AgentAction::AllIn,
                         };
                         // If the game_state.do_bet function returned Ok then
@@ -787,6 +800,7 @@ impl HoldemSimulation {
     // `record_action`. This is critical for making sure replays are deterministic.
     fn record_action(&mut self, action: Action) {
         event!(Level::TRACE, action = ?action, game_state = ?self.game_state, "add_action");
+        self.actions.push(action.clone());
         // Iterate over the historians and record the action
         // If there's an error, log it and remove the historian
         self.historians = self
@@ -821,6 +835,7 @@ impl fmt::Debug for HoldemSimulation {
             .field("deck", &self.deck.len())
             .field("historians", &self.historians.len())
             .field("agents", &self.agents.len())
+            .field("actions", &self.actions.len())
             .field("panic_on_historian_error", &self.panic_on_historian_error)
             .finish()
     }
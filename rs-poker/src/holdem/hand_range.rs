@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+use crate::core::{Card, RSPokerError};
+
+use super::RangeParser;
+
+/// A set of two card starting hands, for example everything a player
+/// would open from early position.
+///
+/// Ranges are parsed with `RangeParser::parse_many` so they use the same
+/// syntax. Lookups don't care about the order of the hole cards.
+///
+/// ```
+/// use rs_poker::core::Card;
+/// use rs_poker::holdem::HandRange;
+///
+/// let range = HandRange::parse("TT+,AKs").unwrap();
+/// let ace = Card::try_from("As").unwrap();
+/// let king = Card::try_from("Ks").unwrap();
+/// assert!(range.contains(&[king, ace]));
+/// assert_eq!(5 * 6 + 4, range.len());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HandRange {
+    // Stored with the higher card first.
+    combos: HashSet<(Card, Card)>,
+}
+
+impl HandRange {
+    /// Parse a comma separated list of ranges. An empty string is an
+    /// empty range.
+    pub fn parse(range: &str) -> Result<Self, RSPokerError> {
+        if range.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let combos = RangeParser::parse_many(range)?
+            .into_iter()
+            .filter(|hand| hand.len() == 2)
+            .map(|hand| ordered(hand[0], hand[1]))
+            .collect();
+        Ok(Self { combos })
+    }
+
+    /// Are these hole cards in the range. Anything other than exactly two
+    /// cards never is.
+    pub fn contains(&self, hole_cards: &[Card]) -> bool {
+        match hole_cards {
+            [first, second] => self.combos.contains(&ordered(*first, *second)),
+            _ => false,
+        }
+    }
+
+    /// The number of card combinations in the range.
+    pub fn len(&self) -> usize {
+        self.combos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.combos.is_empty()
+    }
+
+    /// Every combination in the range, higher card first.
+    pub fn combos(&self) -> impl Iterator<Item = (Card, Card)> + '_ {
+        self.combos.iter().copied()
+    }
+}
+
+fn ordered(first: Card, second: Card) -> (Card, Card) {
+    if first >= second {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cards(s: &str) -> Vec<Card> {
+        s.as_bytes()
+            .chunks(2)
+            .map(|c| Card::try_from(std::str::from_utf8(c).unwrap()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_contains_ignores_order() {
+        let range = HandRange::parse("AKo,22").unwrap();
+        assert!(range.contains(&cards("AsKd")));
+        assert!(range.contains(&cards("KdAs")));
+        assert!(range.contains(&cards("2c2h")));
+        assert!(!range.contains(&cards("AsKs")));
+        assert_eq!(12 + 6, range.len());
+    }
+
+    #[test]
+    fn test_empty_and_odd_sizes() {
+        let range = HandRange::parse("").unwrap();
+        assert!(range.is_empty());
+
+        let range = HandRange::parse("AA").unwrap();
+        assert!(!range.contains(&cards("As")));
+        assert!(!range.contains(&cards("AsAhKd")));
+    }
+
+    #[test]
+    fn test_bad_range() {
+        assert!(HandRange::parse("AK-J9").is_err());
+    }
+}
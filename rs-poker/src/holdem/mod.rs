@@ -13,3 +13,9 @@ pub use self::monte_carlo_game::MonteCarloGame;
 mod parse;
 /// Export `RangeParser`
 pub use self::parse::RangeParser;
+
+/// Module for `HandRange`, a set of starting hands to check hole cards
+/// against.
+mod hand_range;
+/// Export `HandRange`
+pub use self::hand_range::HandRange;
@@ -123,12 +123,14 @@
 //! `Agent` and you can simulate Texas Holdem games with your agent.
 //!
 //! ```rust,ignore
-//! fn act(&mut self, id: u128, game_state: &GameState) -> AgentAction;
+//! fn act(&mut self, id: u128, view: &PlayerView) -> AgentAction;
 //! ```
 //!
-//! Your agent takes in the current game ID and the current game state. From
-//! there it returns what action it would like to play. If you're agent is
-//! better than others it will have +EV and win more money.
+//! Your agent takes in the current game ID and a `PlayerView` of the current
+//! game state. The view only has what that seat is allowed to know; other
+//! players' hole cards are hidden. From there it returns what action it would
+//! like to play. If you're agent is better than others it will have +EV and
+//! win more money.
 //!
 //! The arena is code to simulate different strategies and get outcomes. It
 //! includes utilities to run tournaments of agents to see who would win in an
@@ -201,6 +203,10 @@ pub mod core;
 /// parsing, game state, and starting hand code.
 pub mod holdem;
 
+/// Omaha specific code. Currently this is the hand
+/// ranking that uses exactly two hole cards.
+pub mod omaha;
+
 /// Given a tournament calculate the implied
 /// equity in the total tournament.
 pub mod simulated_icm;
@@ -0,0 +1,84 @@
+//! Omaha specific code.
+//!
+//! In Omaha every player is dealt four hole cards and the best hand must be
+//! made from exactly two of them plus exactly three community cards. That
+//! means the normal seven card ranking in `Rankable::rank` can't be used.
+//!
+//! ```
+//! use rs_poker::core::{FlatHand, Rank};
+//! use rs_poker::omaha::rank_omaha;
+//!
+//! let hole = FlatHand::new_from_str("As2hKdQc").unwrap();
+//! let board = FlatHand::new_from_str("2s3s4s5s9d").unwrap();
+//! // With four spades on the board a single Ace of spades isn't a flush.
+//! let rank = rank_omaha(&hole[..], &board[..]).unwrap();
+//! assert!(matches!(rank, Rank::Straight(_)));
+//! ```
+use crate::core::{Card, CardIter, Rank, Rankable};
+
+/// The number of hole cards each player is dealt in Omaha.
+pub const OMAHA_HOLE_CARDS: usize = 4;
+
+/// Find the best five card hand using exactly two of the `hole_cards` and
+/// exactly three of the `board` cards.
+///
+/// Returns `None` if there are fewer than two hole cards or fewer than three
+/// board cards, since no legal Omaha hand can be made.
+pub fn rank_omaha(hole_cards: &[Card], board: &[Card]) -> Option<Rank> {
+    if hole_cards.len() < 2 || board.len() < 3 {
+        return None;
+    }
+
+    let mut five = Vec::with_capacity(5);
+    CardIter::new(hole_cards, 2)
+        .flat_map(|hole| CardIter::new(board, 3).map(move |community| (hole.clone(), community)))
+        .map(|(hole, community)| {
+            five.clear();
+            five.extend(hole);
+            five.extend(community);
+            five.rank_five()
+        })
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::FlatHand;
+
+    fn cards(s: &str) -> Vec<Card> {
+        FlatHand::new_from_str(s).unwrap().iter().copied().collect()
+    }
+
+    #[test]
+    fn test_must_use_two_hole_cards() {
+        // The board has a straight but Omaha needs two hole cards.
+        let rank = rank_omaha(&cards("2c2d7h8h"), &cards("9sTsJsQsKd")).unwrap();
+        assert!(matches!(rank, Rank::Straight(_)));
+        // The straight uses 8 and 7 from the hand with 9 T J from the board.
+        assert!(rank < rank_omaha(&cards("AcKc7h8h"), &cards("9sTsJsQsKd")).unwrap());
+    }
+
+    #[test]
+    fn test_board_flush_needs_two_suited_hole_cards() {
+        let board = cards("2s5s8sJsKd");
+        let one_spade = rank_omaha(&cards("AsKhQhQd"), &board).unwrap();
+        assert!(!matches!(one_spade, Rank::Flush(_)));
+
+        let two_spades = rank_omaha(&cards("As3sQhQd"), &board).unwrap();
+        assert!(matches!(two_spades, Rank::Flush(_)));
+    }
+
+    #[test]
+    fn test_can_not_play_three_hole_cards() {
+        // Only two of the three aces in hand can play.
+        let rank = rank_omaha(&cards("AsAhAd2c"), &cards("KsKhKd7c3h")).unwrap();
+        assert!(matches!(rank, Rank::FullHouse(_)));
+    }
+
+    #[test]
+    fn test_not_enough_cards() {
+        assert_eq!(None, rank_omaha(&cards("AsAh"), &cards("KsKh")));
+        assert_eq!(None, rank_omaha(&cards("As"), &cards("KsKhKd")));
+    }
+}
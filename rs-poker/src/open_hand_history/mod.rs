@@ -2,8 +2,12 @@
 //! `rs_poker`. It includes parsing, serialization, and deserialization of
 //! hand histories in the open format.
 mod hand_history;
+mod pokerstars;
+mod reader;
 mod serde_utils;
 mod writer;
 
 pub use hand_history::*;
+pub use pokerstars::*;
+pub use reader::*;
 pub use writer::*;
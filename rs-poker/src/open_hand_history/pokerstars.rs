@@ -0,0 +1,1103 @@
+//! Reading and writing the plain text hand history format used by
+//! PokerStars, which most third party tools understand.
+//!
+//! Hands are parsed into [`HandHistory`] so they can be handled like any
+//! other Open Hand History. Players get ids in the order of their seat
+//! lines. Bets returned uncalled are paid back to the bettor as a pot of
+//! their own, since the pot always holds everything that was put in.
+//!
+//! Only the times of hands played in UTC are kept.
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::Path;
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use thiserror::Error;
+
+use super::hand_history::{
+    Action, ActionObj, BetLimitObj, BetType, GameType, HandHistory, PlayerObj, PlayerWinsObj,
+    PotObj, RoundObj, SpeedObj, SpeedType, TournamentInfoObj, TournamentType,
+};
+use crate::core::Card;
+
+const ALL_IN: &str = " and is all-in";
+const DATE_FORMAT: &str = "%Y/%m/%d %H:%M:%S";
+
+#[derive(Error, Debug)]
+pub enum PokerStarsError {
+    #[error("No hand header found")]
+    MissingHeader,
+
+    #[error("Invalid hand header: {0}")]
+    InvalidHeader(String),
+
+    #[error("Invalid table line: {0}")]
+    InvalidTable(String),
+
+    #[error("Unsupported game: {0}")]
+    UnsupportedGame(String),
+
+    #[error("Invalid amount: {0}")]
+    InvalidAmount(String),
+
+    #[error("Invalid cards: {0}")]
+    InvalidCards(String),
+
+    #[error("Unknown player in: {0}")]
+    UnknownPlayer(String),
+
+    #[error("Can't understand line: {0}")]
+    InvalidLine(String),
+}
+
+/// Parse every hand in a PokerStars hand history file.
+pub fn parse_pokerstars_hands(text: &str) -> Result<Vec<HandHistory>, PokerStarsError> {
+    let mut hands: Vec<Vec<&str>> = vec![];
+    for line in text.lines() {
+        // Files often start with a byte order mark.
+        let line = line.trim_start_matches('\u{feff}').trim_end();
+        if is_header(line) {
+            hands.push(vec![]);
+        }
+        if let Some(hand) = hands.last_mut() {
+            hand.push(line);
+        }
+    }
+    hands.iter().map(|lines| parse_lines(lines)).collect()
+}
+
+/// Parse a single PokerStars hand.
+pub fn parse_pokerstars_hand(text: &str) -> Result<HandHistory, PokerStarsError> {
+    parse_pokerstars_hands(text)?
+        .pop()
+        .ok_or(PokerStarsError::MissingHeader)
+}
+
+/// Write a hand in the PokerStars format.
+pub fn write_pokerstars_hand(hand: &HandHistory) -> String {
+    Writer::new(hand).write()
+}
+
+/// Appends a hand to a file in the PokerStars format, with blank lines
+/// between hands.
+pub fn append_pokerstars_hand(path: &Path, hand: &HandHistory) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    write!(file, "{}", write_pokerstars_hand(hand))?;
+    writeln!(file)?;
+    writeln!(file)?;
+    Ok(())
+}
+
+fn is_header(line: &str) -> bool {
+    line.starts_with("PokerStars ") && line.contains('#')
+}
+
+fn is_post(action: &Action) -> bool {
+    matches!(
+        action,
+        Action::PostAnte
+            | Action::PostSmallBlind
+            | Action::PostBigBlind
+            | Action::Straddle
+            | Action::PostDead
+            | Action::PostExtraBlind
+    )
+}
+
+/// The part of posting the small and big blinds together that counts
+/// towards the player's bet. Only the big blind is live, the small blind
+/// is dead money.
+fn live_blind(amount: f32, big_blind: f32) -> f32 {
+    if big_blind > 0.0 {
+        amount.min(big_blind)
+    } else {
+        amount
+    }
+}
+
+fn shown(cards: &[Card]) -> String {
+    cards
+        .iter()
+        .map(|card| card.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn parse_amount(s: &str) -> Result<f32, PokerStarsError> {
+    s.trim()
+        .trim_start_matches(['$', '€', '£'])
+        .replace(',', "")
+        .parse()
+        .map_err(|_| PokerStarsError::InvalidAmount(s.to_string()))
+}
+
+/// Parse the cards in the last `[...]` of a line.
+fn parse_cards(s: &str) -> Result<Vec<Card>, PokerStarsError> {
+    let invalid = || PokerStarsError::InvalidCards(s.to_string());
+    let start = s.rfind('[').ok_or_else(invalid)?;
+    let end = s[start..].find(']').ok_or_else(invalid)? + start;
+    s[start + 1..end]
+        .split_whitespace()
+        .map(|card| Card::try_from(card).map_err(|_| invalid()))
+        .collect()
+}
+
+/// Everything in the first line of a hand.
+struct Header {
+    game_number: String,
+    tournament_info: Option<TournamentInfoObj>,
+    game_type: GameType,
+    bet_type: BetType,
+    small_blind: f32,
+    big_blind: f32,
+    currency: String,
+    start_date_utc: Option<chrono::DateTime<Utc>>,
+}
+
+fn parse_header(line: &str) -> Result<Header, PokerStarsError> {
+    let invalid = || PokerStarsError::InvalidHeader(line.to_string());
+    let (_, rest) = line.split_once('#').ok_or_else(invalid)?;
+    let (game_number, rest) = rest.split_once(':').ok_or_else(invalid)?;
+    let rest = rest.trim();
+
+    let tournament_info = match rest.strip_prefix("Tournament #") {
+        Some(tournament) => {
+            let (number, _) = tournament.split_once(',').ok_or_else(invalid)?;
+            Some(TournamentInfoObj {
+                tournament_number: number.trim().to_string(),
+                name: String::new(),
+                start_date_utc: None,
+                currency: String::new(),
+                buyin_amount: 0.0,
+                fee_amount: 0.0,
+                bounty_fee_amount: 0.0,
+                initial_stack: 0,
+                tournament_type: TournamentType::MultiTableTournament,
+                flags: None,
+                speed: SpeedObj {
+                    speed_type: SpeedType::Normal,
+                    round_time: 0,
+                },
+            })
+        }
+        None => None,
+    };
+
+    let game_type = if rest.contains("Omaha Hi/Lo") {
+        GameType::OmahaHiLo
+    } else if rest.contains("Omaha") {
+        GameType::Omaha
+    } else if rest.contains("Hold'em") || rest.contains("Holdem") {
+        GameType::Holdem
+    } else if rest.contains("Stud Hi/Lo") {
+        GameType::StudHiLo
+    } else if rest.contains("Stud") {
+        GameType::Stud
+    } else if rest.contains("Draw") {
+        GameType::Draw
+    } else {
+        return Err(PokerStarsError::UnsupportedGame(rest.to_string()));
+    };
+    let bet_type = if rest.contains("No Limit") {
+        BetType::NoLimit
+    } else if rest.contains("Pot Limit") {
+        BetType::PotLimit
+    } else {
+        BetType::FixedLimit
+    };
+
+    // The stakes are the first thing in brackets with a slash.
+    let stakes = rest
+        .split('(')
+        .skip(1)
+        .filter_map(|part| part.split_once(')'))
+        .map(|(stakes, _)| stakes)
+        .find(|stakes| stakes.contains('/'))
+        .ok_or_else(invalid)?;
+    let (small_blind, big_blind) = stakes
+        .split_whitespace()
+        .next()
+        .and_then(|blinds| blinds.split_once('/'))
+        .ok_or_else(invalid)?;
+    let currency = if stakes.contains('$') || stakes.contains("USD") {
+        "USD"
+    } else if stakes.contains('€') || stakes.contains("EUR") {
+        "EUR"
+    } else if stakes.contains('£') || stakes.contains("GBP") {
+        "GBP"
+    } else {
+        ""
+    };
+
+    let start_date_utc = rest.split(" - ").skip(1).find_map(|part| {
+        let mut words = part.trim_start_matches('[').split_whitespace();
+        let date = words.next()?;
+        let time = words.next()?;
+        if !matches!(words.next()?.trim_end_matches(']'), "UTC" | "GMT") {
+            return None;
+        }
+        let date = NaiveDateTime::parse_from_str(&format!("{date} {time}"), DATE_FORMAT).ok()?;
+        Some(Utc.from_utc_datetime(&date))
+    });
+
+    Ok(Header {
+        game_number: game_number.trim().to_string(),
+        tournament_info,
+        game_type,
+        bet_type,
+        small_blind: parse_amount(small_blind)?,
+        big_blind: parse_amount(big_blind)?,
+        currency: currency.to_string(),
+        start_date_utc,
+    })
+}
+
+/// Turns the lines of one hand into a hand history.
+struct Parser {
+    hand: HandHistory,
+    /// What each player has put in on the current street.
+    street_bets: Vec<f32>,
+    /// Winnings from each pot, numbered from the main pot.
+    pot_wins: BTreeMap<u64, Vec<PlayerWinsObj>>,
+    returned: Vec<(u64, f32)>,
+    rake: f32,
+    in_summary: bool,
+}
+
+fn parse_lines(lines: &[&str]) -> Result<HandHistory, PokerStarsError> {
+    let header = parse_header(lines[0])?;
+    let table = lines
+        .get(1)
+        .ok_or_else(|| PokerStarsError::InvalidTable(String::new()))?;
+    let (table_name, table_size, dealer_seat) = parse_table(table)?;
+
+    let mut parser = Parser {
+        hand: HandHistory {
+            spec_version: "1.4.7".to_string(),
+            site_name: "PokerStars".to_string(),
+            network_name: "PokerStars".to_string(),
+            internal_version: env!("CARGO_PKG_VERSION").to_string(),
+            tournament: header.tournament_info.is_some(),
+            tournament_info: header.tournament_info,
+            game_number: header.game_number,
+            start_date_utc: header.start_date_utc,
+            table_name,
+            table_handle: None,
+            table_skin: None,
+            game_type: header.game_type,
+            bet_limit: Some(BetLimitObj {
+                bet_type: header.bet_type,
+                bet_cap: 0.0,
+            }),
+            table_size,
+            currency: header.currency,
+            dealer_seat,
+            small_blind_amount: header.small_blind,
+            big_blind_amount: header.big_blind,
+            ante_amount: 0.0,
+            hero_player_id: None,
+            players: vec![],
+            rounds: vec![RoundObj {
+                id: 0,
+                street: "Preflop".to_string(),
+                cards: None,
+                actions: vec![],
+            }],
+            pots: vec![],
+            tournament_bounties: None,
+        },
+        street_bets: vec![],
+        pot_wins: BTreeMap::new(),
+        returned: vec![],
+        rake: 0.0,
+        in_summary: false,
+    };
+    for line in &lines[2..] {
+        parser.parse_line(line)?;
+    }
+    Ok(parser.finish())
+}
+
+fn parse_table(line: &str) -> Result<(String, u64, u64), PokerStarsError> {
+    let invalid = || PokerStarsError::InvalidTable(line.to_string());
+    let rest = line.strip_prefix("Table '").ok_or_else(invalid)?;
+    let end = rest.rfind('\'').ok_or_else(invalid)?;
+    let name = rest[..end].to_string();
+    let mut table_size = 0;
+    let mut dealer_seat = None;
+    for word in rest[end + 1..].split_whitespace() {
+        if let Some(size) = word.strip_suffix("-max") {
+            table_size = size.parse().map_err(|_| invalid())?;
+        } else if let Some(seat) = word.strip_prefix('#') {
+            dealer_seat = Some(seat.parse().map_err(|_| invalid())?);
+        }
+    }
+    Ok((name, table_size, dealer_seat.ok_or_else(invalid)?))
+}
+
+impl Parser {
+    fn parse_line(&mut self, line: &str) -> Result<(), PokerStarsError> {
+        if line.is_empty() {
+            return Ok(());
+        }
+        if let Some(street) = line.strip_prefix("*** ") {
+            return self.parse_street(street, line);
+        }
+        if self.in_summary {
+            return self.parse_summary(line);
+        }
+        if let Some(seat) = line
+            .strip_prefix("Seat ")
+            .filter(|_| line.contains(" in chips"))
+        {
+            return self.parse_seat(seat, line);
+        }
+        if let Some(dealt) = line.strip_prefix("Dealt to ") {
+            let (id, _) = self.player_prefix(dealt, " [", line)?;
+            let cards = parse_cards(line)?;
+            self.push_action(id, Action::DealtCards, 0.0, false, Some(cards));
+            return Ok(());
+        }
+        if let Some(uncalled) = line.strip_prefix("Uncalled bet (") {
+            let (amount, player) = uncalled
+                .split_once(") returned to ")
+                .ok_or_else(|| PokerStarsError::InvalidLine(line.to_string()))?;
+            let (id, _) = self.player_prefix(player, "", line)?;
+            self.returned.push((id, parse_amount(amount)?));
+            return Ok(());
+        }
+        if let Ok((id, collected)) = self.player_prefix(line, " collected ", line) {
+            let (amount, pot) = collected
+                .split_once(" from ")
+                .ok_or_else(|| PokerStarsError::InvalidLine(line.to_string()))?;
+            let number = match pot.split_once("side pot") {
+                Some((_, side)) => side.trim_start_matches('-').parse().unwrap_or(1),
+                None => 0,
+            };
+            self.pot_wins
+                .entry(number)
+                .or_default()
+                .push(PlayerWinsObj {
+                    player_id: id,
+                    win_amount: parse_amount(amount)?,
+                    cashout_amount: None,
+                    cashout_fee: None,
+                    bonus_amount: None,
+                    contributed_rake: None,
+                });
+            return Ok(());
+        }
+        match self.player_prefix(line, ": ", line) {
+            Ok((id, action)) => self.parse_action(id, action, line),
+            // Players joining, leaving and the like.
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn parse_street(&mut self, street: &str, line: &str) -> Result<(), PokerStarsError> {
+        let name = street.split(" ***").next().unwrap_or_default();
+        let (street, cards) = match name {
+            "HOLE CARDS" | "DEALING HANDS" => return Ok(()),
+            "FLOP" => ("Flop", Some(parse_cards(line)?)),
+            "TURN" => ("Turn", Some(parse_cards(line)?)),
+            "RIVER" => ("River", Some(parse_cards(line)?)),
+            "SHOW DOWN" => ("Showdown", None),
+            "SUMMARY" => {
+                self.in_summary = true;
+                return Ok(());
+            }
+            _ => return Err(PokerStarsError::UnsupportedGame(line.to_string())),
+        };
+        self.hand.rounds.push(RoundObj {
+            id: self.hand.rounds.len() as u64,
+            street: street.to_string(),
+            cards,
+            actions: vec![],
+        });
+        self.street_bets.fill(0.0);
+        Ok(())
+    }
+
+    fn parse_seat(&mut self, seat: &str, line: &str) -> Result<(), PokerStarsError> {
+        let invalid = || PokerStarsError::InvalidLine(line.to_string());
+        let (number, player) = seat.split_once(": ").ok_or_else(invalid)?;
+        let chips = player.find(" in chips").ok_or_else(invalid)?;
+        let open = player[..chips].rfind(" (").ok_or_else(invalid)?;
+        let stack = parse_amount(&player[open + 2..chips])?;
+        self.hand.players.push(PlayerObj {
+            id: self.hand.players.len() as u64,
+            seat: number.parse().map_err(|_| invalid())?,
+            name: player[..open].to_string(),
+            display: None,
+            starting_stack: stack,
+            player_bounty: None,
+            is_sitting_out: player.ends_with("is sitting out").then_some(true),
+        });
+        self.street_bets.push(0.0);
+        Ok(())
+    }
+
+    fn parse_action(&mut self, id: u64, action: &str, line: &str) -> Result<(), PokerStarsError> {
+        let is_allin = action.ends_with(ALL_IN);
+        let action = action.trim_end_matches(ALL_IN);
+        let (kind, amount) = action.split_once(' ').unwrap_or((action, ""));
+        let (action, amount) = match kind {
+            "folds" => {
+                let cards = action
+                    .contains('[')
+                    .then(|| parse_cards(action))
+                    .transpose()?;
+                self.push_action(id, Action::Fold, 0.0, false, cards);
+                return Ok(());
+            }
+            "checks" => (Action::Check, 0.0),
+            "calls" => (Action::Call, parse_amount(amount)?),
+            "bets" => (Action::Bet, parse_amount(amount)?),
+            "raises" => {
+                let (_, to) = amount
+                    .split_once(" to ")
+                    .ok_or_else(|| PokerStarsError::InvalidLine(line.to_string()))?;
+                let to = parse_amount(to)?;
+                (Action::Raise, to - self.street_bets[id as usize])
+            }
+            "posts" => {
+                let (kind, amount) = amount
+                    .rsplit_once(' ')
+                    .ok_or_else(|| PokerStarsError::InvalidLine(line.to_string()))?;
+                let action = match kind {
+                    "small blind" => Action::PostSmallBlind,
+                    "big blind" => Action::PostBigBlind,
+                    "the ante" => Action::PostAnte,
+                    "small & big blinds" => Action::PostDead,
+                    "straddle" => Action::Straddle,
+                    _ => return Err(PokerStarsError::InvalidLine(line.to_string())),
+                };
+                let amount = parse_amount(amount)?;
+                match action {
+                    Action::PostAnte => {
+                        self.hand.ante_amount = self.hand.ante_amount.max(amount);
+                    }
+                    Action::PostDead => {
+                        self.street_bets[id as usize] +=
+                            live_blind(amount, self.hand.big_blind_amount);
+                    }
+                    _ => self.street_bets[id as usize] += amount,
+                }
+                self.push_action(id, action, amount, is_allin, None);
+                return Ok(());
+            }
+            "shows" | "mucks" => {
+                let cards = action
+                    .contains('[')
+                    .then(|| parse_cards(action))
+                    .transpose()?;
+                let action = if kind == "shows" {
+                    Action::ShowsCards
+                } else {
+                    Action::MucksCards
+                };
+                self.push_action(id, action, 0.0, false, cards);
+                return Ok(());
+            }
+            // Chat, time outs, sitting out and not showing change nothing.
+            "said," | "is" | "has" | "sits" | "doesn't" | "leaves" | "joins" | "will" => {
+                return Ok(());
+            }
+            _ => return Err(PokerStarsError::InvalidLine(line.to_string())),
+        };
+        self.street_bets[id as usize] += amount;
+        self.push_action(id, action, amount, is_allin, None);
+        Ok(())
+    }
+
+    fn parse_summary(&mut self, line: &str) -> Result<(), PokerStarsError> {
+        if let Some((_, rake)) = line.split_once("| Rake ") {
+            let rake = rake.split_whitespace().next().unwrap_or_default();
+            self.rake = parse_amount(rake)?;
+        } else if let Some(seat) = line.strip_prefix("Seat ") {
+            // Cards shown or mucked that weren't in the showdown.
+            let Some((_, player)) = seat.split_once(": ") else {
+                return Ok(());
+            };
+            let (id, rest) = self.player_prefix(player, "", line)?;
+            let action = if rest.contains(" showed [") {
+                Action::ShowsCards
+            } else if rest.contains(" mucked [") {
+                Action::MucksCards
+            } else {
+                return Ok(());
+            };
+            let shown = self.hand.rounds.iter().flat_map(|r| &r.actions).any(|a| {
+                a.player_id == id && matches!(a.action, Action::ShowsCards | Action::MucksCards)
+            });
+            if !shown {
+                let cards = parse_cards(rest)?;
+                if self
+                    .hand
+                    .rounds
+                    .last()
+                    .is_none_or(|r| r.street != "Showdown")
+                {
+                    self.hand.rounds.push(RoundObj {
+                        id: self.hand.rounds.len() as u64,
+                        street: "Showdown".to_string(),
+                        cards: None,
+                        actions: vec![],
+                    });
+                }
+                self.push_action(id, action, 0.0, false, Some(cards));
+            }
+        }
+        Ok(())
+    }
+
+    /// Find the player whose name starts `s` followed by `separator`,
+    /// returning their id and the rest of `s`.
+    fn player_prefix<'s>(
+        &self,
+        s: &'s str,
+        separator: &str,
+        line: &str,
+    ) -> Result<(u64, &'s str), PokerStarsError> {
+        self.hand
+            .players
+            .iter()
+            .filter(|player| {
+                s.strip_prefix(player.name.as_str())
+                    .is_some_and(|rest| rest.starts_with(separator))
+            })
+            .max_by_key(|player| player.name.len())
+            .map(|player| (player.id, &s[player.name.len() + separator.len()..]))
+            .ok_or_else(|| PokerStarsError::UnknownPlayer(line.to_string()))
+    }
+
+    fn push_action(
+        &mut self,
+        player_id: u64,
+        action: Action,
+        amount: f32,
+        is_allin: bool,
+        cards: Option<Vec<Card>>,
+    ) {
+        let round = self.hand.rounds.last_mut().unwrap();
+        round.actions.push(ActionObj {
+            action_number: round.actions.len() as u64 + 1,
+            player_id,
+            action,
+            amount,
+            is_allin,
+            cards,
+        });
+    }
+
+    fn finish(mut self) -> HandHistory {
+        let mut pots: Vec<PotObj> = self
+            .pot_wins
+            .into_values()
+            .enumerate()
+            .map(|(number, player_wins)| PotObj {
+                number: number as u64,
+                amount: player_wins.iter().map(|win| win.win_amount).sum(),
+                rake: None,
+                jackpot: None,
+                player_wins,
+            })
+            .collect();
+        if let Some(main) = pots.first_mut().filter(|_| self.rake > 0.0) {
+            main.amount += self.rake;
+            main.rake = Some(self.rake);
+        }
+        for (player_id, amount) in self.returned {
+            pots.push(PotObj {
+                number: pots.len() as u64,
+                amount,
+                rake: None,
+                jackpot: None,
+                player_wins: vec![PlayerWinsObj {
+                    player_id,
+                    win_amount: amount,
+                    cashout_amount: None,
+                    cashout_fee: None,
+                    bonus_amount: None,
+                    contributed_rake: None,
+                }],
+            });
+        }
+        self.hand.pots = pots;
+
+        if self.hand.table_size == 0 {
+            self.hand.table_size = self.hand.players.len() as u64;
+        }
+        let mut dealt = self
+            .hand
+            .rounds
+            .iter()
+            .flat_map(|round| &round.actions)
+            .filter(|action| action.action == Action::DealtCards);
+        if let (Some(hero), None) = (dealt.next(), dealt.next()) {
+            self.hand.hero_player_id = Some(hero.player_id);
+        }
+        self.hand
+    }
+}
+
+/// Writes a hand history as PokerStars text.
+struct Writer<'a> {
+    hand: &'a HandHistory,
+    out: String,
+}
+
+impl<'a> Writer<'a> {
+    fn new(hand: &'a HandHistory) -> Self {
+        Self {
+            hand,
+            out: String::new(),
+        }
+    }
+
+    fn money(&self, amount: f32) -> String {
+        // Money and chips are written in hundredths, which also hides float
+        // error from working out raises.
+        let cents = (amount * 100.0).round() / 100.0;
+        match self.hand.currency.as_str() {
+            "USD" => format!("${cents}"),
+            "EUR" => format!("€{cents}"),
+            "GBP" => format!("£{cents}"),
+            _ => cents.to_string(),
+        }
+    }
+
+    fn name(&self, player_id: u64) -> String {
+        self.hand
+            .players
+            .iter()
+            .find(|player| player.id == player_id)
+            .map(|player| player.name.clone())
+            .unwrap_or_else(|| format!("Player {player_id}"))
+    }
+
+    fn write(mut self) -> String {
+        let hand = self.hand;
+        self.header();
+        for player in &hand.players {
+            let sitting_out = if player.is_sitting_out == Some(true) {
+                " is sitting out"
+            } else {
+                ""
+            };
+            let line = format!(
+                "Seat {}: {} ({} in chips){sitting_out}",
+                player.seat,
+                player.name,
+                self.money(player.starting_stack)
+            );
+            self.line(line);
+        }
+
+        let mut board: Vec<Card> = vec![];
+        // Where each player folded, if they did.
+        let mut folded: BTreeMap<u64, String> = BTreeMap::new();
+        for round in &hand.rounds {
+            let cards = round.cards.clone().unwrap_or_default();
+            match round.street.as_str() {
+                "Preflop" => {}
+                "Showdown" => self.line("*** SHOW DOWN ***".to_string()),
+                street if board.is_empty() => {
+                    self.line(format!(
+                        "*** {} *** [{}]",
+                        street.to_uppercase(),
+                        shown(&cards)
+                    ));
+                }
+                street => self.line(format!(
+                    "*** {} *** [{}] [{}]",
+                    street.to_uppercase(),
+                    shown(&board),
+                    shown(&cards)
+                )),
+            }
+            board.extend(cards);
+
+            // Blinds and antes come before the hole cards are dealt.
+            let preflop = round.street == "Preflop";
+            let mut actions: Vec<&ActionObj> = round.actions.iter().collect();
+            if preflop {
+                actions.sort_by_key(|action| match action.action {
+                    Action::DealtCards => 1,
+                    _ if is_post(&action.action) => 0,
+                    _ => 2,
+                });
+            }
+            let mut hole_cards_written = !preflop;
+
+            let mut street_bets: BTreeMap<u64, f32> = BTreeMap::new();
+            let mut bet = 0.0;
+            for action in actions {
+                if !hole_cards_written && !is_post(&action.action) {
+                    hole_cards_written = true;
+                    self.line("*** HOLE CARDS ***".to_string());
+                }
+                let name = self.name(action.player_id);
+                let amount = self.money(action.amount);
+                let player_bet = street_bets.entry(action.player_id).or_default();
+                let all_in = if action.is_allin { ALL_IN } else { "" };
+                let line = match action.action {
+                    Action::DealtCards => {
+                        let cards = action.cards.as_deref().unwrap_or_default();
+                        format!("Dealt to {name} [{}]", shown(cards))
+                    }
+                    Action::ShowsCards => {
+                        let cards = action.cards.as_deref().unwrap_or_default();
+                        format!("{name}: shows [{}]", shown(cards))
+                    }
+                    Action::MucksCards => match action.cards.as_deref() {
+                        Some(cards) => format!("{name}: mucks [{}]", shown(cards)),
+                        None => format!("{name}: mucks hand"),
+                    },
+                    Action::PostAnte => format!("{name}: posts the ante {amount}{all_in}"),
+                    Action::PostSmallBlind
+                    | Action::PostBigBlind
+                    | Action::Straddle
+                    | Action::PostDead
+                    | Action::PostExtraBlind => {
+                        *player_bet += if action.action == Action::PostDead {
+                            live_blind(action.amount, hand.big_blind_amount)
+                        } else {
+                            action.amount
+                        };
+                        bet = f32::max(bet, *player_bet);
+                        let kind = match action.action {
+                            Action::PostSmallBlind => "small blind",
+                            Action::PostBigBlind => "big blind",
+                            Action::Straddle => "straddle",
+                            _ => "small & big blinds",
+                        };
+                        format!("{name}: posts {kind} {amount}{all_in}")
+                    }
+                    Action::Fold => {
+                        folded.insert(action.player_id, round.street.clone());
+                        format!("{name}: folds")
+                    }
+                    Action::Check => format!("{name}: checks"),
+                    Action::Call => {
+                        *player_bet += action.amount;
+                        format!("{name}: calls {amount}{all_in}")
+                    }
+                    Action::Bet => {
+                        *player_bet += action.amount;
+                        bet = *player_bet;
+                        format!("{name}: bets {amount}{all_in}")
+                    }
+                    Action::Raise => {
+                        *player_bet += action.amount;
+                        let raise = self.money(*player_bet - bet);
+                        let to = self.money(*player_bet);
+                        bet = f32::max(bet, *player_bet);
+                        format!("{name}: raises {raise} to {to}{all_in}")
+                    }
+                    Action::AddedChips
+                    | Action::SitsDown
+                    | Action::StandsUp
+                    | Action::AddedToPot => continue,
+                };
+                self.line(line);
+            }
+            if !hole_cards_written {
+                self.line("*** HOLE CARDS ***".to_string());
+            }
+        }
+
+        let multiple_pots = hand.pots.len() > 1;
+        for pot in &hand.pots {
+            let from = match pot.number {
+                _ if !multiple_pots => "pot".to_string(),
+                0 => "main pot".to_string(),
+                number => format!("side pot-{number}"),
+            };
+            for win in &pot.player_wins {
+                let line = format!(
+                    "{} collected {} from {from}",
+                    self.name(win.player_id),
+                    self.money(win.win_amount)
+                );
+                self.line(line);
+            }
+        }
+
+        self.summary(&board, &folded);
+        self.out
+    }
+
+    fn header(&mut self) {
+        let hand = self.hand;
+        let game = match hand.game_type {
+            GameType::Holdem => "Hold'em",
+            GameType::Omaha => "Omaha",
+            GameType::OmahaHiLo => "Omaha Hi/Lo",
+            GameType::Stud => "7 Card Stud",
+            GameType::StudHiLo => "7 Card Stud Hi/Lo",
+            GameType::Draw => "5 Card Draw",
+        };
+        let limit = match hand.bet_limit.as_ref().map(|limit| &limit.bet_type) {
+            Some(BetType::PotLimit) => "Pot Limit",
+            Some(BetType::FixedLimit) => "Limit",
+            Some(BetType::NoLimit) | None => "No Limit",
+        };
+        let stakes = format!(
+            "{}/{}",
+            self.money(hand.small_blind_amount),
+            self.money(hand.big_blind_amount)
+        );
+        let mut line = format!("PokerStars Hand #{}: ", hand.game_number);
+        match &hand.tournament_info {
+            Some(info) => {
+                let _ = write!(
+                    line,
+                    "Tournament #{}, {game} {limit} - Level I ({stakes})",
+                    info.tournament_number
+                );
+            }
+            None => {
+                let _ = write!(line, "{game} {limit} ({stakes})");
+            }
+        }
+        if let Some(date) = hand.start_date_utc {
+            let _ = write!(line, " - {} UTC", date.format(DATE_FORMAT));
+        }
+        self.line(line);
+        self.line(format!(
+            "Table '{}' {}-max Seat #{} is the button",
+            hand.table_name, hand.table_size, hand.dealer_seat
+        ));
+    }
+
+    fn summary(&mut self, board: &[Card], folded: &BTreeMap<u64, String>) {
+        let hand = self.hand;
+        self.line("*** SUMMARY ***".to_string());
+        let total: f32 = hand.pots.iter().map(|pot| pot.amount).sum();
+        let rake: f32 = hand.pots.iter().filter_map(|pot| pot.rake).sum();
+        self.line(format!(
+            "Total pot {} | Rake {}",
+            self.money(total),
+            self.money(rake)
+        ));
+        if !board.is_empty() {
+            self.line(format!("Board [{}]", shown(board)));
+        }
+
+        let actions: Vec<&ActionObj> = hand.rounds.iter().flat_map(|r| &r.actions).collect();
+        for player in &hand.players {
+            let mut line = format!("Seat {}: {}", player.seat, player.name);
+            if player.seat == hand.dealer_seat {
+                line.push_str(" (button)");
+            }
+            let posted = |kind: Action| {
+                actions
+                    .iter()
+                    .any(|a| a.player_id == player.id && a.action == kind)
+            };
+            if posted(Action::PostSmallBlind) {
+                line.push_str(" (small blind)");
+            }
+            if posted(Action::PostBigBlind) {
+                line.push_str(" (big blind)");
+            }
+            let won: f32 = hand
+                .pots
+                .iter()
+                .flat_map(|pot| &pot.player_wins)
+                .filter(|win| win.player_id == player.id)
+                .map(|win| win.win_amount)
+                .sum();
+            let showed = actions
+                .iter()
+                .find(|a| a.player_id == player.id && a.action == Action::ShowsCards)
+                .and_then(|a| a.cards.as_deref());
+            match (folded.get(&player.id), showed) {
+                (Some(street), _) if street == "Preflop" => line.push_str(" folded before Flop"),
+                (Some(street), _) => {
+                    let _ = write!(line, " folded on the {street}");
+                }
+                (None, Some(cards)) if won > 0.0 => {
+                    let _ = write!(
+                        line,
+                        " showed [{}] and won ({})",
+                        shown(cards),
+                        self.money(won)
+                    );
+                }
+                (None, Some(cards)) => {
+                    let _ = write!(line, " showed [{}] and lost", shown(cards));
+                }
+                (None, None) if won > 0.0 => {
+                    let _ = write!(line, " collected ({})", self.money(won));
+                }
+                (None, None) => line.push_str(" mucked"),
+            }
+            self.line(line);
+        }
+    }
+
+    fn line(&mut self, line: String) {
+        self.out.push_str(&line);
+        self.out.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HAND: &str = "PokerStars Hand #230455892041:  Hold'em No Limit ($0.05/$0.10 USD) - 2021/09/14 19:18:43 UTC
+Table 'Aaltje II' 6-max Seat #2 is the button
+Seat 1: alice (10.20 in chips)
+Seat 2: bob smith (9.85 in chips)
+Seat 4: carol (10 in chips)
+Seat 6: dave (4 in chips) is sitting out
+carol: posts small blind $0.05
+alice: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to alice [Ah Kd]
+bob smith: raises $0.20 to $0.30
+carol: folds
+alice: calls $0.20
+*** FLOP *** [Th 7c 2d]
+alice: checks
+bob smith: bets $0.45
+alice: raises $1.05 to $1.50
+bob smith: calls $1.05
+*** TURN *** [Th 7c 2d] [Ks]
+alice: bets $8.40 and is all-in
+bob smith: folds
+Uncalled bet ($8.40) returned to alice
+alice collected $3.43 from pot
+*** SUMMARY ***
+Total pot $3.65 | Rake $0.22
+Board [Th 7c 2d Ks]
+Seat 1: alice (big blind) collected ($3.43)
+Seat 2: bob smith (button) folded on the Turn
+Seat 4: carol (small blind) folded before Flop
+Seat 6: dave is sitting out
+";
+
+    #[test]
+    fn test_parse_hand() {
+        let hand = parse_pokerstars_hand(HAND).unwrap();
+        assert_eq!("230455892041", hand.game_number);
+        assert_eq!("Aaltje II", hand.table_name);
+        assert_eq!(6, hand.table_size);
+        assert_eq!(2, hand.dealer_seat);
+        assert_eq!("USD", hand.currency);
+        assert_eq!(0.05, hand.small_blind_amount);
+        assert_eq!(0.1, hand.big_blind_amount);
+        assert_eq!(GameType::Holdem, hand.game_type);
+        assert!(hand.start_date_utc.is_some());
+
+        let names: Vec<&str> = hand.players.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(vec!["alice", "bob smith", "carol", "dave"], names);
+        assert_eq!(Some(true), hand.players[3].is_sitting_out);
+        assert_eq!(Some(0), hand.hero_player_id);
+
+        let streets: Vec<&str> = hand.rounds.iter().map(|r| r.street.as_str()).collect();
+        assert_eq!(vec!["Preflop", "Flop", "Turn"], streets);
+        let flop = &hand.rounds[1];
+        assert_eq!(3, flop.cards.as_ref().unwrap().len());
+        // Raising to 1.50 after calling 0.45 puts in 1.50.
+        assert_eq!(Action::Raise, flop.actions[2].action);
+        assert!((flop.actions[2].amount - 1.5).abs() < 1e-6);
+        assert!(hand.rounds[2].actions[0].is_allin);
+
+        // The main pot with rake and the uncalled bet.
+        assert_eq!(2, hand.pots.len());
+        assert_eq!(Some(0.22), hand.pots[0].rake);
+        assert_eq!(8.4, hand.pots[1].player_wins[0].win_amount);
+    }
+
+    #[test]
+    fn test_write_and_parse() {
+        let hand = parse_pokerstars_hand(HAND).unwrap();
+        let text = write_pokerstars_hand(&hand);
+        assert!(text.starts_with("PokerStars Hand #230455892041: Hold'em No Limit ($0.05/$0.1)"));
+        assert!(text.contains("alice: raises $1.05 to $1.5"));
+        assert!(text.contains("Seat 4: carol (small blind) folded before Flop"));
+
+        let parsed = parse_pokerstars_hand(&text).unwrap();
+        assert_eq!(hand.players, parsed.players);
+        assert_eq!(hand.rounds, parsed.rounds);
+        assert_eq!(hand.start_date_utc, parsed.start_date_utc);
+        let wins = |hand: &HandHistory| -> Vec<(u64, f32)> {
+            hand.pots
+                .iter()
+                .flat_map(|pot| &pot.player_wins)
+                .map(|win| (win.player_id, win.win_amount))
+                .collect()
+        };
+        assert_eq!(wins(&hand), wins(&parsed));
+    }
+
+    #[test]
+    fn test_write_and_parse_chips() {
+        let chips = HAND.replace(" USD", "").replace('$', "");
+        let hand = parse_pokerstars_hand(&chips).unwrap();
+        assert_eq!("", hand.currency);
+
+        // Working out the raises in floats mustn't show up in the text.
+        let text = write_pokerstars_hand(&hand);
+        assert!(text.contains("bob smith: raises 0.2 to 0.3"));
+        assert!(text.contains("alice: calls 0.2"));
+        assert!(text.contains("alice: raises 1.05 to 1.5"));
+        assert_eq!(hand.rounds, parse_pokerstars_hand(&text).unwrap().rounds);
+    }
+
+    #[test]
+    fn test_small_and_big_blinds_are_partly_dead() {
+        let text = HAND.replace(
+            "carol: posts small blind $0.05\nalice: posts big blind $0.10\n",
+            "carol: posts small blind $0.05\nalice: posts big blind $0.10\n\
+             bob smith: posts small & big blinds $0.15\n",
+        );
+        let hand = parse_pokerstars_hand(&text).unwrap();
+        let preflop = &hand.rounds[0].actions;
+        assert_eq!(Action::PostDead, preflop[2].action);
+        assert_eq!(0.15, preflop[2].amount);
+        // Only the big blind counts towards bob's bet, so raising to 0.30
+        // puts in another 0.20.
+        assert_eq!(Action::Raise, preflop[4].action);
+        assert!((preflop[4].amount - 0.2).abs() < 1e-6);
+
+        let written = write_pokerstars_hand(&hand);
+        assert!(written.contains("bob smith: posts small & big blinds $0.15"));
+        assert!(written.contains("bob smith: raises $0.2 to $0.3"));
+        assert_eq!(hand.rounds, parse_pokerstars_hand(&written).unwrap().rounds);
+    }
+
+    #[test]
+    fn test_parse_many_hands() {
+        let text = format!("\u{feff}{HAND}\n\n\n{}", HAND.replace("230455892041", "2"));
+        let hands = parse_pokerstars_hands(&text).unwrap();
+        assert_eq!(2, hands.len());
+        assert_eq!("2", hands[1].game_number);
+    }
+
+    #[test]
+    fn test_invalid_hands() {
+        assert!(matches!(
+            parse_pokerstars_hand("nothing to see"),
+            Err(PokerStarsError::MissingHeader)
+        ));
+        assert!(matches!(
+            parse_pokerstars_hand(&HAND.replace("calls $0.20", "calls lots")),
+            Err(PokerStarsError::InvalidAmount(_))
+        ));
+        assert!(matches!(
+            parse_pokerstars_hand(&HAND.replace("*** FLOP ***", "*** FIRST FLOP ***")),
+            Err(PokerStarsError::UnsupportedGame(_))
+        ));
+        assert!(matches!(
+            parse_pokerstars_hand(&HAND.replace("Hold'em", "Razz")),
+            Err(PokerStarsError::UnsupportedGame(_))
+        ));
+    }
+}
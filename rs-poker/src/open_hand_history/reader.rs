@@ -0,0 +1,71 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+
+use super::hand_history::{HandHistory, OpenHandHistoryWrapper};
+
+/// Reads every hand history in a file written one JSON object per hand, the
+/// way [`append_hand`](super::append_hand) writes them.
+pub fn read_hands(path: &Path) -> io::Result<Vec<HandHistory>> {
+    let reader = BufReader::new(File::open(path)?);
+    serde_json::Deserializer::from_reader(reader)
+        .into_iter::<OpenHandHistoryWrapper>()
+        .map(|wrapped| Ok(wrapped?.ohh))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::NamedTempFile;
+
+    use super::*;
+    use crate::open_hand_history::{GameType, append_hand};
+
+    fn hand(game_number: &str) -> HandHistory {
+        HandHistory {
+            spec_version: "1.4.7".to_string(),
+            site_name: "Test Site".to_string(),
+            network_name: "Test Network".to_string(),
+            internal_version: "1.0".to_string(),
+            tournament: false,
+            tournament_info: None,
+            game_number: game_number.to_string(),
+            start_date_utc: None,
+            table_name: "Test Table".to_string(),
+            table_handle: None,
+            table_skin: None,
+            game_type: GameType::Holdem,
+            bet_limit: None,
+            table_size: 9,
+            currency: "USD".to_string(),
+            dealer_seat: 1,
+            small_blind_amount: 1.0,
+            big_blind_amount: 2.0,
+            ante_amount: 0.0,
+            hero_player_id: None,
+            players: vec![],
+            rounds: vec![],
+            pots: vec![],
+            tournament_bounties: None,
+        }
+    }
+
+    #[test]
+    fn test_read_appended_hands() {
+        let temp_file = NamedTempFile::new().unwrap();
+        append_hand(temp_file.path(), hand("1")).unwrap();
+        append_hand(temp_file.path(), hand("2")).unwrap();
+
+        let hands = read_hands(temp_file.path()).unwrap();
+        assert_eq!(vec![hand("1"), hand("2")], hands);
+    }
+
+    #[test]
+    fn test_read_invalid_json() {
+        let mut temp_file = NamedTempFile::new().unwrap();
+        writeln!(temp_file, "{{\"ohh\": 12}}").unwrap();
+        assert!(read_hands(temp_file.path()).is_err());
+    }
+}
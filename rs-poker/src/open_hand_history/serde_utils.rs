@@ -44,10 +44,14 @@ where
         Result(T),
     }
 
-    match Wrapper::deserialize(deserializer)? {
-        Wrapper::String(s) if s.is_empty() => Ok(None),
-        Wrapper::String(_) => Err(serde::de::Error::custom("expected empty string or vector")),
-        Wrapper::Result(v) => Ok(Some(v)),
+    // `null` is what a `None` serializes to, so it has to come back as one.
+    match Option::<Wrapper<T>>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Wrapper::String(s)) if s.is_empty() => Ok(None),
+        Some(Wrapper::String(_)) => {
+            Err(serde::de::Error::custom("expected empty string or vector"))
+        }
+        Some(Wrapper::Result(v)) => Ok(Some(v)),
     }
 }
 
@@ -119,6 +123,27 @@ mod tests {
         assert!(parsed.date.is_none());
     }
 
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct TestCards {
+        #[serde(default, deserialize_with = "empty_string_is_none")]
+        cards: Option<Vec<String>>,
+    }
+
+    #[test]
+    fn test_empty_string_or_null_is_none() {
+        let parsed: TestCards = serde_json::from_str(r#"{"cards": ""}"#).unwrap();
+        assert_eq!(None, parsed.cards);
+
+        let parsed: TestCards = serde_json::from_str(r#"{"cards": null}"#).unwrap();
+        assert_eq!(None, parsed.cards);
+
+        let json = serde_json::to_string(&TestCards { cards: None }).unwrap();
+        assert_eq!(
+            TestCards { cards: None },
+            serde_json::from_str(&json).unwrap()
+        );
+    }
+
     #[test]
     fn test_roundtrip() {
         let original = TestDate {